@@ -1,4 +1,5 @@
 pub mod core;
 pub mod compiler;
 pub mod rustblanc;
-pub mod include;
\ No newline at end of file
+pub mod include;
+pub mod lsp;
\ No newline at end of file
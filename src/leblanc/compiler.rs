@@ -1,16 +1,26 @@
+// The token based front end below only still produces type stubs. It is kept as it was rather
+// than brought up to the lints the rest of the crate follows, so each module allows what it trips.
+#[allow(dead_code)]
 pub mod char_reader;
 pub mod compile;
 pub mod driver;
+#[allow(unused_assignments)]
 pub mod tokenizer;
+#[allow(clippy::derived_hash_with_manual_eq, clippy::too_many_arguments)]
 pub mod symbols;
 pub mod lang;
+#[allow(unused_assignments)]
 pub mod compiler_util;
 pub mod compile_error_reporter;
+#[allow(dead_code)]
 pub mod fabric;
+#[allow(clippy::ptr_arg)]
 pub mod syntax_rules;
-pub mod token_stack_generator;
+#[allow(unused_assignments, clippy::ptr_arg, clippy::unnecessary_unwrap, clippy::derived_hash_with_manual_eq, clippy::len_without_is_empty)]
 pub mod identifier;
 pub mod module_resolver;
 pub mod compile_types;
+#[allow(clippy::ptr_arg)]
 pub mod import;
 pub mod compiler_rewrite;
+pub mod diagnostic;
@@ -30,7 +30,7 @@ impl CharReader {
                 Ok(c) => c,
                 Err(error) => {
                     let b: Box<dyn Error> = error.into();
-                    let borrowed: &Box<dyn Error> = b.borrow();
+                    let borrowed: &dyn Error = b.borrow();
                     let string = borrowed.to_string();
                     LeblancBaseException::from(b, &format!("Error parsing lines in source file: {}", string)
                                                , true, 5006001).handle().unwrap()
@@ -1,65 +1,106 @@
+use std::fs;
 use std::fs::File;
-use crate::{BOUNDARY, CompileVocab, create_stack, create_tokens, Fabric, TypedToken};
-use crate::leblanc::compiler::lang::leblanc_lang::BoundaryType::*;
+use crate::{create_tokens, Fabric};
 use crate::leblanc::compiler::char_reader::CharReader;
-use crate::leblanc::compiler::compile_error_reporter::error_report;
+use crate::leblanc::compiler::compile_error_reporter::{error_report, report_diagnostics};
 use crate::leblanc::compiler::compile_types::CompilationMode;
-use crate::leblanc::compiler::compile_types::full_compiler::write_bytecode;
-use crate::leblanc::compiler::compile_types::stub_compiler::read_from_stub_dump;
-use crate::leblanc::compiler::compiler_util::flatmap_node_tokens;
-use crate::leblanc::compiler::lang::leblanc_keywords::LBKeyword;
-use crate::leblanc::rustblanc::Appendable;
+use crate::leblanc::compiler::compile_types::full_reader::write_file;
+use crate::leblanc::compiler::compiler_rewrite::codegen::generate;
+use crate::leblanc::compiler::compiler_rewrite::parser::parse;
+use crate::leblanc::compiler::compile_types::stub_compiler::{read_from_stub_dump, write_stub_dump};
+use crate::leblanc::compiler::diagnostic::{Diagnostic, MessageFormat};
+use crate::leblanc::rustblanc::hex::Hexadecimal;
 
 static DEBUG: bool = false;
 
 pub fn compile(string: String, mode: CompilationMode) -> Fabric {
-    println!("Compiling: {}", string);
-    let filesf_name = string.replace(".lb", ".lbsf");
-    let filesf = File::open(filesf_name);
-    let mut stub_file_exists = false;
-    let mut fabric = Fabric::no_path(vec![], vec![], vec![],vec![],vec![]);
-    if filesf.is_ok() {
-        fabric = read_from_stub_dump(filesf.unwrap());
-        stub_file_exists = true;
-    }
-
-    if mode == CompilationMode::Realtime {
-        let mut cr = CharReader::from_line(string);
-        fabric = partial_spin(&mut cr ,mode);
-        println!("Fabric: {:?}", fabric.tokens());
-    }
+    let output = (mode != CompilationMode::Realtime).then(|| string.replace(".lb", ".lbbc"));
+    compile_to(string, output, mode, MessageFormat::Human)
+}
 
-    else if fabric.is_null() {
-        let f = File::open(string).unwrap();
-        let mut cr = CharReader::new(f);
-        println!("Spin in");
-        fabric = partial_spin(&mut cr, mode);
-        println!("Spin out");
+/// Compiles `string` (a source path, or the source itself in `Realtime` mode) and writes the
+/// bytecode to `output`, if there is one. Diagnostics are reported to stderr in `format`, and any
+/// error leaves the returned fabric's bytecode empty.
+pub fn compile_to(string: String, output: Option<String>, mode: CompilationMode, format: MessageFormat) -> Fabric {
+    if DEBUG { println!("Compiling: {}", string); }
+    if mode == CompilationMode::StubFile {
+        return match stub_spin(string, format) {
+            Ok(fabric) => fabric,
+            Err(message) => {
+                eprintln!("error: {}", message);
+                Fabric::no_path(vec![], vec![], vec![], vec![], vec![])
+            }
+        };
     }
 
-    if mode == CompilationMode::StubFile {
-        if !stub_file_exists {
-            //create_stub_dump(&mut fabric);
+    let (path, source) = match mode {
+        CompilationMode::Realtime => (String::from("<input>"), string),
+        _ => match fs::read_to_string(&string) {
+            Ok(source) => (string, source),
+            Err(err) => {
+                eprintln!("error: cannot read '{}': {}", string, err);
+                return Fabric::no_path(vec![], vec![], vec![], vec![], vec![]);
+            }
         }
-        return fabric;
-    } else {
-
-        let tokens = create_execution_stack(&mut fabric);
+    };
+
+    let mut fabric = Fabric::new(path.clone(), vec![], vec![], vec![], vec![], vec![]);
+    let (bytecode, diagnostics) = compile_source(&source, &path);
+    report_diagnostics(&path, &source, &diagnostics, format);
+    if let Some(bytecode) = bytecode {
+        fabric.bytecode = bytecode;
+        if let Some(output) = output {
+            if let Err(err) = write_file(&output, &hex::decode(fabric.bytecode.to_string()).unwrap()) {
+                eprintln!("error: cannot write '{}': {}", output, err);
+                fabric.bytecode = Hexadecimal::empty();
+            }
+        }
+    }
+    fabric
+}
 
+/// Parses `source` and lowers it to bytecode, returning every diagnostic found along the way.
+/// There is no bytecode if any of them is an error.
+pub fn compile_source(source: &str, path: &str) -> (Option<Hexadecimal>, Vec<Diagnostic>) {
+    let file = match parse(source) {
+        Ok(file) => file,
+        Err(error) => return (None, vec![error.into()])
+    };
+    match generate(&file, path) {
+        Ok((bytecode, warnings)) => (Some(bytecode), warnings),
+        Err(diagnostics) => (None, diagnostics)
+    }
+}
 
-        write_bytecode(tokens, &mut fabric, mode);
+/// Runs the token based front end, which is still what produces type stubs, reusing the stub
+/// already written for `string` if there is one.
+fn stub_spin(string: String, format: MessageFormat) -> Result<Fabric, String> {
+    let filesf_name = string.replace(".lb", ".lbsf");
+    if let Ok(filesf) = File::open(filesf_name) {
+        return Ok(read_from_stub_dump(filesf));
     }
+    let f = File::open(&string).map_err(|err| format!("cannot read '{}': {}", string, err))?;
+    let mut cr = CharReader::new(f);
+    Ok(partial_spin(&mut cr, CompilationMode::StubFile, format))
+}
 
-    //("test.lbsf".to_string());
-    fabric
+/// Writes the type stub of the source file at `path` to `output`. Errors in the source are
+/// reported to stderr in `format`, and leave `output` alone.
+pub fn compile_stub(path: &str, output: &str, format: MessageFormat) -> Result<(), String> {
+    let f = File::open(path).map_err(|err| format!("cannot read '{}': {}", path, err))?;
+    let mut fabric = partial_spin(&mut CharReader::new(f), CompilationMode::StubFile, format);
+    if !fabric.errors().is_empty() {
+        return Err(format!("could not compile '{}'", path));
+    }
+    write_stub_dump(&mut fabric, output).map_err(|err| format!("cannot write '{}': {}", output, err))
 }
 
 // haha I'm so hip because I call my methods fancy things
 // here we're "spinning" the "fabric"
 // 😎 (Sunglasses emoji)
-pub fn partial_spin(cr: &mut CharReader, mode: CompilationMode) -> Fabric {
+pub fn partial_spin(cr: &mut CharReader, mode: CompilationMode, format: MessageFormat) -> Fabric {
     let mut fabric = create_tokens(cr, mode);
-    println!("Done creating tokens");
+    if DEBUG { println!("Done creating tokens"); }
 
     if DEBUG {
         println!("imports: {:?}", fabric.imports());
@@ -69,59 +110,13 @@ pub fn partial_spin(cr: &mut CharReader, mode: CompilationMode) -> Fabric {
     }
 
 
-    println!("Errors: {:?}", fabric.errors());
+    if DEBUG { println!("Errors: {:?}", fabric.errors()); }
 
     if !fabric.errors().is_empty() {
-        //error_report(cr, &fabric.tokens().iter().cloned().map(|t| t.value.clone()).collect(), fabric.errors());
+        error_report(cr, &fabric.tokens().iter().map(|t| t.value.clone()).collect::<Vec<_>>(), fabric.errors(), format);
     }
 
     fabric
 
 
 }
-
-pub fn create_execution_stack(fabric: &mut Fabric) -> Vec<TypedToken> {
-    let mut stack: Vec<TypedToken> = Vec::new();
-    for token in fabric.tokens() {
-        println!("Token: {:?}", token.value);
-    }
-    let token_length = fabric.tokens().len();
-    let mut boundary_index = fabric.tokens().iter().enumerate().filter(|(_, r)| {
-        if let BOUNDARY(boundary_type) = r.value.lang_type() {
-            matches!(boundary_type, Semicolon | BraceOpen | BraceClosed)
-        } else { false }
-    })
-        .map(|(index, _i)| {
-            if index+1 >= token_length {
-                index
-            } else {
-                index + 1
-            }
-        })
-        .collect::<Vec<_>>();
-    boundary_index.insert(0, 0);
-    boundary_index.append_item(fabric.tokens().len()-1);
-
-    let stack_print = true;
-
-    for i in 0..boundary_index.len()-1 {
-        if stack_print {println!("-----------");}
-        let mut mini_stack = Vec::new();
-        if fabric.tokens()[boundary_index[i]].value.lang_type() == CompileVocab::KEYWORD(LBKeyword::Func) {
-            for token in flatmap_node_tokens(&mut fabric.tokens()[boundary_index[i]..boundary_index[i + 1]].to_vec()) { mini_stack.insert(mini_stack.len(), token) }
-        } else {
-            create_stack(&mut fabric.tokens()[boundary_index[i]..boundary_index[i + 1]].to_vec(), &mut mini_stack);
-            mini_stack.reverse();
-        }
-        if stack_print {
-            for token in &mini_stack {
-                if token.lang_type().matches("boundary") { println!("{:?}", token); } else {
-                   println!("{:?}", token);
-                }
-            }
-        }
-        stack.append(&mut mini_stack);
-    }
-
-    stack
-}
\ No newline at end of file
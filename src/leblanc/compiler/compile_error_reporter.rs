@@ -1,246 +1,182 @@
-use std::process::exit;
-use regex::Regex;
 use crate::leblanc::compiler::char_reader::CharReader;
+use crate::leblanc::compiler::compiler_rewrite::ast::Location;
 use crate::leblanc::compiler::compiler_util::strip_start_of_line;
+use crate::leblanc::compiler::diagnostic::{similar_name, Diagnostic, DiagnosticCode, MessageFormat, Severity};
 use crate::leblanc::compiler::identifier::typed_token::TypedToken;
-use crate::leblanc::rustblanc::Appendable;
 use crate::leblanc::rustblanc::exception::error_stubbing::ErrorStub;
 use crate::leblanc::rustblanc::exception::leblanc_base_exception::LeblancBaseException;
 use crate::leblanc::include::lib::leblanc_colored::{Color, ColorBright, colorize, colorize_str, ColorString};
-use crate::leblanc::include::lib::leblanc_colored::Color::Bright;
-use crate::leblanc::include::lib::leblanc_colored::ColorBright::{BrightGreen, BrightWhite};
+use crate::leblanc::include::lib::leblanc_colored::ColorBright::BrightWhite;
 
 
-fn create_dashes_to_symbol(number: u32, symbol_number: u32, string: String) -> String {
-    let mut s = String::new();
-    let real_number = if number > 2 {
-        number - 2
-    } else {
-        0
-    };
-    for _i in 0..real_number {
-        s += "-";
-    }
-
-    //println!("Symbol number: {}", symbol_number);
-    //println!("String: {}", string);
-    //println!("String: {}", string.len());
-
-    if (symbol_number as i32) - (string.len() as i32) > 0 {
-        s += " ";
-    }
-
-    s += &generate_arrows(string);
-    //return colorize_str(s.as_str(), Color::Bright(ColorBright::BrightYellow));
-    return ColorString::new(s.as_str()).colorize(Color::Bright(ColorBright::BrightYellow)).bold().string()
+/// Reports the errors the token based front end collected, all in one pass.
+pub fn error_report(cr: &mut CharReader, tokens: &[TypedToken], errors: &[ErrorStub], format: MessageFormat) {
+    let diagnostics = errors.iter().map(|error| stub_diagnostic(cr.lines(), tokens, error)).collect::<Vec<Diagnostic>>();
+    report_diagnostics(cr.path(), &cr.lines().join("\n"), &diagnostics, format);
 }
 
-
-pub fn error_report(cr: &mut CharReader, tokens: &Vec<TypedToken>, errors: &Vec<ErrorStub>) {
+fn stub_diagnostic(lines: &[String], tokens: &[TypedToken], error: &ErrorStub) -> Diagnostic {
     let digits = &['0', '1', '2', '3', '4', '5', '6', '7', '8', '9'];
-
-    let mut complete_errors: Vec<LeblancBaseException> = Vec::new();
-    let error = errors[0].clone();
-    let line_number = match error {
-        ErrorStub::ParseImbalancedQuotation(line_number, _) => { line_number }
-        ErrorStub::ImbalancedDelimiter(Symbol) => { Symbol.line_number() }
-        ErrorStub::MissingSemicolon(line_number, _) => { line_number }
-        ErrorStub::UndeclaredVariable(ref typed) => { typed.token().line_number() }
-        ErrorStub::InvalidGlobalVariableDeclaration(ref typed) => {typed.token().line_number()}
-        ErrorStub::FlexReassignment(ref typed) => {typed.token().line_number()}
-        ErrorStub::IncompatibleType(ref typed) => {typed.token().line_number()}
-        ErrorStub::VariableAlreadyDefined(ref typed) => {typed.token().line_number()}
-        ErrorStub::InvalidSyntax(ref typed) => {typed.token().line_number()}
+    let at = |token: &TypedToken| {
+        let symbol = token.token().first_symbol_or_empty().symbol_number().max(1);
+        stub_location(lines, token.token().line_number(), symbol, token.as_string().len())
     };
-    let mut symbol_number = 0;
-
-    let line_tokens = get_line_tokens(tokens, line_number);
-
-    let mut exact_token = TypedToken::empty();
-    let mut fix = String::new();
-
-    let error_line: String = cr.lines()[(line_number as usize)-1].clone();
-    let stripped_error_line = strip_start_of_line(error_line.clone());
-    let strip_amount = error_line.len() - stripped_error_line.len();
-    let mut error_name = String::new();
-    let mut error_message_extra = String::new();
-    let mut error_syntax = String::new();
-
-    if let ErrorStub::UndeclaredVariable(ref undeclared) = error {
-        error_name = String::from("Undeclared Variable");
-        symbol_number = undeclared.token().first_symbol_or_empty().symbol_number();
-        error_syntax = undeclared.as_string();
 
-        for token in tokens.iter().filter(|t| t.scope() == 0 || t.scope() == undeclared.scope()) {
-            if (token.as_string().starts_with(&undeclared.as_string()) || undeclared.as_string().starts_with(&token.as_string()))
-            && !token.as_string().eq(&undeclared.as_string()) {
-                fix = bright_white(format!("\nNote - There's a similar variable named: {} in this scope",
-                            colorize(token.as_string(), Color::Green)).as_str());
-                break;
+    match error {
+        ErrorStub::ParseImbalancedQuotation(line_number, symbol_number) => Diagnostic::new(DiagnosticCode::ImbalancedQuotation,
+            String::from("unbalanced quotation mark"), stub_location(lines, *line_number, *symbol_number, 1))
+            .with_note(String::from("possible fix - close the string with a matching '\"'")),
+        ErrorStub::ImbalancedDelimiter(symbol) => {
+            let diagnostic = Diagnostic::new(DiagnosticCode::ImbalancedDelimiter, format!("unbalanced delimiter '{}'", symbol.char()),
+                stub_location(lines, symbol.line_number(), symbol.symbol_number(), 1));
+            match symbol.char() {
+                '(' => diagnostic.with_note(String::from("possible fix - add a closing ')'")),
+                ')' => diagnostic.with_note(String::from("possible fix - add an opening '(' or remove this one")),
+                _ => diagnostic
             }
         }
-        error_message_extra += &("\n".to_owned() + &fix);
-    } else if let ErrorStub::InvalidGlobalVariableDeclaration(ref undeclared) = error {
-        error_name = String::from("Global declaration of variable without 'global' keyword");
-        symbol_number = undeclared.token().first_symbol_or_empty().symbol_number();
-        error_syntax = error_line.clone();
-        fix = bright_white("\nPossible Fix - ") + &ColorString::new("Add 'global' keyword to declaration").green().string();
-        error_message_extra += &("\n".to_owned() + &fix
-            + "\n| " + &line_number.to_string() + " \t" + repair_missing_global_error(error_line).as_str());
-    } else if let ErrorStub::IncompatibleType(ref incompatible) = error {
-        error_name = String::from("Attempt to assign variable an incompatible type.");
-        let line_tokens = get_line_tokens(tokens, line_number);
-        symbol_number = line_tokens[0].token().first_symbol_or_empty().symbol_number();
-        error_syntax = stripped_error_line.clone();
-        let mut declaration_line = Option::None;
-        for token in tokens.iter().filter(|t| t.scope() == 0 || t.scope() == incompatible.scope()) {
-            if token.as_string() == incompatible.as_string() {
-                declaration_line = Option::Some(token.token().line_number());
-                break;
+        ErrorStub::MissingSemicolon(line_number, symbol_number) => Diagnostic::new(DiagnosticCode::MissingSemicolon,
+            String::from("missing semicolon"), stub_location(lines, *line_number, *symbol_number, 1)),
+        ErrorStub::UndeclaredVariable(undeclared) => {
+            let diagnostic = Diagnostic::new(DiagnosticCode::UndeclaredVariable, format!("undeclared variable '{}'", undeclared.as_string()), at(undeclared));
+            let names = tokens.iter().filter(|t| in_same_scope(t, undeclared.scope())).map(|t| t.as_string()).collect::<Vec<String>>();
+            match similar_name(&undeclared.as_string(), names.iter().map(String::as_str)) {
+                Some(similar) => diagnostic.with_note(format!("there's a similar variable named '{}' in this scope", similar)),
+                None => diagnostic
             }
         }
-        let assignee_var_type = if declaration_line.is_some() && declaration_line.unwrap() != line_number {
-            String::from("dynamic")
-        } else {
-            line_tokens[3].lang_type().extract_native_type().as_str().to_string()
-        };
-
-        fix = bright_white("\nPossible Fix - ") + &ColorString::new(&format!("Change assignee variable type to '{}'", assignee_var_type)).green().string();
-
-        if declaration_line.is_some() {
-            let declaration_line = declaration_line.unwrap();
-            error_message_extra += &("\n".to_owned() + &fix
-                + "\n| " + &declaration_line.to_string() + " \t"
-                + repair_incompatible_type_error(cr.lines()[(declaration_line as usize)-1].clone(), incompatible, assignee_var_type).as_str());
+        ErrorStub::InvalidGlobalVariableDeclaration(undeclared) => {
+            let line = lines.get(undeclared.token().line_number() as usize - 1).cloned().unwrap_or_default();
+            Diagnostic::new(DiagnosticCode::InvalidGlobalDeclaration, String::from("global declaration of variable without 'global' keyword"), at(undeclared))
+                .with_note(format!("possible fix - add the 'global' keyword: global {}", strip_start_of_line(line)))
         }
-    }
-    else if let ErrorStub::VariableAlreadyDefined(imbalanced) = error {
-        error_name = String::from("Variable already declared in this scope");
-        println!("tokens: {:?}", &tokens);
-        symbol_number = get_line_tokens(tokens, line_number)[0].token().first_symbol_or_empty().symbol_number();
-        error_syntax = stripped_error_line.clone();
-        fix = bright_white("\nPossible Fix - ") + &ColorString::new("Rename the new variable").green().string();
-
-        let similar_tokens: Vec<TypedToken> = tokens.iter().filter(|t|
-            (t.scope() == imbalanced.scope()) && t.as_string().starts_with(&imbalanced.as_string().trim_end_matches(digits).to_owned())).cloned().collect();
-        let mut var_count = 1;
-        let mut new_var_name = imbalanced.as_string().trim_end_matches(digits).to_owned() + &var_count.to_string();
-        println!("Similar tokens: {:?}", similar_tokens);
-        while similar_tokens.iter().any(|t| t.as_string() == new_var_name) {
-            var_count += 1;
-            new_var_name = imbalanced.as_string().trim_end_matches(digits).to_owned() + &var_count.to_string();
+        ErrorStub::FlexReassignment(flex) => Diagnostic::new(DiagnosticCode::FlexReassignment,
+            format!("cannot reassign flex variable '{}'", flex.as_string()), at(flex)),
+        ErrorStub::IncompatibleType(incompatible) => {
+            let line_number = incompatible.token().line_number();
+            let line_tokens = get_line_tokens(tokens, line_number);
+            let mut diagnostic = Diagnostic::new(DiagnosticCode::IncompatibleType,
+                format!("attempt to assign variable '{}' an incompatible type", incompatible.as_string()), at(incompatible));
+            let declaration = tokens.iter().filter(|t| in_same_scope(t, incompatible.scope()))
+                .find(|t| t.as_string() == incompatible.as_string());
+            let assignee_var_type = match declaration {
+                Some(declaration) if declaration.token().line_number() != line_number => {
+                    diagnostic = diagnostic.with_label(at(declaration), "variable declared here");
+                    String::from("dynamic")
+                }
+                _ => line_tokens.get(3).map_or_else(|| String::from("dynamic"), |t| t.lang_type().extract_native_type().as_str().to_string())
+            };
+            diagnostic.with_note(format!("possible fix - change the type of '{}' to '{}'", incompatible.as_string(), assignee_var_type))
         }
-
-        error_message_extra += &("\n".to_owned() + &fix
-            + "\n| " + &line_number.to_string() + " \t" + repair_var_already_defined_error(error_line, imbalanced.as_string(), new_var_name).as_str());
-    }
-
-    else if let ErrorStub::ImbalancedDelimiter(imbalanced) = error {
-        error_name = String::from("Unbalanced Delimiter");
-        symbol_number = imbalanced.symbol_number();
-        error_syntax = imbalanced.as_string();
-        for token in line_tokens {
-            let imbalance_char = imbalanced.char();
-            let first_symbol = token.token().first_symbol_or_empty();
-
-            if *imbalance_char == '(' && first_symbol.is_boundary() {
-                exact_token = token;
-                fix = bright_white("\nPossible Fix - ") + &ColorString::new("Add closing ')'").green().string();
+        ErrorStub::VariableAlreadyDefined(defined) => {
+            let base = defined.as_string().trim_end_matches(digits).to_owned();
+            let mut var_count = 1;
+            while tokens.iter().any(|t| t.scope() == defined.scope() && t.as_string() == base.clone() + &var_count.to_string()) {
+                var_count += 1;
             }
+            let mut diagnostic = Diagnostic::new(DiagnosticCode::VariableAlreadyDeclared,
+                format!("variable '{}' is already declared in this scope", defined.as_string()), at(defined));
+            if let Some(first) = tokens.iter().find(|t| t.scope() == defined.scope() && t.as_string() == defined.as_string() && *t != defined) {
+                diagnostic = diagnostic.with_label(at(first), "first declared here");
+            }
+            diagnostic.with_note(format!("possible fix - rename the new variable, e.g. to '{}{}'", base, var_count))
         }
+        ErrorStub::InvalidSyntax(invalid) => Diagnostic::new(DiagnosticCode::InvalidSyntax,
+            format!("invalid syntax near '{}'", invalid.as_string()), at(invalid))
+    }
+}
 
-        if exact_token != TypedToken::empty() {
-            let insert_char = match imbalanced.char() {
-                '(' => ')',
-                ')' => '(',
-                _ => '\0'
-            };
+/// The location of `width` bytes starting at the 1-based `line` and `column` of `lines`.
+fn stub_location(lines: &[String], line: u32, column: u32, width: usize) -> Location {
+    let line = line.max(1) as usize;
+    let start = lines.iter().take(line - 1).map(|l| l.len() + 1).sum::<usize>() + column.saturating_sub(1) as usize;
+    Location::new(start, start + width.max(1), line, column.max(1) as usize)
+}
 
-            error_message_extra += &("\n".to_owned() + &fix
-                + "\n" + &line_number.to_string() + "\t" + repair_syntax_error(error_line, exact_token, insert_char).as_str());
+/// Writes every diagnostic to stderr, errors and warnings alike, in source order. Human output
+/// ends with a count of what was found.
+pub fn report_diagnostics(path: &str, source: &str, diagnostics: &[Diagnostic], format: MessageFormat) {
+    let mut diagnostics = diagnostics.iter().collect::<Vec<&Diagnostic>>();
+    diagnostics.sort_by_key(|diagnostic| (diagnostic.location.line, diagnostic.location.column));
+    match format {
+        MessageFormat::Json => diagnostics.iter().for_each(|diagnostic| eprintln!("{}", diagnostic.to_json(path))),
+        MessageFormat::Human => {
+            let lines: Vec<&str> = source.lines().collect();
+            for diagnostic in &diagnostics {
+                LeblancBaseException::new(&render_diagnostic(path, source, &lines, diagnostic), diagnostic.is_error(), 5009003).output();
+            }
+            let errors = diagnostics.iter().filter(|diagnostic| diagnostic.is_error()).count();
+            let warnings = diagnostics.len() - errors;
+            if !diagnostics.is_empty() {
+                eprintln!("{}", summary(path, errors, warnings));
+            }
         }
-    } else {
-        error_name = String::from("Unknown Error");
-        symbol_number = 1;
-        error_syntax = stripped_error_line.clone();
-        error_message_extra = colorize("\nExtra Error Information:\n".to_owned() + &error.to_string(), Bright(ColorBright::BrightMagenta));
     }
+}
 
+fn render_diagnostic(path: &str, source: &str, lines: &[&str], diagnostic: &Diagnostic) -> String {
+    let location = &diagnostic.location;
+    let heading = format!("{}[{}]", diagnostic.severity(), diagnostic.code);
+    let heading = match diagnostic.severity() {
+        Severity::Error => ColorString::new(&heading).bold().red().string(),
+        Severity::Warning => ColorString::new(&heading).bold().yellow().string()
+    };
 
-
-    let mut error_message = generate_file_path(cr.path(), line_number, symbol_number) + "\n\n"
-        + &colorize(stripped_error_line, Color::Red)
-        + &("\n".to_owned()
-        + create_dashes_to_symbol(symbol_number - (strip_amount as u32), symbol_number - 1 - (strip_amount as u32) + error_syntax.len() as u32,error_syntax).as_str())
-        + "\n" + &generate_error_name(error_name.as_str(), line_number, symbol_number);
-
-    if error_message_extra != String::new() {
-        error_message += &error_message_extra;
+    let mut message = heading + ": " + &ColorString::new(&diagnostic.message).bold().string() + "\n"
+        + &generate_file_path(path, diagnostic.severity(), location.line as u32, location.column as u32) + "\n\n"
+        + &located_excerpt(source, lines, location);
+    for label in &diagnostic.labels {
+        message += &("\n".to_owned() + &bright_white(&format!("{}:{}: {}", path, label.location, label.message)) + "\n"
+            + &located_excerpt(source, lines, &label.location));
     }
-
-    complete_errors.append_item(LeblancBaseException::new(&error_message, true, 5009003));
-
-    for error in complete_errors {
-        error.output();
+    for note in &diagnostic.notes {
+        message += &("\n".to_owned() + &bright_white("Note - ") + note);
     }
-    exit(0)
+    message + "\n"
 }
 
-fn get_line_tokens(all_tokens: &Vec<TypedToken>, line_number: u32) -> Vec<TypedToken> {
-    let mut line_tokens: Vec<TypedToken> = Vec::new();
-    all_tokens.iter().filter(|t| t.token().line_number() == line_number).for_each(|t| line_tokens.insert(line_tokens.len(), t.clone()));
-    line_tokens
+/// The line `location` starts on with carets under as much of its span as fits on that line.
+fn located_excerpt(source: &str, lines: &[&str], location: &Location) -> String {
+    let line = lines.get(location.line.saturating_sub(1)).unwrap_or(&"");
+    let remaining = line.chars().count().saturating_sub(location.column - 1);
+    let width = source.get(location.start..location.end).map_or(1, |span| span.chars().count()).clamp(1, remaining.max(1));
+    source_excerpt(line, location.column, width)
 }
 
-fn generate_file_path(path: &String, line_number: u32, symbol_number: u32) -> String {
-    ColorString::new("Error in: ").bold().red().string() + path + ":" + &line_number.to_string() + ":" + &symbol_number.to_string()
-}
-
-fn repair_syntax_error(text: String, token: TypedToken, insert_char: char) -> String {
-    let stripped = strip_start_of_line(text.clone());
-    let strip_amount = text.len() - stripped.len();
-    let last_symbol_index = token.token().last_symbol_or_empty().symbol_number() as usize - 1 - strip_amount;
-    colorize(stripped[..last_symbol_index].to_string() + &colorize(String::from(insert_char), Color::Bright(BrightGreen)) + &stripped[last_symbol_index..], Color::White)
-}
-
-fn repair_missing_global_error(text: String) -> String {
-    let stripped = strip_start_of_line(text);
-    colorize(String::from("global "), Color::Bright(ColorBright::BrightGreen)) + &colorize(stripped, Color::Yellow)
-}
-
-fn repair_incompatible_type_error(text: String, token: &TypedToken, fix_type: String) -> String {
-    let stripped = strip_start_of_line(text);
-    let re = Regex::new(&format!("{}{}{}", r"\b", token.as_string(), r"\b")).unwrap();
-    let p = re.find(&stripped).unwrap().start();
-    colorize(fix_type + " ", Color::Bright(ColorBright::BrightGreen)) + &colorize_str(&stripped[p..], Color::Yellow)
+fn summary(path: &str, errors: usize, warnings: usize) -> String {
+    let plural = |count: usize, noun: &str| format!("{} {}{}", count, noun, if count == 1 { "" } else { "s" });
+    match (errors, warnings) {
+        (0, warnings) => ColorString::new(&format!("{}: {} generated", path, plural(warnings, "warning"))).bold().yellow().string(),
+        (errors, 0) => ColorString::new(&format!("could not compile {}: {}", path, plural(errors, "error"))).bold().red().string(),
+        (errors, warnings) => ColorString::new(&format!("could not compile {}: {} and {}", path, plural(errors, "error"), plural(warnings, "warning"))).bold().red().string()
+    }
 }
 
-fn repair_var_already_defined_error(text: String, old_name: String, new_name: String) -> String {
-    let stripped = strip_start_of_line(text);
-    let re = Regex::new(&format!("{}{}{}", r"\b", old_name.as_str(), r"\b")).unwrap();
-    let stripped = re.replace(&stripped, &colorize(new_name, Bright(BrightGreen)));
-    colorize(stripped.to_string(), Color::Yellow)
+/// A source line without its indentation, in red, with `width` bright yellow carets underneath
+/// starting at the 1-based `column`.
+pub fn source_excerpt(line: &str, column: usize, width: usize) -> String {
+    let stripped_line = strip_start_of_line(line.to_string());
+    let strip_amount = line.chars().count() - stripped_line.chars().count();
+    let marker = " ".repeat(column.saturating_sub(1).saturating_sub(strip_amount)) + &"^".repeat(width);
+    colorize(stripped_line, Color::Red) + "\n" + &ColorString::new(&marker).colorize(Color::Bright(ColorBright::BrightYellow)).bold().string()
 }
 
-fn generate_error_name(text: &str, line_number: u32, symbol_number: u32) -> String {
-    colorize(format!("Parse Error at: ({}::{}) -> {}", line_number, symbol_number, text), Color::Red)
+fn get_line_tokens(all_tokens: &[TypedToken], line_number: u32) -> Vec<TypedToken> {
+    all_tokens.iter().filter(|t| t.token().line_number() == line_number).cloned().collect()
 }
 
-fn generate_arrows(token_string: String) -> String {
-    let mut arrows = String::new();
-    for _i in 0..token_string.len() {
-        arrows += "^";
-    }
-    arrows
+fn generate_file_path(path: &str, severity: Severity, line_number: u32, symbol_number: u32) -> String {
+    let prefix = match severity {
+        Severity::Error => ColorString::new("Error in: ").bold().red().string(),
+        Severity::Warning => ColorString::new("Warning in: ").bold().yellow().string()
+    };
+    prefix + path + ":" + &line_number.to_string() + ":" + &symbol_number.to_string()
 }
 
 fn bright_white(string: &str) -> String {
     colorize_str(string, Color::Bright(BrightWhite))
 }
 
-fn in_same_scope(token: TypedToken, scope: i32) -> bool {
-    if token.scope() == 0 || token.scope() == scope {
-        return true;
-    }
-    false
-}
\ No newline at end of file
+fn in_same_scope(token: &TypedToken, scope: i32) -> bool {
+    token.scope() == 0 || token.scope() == scope
+}
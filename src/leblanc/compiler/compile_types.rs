@@ -1,8 +1,9 @@
+use std::str::FromStr;
 use crate::LeBlancType;
 
 pub mod stub_compiler;
-pub mod full_compiler;
 pub mod full_reader;
+#[allow(clippy::derived_hash_with_manual_eq)]
 pub mod partial_function;
 pub mod partial_class;
 pub mod partial_token;
@@ -15,6 +16,20 @@ pub enum CompilationMode {
     Realtime
 }
 
+impl FromStr for CompilationMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "full" => Ok(CompilationMode::Full),
+            "stub" | "stubfile" => Ok(CompilationMode::StubFile),
+            "bytecode" => Ok(CompilationMode::ByteCode),
+            "realtime" => Ok(CompilationMode::Realtime),
+            other => Err(format!("unknown compilation mode '{}' (expected full, stub, bytecode or realtime)", other))
+        }
+    }
+}
+
 #[derive(PartialEq, Eq, Debug, Clone)]
 pub struct ExtensionExport {
     name: String,
@@ -1,5 +1,4 @@
-use std::fs::File;
-use std::io::{BufReader, Read};
+use std::fs;
 
 
 use crate::leblanc::core::bytecode::LeblancBytecode;
@@ -8,16 +7,48 @@ use crate::leblanc::core::bytecode::LeblancBytecode;
 use crate::leblanc::rustblanc::hex::Hexadecimal;
 use crate::leblanc::rustblanc::utils::encode_hex;
 
-pub fn read_file(path: String) -> LeblancBytecode {
-    let file = File::open(path.replace(".lb", ".lbbc")).unwrap();
-    let file_reader = BufReader::new(file);
-    let hex = encode_hex(&file_reader.bytes().map(|l| l.unwrap()).collect::<Vec<u8>>());
-
-    
+/// Starts every bytecode file.
+const MAGIC: &[u8; 4] = b"LBBC";
+/// Bumped whenever the encoding changes, e.g. an instruction is added, so a file written by another
+/// version is rejected instead of misread.
+const VERSION: u16 = 2;
+
+///     magic   | version | body length | body checksum |  body   |
+///    4 bytes  | 2 bytes |   4 bytes   |    4 bytes    | X bytes |
+const HEADER_SIZE: usize = 14;
+
+/// Writes `body` to `path` behind the header `read_file` checks.
+pub fn write_file(path: &str, body: &[u8]) -> std::io::Result<()> {
+    let mut bytes = Vec::with_capacity(HEADER_SIZE + body.len());
+    bytes.extend_from_slice(MAGIC);
+    bytes.extend_from_slice(&VERSION.to_be_bytes());
+    bytes.extend_from_slice(&(body.len() as u32).to_be_bytes());
+    bytes.extend_from_slice(&fxhash::hash32(body).to_be_bytes());
+    bytes.extend_from_slice(body);
+    fs::write(path, bytes)
+}
 
-    LeblancBytecode::from(hex)
+/// Reads a file `write_file` wrote. Anything else, a file from another version, or one that was
+/// cut short or changed since, is rejected before it is decoded.
+pub fn read_file(path: String) -> Result<LeblancBytecode, String> {
+    let path = if path.ends_with(".lb") { path + "bc" } else { path };
+    let bytes = fs::read(&path).map_err(|err| format!("cannot read '{}': {}", path, err))?;
+    if bytes.len() < HEADER_SIZE || &bytes[0..4] != MAGIC {
+        return Err(format!("'{}' is not a LeBlanc bytecode file", path));
+    }
+    let version = u16::from_be_bytes([bytes[4], bytes[5]]);
+    if version != VERSION {
+        return Err(format!("'{}' was compiled to bytecode version {}, but this is version {}; compile it again", path, version, VERSION));
+    }
+    let length = u32::from_be_bytes([bytes[6], bytes[7], bytes[8], bytes[9]]) as usize;
+    let checksum = u32::from_be_bytes([bytes[10], bytes[11], bytes[12], bytes[13]]);
+    let body = &bytes[HEADER_SIZE..];
+    if body.len() != length || fxhash::hash32(body) != checksum {
+        return Err(format!("'{}' is truncated or corrupt; compile it again", path));
+    }
+    Ok(LeblancBytecode::from(encode_hex(body)))
 }
 
 pub fn read_bytecode(hex: Hexadecimal) -> LeblancBytecode {
     LeblancBytecode::from(hex)
-}
\ No newline at end of file
+}
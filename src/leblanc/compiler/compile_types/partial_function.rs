@@ -12,7 +12,7 @@ pub struct PartialFunction {
 
 impl PartialFunction {
     pub fn from_token_args(token: &TypedToken) -> PartialFunction {
-        return PartialFunction {
+        PartialFunction {
             name: token.as_string(),
             args: LeBlancArgument::from_positional(&token.typing()[0].clone()),
             returns: vec![]
@@ -20,7 +20,7 @@ impl PartialFunction {
     }
 
     pub fn from_token_returns(token: &TypedToken) -> PartialFunction {
-        return PartialFunction {
+        PartialFunction {
             name: token.as_string(),
             args: vec![],
             returns: token.typing()[1].clone()
@@ -28,7 +28,7 @@ impl PartialFunction {
     }
 
     pub fn from_method(method: Method, returns: Vec<LeBlancType>) -> PartialFunction {
-        return PartialFunction::from_method_store(method.store(), returns)
+        PartialFunction::from_method_store(method.store(), returns)
     }
 
     pub fn from_method_store(method_store: &MethodStore, returns: Vec<LeBlancType>) -> PartialFunction {
@@ -64,7 +64,7 @@ impl PartialEq for PartialFunction {
             (max_other_args, other.args.clone(), main_iter)
         };
         for i in 0..max {
-            if !main_iter.iter().any(|arg| arg.position == (i as u32) && other_iter.iter().any(|o| o == arg)) {
+            if !main_iter.iter().any(|arg| arg.position == i && other_iter.iter().any(|o| o == arg)) {
                 return false;
             }
         }
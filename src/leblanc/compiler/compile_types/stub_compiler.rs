@@ -1,4 +1,5 @@
 use std::fs::File;
+use std::io;
 use std::io::{BufRead, BufReader, Write};
 use filepath::FilePath;
 use crate::{BOUNDARY, CompileVocab, Fabric, TypedToken};
@@ -11,15 +12,18 @@ use crate::leblanc::compiler::symbols::{Symbol, SymbolType};
 use crate::leblanc::core::native_types::type_value;
 use crate::leblanc::rustblanc::Appendable;
 use crate::leblanc::rustblanc::relationship::Node;
-use crate::LeBlancType::{Class, Null};
+use crate::LeBlancType::Null;
 
-pub fn create_stub_dump(fabric: &mut Fabric) {
+static DEBUG: bool = false;
+
+/// Writes the imports and typed tokens of `fabric` to `path`, to be read back by `read_from_stub_dump`.
+pub fn write_stub_dump(fabric: &mut Fabric, path: &str) -> io::Result<()> {
     let mut output = fabric.imports().iter().map(|i| i.source.clone()).collect::<Vec<String>>().join("|") + "\n";
 
-    let file = File::create(fabric.path.replace(".lb", ".lbsf"));
-    println!("File: {:?}", file);
+    let mut file = File::create(path)?;
+    if DEBUG { println!("File: {:?}", file); }
     fabric.tokens().iter().for_each(|t| output += &(t.value.as_stub_string() + "\n"));
-    file.unwrap().write_all(output.as_bytes()).unwrap();
+    file.write_all(output.as_bytes())
 }
 
 pub fn read_from_stub_dump(file: File) -> Fabric {
@@ -69,10 +73,12 @@ pub fn parse_stub_token(line: String) -> TypedToken {
 
     let scope_sep = line.find('|').unwrap();
     let scope = line[0..scope_sep].to_string().parse::<i32>().unwrap();
+    let line = line[scope_sep+1..].to_string();
 
     let global_sep = line.find('|').unwrap();
     let global_value = line[0..global_sep].to_string().parse::<i32>().unwrap();
     let global = global_value == 1;
+    let line = line[global_sep+1..].to_string();
 
     let class_member_sep = line.find('|').unwrap();
     let class_member_value = line[0..class_member_sep].to_string().parse::<i32>().unwrap();
@@ -80,7 +86,7 @@ pub fn parse_stub_token(line: String) -> TypedToken {
 
     let mut typed_token = TypedToken::new(token, vocab_type, scope, global, class_member);
 
-    let mut line = line[scope_sep+1..].to_string();
+    let mut line = line[class_member_sep+1..].to_string();
     let mut typings = vec![];
     while line[0..2].to_string() != "&&" {
         let vocab_sep = line.find('|').unwrap();
@@ -103,7 +109,7 @@ fn match_leblanc_type(vocab_string: String) -> CompileVocab {
     let vocab_string_sep = vocab_string_sep.unwrap();
     let first_vocab = vocab_string[0..vocab_string_sep].to_string();
     let second_vocab = vocab_string[vocab_string_sep+1..].to_string();
-    return match first_vocab.as_str() {
+    match first_vocab.as_str() {
         "constant" => CONSTANT(type_value(&second_vocab)),
         "variable" => VARIABLE(type_value(&second_vocab)),
         "constructor" => CONSTRUCTOR(type_value(&second_vocab)),
@@ -117,6 +123,6 @@ fn match_leblanc_type(vocab_string: String) -> CompileVocab {
         "boundary" => BOUNDARY(boundary_value(&second_vocab.chars().next().unwrap())),
         "function" => FUNCTION(function_type_value(&second_vocab)),
         _ => UNKNOWN(Null)
-    };
+    }
 
 }
\ No newline at end of file
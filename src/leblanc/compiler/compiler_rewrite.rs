@@ -1,2 +1,6 @@
+pub mod ast;
+pub mod captures;
+pub mod codegen;
 pub mod lexer;
-pub mod parser;
\ No newline at end of file
+pub mod parser;
+pub mod peephole;
@@ -0,0 +1,332 @@
+use std::fmt::{Display, Formatter};
+
+use num::BigInt;
+
+use crate::leblanc::compiler::lang::leblanc_operators::LBOperator;
+use crate::leblanc::core::native_types::{type_value, LeBlancType};
+use crate::leblanc::core::native_types::decimal_type::LeblancDecimal;
+use crate::leblanc::core::native_types::derived::DerivedType;
+
+/// Byte range of a node in its source file along with the line and column (both 1-based) it starts at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Location {
+    pub start: usize,
+    pub end: usize,
+    pub line: usize,
+    pub column: usize
+}
+
+impl Location {
+    pub fn new(start: usize, end: usize, line: usize, column: usize) -> Location {
+        Location { start, end, line, column }
+    }
+
+    /// A location spanning from the start of `self` to the end of `other`.
+    pub fn to(&self, other: &Location) -> Location {
+        Location { start: self.start, end: other.end, line: self.line, column: self.column }
+    }
+}
+
+impl Display for Location {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Located<T> {
+    pub data: T,
+    pub location: Location
+}
+
+impl<T> Located<T> {
+    pub fn new(data: T, location: Location) -> Located<T> {
+        Located { data, location }
+    }
+}
+
+pub type Ident = Located<String>;
+pub type Expression = Located<Expr>;
+pub type Statement = Located<Stmt>;
+pub type Block = Located<Vec<Statement>>;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct File {
+    pub items: Vec<Located<Item>>
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Item {
+    Import(Import),
+    Function(Function),
+    Class(Class),
+    Trait(Trait),
+    Enum(Enum),
+    Extension(Extension)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Import {
+    Module(Ident),
+    Extension { name: Ident, file: Located<String> }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Function {
+    pub name: Ident,
+    pub parameters: Vec<Located<Parameter>>,
+    pub returns: Vec<Located<TypeName>>,
+    /// `None` for declarations without a body, e.g. `func expose(Self) returns class;`
+    pub body: Option<Block>
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Parameter {
+    pub type_name: Located<TypeName>,
+    pub name: Ident
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Class {
+    pub name: Ident,
+    pub supertypes: Vec<Ident>,
+    pub fields: Vec<Located<Field>>,
+    pub methods: Vec<Located<Function>>
+}
+
+/// `Trait Name(Supertraits) { ... }`. Traits only declare methods, see `TraitMethod`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Trait {
+    pub name: Ident,
+    pub supertypes: Vec<Ident>,
+    pub methods: Vec<Located<TraitMethod>>
+}
+
+/// A method of a trait. `auto` methods are default implementations, every other method is a
+/// requirement that classes using the trait have to implement.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TraitMethod {
+    pub auto: bool,
+    pub function: Function
+}
+
+/// `Enum Name<T> { Unit, WithPayload(type, ...) }`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Enum {
+    pub name: Ident,
+    pub type_parameters: Vec<Ident>,
+    pub variants: Vec<Located<Variant>>
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Variant {
+    pub name: Ident,
+    pub fields: Vec<Located<TypeName>>
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Extension {
+    pub name: Ident,
+    pub target: Located<TypeName>,
+    pub fields: Vec<Located<Field>>,
+    pub methods: Vec<Located<Function>>
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Field {
+    pub property: bool,
+    pub type_name: Located<TypeName>,
+    pub name: Ident,
+    pub value: Option<Expression>
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypeName {
+    pub name: String,
+    pub generics: Vec<Located<TypeName>>,
+    /// Set for `Name!`, a value of any class implementing the trait `Name`.
+    pub trait_object: bool
+}
+
+impl TypeName {
+    pub fn new(name: &str) -> TypeName {
+        TypeName { name: name.to_string(), generics: vec![], trait_object: false }
+    }
+
+    /// Untyped lambda parameters are `flex`.
+    pub fn flex() -> TypeName { TypeName::new("flex") }
+
+    pub fn leblanc_type(&self) -> LeBlancType {
+        type_value(&self.name)
+    }
+}
+
+impl Display for TypeName {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name)?;
+        if self.trait_object {
+            write!(f, "!")?;
+        }
+        if !self.generics.is_empty() {
+            let generics = self.generics.iter().map(|generic| generic.data.to_string()).collect::<Vec<String>>();
+            write!(f, "<{}>", generics.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Stmt {
+    Declaration { type_name: Located<TypeName>, name: Ident, value: Option<Expression> },
+    /// `int a, int b = value;`, declaring a variable for each value of a tuple or list.
+    Destructuring { targets: Vec<(Located<TypeName>, Ident)>, value: Expression },
+    /// `operator` is set for compound assignments such as `a += 1`.
+    Assignment { target: Expression, operator: Option<LBOperator>, value: Expression },
+    Expression(Expression),
+    Return(Option<Expression>),
+    /// `if`, followed by every `elif`, in source order.
+    Conditional { branches: Vec<(Expression, Block)>, otherwise: Option<Block> },
+    While { condition: Expression, body: Block },
+    For { type_name: Located<TypeName>, variable: Ident, iterable: Expression, body: Block },
+    /// `try`, its `except` clauses in source order and an optional `finally`.
+    Try { body: Block, handlers: Vec<Located<Handler>>, finally: Option<Block> },
+    /// `throw value;` or `raise value;`
+    Throw(Expression),
+    /// `yield value;`, which makes the function a generator.
+    Yield(Expression),
+    /// `when value { pattern => { ... } }`, whose arm bodies are always blocks.
+    When { value: Expression, arms: Vec<Located<MatchArm>> }
+}
+
+/// `except (exception name) { ... }`, where the binding name is optional. `family` is set for
+/// `except (Exception! name)`, which also catches every exception derived from `exception`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Handler {
+    pub exception: Ident,
+    pub family: bool,
+    pub name: Option<Ident>,
+    pub body: Block
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    Constant(Constant),
+    Ident(String),
+    List(Vec<Expression>),
+    /// `{key: value, ...}`, or `{:}` for an empty dict. `{}` given to a variable declared as a dict
+    /// is parsed as an empty block, which the compiler turns into an empty dict.
+    Dict(Vec<(Expression, Expression)>),
+    /// `{value, ...}`. As with a dict, `{}` given to a variable declared as a set is an empty set.
+    Set(Vec<Expression>),
+    /// `(value, ...)`, also what `return a, b;` returns.
+    Tuple(Vec<Expression>),
+    Call { function: Box<Expression>, arguments: Vec<Expression> },
+    MethodCall { object: Box<Expression>, method: Ident, arguments: Vec<Expression> },
+    Attribute { object: Box<Expression>, attribute: Ident },
+    Index { object: Box<Expression>, index: Box<Expression> },
+    Slice { object: Box<Expression>, start: Option<Box<Expression>>, end: Option<Box<Expression>> },
+    Unary { operator: LBOperator, operand: Box<Expression> },
+    Binary { operator: LBOperator, left: Box<Expression>, right: Box<Expression> },
+    Cast { value: Box<Expression>, type_name: Located<TypeName> },
+    /// `start to end by step`
+    Range { start: Box<Expression>, end: Box<Expression>, step: Option<Box<Expression>> },
+    Lambda { parameters: Vec<Located<Parameter>>, body: Box<LambdaBody> },
+    /// `function -> group`
+    Groupment { function: Box<Expression>, group: Box<Expression> },
+    /// `Enum::Variant`, called like a function to give the variant its payload.
+    Variant { enum_name: Ident, variant: Ident },
+    Match { value: Box<Expression>, arms: Vec<Located<MatchArm>> },
+    /// `guard { condition => exit, ..., else => value }`. Evaluates to `otherwise`, or null without
+    /// one, unless an arm leaves the function first.
+    Guard { arms: Vec<Located<GuardArm>>, otherwise: Option<Box<LambdaBody>> },
+    /// `{ statements }` as a value. The statements run when the block is executed, in the frame
+    /// of the function that created it.
+    Block(Block)
+}
+
+/// `condition => exit`. `return value` and `throw value` exits are parsed into a block of that
+/// one statement, so the compiler can check that every exit leaves the function.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GuardArm {
+    pub condition: Expression,
+    pub exit: Block
+}
+
+/// `pattern if guard => body`
+#[derive(Debug, Clone, PartialEq)]
+pub struct MatchArm {
+    pub pattern: Located<Pattern>,
+    pub guard: Option<Expression>,
+    pub body: LambdaBody
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Pattern {
+    /// `_`
+    Wildcard,
+    /// A name without a payload: a unit variant when an enum declares one of that name, otherwise
+    /// a variable bound to the value. The compiler decides which.
+    Name(String),
+    Constant(Constant),
+    /// `Variant(patterns)` or `Enum::Variant(patterns)`. `fields` is `None` when no parentheses follow.
+    Variant { enum_name: Option<Ident>, variant: Ident, fields: Option<Vec<Located<Pattern>>> }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum LambdaBody {
+    Expression(Expression),
+    Block(Block)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Constant {
+    /// Integer literals carry the type picked from their suffix and length, see `constant_type`.
+    Integer(i128, LeBlancType),
+    Decimal(f64, LeBlancType),
+    /// A `bigint` literal, written with an `n` suffix.
+    BigInt(BigInt),
+    /// A `decimal` literal, written with a `d` suffix. It keeps every digit as written.
+    BigDecimal(LeblancDecimal),
+    String(String),
+    Char(char),
+    Boolean(bool),
+    Null,
+    /// A tuple or set literal whose values are all constants, stored as one constant.
+    Tuple(Vec<Constant>),
+    Set(Vec<Constant>)
+}
+
+impl Constant {
+    pub fn leblanc_type(&self) -> LeBlancType {
+        match self {
+            Constant::Integer(_, typing) => *typing,
+            Constant::Decimal(_, typing) => *typing,
+            Constant::BigInt(_) => LeBlancType::BigInt,
+            Constant::BigDecimal(_) => LeBlancType::Decimal,
+            Constant::String(_) => LeBlancType::String,
+            Constant::Char(_) => LeBlancType::Char,
+            Constant::Boolean(_) => LeBlancType::Boolean,
+            Constant::Null => LeBlancType::Null,
+            Constant::Tuple(_) => LeBlancType::Derived(DerivedType::Tuple),
+            Constant::Set(_) => LeBlancType::Derived(DerivedType::Set)
+        }
+    }
+}
+
+impl Display for Constant {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Constant::Integer(value, _) => write!(f, "{}", value),
+            Constant::Decimal(value, _) => write!(f, "{}", value),
+            Constant::BigInt(value) => write!(f, "{}n", value),
+            Constant::BigDecimal(value) => write!(f, "{}d", value),
+            Constant::String(value) => write!(f, "{:?}", value),
+            Constant::Char(value) => write!(f, "{:?}", value),
+            Constant::Boolean(value) => write!(f, "{}", value),
+            Constant::Null => write!(f, "null"),
+            Constant::Tuple(values) if values.len() == 1 => write!(f, "({},)", values[0]),
+            Constant::Tuple(values) => write!(f, "({})", values.iter().map(|value| value.to_string()).collect::<Vec<String>>().join(", ")),
+            Constant::Set(values) => write!(f, "{{{}}}", values.iter().map(|value| value.to_string()).collect::<Vec<String>>().join(", "))
+        }
+    }
+}
@@ -0,0 +1,153 @@
+use fxhash::FxHashSet;
+
+use crate::leblanc::compiler::compiler_rewrite::ast::{Block, Expr, Expression, LambdaBody, MatchArm, Located, Stmt, Statement};
+
+/// The names the lambdas inside a function body refer to, other than their own parameters. The
+/// function keeps its variables with these names in upvalues, so the lambdas can share them.
+///
+/// This errs on the side of capturing: a name is captured even where the lambda declares its own
+/// variable of that name, which only costs an upvalue the lambda never touches.
+pub fn captured_names(statements: &[Statement]) -> FxHashSet<String> {
+    let mut captures = Captures::default();
+    captures.statements(statements);
+    captures.names
+}
+
+/// `captured_names` for the body of a lambda whose body is a single expression.
+pub fn captured_in_expression(expression: &Expression) -> FxHashSet<String> {
+    let mut captures = Captures::default();
+    captures.expression(expression);
+    captures.names
+}
+
+/// Whether a function body yields, which makes the function a generator. A `yield` inside one of
+/// its lambdas belongs to that lambda instead.
+pub fn is_generator(statements: &[Statement]) -> bool {
+    let mut captures = Captures::default();
+    captures.statements(statements);
+    captures.yields
+}
+
+#[derive(Default)]
+struct Captures {
+    names: FxHashSet<String>,
+    /// The parameters of each lambda being walked, outermost first.
+    lambdas: Vec<Vec<String>>,
+    yields: bool
+}
+
+impl Captures {
+    fn reference(&mut self, name: &str) {
+        if self.lambdas.is_empty() || self.lambdas.iter().any(|parameters| parameters.iter().any(|parameter| parameter == name)) { return; }
+        self.names.insert(name.to_string());
+    }
+
+    fn block(&mut self, block: &Block) {
+        self.statements(&block.data);
+    }
+
+    fn statements(&mut self, statements: &[Statement]) {
+        statements.iter().for_each(|statement| self.statement(statement));
+    }
+
+    fn statement(&mut self, statement: &Statement) {
+        match &statement.data {
+            Stmt::Declaration { value, .. } => if let Some(value) = value { self.expression(value); }
+            Stmt::Destructuring { value, .. } => self.expression(value),
+            Stmt::Assignment { target, value, .. } => {
+                self.expression(target);
+                self.expression(value);
+            }
+            Stmt::Expression(expression) | Stmt::Throw(expression) | Stmt::Return(Some(expression)) => self.expression(expression),
+            Stmt::Yield(expression) => {
+                if self.lambdas.is_empty() { self.yields = true; }
+                self.expression(expression);
+            }
+            Stmt::Return(None) => {}
+            Stmt::Conditional { branches, otherwise } => {
+                for (condition, body) in branches {
+                    self.expression(condition);
+                    self.block(body);
+                }
+                if let Some(otherwise) = otherwise { self.block(otherwise); }
+            }
+            Stmt::While { condition, body } => {
+                self.expression(condition);
+                self.block(body);
+            }
+            Stmt::For { iterable, body, .. } => {
+                self.expression(iterable);
+                self.block(body);
+            }
+            Stmt::Try { body, handlers, finally } => {
+                self.block(body);
+                handlers.iter().for_each(|handler| self.block(&handler.data.body));
+                if let Some(finally) = finally { self.block(finally); }
+            }
+            Stmt::When { value, arms } => self.arms(value, arms)
+        }
+    }
+
+    fn expression(&mut self, expression: &Expression) {
+        match &expression.data {
+            Expr::Ident(name) => self.reference(name),
+            Expr::Constant(_) | Expr::Variant { .. } => {}
+            Expr::List(items) | Expr::Set(items) | Expr::Tuple(items) => items.iter().for_each(|item| self.expression(item)),
+            Expr::Dict(entries) => entries.iter().for_each(|(key, value)| {
+                self.expression(key);
+                self.expression(value);
+            }),
+            Expr::Call { function, arguments } => {
+                self.expression(function);
+                arguments.iter().for_each(|argument| self.expression(argument));
+            }
+            Expr::MethodCall { object, arguments, .. } => {
+                self.expression(object);
+                arguments.iter().for_each(|argument| self.expression(argument));
+            }
+            Expr::Attribute { object, .. } | Expr::Unary { operand: object, .. } | Expr::Cast { value: object, .. } => self.expression(object),
+            Expr::Index { object, index } | Expr::Binary { left: object, right: index, .. } | Expr::Groupment { function: object, group: index } => {
+                self.expression(object);
+                self.expression(index);
+            }
+            Expr::Slice { object, start, end } => {
+                self.expression(object);
+                start.iter().chain(end.iter()).for_each(|bound| self.expression(bound));
+            }
+            Expr::Range { start, end, step } => {
+                self.expression(start);
+                self.expression(end);
+                if let Some(step) = step { self.expression(step); }
+            }
+            Expr::Lambda { parameters, body } => {
+                self.lambdas.push(parameters.iter().map(|parameter| parameter.data.name.data.clone()).collect());
+                self.body(body);
+                self.lambdas.pop();
+            }
+            Expr::Match { value, arms } => self.arms(value, arms),
+            Expr::Guard { arms, otherwise } => {
+                for arm in arms {
+                    self.expression(&arm.data.condition);
+                    self.block(&arm.data.exit);
+                }
+                if let Some(otherwise) = otherwise { self.body(otherwise); }
+            }
+            Expr::Block(body) => self.block(body)
+        }
+    }
+
+    fn body(&mut self, body: &LambdaBody) {
+        match body {
+            LambdaBody::Expression(body) => self.expression(body),
+            LambdaBody::Block(body) => self.block(body)
+        }
+    }
+
+    fn arms(&mut self, value: &Expression, arms: &[Located<MatchArm>]) {
+        self.expression(value);
+        for arm in arms {
+            if let Some(guard) = &arm.data.guard { self.expression(guard); }
+            self.body(&arm.data.body);
+        }
+    }
+}
@@ -0,0 +1,2919 @@
+use crate::leblanc::compiler::compile_types::partial_function::PartialFunction;
+use std::fs;
+use fxhash::{FxHashMap, FxHashSet};
+use num::BigInt;
+use std::path::Path;
+
+use crate::leblanc::compiler::compiler_rewrite::captures::{captured_in_expression, captured_names, is_generator};
+use crate::leblanc::compiler::compiler_rewrite::ast::{Block, Class, Constant, Enum, Expr, Expression, Field, Extension, File, Function, GuardArm, Handler, Ident, Import, Item, LambdaBody, Located, Location, MatchArm, Parameter, Pattern, Stmt, Statement, Trait, TypeName};
+use crate::leblanc::compiler::diagnostic::{similar_name, Diagnostic, DiagnosticCode};
+use crate::leblanc::compiler::lang::leblanc_operators::LBOperator;
+use crate::leblanc::core::bytecode::class_bytes::ClassBytecode;
+use crate::leblanc::compiler::compiler_rewrite::parser::parse;
+use crate::leblanc::compiler::compiler_rewrite::peephole;
+use crate::leblanc::core::bytecode::exception_table::ExceptionHandler;
+use crate::leblanc::core::bytecode::extension_bytes::ExtensionBytecode;
+use crate::leblanc::core::bytecode::file_body::FileBodyBytecode;
+use crate::leblanc::core::bytecode::file_header::FileHeaderBytecode;
+use crate::leblanc::core::bytecode::function_bytes::FunctionBytecode;
+use crate::leblanc::core::bytecode::instruction_line_bytes::InstructionBytecode;
+use crate::leblanc::core::bytecode::{LeblancBytecode, ToBytecode};
+use crate::leblanc::core::internal::methods::builtins::builtin_debug::builtin_disassemble::{disassemble, Symbols};
+use crate::leblanc::core::internal::methods::builtins::create_partial_functions;
+use crate::leblanc::core::interpreter::instructions::{Instruction, InstructionBase};
+use crate::leblanc::core::interpreter::instructions::InstructionBase::*;
+use crate::leblanc::core::interpreter::jump_table::{JumpTable, TableKey};
+use crate::leblanc::core::leblanc_argument::LeBlancArgument;
+use crate::leblanc::core::native_types::base_type::{base_methods, native_methods};
+use crate::leblanc::core::native_types::error_type::{builtin_family, is_builtin_exception};
+use crate::leblanc::core::native_types::decimal_type::LeblancDecimal;
+use crate::leblanc::core::native_types::derived::DerivedType;
+use crate::leblanc::core::native_types::LeBlancType;
+use crate::leblanc::include::lib::get_core_modules;
+use crate::leblanc::rustblanc::hex::Hexadecimal;
+use crate::leblanc::rustblanc::Hexable;
+
+/// How many instructions, constants or local variables a function can have, as instructions
+/// address them with 16 bits.
+const ADDRESSABLE: usize = u16::MAX as usize + 1;
+
+/// Lowers a parsed file to bytecode, collecting every diagnostic rather than stopping at the first
+/// error. Warnings come back alongside the bytecode; any error means there is no bytecode.
+pub fn generate(file: &File, file_name: &str) -> Result<(Hexadecimal, Vec<Diagnostic>), Vec<Diagnostic>> {
+    let mut codegen = Codegen::new(file_name);
+    let (functions, classes, extensions) = codegen.file(file);
+    if codegen.diagnostics.iter().any(Diagnostic::is_error) {
+        return Err(codegen.diagnostics);
+    }
+
+    let mut header = FileHeaderBytecode::new();
+    header.set_file_name(&file_name.to_string());
+    for import in &codegen.imports {
+        header.add_import_name(import);
+    }
+
+    let mut body = FileBodyBytecode::new();
+    for function in functions {
+        body.add_function(function);
+    }
+    for class in classes {
+        body.add_class(class);
+    }
+    for extension in extensions {
+        body.add_extension(extension);
+    }
+
+    Ok((LeblancBytecode::new(header, body).generate(), codegen.diagnostics))
+}
+
+/// An entry of the global function table, mirroring the order `interpreter::load_globals` builds
+/// it in so that `LoadFunction` indices line up at runtime.
+struct Global {
+    name: String,
+    arguments: Vec<LeBlancArgument>,
+    /// Where the function is defined, for those defined in the file being compiled.
+    location: Option<Location>
+}
+
+impl Global {
+    fn from_partial(partial: PartialFunction) -> Global {
+        Global { name: partial.name, arguments: partial.args, location: None }
+    }
+
+    fn accepts(&self, count: usize) -> bool {
+        let required = self.arguments.iter().filter(|argument| argument.required).count();
+        let variable = self.arguments.iter().any(|argument| argument.variable);
+        count >= required && (variable || count <= self.arguments.len())
+    }
+}
+
+/// A class declared in the file. `fields` and `methods` include everything it inherits, so that
+/// members accessed through `self` can be checked without walking the supertypes again.
+struct ClassInfo {
+    name: Ident,
+    supertypes: Vec<usize>,
+    fields: Vec<Ident>,
+    /// Names and parameter counts, `self` excluded.
+    methods: Vec<(Ident, usize)>,
+    /// Global index of the object every instance is copied from.
+    prototype: u16,
+    /// Global index of the function running the field initializers the class declares, if any.
+    initializer: Option<u16>,
+    /// Global indices of the constructors, which are inherited when a class declares none,
+    /// along with their parameters.
+    constructors: Vec<(u16, Vec<Located<Parameter>>)>,
+    /// Global indices of the methods the class declares, constructors excluded, followed by the
+    /// default methods it takes from its traits.
+    own_methods: Vec<usize>,
+    own_fields: Vec<String>,
+    /// Every trait the class implements, directly, through a supertrait or through a supertype.
+    traits: Vec<usize>
+}
+
+/// A trait declared in the file. `required` and `defaults` include those of its supertraits, with
+/// the default methods a trait declares replacing inherited ones of the same name and arity.
+struct TraitInfo {
+    name: Ident,
+    /// Names and parameter counts of the methods implementing classes have to declare.
+    required: Vec<(Ident, usize)>,
+    /// Names, parameter counts and global indices of the default methods.
+    defaults: Vec<(Ident, usize, usize)>,
+    /// The trait itself and every trait it inherits from.
+    family: Vec<usize>
+}
+
+/// The trait the values of a variable declared `Name!`, or `List<Name!>`, must implement.
+#[derive(Clone, Copy)]
+enum TraitBound {
+    Value(usize),
+    Elements(usize)
+}
+
+/// What is known before a value runs about whether it implements a trait.
+enum Known {
+    Class(usize),
+    /// A value of a variable declared `Name!`.
+    Trait(usize),
+    /// A value of a type no class has, which can't implement a trait.
+    Native(LeBlancType),
+    Unknown
+}
+
+/// An enum declared in the file. Enums only exist at compile time: every value carries the names
+/// of its enum and variant, so nothing is added to the global table.
+struct EnumInfo {
+    name: Ident,
+    /// Names and payload sizes of the variants.
+    variants: Vec<(Ident, usize)>
+}
+
+/// An extension declared in the file or imported from another one.
+struct ExtensionInfo {
+    name: Ident,
+    target: LeBlancType,
+    properties: Vec<(Ident, LeBlancType)>,
+    /// Global index of the function setting the properties that have a default value.
+    initializer: Option<usize>,
+    /// Global indices of the methods, along with their names and parameter counts.
+    methods: Vec<(Ident, usize, usize)>,
+    /// The import that brought the extension in and the file it was declared in, for imported ones.
+    import: Option<(Ident, String)>,
+    /// Where the file an imported extension was declared in is, for tracebacks.
+    source: Option<String>
+}
+
+/// An extension declared in another file, parsed for `using ext name from file;`.
+struct ImportedExtension {
+    import: Ident,
+    path: String,
+    source: String,
+    extension: Extension
+}
+
+/// The class, trait or extension a method is compiled for.
+#[derive(Clone, Copy)]
+enum Owner {
+    Class(usize),
+    Trait(usize),
+    Extension(usize)
+}
+
+/// A function generated for a class or trait, in the order they are added to the global table.
+enum ClassFunction<'a> {
+    /// Called through the class name: copies the prototype, runs the field initializers of the
+    /// class and its ancestors, then the constructor.
+    Factory { class: usize, constructor: Option<(u16, Vec<Located<Parameter>>)> },
+    Initializer { class: usize, fields: Vec<&'a Located<Field>> },
+    Method { class: usize, function: &'a Function },
+    /// A default method of a trait, compiled once and shared by every class using it.
+    TraitMethod { trait_index: usize, function: &'a Function },
+    /// Sets the properties an extension declares with a value on an object of its target type.
+    ExtensionInitializer { extension: usize, fields: Vec<&'a Located<Field>> },
+    ExtensionMethod { extension: usize, function: &'a Function }
+}
+
+struct Codegen {
+    /// Path of the file being compiled, which extension imports are relative to.
+    path: String,
+    globals: Vec<Global>,
+    imports: Vec<String>,
+    /// Exception classes declared in the file along with their ancestors, nearest first.
+    exceptions: Vec<(String, Vec<String>)>,
+    /// Every other class declared in the file, supertypes before the classes extending them.
+    classes: Vec<ClassInfo>,
+    /// Every trait declared in the file, supertraits before the traits extending them.
+    traits: Vec<TraitInfo>,
+    enums: Vec<EnumInfo>,
+    /// The extensions declared in the file followed by the imported ones.
+    extensions: Vec<ExtensionInfo>,
+    lambdas: Vec<FunctionBytecode>,
+    diagnostics: Vec<Diagnostic>
+}
+
+impl Codegen {
+    fn new(path: &str) -> Codegen {
+        Codegen {
+            path: path.to_string(),
+            globals: create_partial_functions().into_iter().map(Global::from_partial).collect(),
+            imports: vec![],
+            exceptions: vec![],
+            classes: vec![],
+            traits: vec![],
+            enums: vec![],
+            extensions: vec![],
+            lambdas: vec![],
+            diagnostics: vec![]
+        }
+    }
+
+    /// Optimizes the code of a finished function and serializes it, printing it before and after
+    /// when bytecode is being dumped. A function outgrowing the 16 bit indices of the bytecode is
+    /// reported instead.
+    fn finish(&mut self, mut state: FunctionState) -> FunctionBytecode {
+        if state.instructions.len() > ADDRESSABLE {
+            state.outgrown.get_or_insert("instructions");
+        }
+        if state.outgrown.is_none() {
+            self.dump(&state, "before");
+            peephole::optimize(&mut state.instructions, &mut state.constants, &mut state.handlers, &mut state.tables);
+            self.dump(&state, "after");
+            if state.constants.len() + state.tables.len() > ADDRESSABLE {
+                state.outgrown = Some("constants");
+            }
+        }
+        if let Some(space) = state.outgrown {
+            let message = format!("function '{}' needs more than {} {}", state.name, ADDRESSABLE, space);
+            self.error(DiagnosticCode::FunctionTooLarge, message, state.location);
+            return FunctionBytecode::new();
+        }
+        state.bytecode()
+    }
+
+    fn dump(&self, state: &FunctionState, stage: &str) {
+        if !peephole::dumping() { return; }
+        let listing = Listing { state, globals: &self.globals };
+        eprintln!("Bytecode of {} {} optimization:\n{}", state.name, stage, disassemble(&state.instructions, &state.handlers, &listing));
+    }
+
+    fn error(&mut self, code: DiagnosticCode, message: String, location: Location) {
+        self.diagnostics.push(Diagnostic::new(code, message, location));
+    }
+
+    fn file(&mut self, file: &File) -> (Vec<FunctionBytecode>, Vec<ClassBytecode>, Vec<ExtensionBytecode>) {
+        let core_modules = get_core_modules();
+        for item in &file.items {
+            match &item.data {
+                Item::Import(Import::Module(name)) => {
+                    if self.imports.contains(&name.data) { continue; }
+                    match core_modules.iter().find(|module| module.name == name.data) {
+                        Some(module) => {
+                            self.imports.push(name.data.clone());
+                            self.globals.extend(module.methods_as_partials().into_iter().map(Global::from_partial));
+                        }
+                        None => self.error(DiagnosticCode::UnknownModule, format!("unknown module '{}'", name.data), name.location)
+                    }
+                }
+                Item::Import(Import::Extension { .. }) | Item::Class(_) | Item::Trait(_) | Item::Enum(_) | Item::Extension(_) | Item::Function(_) => {}
+            }
+        }
+
+        let classes = file.items.iter().filter_map(|item| match &item.data {
+            Item::Class(class) => Some(class),
+            _ => None
+        }).collect::<Vec<&Class>>();
+        self.exception_classes(&classes);
+        let traits = file.items.iter().filter_map(|item| match &item.data {
+            Item::Trait(trait_item) => Some(trait_item),
+            _ => None
+        }).collect::<Vec<&Trait>>();
+        let traits = self.trait_order(&traits, &classes);
+        let classes = self.class_order(&classes, &traits);
+        let enums = file.items.iter().filter_map(|item| match &item.data {
+            Item::Enum(enum_item) => Some(enum_item),
+            _ => None
+        }).collect::<Vec<&Enum>>();
+        self.register_enums(&enums, &classes, &traits);
+        for class in &classes {
+            self.globals.push(Global { name: format!("class {}", class.name.data), arguments: vec![], location: Some(class.name.location) });
+        }
+
+        let functions = file.items.iter().filter_map(|item| match &item.data {
+            Item::Function(function) => Some(function),
+            _ => None
+        }).collect::<Vec<&Function>>();
+
+        let first_function = self.globals.len();
+        for function in &functions {
+            let arity = function.parameters.len();
+            let duplicate = self.globals[first_function..].iter()
+                .find(|global| global.name == function.name.data && global.arguments.len() == arity)
+                .and_then(|global| global.location);
+            if let Some(previous) = duplicate {
+                let message = format!("function '{}' is already defined with {} parameter(s)", function.name.data, arity);
+                self.diagnostics.push(Diagnostic::new(DiagnosticCode::DuplicateFunction, message, function.name.location)
+                    .with_label(previous, "first defined here"));
+            }
+            if function.body.is_none() {
+                self.error(DiagnosticCode::MissingBody, format!("function '{}' has no body", function.name.data), function.name.location);
+            }
+            let types = function.parameters.iter().map(|parameter| parameter.data.type_name.data.leblanc_type()).collect::<Vec<LeBlancType>>();
+            self.globals.push(Global { name: function.name.data.clone(), arguments: LeBlancArgument::from_positional(&types), location: Some(function.name.location) });
+        }
+        let imported = self.import_extensions(file);
+        let mut extensions = file.items.iter().filter_map(|item| match &item.data {
+            Item::Extension(extension) => Some((extension, None)),
+            _ => None
+        }).collect::<Vec<(&Extension, Option<&ImportedExtension>)>>();
+        extensions.extend(imported.iter().map(|imported| (&imported.extension, Some(imported))));
+
+        let mut class_functions = self.register_traits(&traits);
+        class_functions.append(&mut self.register_classes(&classes, first_function));
+        class_functions.append(&mut self.register_extensions(&extensions));
+
+        let mut bytecode = vec![];
+        for function in functions {
+            let mut state = FunctionState::new(function.name.data.clone(), &function.parameters, function.name.location);
+            self.parameters(&mut state, &function.parameters);
+            if let Some(body) = &function.body {
+                self.body(&mut state, body);
+            }
+            self.unused_variables(&state);
+            bytecode.push(self.finish(state));
+        }
+        for function in class_functions {
+            bytecode.push(self.class_function(function));
+        }
+        bytecode.append(&mut self.lambdas);
+
+        let classes = self.classes.iter().map(|class| {
+            let mut bytes = ClassBytecode::new();
+            bytes.set_name(class.name.data.clone());
+            for supertype in &class.supertypes {
+                bytes.add_supertype(self.classes[*supertype].name.data.clone());
+            }
+            for field in &class.own_fields {
+                bytes.add_field(field.clone());
+            }
+            // Default methods are stored with the class, so traits don't need to exist at runtime.
+            for method in &class.own_methods {
+                bytes.add_method((method - first_function) as u32);
+            }
+            bytes
+        }).collect();
+        let extensions = self.extensions.iter().map(|extension| {
+            let mut bytes = ExtensionBytecode::new();
+            bytes.set_name(extension.name.data.clone());
+            bytes.set_target(extension.target);
+            bytes.set_source(extension.source.clone().unwrap_or_default());
+            for (property, typing) in &extension.properties {
+                bytes.add_property(property.data.clone(), *typing);
+            }
+            bytes.set_initializer(extension.initializer.map(|initializer| (initializer - first_function) as u32));
+            for (_, _, method) in &extension.methods {
+                bytes.add_reference_method((method - first_function) as u32);
+            }
+            bytes
+        }).collect();
+        (bytecode, classes, extensions)
+    }
+
+    /// Registers every class that extends an exception, directly or through another class in the
+    /// file. Every exception already has a `message` field, so they cannot declare their own.
+    fn exception_classes(&mut self, classes: &[&Class]) {
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for class in classes {
+                if self.exception_family(&class.name.data).is_some() { continue; }
+                let parent = class.supertypes.iter().find_map(|supertype| {
+                    self.exception_family(&supertype.data).map(|family| (supertype.data.clone(), family))
+                });
+                if let Some((parent, mut family)) = parent {
+                    family.insert(0, parent);
+                    self.exceptions.push((class.name.data.clone(), family));
+                    changed = true;
+                }
+            }
+        }
+
+        for class in classes {
+            let name = &class.name;
+            if is_builtin_exception(&name.data) {
+                self.error(DiagnosticCode::BuiltinRedeclared, format!("cannot declare class '{}': it is a built-in exception", name.data), name.location);
+            } else if self.exception_family(&name.data).is_some() {
+                if let Some(field) = class.fields.iter().find(|field| field.data.name.data == "message") {
+                    self.error(DiagnosticCode::DuplicateMember, format!("exception class '{}' cannot declare a field named 'message': every exception already has one", name.data), field.data.name.location);
+                }
+            }
+        }
+    }
+
+    /// The ancestors of a built-in or declared exception, or `None` if `name` isn't an exception.
+    fn exception_family(&self, name: &str) -> Option<Vec<String>> {
+        if is_builtin_exception(name) {
+            return Some(builtin_family(name));
+        }
+        self.exceptions.iter().find(|(exception, _)| exception == name).map(|(_, family)| family.clone())
+    }
+
+    /// Orders the classes so that every class comes after its supertypes, reporting duplicate names,
+    /// unknown supertypes and circular inheritance along the way. Built-in exceptions aren't classes
+    /// of the file, so they are left out of the order.
+    fn class_order<'a>(&mut self, classes: &[&'a Class], traits: &[&Trait]) -> Vec<&'a Class> {
+        let mut declared: Vec<&Class> = vec![];
+        for class in classes {
+            let name = &class.name;
+            if is_builtin_exception(&name.data) { continue; }
+            if let Some(previous) = declared.iter().find(|declared| declared.name.data == name.data) {
+                self.diagnostics.push(Diagnostic::new(DiagnosticCode::DuplicateFunction, format!("class '{}' is already defined", name.data), name.location)
+                    .with_label(previous.name.location, "first defined here"));
+                continue;
+            }
+            declared.push(class);
+        }
+
+        for class in &declared {
+            for supertype in &class.supertypes {
+                if declared.iter().any(|declared| declared.name.data == supertype.data) { continue; }
+                if traits.iter().any(|trait_item| trait_item.name.data == supertype.data) { continue; }
+                if is_builtin_exception(&supertype.data) { continue; }
+                let mut diagnostic = Diagnostic::new(DiagnosticCode::UnknownClass, format!("unknown class '{}'", supertype.data), supertype.location);
+                let names = declared.iter().map(|declared| declared.name.data.as_str()).chain(traits.iter().map(|trait_item| trait_item.name.data.as_str()));
+                if let Some(similar) = similar_name(&supertype.data, names) {
+                    diagnostic = diagnostic.with_note(format!("there's a similar class or trait named '{}'", similar));
+                }
+                self.diagnostics.push(diagnostic);
+            }
+        }
+
+        self.inheritance_order(declared, |class| (&class.name, &class.supertypes), "class")
+    }
+
+    /// Orders the traits so that every trait comes after its supertraits, reporting duplicate names,
+    /// supertypes that aren't traits and circular inheritance.
+    fn trait_order<'a>(&mut self, traits: &[&'a Trait], classes: &[&Class]) -> Vec<&'a Trait> {
+        let mut declared: Vec<&Trait> = vec![];
+        for trait_item in traits {
+            let name = &trait_item.name;
+            let previous = declared.iter().map(|declared| &declared.name).chain(classes.iter().map(|class| &class.name))
+                .find(|declared| declared.data == name.data)
+                .map(|declared| declared.location);
+            if let Some(previous) = previous {
+                self.diagnostics.push(Diagnostic::new(DiagnosticCode::DuplicateFunction, format!("'{}' is already defined", name.data), name.location)
+                    .with_label(previous, "first defined here"));
+                continue;
+            }
+            declared.push(trait_item);
+        }
+
+        for trait_item in &declared {
+            for supertype in &trait_item.supertypes {
+                if declared.iter().any(|declared| declared.name.data == supertype.data) { continue; }
+                let diagnostic = match classes.iter().any(|class| class.name.data == supertype.data) || is_builtin_exception(&supertype.data) {
+                    true => Diagnostic::new(DiagnosticCode::InvalidInheritance, format!("trait '{}' can only extend traits, '{}' is a class", trait_item.name.data, supertype.data), supertype.location),
+                    false => {
+                        let mut diagnostic = Diagnostic::new(DiagnosticCode::UnknownClass, format!("unknown trait '{}'", supertype.data), supertype.location);
+                        if let Some(similar) = similar_name(&supertype.data, declared.iter().map(|declared| declared.name.data.as_str())) {
+                            diagnostic = diagnostic.with_note(format!("there's a similar trait named '{}'", similar));
+                        }
+                        diagnostic
+                    }
+                };
+                self.diagnostics.push(diagnostic);
+            }
+        }
+
+        self.inheritance_order(declared, |trait_item| (&trait_item.name, &trait_item.supertypes), "trait")
+    }
+
+    /// Records the variants of every enum, reporting enums named like another enum, class or trait
+    /// and variants declared twice.
+    fn register_enums(&mut self, enums: &[&Enum], classes: &[&Class], traits: &[&Trait]) {
+        for enum_item in enums {
+            let name = &enum_item.name;
+            let previous = self.enums.iter().map(|declared| &declared.name)
+                .chain(classes.iter().map(|class| &class.name))
+                .chain(traits.iter().map(|trait_item| &trait_item.name))
+                .find(|declared| declared.data == name.data)
+                .map(|declared| declared.location);
+            if let Some(previous) = previous {
+                self.diagnostics.push(Diagnostic::new(DiagnosticCode::DuplicateFunction, format!("'{}' is already defined", name.data), name.location)
+                    .with_label(previous, "first defined here"));
+                continue;
+            }
+
+            let mut variants: Vec<(Ident, usize)> = vec![];
+            for variant in &enum_item.variants {
+                let variant_name = &variant.data.name;
+                if let Some((previous, _)) = variants.iter().find(|(declared, _)| declared.data == variant_name.data) {
+                    let message = format!("variant '{}' is already declared in enum '{}'", variant_name.data, name.data);
+                    self.diagnostics.push(Diagnostic::new(DiagnosticCode::DuplicateMember, message, variant_name.location)
+                        .with_label(previous.location, "first declared here"));
+                    continue;
+                }
+                variants.push((variant_name.clone(), variant.data.fields.len()));
+            }
+            self.enums.push(EnumInfo { name: name.clone(), variants });
+        }
+    }
+
+    /// Sorts `declared` so that every item comes after the items it names as supertypes, reporting
+    /// the items that inherit from themselves. Supertypes that aren't in `declared` are ignored.
+    fn inheritance_order<'a, T>(&mut self, mut declared: Vec<&'a T>, parts: fn(&T) -> (&Ident, &Vec<Ident>), kind: &str) -> Vec<&'a T> {
+        let known = declared.iter().map(|item| parts(item).0.data.clone()).collect::<Vec<String>>();
+        let mut ordered: Vec<&T> = vec![];
+        while !declared.is_empty() {
+            let (ready, blocked): (Vec<&T>, Vec<&T>) = declared.into_iter().partition(|item| parts(item).1.iter().all(|supertype| {
+                !known.contains(&supertype.data) || ordered.iter().any(|ordered| parts(ordered).0.data == supertype.data)
+            }));
+            if ready.is_empty() {
+                for item in blocked {
+                    let name = parts(item).0;
+                    self.error(DiagnosticCode::InvalidInheritance, format!("{} '{}' inherits from itself", kind, name.data), name.location);
+                }
+                break;
+            }
+            ordered.extend(ready);
+            declared = blocked;
+        }
+        ordered
+    }
+
+    /// Adds the default methods of every trait to the global table and returns them in that order.
+    /// Requirements and defaults are inherited from supertraits, so classes only look at the traits
+    /// they name directly.
+    fn register_traits<'a>(&mut self, traits: &[&'a Trait]) -> Vec<ClassFunction<'a>> {
+        let mut functions = vec![];
+        for trait_item in traits {
+            let name = &trait_item.name;
+            let mut required: Vec<(Ident, usize)> = vec![];
+            let mut defaults: Vec<(Ident, usize, usize)> = vec![];
+            let mut family = vec![self.traits.len()];
+            for supertype in &trait_item.supertypes {
+                if let Some(inherited) = self.traits.iter().find(|declared| declared.name.data == supertype.data) {
+                    family.extend(inherited.family.iter().copied());
+                    required.extend(inherited.required.iter().cloned());
+                    for default in &inherited.defaults {
+                        if !defaults.iter().any(|(known, known_arity, _)| known.data == default.0.data && *known_arity == default.1) {
+                            defaults.push(default.clone());
+                        }
+                    }
+                }
+            }
+
+            for method in &trait_item.methods {
+                let function = &method.data.function;
+                let method_name = &function.name;
+                let parameters = receiver_parameters(function);
+                let arity = parameters.len();
+                let duplicate = trait_item.methods.iter().take_while(|other| !std::ptr::eq(*other, method))
+                    .find(|other| other.data.function.name.data == method_name.data && receiver_parameters(&other.data.function).len() == arity);
+                if let Some(previous) = duplicate {
+                    self.diagnostics.push(Diagnostic::new(DiagnosticCode::DuplicateMember, format!("method '{}' is already defined in trait '{}' with {} parameter(s)", method_name.data, name.data, arity), method_name.location)
+                        .with_label(previous.data.function.name.location, "first defined here"));
+                    continue;
+                }
+
+                match (method.data.auto, &function.body) {
+                    (false, None) => required.push((method_name.clone(), arity)),
+                    (false, Some(_)) => {
+                        self.diagnostics.push(Diagnostic::new(DiagnosticCode::MissingBody, format!("method '{}' of trait '{}' has a body but isn't marked `auto`", method_name.data, name.data), method_name.location)
+                            .with_note("mark it `auto` to make it a default implementation, or remove the body to make it required".to_string()));
+                    }
+                    (true, None) => self.error(DiagnosticCode::MissingBody, format!("default method '{}' of trait '{}' has no body", method_name.data, name.data), method_name.location),
+                    (true, Some(_)) => {
+                        let mut types = vec![LeBlancType::SelfType];
+                        types.extend(parameters.iter().map(|parameter| parameter.data.type_name.data.leblanc_type()));
+                        self.globals.push(Global { name: format!("{}.{}", name.data, method_name.data), arguments: LeBlancArgument::from_positional(&types), location: Some(method_name.location) });
+                        defaults.retain(|(known, known_arity, _)| known.data != method_name.data || *known_arity != arity);
+                        defaults.push((method_name.clone(), arity, self.globals.len() - 1));
+                        functions.push(ClassFunction::TraitMethod { trait_index: self.traits.len(), function });
+                    }
+                }
+            }
+            family.sort_unstable();
+            family.dedup();
+            self.traits.push(TraitInfo { name: name.clone(), required, defaults, family });
+        }
+        functions
+    }
+
+    /// Adds the factories, field initializers and methods of every class to the global table and
+    /// returns them in that order, to be compiled once every function of the file is known.
+    fn register_classes<'a>(&mut self, classes: &[&'a Class], first_function: usize) -> Vec<ClassFunction<'a>> {
+        let mut functions = vec![];
+        for (index, class) in classes.iter().enumerate() {
+            let name = &class.name;
+            let supertypes = class.supertypes.iter()
+                .filter_map(|supertype| self.classes.iter().position(|declared| declared.name.data == supertype.data))
+                .collect::<Vec<usize>>();
+
+            let mut fields: Vec<Ident> = vec![];
+            let mut methods: Vec<(Ident, usize)> = vec![];
+            for supertype in &supertypes {
+                let inherited = &self.classes[*supertype];
+                for field in &inherited.fields {
+                    if !fields.iter().any(|known| known.data == field.data) {
+                        fields.push(field.clone());
+                    }
+                }
+                methods.extend(inherited.methods.iter().cloned());
+            }
+
+            let mut own_fields = vec![];
+            for field in &class.fields {
+                let field_name = &field.data.name;
+                if let Some(previous) = fields.iter().find(|known| known.data == field_name.data) {
+                    self.diagnostics.push(Diagnostic::new(DiagnosticCode::DuplicateMember, format!("field '{}' is already declared in class '{}' or one of its supertypes", field_name.data, name.data), field_name.location)
+                        .with_label(previous.location, "first declared here"));
+                    continue;
+                }
+                fields.push(field_name.clone());
+                own_fields.push(field_name.data.clone());
+            }
+
+            let prototype = (first_function - classes.len() + index) as u16;
+            let initialized = class.fields.iter().filter(|field| field.data.value.is_some()).collect::<Vec<&Located<Field>>>();
+            let initializer = (!initialized.is_empty()).then(|| {
+                self.globals.push(Global { name: format!("<fields:{}>", name.data), arguments: LeBlancArgument::from_positional(&[LeBlancType::SelfType]), location: Some(name.location) });
+                functions.push(ClassFunction::Initializer { class: index, fields: initialized });
+                (self.globals.len() - 1) as u16
+            });
+
+            let mut own_methods = vec![];
+            let mut constructors = vec![];
+            for method in &class.methods {
+                let function = &method.data;
+                let method_name = &function.name;
+                let parameters = receiver_parameters(function);
+                let arity = parameters.len();
+                let duplicate = class.methods.iter().take_while(|other| !std::ptr::eq(*other, method))
+                    .find(|other| other.data.name.data == method_name.data && receiver_parameters(&other.data).len() == arity);
+                if let Some(previous) = duplicate {
+                    self.diagnostics.push(Diagnostic::new(DiagnosticCode::DuplicateMember, format!("method '{}' is already defined in class '{}' with {} parameter(s)", method_name.data, name.data, arity), method_name.location)
+                        .with_label(previous.data.name.location, "first defined here"));
+                }
+                if let Some(field) = fields.iter().find(|field| field.data == method_name.data) {
+                    self.diagnostics.push(Diagnostic::new(DiagnosticCode::DuplicateMember, format!("method '{}' has the same name as a field of class '{}'", method_name.data, name.data), method_name.location)
+                        .with_label(field.location, "field declared here"));
+                }
+                if function.body.is_none() {
+                    self.error(DiagnosticCode::MissingBody, format!("method '{}' of class '{}' has no body", method_name.data, name.data), method_name.location);
+                }
+
+                let mut types = vec![LeBlancType::SelfType];
+                types.extend(parameters.iter().map(|parameter| parameter.data.type_name.data.leblanc_type()));
+                self.globals.push(Global { name: format!("{}.{}", name.data, method_name.data), arguments: LeBlancArgument::from_positional(&types), location: Some(method_name.location) });
+                let global = self.globals.len() - 1;
+                functions.push(ClassFunction::Method { class: index, function });
+                if method_name.data == name.data {
+                    constructors.push((global as u16, parameters.to_vec()));
+                } else {
+                    own_methods.push(global);
+                    methods.push((method_name.clone(), arity));
+                }
+            }
+            self.implement_traits(class, &mut methods, &mut own_methods);
+            if constructors.is_empty() {
+                if let Some(supertype) = supertypes.iter().find(|supertype| !self.classes[**supertype].constructors.is_empty()) {
+                    constructors = self.classes[*supertype].constructors.clone();
+                }
+            }
+
+            if let Some(function) = self.globals[first_function..].iter().find(|global| global.name == name.data) {
+                let message = format!("class '{}' has the same name as a function", name.data);
+                let mut diagnostic = Diagnostic::new(DiagnosticCode::DuplicateFunction, message, name.location);
+                if let Some(location) = function.location {
+                    diagnostic = diagnostic.with_label(location, "function defined here");
+                }
+                self.diagnostics.push(diagnostic);
+            }
+            // Exceptions are built where they are thrown, as a function returning one would raise it.
+            let factories = match constructors.is_empty() {
+                _ if self.exception_family(&name.data).is_some() => vec![],
+                true => vec![None],
+                false => constructors.iter().cloned().map(Some).collect()
+            };
+            for constructor in factories {
+                let types = constructor.iter().flat_map(|(_, parameters)| parameters).map(|parameter| parameter.data.type_name.data.leblanc_type()).collect::<Vec<LeBlancType>>();
+                self.globals.push(Global { name: name.data.clone(), arguments: LeBlancArgument::from_positional(&types), location: Some(name.location) });
+                functions.push(ClassFunction::Factory { class: index, constructor });
+            }
+
+            let mut traits = supertypes.iter().flat_map(|supertype| self.classes[*supertype].traits.iter().copied()).collect::<Vec<usize>>();
+            for supertype in &class.supertypes {
+                if let Some(implemented) = self.traits.iter().find(|declared| declared.name.data == supertype.data) {
+                    traits.extend(implemented.family.iter().copied());
+                }
+            }
+            traits.sort_unstable();
+            traits.dedup();
+            self.classes.push(ClassInfo { name: name.clone(), supertypes, fields, methods, prototype, initializer, constructors, own_methods, own_fields, traits });
+        }
+        functions
+    }
+
+    /// Adds the default methods of the traits `class` names that it doesn't already have, then
+    /// reports every required method that is still missing.
+    fn implement_traits(&mut self, class: &Class, methods: &mut Vec<(Ident, usize)>, own_methods: &mut Vec<usize>) {
+        let traits = class.supertypes.iter()
+            .filter_map(|supertype| self.traits.iter().position(|declared| declared.name.data == supertype.data))
+            .collect::<Vec<usize>>();
+        let implements = |methods: &[(Ident, usize)], name: &str, arity: usize| methods.iter().any(|(method, known)| method.data == name && *known == arity);
+
+        for trait_index in &traits {
+            for (method, arity, global) in &self.traits[*trait_index].defaults {
+                if implements(methods, &method.data, *arity) { continue; }
+                methods.push((method.clone(), *arity));
+                own_methods.push(*global);
+            }
+        }
+        for trait_index in traits {
+            let trait_info = &self.traits[trait_index];
+            let missing = trait_info.required.iter().filter(|(method, arity)| !implements(methods, &method.data, *arity));
+            let diagnostics = missing.map(|(method, arity)| {
+                let message = format!("class '{}' does not implement method '{}' ({} parameter(s)) required by trait '{}'", class.name.data, method.data, arity, trait_info.name.data);
+                Diagnostic::new(DiagnosticCode::MissingTraitMethod, message, class.name.location).with_label(method.location, "required here")
+            }).collect::<Vec<Diagnostic>>();
+            self.diagnostics.extend(diagnostics);
+        }
+    }
+
+    /// Parses the files named by `using ext name from file;`, relative to the file being compiled,
+    /// and returns the extensions they import. Extensions declared in the file itself are always
+    /// in use, so importing one of them does nothing.
+    fn import_extensions(&mut self, file: &File) -> Vec<ImportedExtension> {
+        let local = file.items.iter().filter_map(|item| match &item.data {
+            Item::Extension(extension) => Some(&extension.name),
+            _ => None
+        }).collect::<Vec<&Ident>>();
+        let current = Path::new(&self.path).to_path_buf();
+        let directory = current.parent().unwrap_or_else(|| Path::new("")).to_path_buf();
+
+        let mut imported: Vec<ImportedExtension> = vec![];
+        for item in &file.items {
+            let (name, path) = match &item.data {
+                Item::Import(Import::Extension { name, file }) => (name, file),
+                _ => continue
+            };
+            let resolved = directory.join(&path.data);
+            let same_file = match (resolved.canonicalize(), current.canonicalize()) {
+                (Ok(resolved), Ok(current)) => resolved == current,
+                _ => false
+            };
+            if same_file {
+                if !local.iter().any(|extension| extension.data == name.data) {
+                    self.unknown_extension(name, &path.data, local.iter().map(|extension| extension.data.as_str()));
+                }
+                continue;
+            }
+            if let Some(previous) = local.iter().find(|extension| extension.data == name.data) {
+                self.diagnostics.push(Diagnostic::new(DiagnosticCode::DuplicateFunction, format!("extension '{}' is already defined", name.data), name.location)
+                    .with_label(previous.location, "first defined here"));
+                continue;
+            }
+            if imported.iter().any(|extension| extension.import.data == name.data) { continue; }
+
+            let source = match fs::read_to_string(&resolved) {
+                Ok(source) => source,
+                Err(error) => {
+                    self.error(DiagnosticCode::UnknownExtension, format!("cannot import extension '{}': cannot read '{}': {}", name.data, path.data, error), path.location);
+                    continue;
+                }
+            };
+            let parsed = match parse(&source) {
+                Ok(parsed) => parsed,
+                Err(error) => {
+                    self.error(DiagnosticCode::UnknownExtension, format!("cannot import extension '{}': '{}' does not parse", name.data, path.data), path.location);
+                    self.diagnostics.last_mut().unwrap().notes.push(format!("{}:{}: {}", path.data, error.location, error.message));
+                    continue;
+                }
+            };
+            let extensions = parsed.items.into_iter().filter_map(|item| match item.data {
+                Item::Extension(extension) => Some(extension),
+                _ => None
+            }).collect::<Vec<Extension>>();
+            match extensions.iter().position(|extension| extension.name.data == name.data) {
+                Some(index) => imported.push(ImportedExtension { import: name.clone(), path: path.data.clone(), source: resolved.to_string_lossy().to_string(), extension: extensions.into_iter().nth(index).unwrap() }),
+                None => self.unknown_extension(name, &path.data, extensions.iter().map(|extension| extension.name.data.as_str()))
+            }
+        }
+        imported
+    }
+
+    fn unknown_extension<'a>(&mut self, name: &Ident, path: &str, declared: impl IntoIterator<Item=&'a str>) {
+        let mut diagnostic = Diagnostic::new(DiagnosticCode::UnknownExtension, format!("'{}' has no extension named '{}'", path, name.data), name.location);
+        if let Some(similar) = similar_name(&name.data, declared) {
+            diagnostic = diagnostic.with_note(format!("there's a similar extension named '{}'", similar));
+        }
+        self.diagnostics.push(diagnostic);
+    }
+
+    /// Moves the errors reported from `from` on, which point into the file an extension was
+    /// imported from, onto the import. Warnings about the other file are dropped.
+    fn relocate(&mut self, from: usize, import: &Ident, path: &str) {
+        let diagnostics = self.diagnostics.split_off(from);
+        for diagnostic in diagnostics.into_iter().filter(Diagnostic::is_error) {
+            let message = format!("in extension '{}' imported from '{}': {}", import.data, path, diagnostic.message);
+            self.diagnostics.push(Diagnostic::new(diagnostic.code, message, import.location)
+                .with_note(format!("{}:{}", path, diagnostic.location)));
+        }
+    }
+
+    /// Adds the property initializers and methods of every extension to the global table and
+    /// returns them in that order. Extensions can only add members a type doesn't already have.
+    fn register_extensions<'a>(&mut self, extensions: &[(&'a Extension, Option<&ImportedExtension>)]) -> Vec<ClassFunction<'a>> {
+        let mut functions = vec![];
+        for (extension, imported) in extensions {
+            let before = self.diagnostics.len();
+            let index = self.extensions.len();
+            let name = &extension.name;
+            let target = extension.target.data.leblanc_type();
+            if let LeBlancType::Class(_) = target {
+                self.error(DiagnosticCode::InvalidExtension, format!("cannot extend '{}': extensions can only extend built-in types", extension.target.data), extension.target.location);
+            }
+            if let Some(previous) = self.extensions.iter().find(|declared| declared.name.data == name.data) {
+                let location = previous.name.location;
+                self.diagnostics.push(Diagnostic::new(DiagnosticCode::DuplicateFunction, format!("extension '{}' is already defined", name.data), name.location)
+                    .with_label(location, "first defined here"));
+            }
+            let siblings = self.extensions.iter().filter(|declared| declared.target == target).collect::<Vec<&ExtensionInfo>>();
+
+            let mut properties: Vec<(Ident, LeBlancType)> = vec![];
+            for field in &extension.fields {
+                let property = &field.data.name;
+                if !field.data.property {
+                    self.diagnostics.push(Diagnostic::new(DiagnosticCode::InvalidExtension, format!("field '{}' of extension '{}' must be declared as a `property`", property.data, name.data), property.location));
+                }
+                let previous = properties.iter().map(|(known, _)| known)
+                    .chain(siblings.iter().flat_map(|sibling| sibling.properties.iter().map(|(known, _)| known)))
+                    .find(|known| known.data == property.data);
+                if let Some(previous) = previous {
+                    self.diagnostics.push(Diagnostic::new(DiagnosticCode::DuplicateMember, format!("property '{}' is already declared by an extension of {}", property.data, target), property.location)
+                        .with_label(previous.location, "first declared here"));
+                    continue;
+                }
+                properties.push((property.clone(), field.data.type_name.data.leblanc_type()));
+            }
+
+            let initialized = extension.fields.iter().filter(|field| field.data.value.is_some()).collect::<Vec<&Located<Field>>>();
+            let initializer = (!initialized.is_empty()).then(|| {
+                self.globals.push(Global { name: format!("<properties:{}>", name.data), arguments: LeBlancArgument::from_positional(&[target]), location: Some(name.location) });
+                functions.push(ClassFunction::ExtensionInitializer { extension: index, fields: initialized });
+                self.globals.len() - 1
+            });
+
+            let natives = native_methods(target);
+            let mut methods: Vec<(Ident, usize, usize)> = vec![];
+            for method in &extension.methods {
+                let function = &method.data;
+                let method_name = &function.name;
+                let parameters = receiver_parameters(function);
+                let arity = parameters.len();
+                if natives.iter().any(|native| native.context.name == method_name.data) {
+                    self.diagnostics.push(Diagnostic::new(DiagnosticCode::InvalidExtension, format!("method '{}' of extension '{}' conflicts with the native method '{}' of {}", method_name.data, name.data, method_name.data, target), method_name.location));
+                }
+                let previous = methods.iter().chain(siblings.iter().flat_map(|sibling| sibling.methods.iter()))
+                    .find(|(known, known_arity, _)| known.data == method_name.data && *known_arity == arity);
+                if let Some((previous, _, _)) = previous {
+                    self.diagnostics.push(Diagnostic::new(DiagnosticCode::DuplicateMember, format!("method '{}' with {} parameter(s) is already defined by an extension of {}", method_name.data, arity, target), method_name.location)
+                        .with_label(previous.location, "first defined here"));
+                }
+                if let Some((property, _)) = properties.iter().find(|(property, _)| property.data == method_name.data) {
+                    self.diagnostics.push(Diagnostic::new(DiagnosticCode::DuplicateMember, format!("method '{}' has the same name as a property of extension '{}'", method_name.data, name.data), method_name.location)
+                        .with_label(property.location, "property declared here"));
+                }
+                if function.body.is_none() {
+                    self.diagnostics.push(Diagnostic::new(DiagnosticCode::MissingBody, format!("method '{}' of extension '{}' has no body", method_name.data, name.data), method_name.location));
+                }
+                let mut types = vec![target];
+                types.extend(parameters.iter().map(|parameter| parameter.data.type_name.data.leblanc_type()));
+                self.globals.push(Global { name: format!("{}.{}", name.data, method_name.data), arguments: LeBlancArgument::from_positional(&types), location: Some(method_name.location) });
+                methods.push((method_name.clone(), arity, self.globals.len() - 1));
+                functions.push(ClassFunction::ExtensionMethod { extension: index, function });
+            }
+
+
+            let import = imported.map(|imported| (imported.import.clone(), imported.path.clone()));
+            let source = imported.map(|imported| imported.source.clone());
+            if let Some((import, path)) = &import {
+                self.relocate(before, import, path);
+            }
+            self.extensions.push(ExtensionInfo { name: name.clone(), target, properties, initializer, methods, import, source });
+        }
+        functions
+    }
+
+    fn class_function(&mut self, function: ClassFunction) -> FunctionBytecode {
+        match function {
+            ClassFunction::Factory { class, constructor } => {
+                let info = &self.classes[class];
+                let location = info.name.location;
+                let parameters = constructor.as_ref().map_or(&[][..], |(_, parameters)| parameters.as_slice());
+                let mut state = FunctionState::new(info.name.data.clone(), parameters, location);
+                for parameter in parameters {
+                    state.declare(&parameter.data.name);
+                }
+                let this = state.declare(&Ident::new(String::from("self"), location));
+                let this = state.slot(this);
+                state.emit(MakeInstance, info.prototype, location);
+                state.emit(StoreLocal, this, location);
+                for ancestor in self.lineage(class) {
+                    if let Some(initializer) = self.classes[ancestor].initializer {
+                        state.emit(LoadLocal, this, location);
+                        state.emit(LoadFunction, initializer, location);
+                        state.emit(CallFunction, 1, location);
+                        state.emit(PopTop, 0, location);
+                    }
+                }
+                if let Some((constructor, parameters)) = &constructor {
+                    state.emit(LoadLocal, this, location);
+                    for slot in 0..parameters.len() {
+                        let slot = state.slot(slot as u32);
+                        state.emit(LoadLocal, slot, location);
+                    }
+                    state.emit(LoadFunction, *constructor, location);
+                    state.emit(CallFunction, parameters.len() as u16 + 1, location);
+                    state.emit(PopTop, 0, location);
+                }
+                state.emit(LoadLocal, this, location);
+                state.emit(Return, 0, location);
+                self.finish(state)
+            }
+            ClassFunction::Initializer { class, fields } => {
+                let info = &self.classes[class];
+                let mut state = FunctionState::method(format!("<fields:{}>", info.name.data), Owner::Class(class), &[], info.name.location);
+                for field in fields {
+                    let name = &field.data.name;
+                    self.expression(&mut state, field.data.value.as_ref().unwrap());
+                    state.emit(LoadLocal, 0, name.location);
+                    let constant = state.constant(Constant::String(name.data.clone()));
+                    state.emit(AttributeStore, constant, name.location);
+                }
+                self.finish(state)
+            }
+            ClassFunction::Method { class, function } => {
+                let name = format!("{}.{}", self.classes[class].name.data, function.name.data);
+                let parameters = receiver_parameters(function);
+                let mut state = FunctionState::method(name, Owner::Class(class), parameters, function.name.location);
+                self.parameters(&mut state, parameters);
+                if let Some(body) = &function.body {
+                    self.body(&mut state, body);
+                }
+                self.unused_variables(&state);
+                self.finish(state)
+            }
+            ClassFunction::ExtensionInitializer { extension, fields } => {
+                let before = self.diagnostics.len();
+                let info = &self.extensions[extension];
+                let mut state = FunctionState::method(format!("<properties:{}>", info.name.data), Owner::Extension(extension), &[], info.name.location);
+                for field in fields {
+                    let name = &field.data.name;
+                    self.expression(&mut state, field.data.value.as_ref().unwrap());
+                    state.emit(LoadLocal, 0, name.location);
+                    let constant = state.constant(Constant::String(name.data.clone()));
+                    state.emit(AttributeStore, constant, name.location);
+                }
+                self.relocate_extension(before, extension);
+                self.finish(state)
+            }
+            ClassFunction::ExtensionMethod { extension, function } => {
+                let before = self.diagnostics.len();
+                let name = format!("{}.{}", self.extensions[extension].name.data, function.name.data);
+                let parameters = receiver_parameters(function);
+                let mut state = FunctionState::method(name, Owner::Extension(extension), parameters, function.name.location);
+                self.parameters(&mut state, parameters);
+                if let Some(body) = &function.body {
+                    self.body(&mut state, body);
+                }
+                self.unused_variables(&state);
+                self.relocate_extension(before, extension);
+                self.finish(state)
+            }
+            ClassFunction::TraitMethod { trait_index, function } => {
+                let name = format!("{}.{}", self.traits[trait_index].name.data, function.name.data);
+                let parameters = receiver_parameters(function);
+                let mut state = FunctionState::method(name, Owner::Trait(trait_index), parameters, function.name.location);
+                self.parameters(&mut state, parameters);
+                if let Some(body) = &function.body {
+                    self.body(&mut state, body);
+                }
+                self.unused_variables(&state);
+                self.finish(state)
+            }
+        }
+    }
+
+    fn relocate_extension(&mut self, from: usize, extension: usize) {
+        if let Some((import, path)) = self.extensions[extension].import.clone() {
+            self.relocate(from, &import, &path);
+        }
+    }
+
+    /// `class` and every class it inherits from, each once, supertypes first.
+    fn lineage(&self, class: usize) -> Vec<usize> {
+        let mut lineage = vec![];
+        for supertype in &self.classes[class].supertypes {
+            for ancestor in self.lineage(*supertype) {
+                if !lineage.contains(&ancestor) { lineage.push(ancestor); }
+            }
+        }
+        lineage.push(class);
+        lineage
+    }
+
+    /// Checks `self.name` against the members of the class or trait whose method is being compiled.
+    /// `self`, or `Self`, is the only receiver whose type is known at compile time, anything else is
+    /// checked at runtime. Traits have no fields, and their methods can only use the methods the
+    /// trait declares.
+    fn member(&mut self, state: &FunctionState, object: &Expression, name: &Ident, method: bool) {
+        match (state.owner, &object.data) {
+            (Some(_), Expr::Ident(receiver)) if (receiver == "self" || receiver == "Self") && state.lookup(receiver) == Some(0) => {}
+            _ => return
+        }
+        let (owner, owner_name, methods, fields) = match state.owner.unwrap() {
+            Owner::Class(class) => {
+                let class = &self.classes[class];
+                let mut methods = class.methods.iter().map(|(method, _)| method.data.clone()).collect::<Vec<String>>();
+                let mut fields = class.fields.iter().map(|field| field.data.clone()).collect::<Vec<String>>();
+                if self.exception_family(&class.name.data).is_some() {
+                    methods.extend(["name", "message", "trace"].map(String::from));
+                    fields.push(String::from("message"));
+                }
+                ("class", &class.name, methods, fields)
+            }
+            // The native methods of the target aren't known here, only its properties are checked.
+            Owner::Extension(_) if method => return,
+            Owner::Extension(extension) => {
+                let info = &self.extensions[extension];
+                let properties = self.extensions.iter().filter(|other| other.target == info.target)
+                    .flat_map(|other| other.properties.iter().map(|(property, _)| property.data.clone()))
+                    .collect::<Vec<String>>();
+                ("extension", &info.name, vec![], properties)
+            }
+            Owner::Trait(trait_index) => {
+                let trait_info = &self.traits[trait_index];
+                let methods = trait_info.required.iter().map(|(method, _)| method.data.clone())
+                    .chain(trait_info.defaults.iter().map(|(method, _, _)| method.data.clone()))
+                    .collect::<Vec<String>>();
+                ("trait", &trait_info.name, methods, vec![])
+            }
+        };
+        let (kind, members) = match method {
+            true => ("method", methods.into_iter().chain(base_methods().iter().map(|method| method.context.name.clone())).collect::<Vec<String>>()),
+            false => ("field", fields)
+        };
+        if members.contains(&name.data) { return; }
+        let mut diagnostic = Diagnostic::new(DiagnosticCode::UnknownMember, format!("{} '{}' has no {} named '{}'", owner, owner_name.data, kind, name.data), name.location)
+            .with_label(owner_name.location, &format!("{} declared here", owner));
+        if let Some(similar) = similar_name(&name.data, members.iter().map(String::as_str)) {
+            diagnostic = diagnostic.with_note(format!("there's a similar {} named '{}'", kind, similar));
+        }
+        self.diagnostics.push(diagnostic);
+    }
+
+    fn parameters(&mut self, state: &mut FunctionState, parameters: &[Located<Parameter>]) {
+        for parameter in parameters {
+            let type_name = &parameter.data.type_name;
+            let bound = self.trait_bound(type_name);
+            if let Some(slot) = self.declare(state, &parameter.data.name) {
+                // Parameters are part of the function's signature, so leaving one unused is fine.
+                state.used[slot as usize] = true;
+                if let Some(typing) = checked_type(type_name.data.leblanc_type()) {
+                    state.types.insert(slot, (typing, false));
+                }
+                self.track(state, slot, type_name, bound);
+                if let Some(TraitBound::Value(trait_index)) = bound {
+                    let location = parameter.data.name.location;
+                    state.load(Variable::Slot(slot), location);
+                    self.check_trait(state, trait_index, location);
+                    state.emit(PopTop, 0, location);
+                }
+            }
+        }
+    }
+
+    /// The trait values of a variable of type `type_name` must implement, reporting trait objects
+    /// of unknown traits.
+    fn trait_bound(&mut self, type_name: &Located<TypeName>) -> Option<TraitBound> {
+        let (bounded, elements) = match type_name.data.generics.first() {
+            Some(element) if type_name.data.leblanc_type() == LeBlancType::Derived(DerivedType::List) => (element, true),
+            _ => (type_name, false)
+        };
+        if !bounded.data.trait_object { return None; }
+        match self.traits.iter().position(|declared| declared.name.data == bounded.data.name) {
+            Some(trait_index) if elements => Some(TraitBound::Elements(trait_index)),
+            Some(trait_index) => Some(TraitBound::Value(trait_index)),
+            None => {
+                self.error(DiagnosticCode::UnknownClass, format!("unknown trait '{}'", bounded.data.name), bounded.location);
+                None
+            }
+        }
+    }
+
+    /// Remembers the class or trait bound of a variable declared with `type_name` in `slot`.
+    fn track(&self, state: &mut FunctionState, slot: u32, type_name: &Located<TypeName>, bound: Option<TraitBound>) {
+        if let Some(bound) = bound {
+            state.bounds.insert(slot, bound);
+        } else if let Some(class) = self.classes.iter().position(|class| class.name.data == type_name.data.name) {
+            state.instances.insert(slot, class);
+        }
+    }
+
+    /// Gives `name` a new slot in the innermost scope, warning when it hides a variable of an
+    /// enclosing scope. Returns `None` if the innermost scope already declares it.
+    fn declare(&mut self, state: &mut FunctionState, name: &Ident) -> Option<u32> {
+        let scope = state.scopes.last().unwrap();
+        if let Some((_, slot)) = scope.iter().find(|(declared, _)| *declared == name.data) {
+            let message = format!("variable '{}' is already declared in this scope", name.data);
+            self.diagnostics.push(Diagnostic::new(DiagnosticCode::VariableAlreadyDeclared, message, name.location)
+                .with_label(state.locations[*slot as usize], "first declared here"));
+            return None;
+        }
+        if let Some(shadowed) = state.lookup(&name.data) {
+            let message = format!("variable '{}' shadows a variable of an enclosing scope", name.data);
+            self.diagnostics.push(Diagnostic::new(DiagnosticCode::ShadowedVariable, message, name.location)
+                .with_label(state.locations[shadowed as usize], "shadowed variable declared here"));
+        }
+        Some(state.declare(name))
+    }
+
+    /// Warns about every variable of `state` that is never read. Names starting with an underscore
+    /// are exempt, so a loop variable can be ignored on purpose.
+    fn unused_variables(&mut self, state: &FunctionState) {
+        for (slot, name) in state.variables.iter().enumerate() {
+            if state.used[slot] || name.starts_with('_') { continue; }
+            let message = format!("variable '{}' is never used", name);
+            self.diagnostics.push(Diagnostic::new(DiagnosticCode::UnusedVariable, message, state.locations[slot])
+                .with_note(format!("if this is intentional, prefix it with an underscore: '_{}'", name)));
+        }
+    }
+
+    /// An undeclared variable error, with a hint when a variable or function of a similar name exists.
+    fn undeclared(&mut self, state: &FunctionState, message: String, name: &str, location: Location) {
+        let mut diagnostic = Diagnostic::new(DiagnosticCode::UndeclaredVariable, message, location);
+        let candidates = state.scopes.iter().flatten().map(|(declared, _)| declared.as_str())
+            .chain(state.enclosing.iter().map(|(declared, _)| declared.as_str()))
+            .chain(self.globals.iter().map(|global| global.name.as_str()));
+        if let Some(similar) = similar_name(name, candidates) {
+            diagnostic = diagnostic.with_note(format!("there's a similar variable named '{}' in this scope", similar));
+        }
+        self.diagnostics.push(diagnostic);
+    }
+
+    fn block(&mut self, state: &mut FunctionState, block: &Block) {
+        state.scopes.push(vec![]);
+        self.statements(state, &block.data);
+        state.scopes.pop();
+    }
+
+    fn statements(&mut self, state: &mut FunctionState, statements: &[Statement]) {
+        let mut exit: Option<&Statement> = None;
+        for statement in statements {
+            if let Some(exit) = exit.take() {
+                let keyword = if matches!(exit.data, Stmt::Return(_)) { "return" } else { "throw" };
+                self.diagnostics.push(Diagnostic::new(DiagnosticCode::UnreachableCode, String::from("unreachable statement"), statement.location)
+                    .with_label(exit.location, &format!("any code following this {} is unreachable", keyword)));
+            }
+            self.statement(state, statement);
+            if matches!(statement.data, Stmt::Return(_) | Stmt::Throw(_)) {
+                exit = Some(statement);
+            }
+        }
+    }
+
+    /// Compiles the body of a function. A generator starts by returning a generator over its own
+    /// frame, which runs the rest of the body a `yield` at a time.
+    fn body(&mut self, state: &mut FunctionState, body: &Block) {
+        if is_generator(&body.data) {
+            state.emit(MakeGenerator, 0, body.location);
+            state.emit(Return, 0, body.location);
+        }
+        state.capture(captured_names(&body.data), body.location);
+        self.statements(state, &body.data);
+    }
+
+    fn statement(&mut self, state: &mut FunctionState, statement: &Statement) {
+        let location = statement.location;
+        match &statement.data {
+            Stmt::Declaration { type_name, name, value } => {
+                let declared = type_name.data.leblanc_type();
+                let typing = match (checked_type(declared), value) {
+                    (Some(typing), _) => Some((typing, false)),
+                    // A `flex` variable keeps the type of the value it is declared with.
+                    (None, Some(value)) if declared == LeBlancType::Flex => static_type(state, &value.data)
+                        .filter(|typing| *typing != LeBlancType::Null).and_then(checked_type).map(|typing| (typing, true)),
+                    _ => None
+                };
+                let bound = self.trait_bound(type_name);
+                if let Some(value) = value {
+                    match bound {
+                        Some(bound) => self.bounded_value(state, bound, name, typing.filter(|(_, flex)| !flex), None, value),
+                        None => self.typed_value(state, name, typing.filter(|(_, flex)| !flex), None, value)
+                    }
+                }
+                if let Some(slot) = self.declare(state, name) {
+                    if let Some(typing) = typing {
+                        state.types.insert(slot, typing);
+                    }
+                    self.track(state, slot, type_name, bound);
+                    if value.is_none() && state.cells.contains_key(&slot) {
+                        // A captured variable needs its upvalue even before it is assigned.
+                        let null = state.constant(Constant::Null);
+                        state.emit(LoadConstant, null, location);
+                        state.bind(slot, location);
+                    } else if value.is_some() {
+                        state.bind(slot, location);
+                    }
+                }
+            }
+            Stmt::Destructuring { targets, value } => {
+                self.expression(state, value);
+                state.emit(Unpack, targets.len() as u16, location);
+                for (_, name) in targets {
+                    match self.declare(state, name) {
+                        Some(slot) => state.bind(slot, location),
+                        None => { state.emit(PopTop, 0, name.location); }
+                    }
+                }
+            }
+            Stmt::Assignment { target, operator, value } => self.assignment(state, target, *operator, value),
+            Stmt::Expression(expression) => {
+                self.expression(state, expression);
+                state.emit(PopTop, 0, location);
+            }
+            Stmt::Return(value) => {
+                match value {
+                    Some(value) => self.expression(state, value),
+                    None => { let null = state.constant(Constant::Null); state.emit(LoadConstant, null, location); }
+                }
+                self.pending_finally(state);
+                state.emit(Return, 0, location);
+            }
+            Stmt::Conditional { branches, otherwise } => {
+                let mut exits = vec![];
+                for (index, (condition, body)) in branches.iter().enumerate() {
+                    self.expression(state, condition);
+                    let comparator = if index == 0 { Comparator_If } else { Comparator_ElseIf };
+                    let skip = state.emit(comparator, 0, condition.location);
+                    self.block(state, body);
+                    if index + 1 < branches.len() || otherwise.is_some() {
+                        exits.push(state.emit(Comparator_Else, 0, condition.location));
+                    }
+                    state.patch(skip);
+                }
+                if let Some(otherwise) = otherwise {
+                    self.block(state, otherwise);
+                }
+                for exit in exits {
+                    state.patch(exit);
+                }
+            }
+            Stmt::While { condition, body } => {
+                // The condition is placed after the body so each iteration only needs one jump.
+                let entry = state.emit(Comparator_Else, 0, condition.location);
+                let body_start = state.next_index();
+                self.block(state, body);
+                state.patch(entry);
+                self.expression(state, condition);
+                state.emit(WhileLoop, body_start, condition.location);
+            }
+            Stmt::For { type_name, variable, iterable, body } => {
+                self.expression(state, iterable);
+                let head = state.emit(ForLoop, 0, iterable.location);
+                state.scopes.push(vec![]);
+                let bound = self.trait_bound(type_name);
+                if let Some(TraitBound::Value(trait_index)) = bound {
+                    self.check_trait(state, trait_index, variable.location);
+                }
+                if let Some(slot) = self.declare(state, variable) {
+                    self.track(state, slot, type_name, bound);
+                    state.bind(slot, variable.location);
+                }
+                state.depth += 1;
+                self.block(state, body);
+                state.depth -= 1;
+                state.scopes.pop();
+                let head_index = state.address(head);
+                state.emit(Comparator_Else, head_index, iterable.location);
+                state.patch(head);
+            }
+            Stmt::Try { body, handlers, finally } => self.try_statement(state, body, handlers, finally.as_ref(), location),
+            Stmt::Throw(value) => {
+                self.expression(state, value);
+                state.emit(Raise, 0, location);
+            }
+            Stmt::Yield(value) => {
+                self.expression(state, value);
+                state.emit(Yield, 0, location);
+            }
+            Stmt::When { value, arms } => {
+                self.dispatch(state, value, arms, false, location);
+                self.check_arms(arms, false, location);
+            }
+        }
+    }
+
+    /// Lays a `try` out as the body, then each `except` clause, then the `finally` twice: once for
+    /// an error on its way out, which is rethrown afterwards, and once for normal completion.
+    ///
+    /// ```text
+    /// body; jump normal
+    /// handler: store error; clause body; jump normal   (for each clause)
+    /// unwind: finally; Raise
+    /// normal: finally
+    /// ```
+    fn try_statement(&mut self, state: &mut FunctionState, body: &Block, handlers: &[Located<Handler>], finally: Option<&Block>, location: Location) {
+        state.frames.push(TryFrame { finally: finally.cloned(), holes: vec![] });
+        let try_start = state.next_index();
+        self.block(state, body);
+        let try_end = state.next_index();
+        let mut exits = vec![state.emit(Comparator_Else, 0, location)];
+
+        let mut clauses = vec![];
+        for handler in handlers {
+            let exception = &handler.data.exception;
+            if self.exception_family(&exception.data).is_none() {
+                self.error(DiagnosticCode::UnknownException, format!("unknown exception type '{}'", exception.data), exception.location);
+            }
+            let catches = if handler.data.family { format!("{}!", exception.data) } else { exception.data.clone() };
+            clauses.push((catches, state.next_index()));
+            state.scopes.push(vec![]);
+            match &handler.data.name {
+                Some(name) => if let Some(slot) = self.declare(state, name) {
+                    state.bind(slot, name.location);
+                }
+                None => { state.emit(PopTop, 0, handler.data.exception.location); }
+            }
+            self.block(state, &handler.data.body);
+            state.scopes.pop();
+            exits.push(state.emit(Comparator_Else, 0, handler.location));
+        }
+        let handlers_end = state.next_index();
+        let frame = state.frames.pop().unwrap();
+
+        for (exception, target) in clauses {
+            state.cover(try_start, try_end, &frame.holes, target, &exception);
+        }
+        match finally {
+            Some(finally) => {
+                let unwind = state.next_index();
+                state.cover(try_start, handlers_end, &frame.holes, unwind, "");
+                // The error being unwound stays on the stack underneath the finally block.
+                state.depth += 1;
+                self.block(state, finally);
+                state.depth -= 1;
+                state.emit(Raise, 0, finally.location);
+                for exit in exits {
+                    state.patch(exit);
+                }
+                self.block(state, finally);
+            }
+            None => for exit in exits {
+                state.patch(exit);
+            }
+        }
+    }
+
+    /// Runs the `finally` blocks a `return` leaves, innermost first, with the returned value on
+    /// the stack. Each copy is excluded from the handlers of the `try` it belongs to and of every
+    /// `try` inside that, so an error raised by a `finally` goes to the enclosing handlers.
+    fn pending_finally(&mut self, state: &mut FunctionState) {
+        let mut outer = std::mem::take(&mut state.frames);
+        let mut inner: Vec<TryFrame> = vec![];
+        while let Some(mut frame) = outer.pop() {
+            if let Some(finally) = frame.finally.clone() {
+                state.frames = outer;
+                let start = state.next_index();
+                state.depth += 1;
+                self.block(state, &finally);
+                state.depth -= 1;
+                let end = state.next_index();
+                outer = std::mem::take(&mut state.frames);
+                frame.holes.push((start, end));
+                inner.iter_mut().for_each(|frame| frame.holes.push((start, end)));
+            }
+            inner.push(frame);
+        }
+        inner.reverse();
+        state.frames = inner;
+    }
+
+    fn assignment(&mut self, state: &mut FunctionState, target: &Expression, operator: Option<LBOperator>, value: &Expression) {
+        let location = target.location;
+        match &target.data {
+            Expr::Ident(name) => {
+                let variable = match state.lookup(name) {
+                    Some(slot) => Variable::Slot(slot),
+                    None => match state.upvalue(name) {
+                        Some(upvalue) => Variable::Upvalue(upvalue),
+                        None => {
+                            self.undeclared(state, format!("cannot assign to undeclared variable '{}'", name), name, location);
+                            return;
+                        }
+                    }
+                };
+                if let Some(operator) = operator {
+                    state.load(variable, location);
+                    self.expression(state, value);
+                    self.operator(state, operator, value.location);
+                } else {
+                    let typing = match variable {
+                        Variable::Slot(slot) => state.types.get(&slot).copied(),
+                        Variable::Upvalue(_) => None
+                    };
+                    let declared_at = match variable {
+                        Variable::Slot(slot) => Some(state.locations[slot as usize]),
+                        Variable::Upvalue(_) => None
+                    };
+                    let name = Ident::new(name.clone(), location);
+                    match variable {
+                        Variable::Slot(slot) if state.bounds.contains_key(&slot) => self.bounded_value(state, state.bounds[&slot], &name, typing, declared_at, value),
+                        _ => self.typed_value(state, &name, typing, declared_at, value)
+                    }
+                }
+                state.store(variable, location);
+            }
+            Expr::Index { object, index } => {
+                if let Some(operator) = operator {
+                    self.expression(state, object);
+                    self.expression(state, index);
+                    state.emit(ElementAccess, 0, location);
+                    self.expression(state, value);
+                    self.operator(state, operator, value.location);
+                } else {
+                    self.expression(state, value);
+                }
+                self.expression(state, object);
+                self.expression(state, index);
+                state.emit(ElementStore, 0, location);
+            }
+            Expr::Attribute { object, attribute } => {
+                self.member(state, object, attribute, false);
+                let name = state.constant(Constant::String(attribute.data.clone()));
+                if let Some(operator) = operator {
+                    self.expression(state, object);
+                    state.emit(AttributeAccess, name, attribute.location);
+                    self.expression(state, value);
+                    self.operator(state, operator, value.location);
+                } else {
+                    self.expression(state, value);
+                }
+                self.expression(state, object);
+                state.emit(AttributeStore, name, attribute.location);
+            }
+            _ => self.error(DiagnosticCode::InvalidAssignment, "invalid assignment target".to_string(), location)
+        }
+    }
+
+    /// Compiles `value` for the variable `name`, whose values must implement a trait. A value whose
+    /// class is known has to implement it, any other is checked when it runs. Only the elements of
+    /// a list literal are checked for a list of trait objects.
+    fn bounded_value(&mut self, state: &mut FunctionState, bound: TraitBound, name: &Ident, typing: Option<(LeBlancType, bool)>, declared_at: Option<Location>, value: &Expression) {
+        match (bound, &value.data) {
+            (TraitBound::Value(trait_index), _) => {
+                self.expression(state, value);
+                self.trait_value(state, trait_index, value);
+            }
+            (TraitBound::Elements(trait_index), Expr::List(items)) => {
+                state.emit(InstructionMarker, 0, value.location);
+                for item in items {
+                    self.expression(state, item);
+                    self.trait_value(state, trait_index, item);
+                }
+                state.emit(ListSetup, 0, value.location);
+            }
+            (TraitBound::Elements(_), _) => self.typed_value(state, name, typing, declared_at, value)
+        }
+    }
+
+    /// Checks `value`, just compiled, against the trait at `trait_index`: at compile time when its
+    /// class is known, otherwise when it runs.
+    fn trait_value(&mut self, state: &mut FunctionState, trait_index: usize, value: &Expression) {
+        let trait_name = &self.traits[trait_index].name.data;
+        let problem = match self.known(state, &value.data) {
+            Known::Class(class) if !self.classes[class].traits.contains(&trait_index) => Some(format!("class '{}' does not implement trait '{}'", self.classes[class].name.data, trait_name)),
+            Known::Native(typing) if typing != LeBlancType::Null => Some(format!("a value of type {} does not implement trait '{}'", typing, trait_name)),
+            Known::Trait(other) if self.traits[other].family.contains(&trait_index) => None,
+            Known::Trait(_) | Known::Unknown => {
+                self.check_trait(state, trait_index, value.location);
+                None
+            }
+            _ => None
+        };
+        if let Some(message) = problem {
+            self.error(DiagnosticCode::IncompatibleType, message, value.location);
+        }
+    }
+
+    /// Emits the check that the value on top of the stack is null or implements the trait at
+    /// `trait_index`. Traits don't exist at runtime, so the check is given the classes that do.
+    fn check_trait(&mut self, state: &mut FunctionState, trait_index: usize, location: Location) {
+        let mut names = vec![Constant::String(self.traits[trait_index].name.data.clone())];
+        names.extend(self.classes.iter().filter(|class| class.traits.contains(&trait_index)).map(|class| Constant::String(class.name.data.clone())));
+        let constant = state.constant(Constant::Tuple(names));
+        state.emit(CheckTrait, constant, location);
+    }
+
+    /// What is known of the class of `value` before it runs: that of an instance a class is
+    /// called to make, of `self` in a class method, or of a variable declared with a class or trait.
+    fn known(&self, state: &FunctionState, value: &Expr) -> Known {
+        match value {
+            Expr::Call { function, .. } => match &function.data {
+                Expr::Ident(name) if state.lookup(name).is_none() => self.classes.iter().position(|class| class.name.data == *name).map_or(Known::Unknown, Known::Class),
+                _ => Known::Unknown
+            },
+            Expr::Ident(name) => match (state.lookup(name), state.owner) {
+                (Some(0), Some(Owner::Class(class))) if name == "self" || name == "Self" => Known::Class(class),
+                (Some(slot), _) => match (state.instances.get(&slot), state.bounds.get(&slot)) {
+                    (Some(class), _) => Known::Class(*class),
+                    (_, Some(TraitBound::Value(trait_index))) => Known::Trait(*trait_index),
+                    _ => static_type(state, value).map_or(Known::Unknown, Known::Native)
+                },
+                _ => Known::Unknown
+            },
+            _ => static_type(state, value).map_or(Known::Unknown, Known::Native)
+        }
+    }
+
+    /// Compiles `value` for the variable `name`, of `typing` if known. A numeric literal is given
+    /// that type, unless it doesn't fit in it, and `{}` is an empty dict or set for a variable of
+    /// either. Any other value whose type is known must be one the variable can hold. A `flex`
+    /// variable can only be given values of the type it started with.
+    fn typed_value(&mut self, state: &mut FunctionState, name: &Ident, typing: Option<(LeBlancType, bool)>, declared_at: Option<Location>, value: &Expression) {
+        let (typing, flex) = match typing {
+            Some(typing) => typing,
+            None => return self.expression(state, value)
+        };
+        if matches!(&value.data, Expr::Block(block) if block.data.is_empty()) {
+            let empty = match typing {
+                LeBlancType::Derived(DerivedType::Dict) => Some(Expr::Dict(vec![])),
+                LeBlancType::Derived(DerivedType::Set) => Some(Expr::Set(vec![])),
+                _ => None
+            };
+            if let Some(empty) = empty {
+                return self.expression(state, &Located::new(empty, value.location));
+            }
+        }
+        let code = if flex { DiagnosticCode::FlexReassignment } else { DiagnosticCode::IncompatibleType };
+        let literal = literal(&value.data);
+        let problem = match literal.as_ref().map(|literal| coerce(literal, typing)) {
+            Some(Ok(Some(constant))) => {
+                let index = state.constant(constant);
+                state.emit(LoadConstant, index, value.location);
+                return;
+            }
+            Some(Err(message)) => Some(message),
+            _ => static_type(state, &value.data).filter(|actual| !assignable(typing, *actual))
+                .map(|actual| match flex {
+                    true => format!("cannot reassign flex variable '{}', which holds {}, a value of type {}", name.data, typing, actual),
+                    false => format!("cannot assign a value of type {} to '{}', which is declared {}", actual, name.data, typing)
+                })
+        };
+        if let Some(message) = problem {
+            let mut diagnostic = Diagnostic::new(code, message, value.location);
+            if let Some(declared_at) = declared_at {
+                diagnostic = diagnostic.with_label(declared_at, "variable declared here");
+            }
+            if let Some(actual) = static_type(state, &value.data).filter(|_| !flex) {
+                diagnostic = diagnostic.with_note(format!("possible fix - change the type of '{}' to '{}'", name.data, actual));
+            }
+            self.diagnostics.push(diagnostic);
+        }
+        self.expression(state, value);
+    }
+
+    fn expression(&mut self, state: &mut FunctionState, expression: &Expression) {
+        let location = expression.location;
+        match &expression.data {
+            Expr::Constant(constant) => {
+                let index = state.constant(constant.clone());
+                state.emit(LoadConstant, index, location);
+            }
+            Expr::Ident(name) => {
+                if let Some(slot) = state.lookup(name) {
+                    state.load(Variable::Slot(slot), location);
+                } else if let Some(upvalue) = state.upvalue(name) {
+                    state.load(Variable::Upvalue(upvalue), location);
+                } else if let Some(index) = self.globals.iter().position(|global| global.name == *name) {
+                    state.emit(LoadFunction, index as u16, location);
+                } else if self.is_variant(name) {
+                    if let Some(found) = self.variant(None, &Ident::new(name.clone(), location)) {
+                        self.construct(state, found, &[], location);
+                    }
+                } else {
+                    self.undeclared(state, format!("undeclared variable '{}'", name), name, location);
+                }
+            }
+            Expr::List(items) => {
+                state.emit(InstructionMarker, 0, location);
+                for item in items {
+                    self.expression(state, item);
+                }
+                state.emit(ListSetup, 0, location);
+            }
+            Expr::Dict(entries) => {
+                state.emit(InstructionMarker, 0, location);
+                for (key, value) in entries {
+                    self.expression(state, key);
+                    self.expression(state, value);
+                }
+                state.emit(DictSetup, 0, location);
+            }
+            Expr::Tuple(items) | Expr::Set(items) => {
+                if let Some(constant) = folded(&expression.data) {
+                    let index = state.constant(constant);
+                    state.emit(LoadConstant, index, location);
+                    return;
+                }
+                state.emit(InstructionMarker, 0, location);
+                for item in items {
+                    self.expression(state, item);
+                }
+                let setup = if matches!(expression.data, Expr::Tuple(_)) { TupleSetup } else { SetSetup };
+                state.emit(setup, 0, location);
+            }
+            Expr::Call { function, arguments } => {
+                if let Expr::Variant { enum_name, variant } = &function.data {
+                    if let Some(found) = self.variant(Some(enum_name), variant) {
+                        self.construct(state, found, arguments, location);
+                    }
+                    return;
+                }
+                if let Expr::Ident(name) = &function.data {
+                    let callable = state.is_variable(name) || self.globals.iter().any(|global| global.name == *name);
+                    if let Some(family) = self.exception_family(name).filter(|_| !callable) {
+                        self.exception(state, name, family, arguments, location);
+                        return;
+                    }
+                    if !callable && self.is_variant(name) {
+                        if let Some(found) = self.variant(None, &Ident::new(name.clone(), function.location)) {
+                            self.construct(state, found, arguments, location);
+                        }
+                        return;
+                    }
+                }
+                for argument in arguments {
+                    self.expression(state, argument);
+                }
+                match &function.data {
+                    Expr::Ident(name) if !state.is_variable(name) => self.function_reference(state, name, arguments.len(), function.location),
+                    _ => self.expression(state, function)
+                }
+                state.emit(CallFunction, arguments.len() as u16, location);
+            }
+            Expr::MethodCall { object, method, arguments } => {
+                if let Some(enum_name) = self.enum_name(state, object) {
+                    if let Some(found) = self.variant(Some(&enum_name), method) {
+                        self.construct(state, found, arguments, location);
+                    }
+                    return;
+                }
+                self.member(state, object, method, true);
+                self.expression(state, object);
+                for argument in arguments {
+                    self.expression(state, argument);
+                }
+                let name = state.constant(Constant::String(method.data.clone()));
+                state.emit(LoadConstant, name, method.location);
+                state.emit(CallClassMethod, arguments.len() as u16, method.location);
+            }
+            Expr::Attribute { object, attribute } => {
+                if let Some(enum_name) = self.enum_name(state, object) {
+                    if let Some(found) = self.variant(Some(&enum_name), attribute) {
+                        self.construct(state, found, &[], location);
+                    }
+                    return;
+                }
+                self.member(state, object, attribute, false);
+                self.expression(state, object);
+                let name = state.constant(Constant::String(attribute.data.clone()));
+                state.emit(AttributeAccess, name, attribute.location);
+            }
+            Expr::Index { object, index } => {
+                self.expression(state, object);
+                self.expression(state, index);
+                state.emit(ElementAccess, 0, location);
+            }
+            Expr::Slice { object, start, end } => {
+                self.expression(state, object);
+                for bound in [start, end] {
+                    match bound {
+                        Some(bound) => self.expression(state, bound),
+                        None => { let null = state.constant(Constant::Null); state.emit(LoadConstant, null, location); }
+                    }
+                }
+                state.emit(MakeSlice, 0, location);
+                state.emit(ElementAccess, 0, location);
+            }
+            Expr::Unary { operator, operand } => match (operator, &operand.data) {
+                (LBOperator::Minus, Expr::Constant(Constant::Integer(value, typing))) => {
+                    let index = state.constant(Constant::Integer(-value, *typing));
+                    state.emit(LoadConstant, index, location);
+                }
+                (LBOperator::Minus, Expr::Constant(Constant::Decimal(value, typing))) => {
+                    let index = state.constant(Constant::Decimal(-value, *typing));
+                    state.emit(LoadConstant, index, location);
+                }
+                (LBOperator::Minus, Expr::Constant(Constant::BigInt(value))) => {
+                    let index = state.constant(Constant::BigInt(-value));
+                    state.emit(LoadConstant, index, location);
+                }
+                (LBOperator::Minus, Expr::Constant(Constant::BigDecimal(value))) => {
+                    let index = state.constant(Constant::BigDecimal(-value.clone()));
+                    state.emit(LoadConstant, index, location);
+                }
+                (LBOperator::Minus, _) => {
+                    self.expression(state, operand);
+                    state.emit(BinaryNegate, 0, location);
+                }
+                (LBOperator::Not, _) => {
+                    self.expression(state, operand);
+                    state.emit(BinaryNot, 0, location);
+                }
+                _ => {
+                    self.expression(state, operand);
+                    state.emit(BinaryInverse, 0, location);
+                }
+            }
+            Expr::Binary { operator, left, right } => {
+                self.expression(state, left);
+                self.expression(state, right);
+                self.operator(state, *operator, location);
+            }
+            Expr::Cast { value, type_name } => {
+                self.expression(state, value);
+                state.emit(Cast, type_name.data.leblanc_type().enum_id() as u16, type_name.location);
+            }
+            Expr::Range { start, end, step } => {
+                self.expression(state, start);
+                self.expression(state, end);
+                match step {
+                    Some(step) => self.expression(state, step),
+                    None => { let one = state.constant(Constant::Integer(1, LeBlancType::Int)); state.emit(LoadConstant, one, location); }
+                }
+                state.emit(IteratorSetup(0), 0, location);
+            }
+            Expr::Lambda { parameters, body } => {
+                let (index, closes) = self.lambda(state, parameters, body, location);
+                state.emit(LoadFunction, index, location);
+                if closes { state.emit(MakeClosure, 0, location); }
+            }
+            Expr::Groupment { function, group } => {
+                self.expression(state, function);
+                self.expression(state, group);
+                state.emit(Group, 0, location);
+            }
+            Expr::Variant { enum_name, variant } => {
+                if let Some(found) = self.variant(Some(enum_name), variant) {
+                    self.construct(state, found, &[], location);
+                }
+            }
+            Expr::Match { value, arms } => self.match_expression(state, value, arms, location),
+            Expr::Guard { arms, otherwise } => self.guard(state, arms, otherwise.as_deref(), location),
+            Expr::Block(body) => self.block_literal(state, body, location)
+        }
+    }
+
+    /// Constructs an exception from `Name(message)`. The new error carries its whole lineage,
+    /// `Name:Parent:...:Exception`, so handlers can match it against a family at runtime.
+    /// Builds an exception in place. One declared in the file also gets the members of its class,
+    /// then runs its field initializers and the constructor taking `arguments`. Without a
+    /// constructor, the only argument an exception takes is its message.
+    fn exception(&mut self, state: &mut FunctionState, name: &str, family: Vec<String>, arguments: &[Expression], location: Location) {
+        let class = self.classes.iter().position(|class| class.name.data == name);
+        let constructors = class.map_or(vec![], |class| self.classes[class].constructors.clone());
+        let constructor = constructors.iter().find(|(_, parameters)| parameters.len() == arguments.len()).map(|(constructor, _)| *constructor);
+        if constructors.is_empty() && arguments.len() > 1 {
+            self.error(DiagnosticCode::ExceptionArguments, format!("exception '{}' takes at most 1 argument (its message) but {} were given", name, arguments.len()), location);
+            return;
+        }
+        if !constructors.is_empty() && constructor.is_none() {
+            let counts = constructors.iter().map(|(_, parameters)| parameters.len().to_string()).collect::<Vec<String>>().join(" or ");
+            let message = format!("exception '{}' expects {} argument(s) but {} were given", name, counts, arguments.len());
+            let declared = self.classes[class.unwrap()].name.location;
+            self.diagnostics.push(Diagnostic::new(DiagnosticCode::ArgumentCount, message, location).with_label(declared, "exception declared here"));
+            return;
+        }
+
+        let message = match constructor {
+            Some(_) => 0,
+            None => arguments.len() as u16
+        };
+        if constructor.is_none() {
+            for argument in arguments {
+                self.expression(state, argument);
+            }
+        }
+        let lineage = std::iter::once(name.to_string()).chain(family).collect::<Vec<String>>().join(":");
+        let lineage = state.constant(Constant::String(lineage));
+        state.emit(LoadConstant, lineage, location);
+        state.emit(MakeException, message, location);
+        let Some(class) = class else { return };
+
+        state.emit(ExtendException, self.classes[class].prototype, location);
+        let this = state.temporary(location);
+        state.emit(StoreLocal, this, location);
+        for ancestor in self.lineage(class) {
+            if let Some(initializer) = self.classes[ancestor].initializer {
+                state.emit(LoadLocal, this, location);
+                state.emit(LoadFunction, initializer, location);
+                state.emit(CallFunction, 1, location);
+                state.emit(PopTop, 0, location);
+            }
+        }
+        if let Some(constructor) = constructor {
+            state.emit(LoadLocal, this, location);
+            for argument in arguments {
+                self.expression(state, argument);
+            }
+            state.emit(LoadFunction, constructor, location);
+            state.emit(CallFunction, arguments.len() as u16 + 1, location);
+            state.emit(PopTop, 0, location);
+        }
+        state.emit(LoadLocal, this, location);
+    }
+
+    fn is_variant(&self, name: &str) -> bool {
+        self.enums.iter().any(|info| info.variants.iter().any(|(variant, _)| variant.data == name))
+    }
+
+    /// The enum `object` names when it is a bare name that isn't a variable, as in `Coin.Penny`.
+    fn enum_name(&self, state: &FunctionState, object: &Expression) -> Option<Ident> {
+        match &object.data {
+            Expr::Ident(name) if !state.is_variable(name) && self.enums.iter().any(|info| info.name.data == *name) => {
+                Some(Ident::new(name.clone(), object.location))
+            }
+            _ => None
+        }
+    }
+
+    /// Indices of the enums and variants `Enum::Variant` may refer to. Without an enum name, every
+    /// enum declaring the variant is a candidate.
+    fn variant_candidates(&self, enum_name: Option<&str>, variant: &str) -> Vec<(usize, usize)> {
+        self.enums.iter().enumerate()
+            .filter(|(_, info)| enum_name.is_none_or(|name| info.name.data == name))
+            .filter_map(|(index, info)| info.variants.iter().position(|(declared, _)| declared.data == variant).map(|position| (index, position)))
+            .collect()
+    }
+
+    /// Resolves `Enum::Variant`, or a bare `Variant` declared by a single enum, to the indices of
+    /// its enum and variant. Reports unknown and ambiguous variants.
+    fn variant(&mut self, enum_name: Option<&Ident>, variant: &Ident) -> Option<(usize, usize)> {
+        let candidates = self.variant_candidates(enum_name.map(|name| name.data.as_str()), &variant.data);
+        if let [found] = candidates[..] {
+            return Some(found);
+        }
+
+        let diagnostic = if candidates.len() > 1 {
+            let first = &self.enums[candidates[0].0].name.data;
+            Diagnostic::new(DiagnosticCode::UnknownVariant, format!("variant '{}' is declared by more than one enum", variant.data), variant.location)
+                .with_note(format!("name the enum it belongs to, e.g. '{}::{}'", first, variant.data))
+        } else if let Some(enum_name) = enum_name {
+            match self.enums.iter().find(|info| info.name.data == enum_name.data) {
+                Some(info) => {
+                    let message = format!("enum '{}' has no variant named '{}'", enum_name.data, variant.data);
+                    let mut diagnostic = Diagnostic::new(DiagnosticCode::UnknownVariant, message, variant.location)
+                        .with_label(info.name.location, "enum declared here");
+                    if let Some(similar) = similar_name(&variant.data, info.variants.iter().map(|(declared, _)| declared.data.as_str())) {
+                        diagnostic = diagnostic.with_note(format!("there's a similar variant named '{}'", similar));
+                    }
+                    diagnostic
+                }
+                None => {
+                    let mut diagnostic = Diagnostic::new(DiagnosticCode::UnknownVariant, format!("unknown enum '{}'", enum_name.data), enum_name.location);
+                    if let Some(similar) = similar_name(&enum_name.data, self.enums.iter().map(|info| info.name.data.as_str())) {
+                        diagnostic = diagnostic.with_note(format!("there's a similar enum named '{}'", similar));
+                    }
+                    diagnostic
+                }
+            }
+        } else {
+            let mut diagnostic = Diagnostic::new(DiagnosticCode::UnknownVariant, format!("unknown variant '{}'", variant.data), variant.location);
+            let names = self.enums.iter().flat_map(|info| info.variants.iter().map(|(declared, _)| declared.data.as_str()));
+            if let Some(similar) = similar_name(&variant.data, names) {
+                diagnostic = diagnostic.with_note(format!("there's a similar variant named '{}'", similar));
+            }
+            diagnostic
+        };
+        self.diagnostics.push(diagnostic);
+        None
+    }
+
+    /// `Enum::Variant` with its full path and payload size, along with where it was declared.
+    fn variant_path(&self, (enum_index, variant_index): (usize, usize)) -> (String, usize, Location) {
+        let info = &self.enums[enum_index];
+        let (variant, arity) = &info.variants[variant_index];
+        (format!("{}::{}", info.name.data, variant.data), *arity, variant.location)
+    }
+
+    /// Constructs a variant with `arguments` as its payload.
+    fn construct(&mut self, state: &mut FunctionState, found: (usize, usize), arguments: &[Expression], location: Location) {
+        let (path, arity, declared) = self.variant_path(found);
+        if arguments.len() != arity {
+            let message = format!("variant '{}' holds {} value(s) but {} were given", path, arity, arguments.len());
+            self.diagnostics.push(Diagnostic::new(DiagnosticCode::ArgumentCount, message, location)
+                .with_label(declared, "variant declared here"));
+            return;
+        }
+        for argument in arguments {
+            self.expression(state, argument);
+        }
+        let path = state.constant(Constant::String(path));
+        state.emit(LoadConstant, path, location);
+        state.emit(MakeVariant, arguments.len() as u16, location);
+    }
+
+    fn match_expression(&mut self, state: &mut FunctionState, value: &Expression, arms: &[Located<MatchArm>], location: Location) {
+        self.dispatch(state, value, arms, true, location);
+        self.check_arms(arms, true, location);
+    }
+
+    /// Lays a `match` or `when` out as a test of each arm in turn against the value, kept in a
+    /// hidden slot. When the first arms have constant patterns, a jump table indexed by the value
+    /// takes it straight to the body of the matching one, or to the tests of the remaining arms.
+    /// A `match` arm leaves the value of its body on the stack, `null` for a block, and a
+    /// MatchException is thrown when no arm matches. A `when` without a matching arm does nothing.
+    ///
+    /// ```text
+    /// value; store subject
+    /// load subject; JumpTable            (constant arms first only)
+    /// body; jump end                     (for each constant arm)
+    /// pattern; guard; body; jump end     (for each other arm, a failed test jumps to the next one)
+    /// throw MatchException               (match only)
+    /// end:
+    /// ```
+    fn dispatch(&mut self, state: &mut FunctionState, value: &Expression, arms: &[Located<MatchArm>], produces_value: bool, location: Location) {
+        self.expression(state, value);
+        state.scopes.push(vec![]);
+        let subject = state.temporary(value.location);
+        state.emit(StoreLocal, subject, value.location);
+
+        // A single constant arm is as cheap to test as to look up.
+        let keys = arms.iter().map_while(table_key).collect::<Vec<TableKey>>();
+        let table = match keys.len() {
+            0 | 1 => None,
+            _ => {
+                state.emit(LoadLocal, subject, location);
+                state.tables.push(JumpTable::new());
+                let table = state.tables.len() - 1;
+                let index = state.index(table, "constants");
+                state.emit(InstructionBase::JumpTable, index, location);
+                Some(table)
+            }
+        };
+
+        let mut exits = vec![];
+        for (index, arm) in arms.iter().enumerate() {
+            state.scopes.push(vec![]);
+            let mut fails = vec![];
+            match table.filter(|_| index < keys.len()) {
+                Some(table) => {
+                    let target = state.next_index();
+                    state.tables[table].insert(keys[index].clone(), target);
+                }
+                None => {
+                    self.pattern(state, &arm.data.pattern, subject, &mut fails);
+                    if let Some(guard) = &arm.data.guard {
+                        self.expression(state, guard);
+                        fails.push(state.emit(Comparator_If, 0, guard.location));
+                    }
+                }
+            }
+            match &arm.data.body {
+                LambdaBody::Expression(body) => self.expression(state, body),
+                LambdaBody::Block(body) => {
+                    self.block(state, body);
+                    if produces_value {
+                        let null = state.constant(Constant::Null);
+                        state.emit(LoadConstant, null, body.location);
+                    }
+                }
+            }
+            state.scopes.pop();
+            exits.push(state.emit(Comparator_Else, 0, arm.location));
+            for fail in fails {
+                state.patch(fail);
+            }
+            if let Some(table) = table.filter(|_| index + 1 == keys.len()) {
+                state.tables[table].default = state.next_index();
+            }
+        }
+
+        if produces_value {
+            let message = state.constant(Constant::String(String::from("no arm of the match matched the value")));
+            state.emit(LoadConstant, message, location);
+            let lineage = std::iter::once(String::from("MatchException")).chain(builtin_family("MatchException")).collect::<Vec<String>>().join(":");
+            let lineage = state.constant(Constant::String(lineage));
+            state.emit(LoadConstant, lineage, location);
+            state.emit(MakeException, 1, location);
+            state.emit(Raise, 0, location);
+        }
+        for exit in exits {
+            state.patch(exit);
+        }
+        state.scopes.pop();
+    }
+
+    /// Lays a guard out as a test of each condition in turn, running the exit of the arm whose
+    /// condition holds. Exits have to leave the function, so only `otherwise` gives the guard a value.
+    ///
+    /// ```text
+    /// condition; jump next if false; exit   (for each arm)
+    /// next: otherwise, or null
+    /// ```
+    fn guard(&mut self, state: &mut FunctionState, arms: &[Located<GuardArm>], otherwise: Option<&LambdaBody>, location: Location) {
+        for arm in arms {
+            let condition = &arm.data.condition;
+            self.expression(state, condition);
+            let skip = state.emit(Comparator_If, 0, condition.location);
+            self.block(state, &arm.data.exit);
+            if !diverges(&arm.data.exit.data) {
+                self.diagnostics.push(Diagnostic::new(DiagnosticCode::GuardFallthrough, String::from("guard arm doesn't leave the function"), arm.data.exit.location)
+                    .with_label(condition.location, "taken when this condition holds")
+                    .with_note(String::from("end it with 'return' or 'throw', only the 'else' arm can give the guard a value")));
+            }
+            state.patch(skip);
+        }
+        match otherwise {
+            Some(LambdaBody::Expression(value)) => self.expression(state, value),
+            Some(LambdaBody::Block(body)) => {
+                self.block(state, body);
+                let null = state.constant(Constant::Null);
+                state.emit(LoadConstant, null, body.location);
+            }
+            None => {
+                let null = state.constant(Constant::Null);
+                state.emit(LoadConstant, null, location);
+            }
+        }
+    }
+
+    /// A block literal runs in the frame of the call that creates it, so its body is compiled in
+    /// place, sharing the slots of the function, and jumped over:
+    /// ```text
+    /// MakeBlock end
+    /// body; load null; Return
+    /// end:
+    /// ```
+    /// A `return` in the body leaves the block, not the function, and the block may be executed
+    /// from outside the `try` statements around it, so their handlers and `finally` blocks don't
+    /// apply to the body.
+    fn block_literal(&mut self, state: &mut FunctionState, body: &Block, location: Location) {
+        let make = state.emit(MakeBlock, 0, location);
+        let frames = std::mem::take(&mut state.frames);
+        let depth = std::mem::replace(&mut state.depth, 0);
+        self.block(state, body);
+        let null = state.constant(Constant::Null);
+        state.emit(LoadConstant, null, body.location);
+        state.emit(Return, 0, body.location);
+        state.frames = frames;
+        state.depth = depth;
+        let end = state.next_index();
+        let start = state.address(make + 1);
+        state.frames.iter_mut().for_each(|frame| frame.holes.push((start, end)));
+        state.patch(make);
+    }
+
+    /// Tests the value in `slot` against `pattern`, binding the names it introduces. The jumps
+    /// taken when the value doesn't match are added to `fails`.
+    fn pattern(&mut self, state: &mut FunctionState, pattern: &Located<Pattern>, slot: u16, fails: &mut Vec<usize>) {
+        let location = pattern.location;
+        match &pattern.data {
+            Pattern::Wildcard => {}
+            Pattern::Constant(constant) => {
+                state.emit(LoadLocal, slot, location);
+                let constant = state.constant(constant.clone());
+                state.emit(LoadConstant, constant, location);
+                state.emit(Equality(0), 0, location);
+                fails.push(state.emit(Comparator_If, 0, location));
+            }
+            Pattern::Name(name) if !self.is_variant(name) => {
+                if let Some(binding) = self.declare(state, &Ident::new(name.clone(), location)) {
+                    state.emit(LoadLocal, slot, location);
+                    state.bind(binding, location);
+                }
+            }
+            Pattern::Name(name) => self.variant_pattern(state, None, &Ident::new(name.clone(), location), None, slot, fails),
+            Pattern::Variant { enum_name, variant, fields } => self.variant_pattern(state, enum_name.as_ref(), variant, fields.as_deref(), slot, fails)
+        }
+    }
+
+    /// Tests whether the value in `slot` is a variant, then its payload against `fields`. Without
+    /// `fields`, as in `Some`, any payload matches.
+    fn variant_pattern(&mut self, state: &mut FunctionState, enum_name: Option<&Ident>, variant: &Ident, fields: Option<&[Located<Pattern>]>, slot: u16, fails: &mut Vec<usize>) {
+        let Some(found) = self.variant(enum_name, variant) else { return };
+        let (path, arity, declared) = self.variant_path(found);
+        let location = variant.location;
+        if let Some(fields) = fields.filter(|fields| fields.len() != arity) {
+            let message = format!("variant '{}' holds {} value(s) but the pattern has {}", path, arity, fields.len());
+            self.diagnostics.push(Diagnostic::new(DiagnosticCode::ArgumentCount, message, location)
+                .with_label(declared, "variant declared here"));
+            return;
+        }
+
+        state.emit(LoadLocal, slot, location);
+        let path = state.constant(Constant::String(path));
+        state.emit(MapMatch, path, location);
+        fails.push(state.emit(Comparator_If, 0, location));
+        // The payload is left on the stack with its last value on top.
+        let mut nested = vec![];
+        for index in (0..arity).rev() {
+            match fields.map(|fields| &fields[index]) {
+                None | Some(Located { data: Pattern::Wildcard, .. }) => { state.emit(PopTop, 0, location); }
+                Some(field) => {
+                    let temporary = state.temporary(field.location);
+                    state.emit(StoreLocal, temporary, field.location);
+                    nested.push((field, temporary));
+                }
+            }
+        }
+        for (field, temporary) in nested.into_iter().rev() {
+            self.pattern(state, field, temporary, fails);
+        }
+    }
+
+    /// Warns about arms no value can reach because the arms before them already match everything
+    /// they would and, for a `match`, when some value would fall through every arm. Arms with a
+    /// guard may not match, so they don't cover anything.
+    fn check_arms(&mut self, arms: &[Located<MatchArm>], exhaustive: bool, location: Location) {
+        let mut rows: Vec<Vec<Shape>> = vec![];
+        for arm in arms {
+            // Patterns with errors have been reported already and say nothing about coverage.
+            let Some(shape) = self.shape(&arm.data.pattern.data) else { return };
+            if !self.useful(&rows, std::slice::from_ref(&shape)) {
+                self.diagnostics.push(Diagnostic::new(DiagnosticCode::UnreachableCode, String::from("unreachable match arm"), arm.data.pattern.location)
+                    .with_note(String::from("the arms before it already match every value it could")));
+            }
+            if arm.data.guard.is_none() {
+                rows.push(vec![shape]);
+            }
+        }
+        if !exhaustive || !self.useful(&rows, &[Shape::Any]) { return; }
+
+        let signature = rows.iter().find_map(|row| match &row[0] {
+            Shape::Constructor(constructor, _) => self.signature(constructor),
+            Shape::Any => None
+        });
+        let note = match signature {
+            Some(signature) => {
+                let missing = signature.into_iter()
+                    .filter(|(constructor, arity)| self.useful(&rows, &[Shape::Constructor(constructor.clone(), vec![Shape::Any; *arity])]))
+                    .map(|(constructor, arity)| self.describe(&constructor, arity))
+                    .collect::<Vec<String>>();
+                format!("not covered: {}", missing.join(", "))
+            }
+            None => String::from("add a '_' arm to match any other value")
+        };
+        self.diagnostics.push(Diagnostic::new(DiagnosticCode::NonExhaustiveMatch, String::from("match doesn't cover every value"), location)
+            .with_note(note));
+    }
+
+    /// What exhaustiveness checking needs to know about a pattern, or `None` if it has errors.
+    fn shape(&self, pattern: &Pattern) -> Option<Shape> {
+        let variant = |enum_name: Option<&str>, variant: &str, fields: Option<&[Located<Pattern>]>| {
+            let [found] = self.variant_candidates(enum_name, variant)[..] else { return None };
+            let arity = self.enums[found.0].variants[found.1].1;
+            let fields = match fields {
+                Some(fields) if fields.len() != arity => return None,
+                Some(fields) => fields.iter().map(|field| self.shape(&field.data)).collect::<Option<Vec<Shape>>>()?,
+                None => vec![Shape::Any; arity]
+            };
+            Some(Shape::Constructor(Constructor::Variant(found.0, found.1), fields))
+        };
+        match pattern {
+            Pattern::Wildcard => Some(Shape::Any),
+            Pattern::Name(name) if self.is_variant(name) => variant(None, name, None),
+            Pattern::Name(_) => Some(Shape::Any),
+            Pattern::Constant(Constant::Boolean(value)) => Some(Shape::Constructor(Constructor::Boolean(*value), vec![])),
+            Pattern::Constant(constant) => Some(Shape::Constructor(Constructor::Constant(constant.clone()), vec![])),
+            Pattern::Variant { enum_name, variant: name, fields } => variant(enum_name.as_ref().map(|name| name.data.as_str()), &name.data, fields.as_deref())
+        }
+    }
+
+    /// Every constructor of the type `constructor` belongs to, with their payload sizes, or `None`
+    /// when there are too many to list, as for numbers and strings.
+    fn signature(&self, constructor: &Constructor) -> Option<Vec<(Constructor, usize)>> {
+        match constructor {
+            Constructor::Variant(enum_index, _) => Some(self.enums[*enum_index].variants.iter().enumerate()
+                .map(|(variant, (_, arity))| (Constructor::Variant(*enum_index, variant), *arity))
+                .collect()),
+            Constructor::Boolean(_) => Some(vec![(Constructor::Boolean(true), 0), (Constructor::Boolean(false), 0)]),
+            Constructor::Constant(_) => None
+        }
+    }
+
+    fn describe(&self, constructor: &Constructor, arity: usize) -> String {
+        match constructor {
+            Constructor::Variant(enum_index, variant) => {
+                let (path, _, _) = self.variant_path((*enum_index, *variant));
+                if arity > 0 { format!("{}(..)", path) } else { path }
+            }
+            Constructor::Boolean(value) => value.to_string(),
+            Constructor::Constant(constant) => constant.to_string()
+        }
+    }
+
+    /// Whether some value matched by `vector` isn't matched by any of `rows`, following Maranget's
+    /// usefulness algorithm. A match is exhaustive when a wildcard isn't useful after its arms.
+    fn useful(&self, rows: &[Vec<Shape>], vector: &[Shape]) -> bool {
+        let Some((head, rest)) = vector.split_first() else { return rows.is_empty() };
+        let specialize = |constructor: &Constructor, arity: usize| rows.iter().filter_map(|row| match &row[0] {
+            Shape::Constructor(other, fields) if other == constructor => Some(fields.iter().chain(&row[1..]).cloned().collect()),
+            Shape::Constructor(..) => None,
+            Shape::Any => Some(std::iter::repeat_n(Shape::Any, arity).chain(row[1..].iter().cloned()).collect())
+        }).collect::<Vec<Vec<Shape>>>();
+
+        match head {
+            Shape::Constructor(constructor, fields) => {
+                let vector = fields.iter().chain(rest).cloned().collect::<Vec<Shape>>();
+                self.useful(&specialize(constructor, fields.len()), &vector)
+            }
+            Shape::Any => {
+                let heads = rows.iter().filter_map(|row| match &row[0] {
+                    Shape::Constructor(constructor, _) => Some(constructor),
+                    Shape::Any => None
+                }).collect::<Vec<&Constructor>>();
+                let complete = heads.first().and_then(|first| self.signature(first))
+                    .filter(|signature| signature.iter().all(|(constructor, _)| heads.contains(&constructor)));
+                match complete {
+                    Some(signature) => signature.iter().any(|(constructor, arity)| {
+                        let vector = std::iter::repeat_n(Shape::Any, *arity).chain(rest.iter().cloned()).collect::<Vec<Shape>>();
+                        self.useful(&specialize(constructor, *arity), &vector)
+                    }),
+                    None => {
+                        let defaults = rows.iter().filter(|row| matches!(row[0], Shape::Any)).map(|row| row[1..].to_vec()).collect::<Vec<Vec<Shape>>>();
+                        self.useful(&defaults, rest)
+                    }
+                }
+            }
+        }
+    }
+
+    /// Emits the `LoadFunction` for a call to a global, picking the overload that accepts `count` arguments.
+    fn function_reference(&mut self, state: &mut FunctionState, name: &str, count: usize, location: Location) {
+        let candidates = self.globals.iter().enumerate().filter(|(_, global)| global.name == name).collect::<Vec<(usize, &Global)>>();
+        match candidates.iter().find(|(_, global)| global.accepts(count)) {
+            Some((index, _)) => { state.emit(LoadFunction, *index as u16, location); }
+            None if candidates.is_empty() => {
+                let mut diagnostic = Diagnostic::new(DiagnosticCode::UndefinedFunction, format!("call to undefined function '{}'", name), location);
+                if let Some(similar) = similar_name(name, self.globals.iter().map(|global| global.name.as_str())) {
+                    diagnostic = diagnostic.with_note(format!("there's a similar function named '{}'", similar));
+                }
+                self.diagnostics.push(diagnostic);
+            }
+            None => {
+                let (_, global) = candidates[0];
+                let message = format!("function '{}' expects {} argument(s) but {} were given", name, global.arguments.len(), count);
+                let mut diagnostic = Diagnostic::new(DiagnosticCode::ArgumentCount, message, location);
+                if let Some(definition) = global.location {
+                    diagnostic = diagnostic.with_label(definition, "function defined here");
+                }
+                self.diagnostics.push(diagnostic);
+            }
+        }
+    }
+
+    fn operator(&mut self, state: &mut FunctionState, operator: LBOperator, location: Location) {
+        let (instruction, arg) = match operator {
+            LBOperator::Plus => (BinaryAdd, 0),
+            LBOperator::Minus => (BinarySubtract, 0),
+            LBOperator::Multiply => (BinaryMultiply, 0),
+            LBOperator::Divide => (BinaryDivide, 0),
+            LBOperator::Power => (BinaryPower, 0),
+            LBOperator::Modulo => (BinaryModulo, 0),
+            LBOperator::Or => (BinaryOr, 0),
+            LBOperator::And => (BinaryAnd, 0),
+            LBOperator::Xor => (BinaryXor, 0),
+            LBOperator::LShift => (BinaryLShift, 0),
+            LBOperator::RShift => (BinaryRShift, 0),
+            LBOperator::Equals => (Equality(0), 0),
+            LBOperator::NotEquals => (Equality(0), 1),
+            LBOperator::GreaterThan => (Equality(0), 2),
+            LBOperator::LessThan => (Equality(0), 3),
+            LBOperator::GreaterThanOrEqual => (Equality(0), 4),
+            LBOperator::LessThanOrEqual => (Equality(0), 5),
+            other => {
+                self.error(DiagnosticCode::InvalidOperator, format!("'{:?}' is not a binary operator", other), location);
+                return;
+            }
+        };
+        state.emit(instruction, arg, location);
+    }
+
+    /// Compiles a lambda into its own function and returns its index in the global table, along
+    /// with whether it uses variables of `outer`, in which case it has to be made into a closure
+    /// carrying the upvalues of the call creating it.
+    fn lambda(&mut self, outer: &mut FunctionState, parameters: &[Located<Parameter>], body: &LambdaBody, location: Location) -> (u16, bool) {
+        let name = format!("<lambda:{}>", location);
+        let mut state = FunctionState::new(name.clone(), parameters, location);
+        state.enclosing = outer.visible();
+        state.inherited = outer.upvalues;
+        state.upvalues = outer.upvalues;
+        self.parameters(&mut state, parameters);
+        match body {
+            LambdaBody::Expression(expression) => {
+                state.capture(captured_in_expression(expression), location);
+                self.expression(&mut state, expression);
+                state.emit(Return, 0, expression.location);
+            }
+            LambdaBody::Block(block) => self.body(&mut state, block)
+        }
+        self.unused_variables(&state);
+        for upvalue in &state.referenced {
+            match outer.cells.iter().find(|(_, cell)| *cell == upvalue) {
+                Some((slot, _)) => outer.used[*slot as usize] = true,
+                None => { outer.referenced.insert(*upvalue); }
+            }
+        }
+
+        let types = parameters.iter().map(|parameter| parameter.data.type_name.data.leblanc_type()).collect::<Vec<LeBlancType>>();
+        self.globals.push(Global { name, arguments: LeBlancArgument::from_positional(&types), location: Some(location) });
+        let closes = !state.referenced.is_empty();
+        let lambda = self.finish(state);
+        self.lambdas.push(lambda);
+        ((self.globals.len() - 1) as u16, closes)
+    }
+}
+
+/// A pattern reduced to the values it matches, for exhaustiveness checking.
+#[derive(Clone)]
+enum Shape {
+    /// A wildcard or a binding.
+    Any,
+    Constructor(Constructor, Vec<Shape>)
+}
+
+#[derive(Clone, PartialEq)]
+enum Constructor {
+    Variant(usize, usize),
+    Boolean(bool),
+    Constant(Constant)
+}
+
+/// A `try` being compiled: its `finally`, which a `return` has to run on the way out, and the
+/// instruction ranges its handlers must not cover.
+#[derive(Clone)]
+struct TryFrame {
+    finally: Option<Block>,
+    holes: Vec<(u16, u16)>
+}
+
+/// The parameters of a method after the optional leading `Self`, which stands for the object the
+/// method is called on and is always passed.
+fn receiver_parameters(function: &Function) -> &[Located<Parameter>] {
+    match function.parameters.first() {
+        Some(first) if first.data.type_name.data.name == "Self" => &function.parameters[1..],
+        _ => &function.parameters
+    }
+}
+
+/// Per-function state: the constant pool, the local slots handed out so far and the scopes that
+/// map names onto them. Every declaration gets its own slot, so a variable declared in an inner
+/// block never overwrites one of the same name in an enclosing block.
+struct FunctionState {
+    name: String,
+    arguments: Vec<LeBlancType>,
+    constants: Vec<Constant>,
+    variables: Vec<String>,
+    /// Where each slot was declared and whether it is ever read, indexed like `variables`.
+    locations: Vec<Location>,
+    used: Vec<bool>,
+    scopes: Vec<Vec<(String, u32)>>,
+    instructions: Vec<Instruction>,
+    handlers: Vec<ExceptionHandler>,
+    /// Jump tables of `match` and `when`, stored after the other constants.
+    tables: Vec<JumpTable>,
+    frames: Vec<TryFrame>,
+    /// Values left on the stack by the statements being compiled, i.e. the iterators of enclosing `for` loops.
+    depth: u16,
+    /// The class or trait a method belongs to, whose instance is the receiver in slot 0.
+    owner: Option<Owner>,
+    /// Names the lambdas of the function refer to. Variables with these names live in upvalues,
+    /// which the lambdas share with the call creating them, instead of in slots.
+    captured: FxHashSet<String>,
+    /// The upvalue of each captured slot.
+    cells: FxHashMap<u32, u16>,
+    /// The upvalues of enclosing functions in scope, by name, innermost last.
+    enclosing: Vec<(String, u16)>,
+    /// Upvalues `0..inherited` come from the enclosing functions, the function's own follow.
+    inherited: u16,
+    upvalues: u16,
+    /// The inherited upvalues used here or by nested lambdas, which a closure has to carry.
+    referenced: FxHashSet<u16>,
+    /// The type of each slot declared with a native type, or declared `flex` and given a value of
+    /// one, which is marked `true`. See `checked_type`.
+    types: FxHashMap<u32, (LeBlancType, bool)>,
+    /// The class of each slot declared with the type of a class in the file.
+    instances: FxHashMap<u32, usize>,
+    /// The trait the values of each slot declared with a trait object type must implement.
+    bounds: FxHashMap<u32, TraitBound>,
+    /// Where the function is declared, which is where it is reported if it gets too large.
+    location: Location,
+    /// The first index space that ran out of 16 bit indices, e.g. "constants".
+    outgrown: Option<&'static str>
+}
+
+/// Where a variable is kept.
+#[derive(Clone, Copy)]
+enum Variable {
+    Slot(u32),
+    Upvalue(u16)
+}
+
+impl FunctionState {
+    fn new(name: String, parameters: &[Located<Parameter>], location: Location) -> FunctionState {
+        FunctionState {
+            name,
+            arguments: parameters.iter().map(|parameter| parameter.data.type_name.data.leblanc_type()).collect(),
+            constants: vec![],
+            variables: vec![],
+            locations: vec![],
+            used: vec![],
+            scopes: vec![vec![]],
+            instructions: vec![],
+            handlers: vec![],
+            tables: vec![],
+            frames: vec![],
+            depth: 0,
+            owner: None,
+            captured: FxHashSet::default(),
+            cells: FxHashMap::default(),
+            enclosing: vec![],
+            inherited: 0,
+            upvalues: 0,
+            referenced: FxHashSet::default(),
+            types: FxHashMap::default(),
+            instances: FxHashMap::default(),
+            bounds: FxHashMap::default(),
+            location,
+            outgrown: None
+        }
+    }
+
+    /// A method of `owner`, which takes the object it is called on as its first argument, `self`.
+    fn method(name: String, owner: Owner, parameters: &[Located<Parameter>], location: Location) -> FunctionState {
+        let mut state = FunctionState::new(name, parameters, location);
+        state.arguments.insert(0, LeBlancType::SelfType);
+        state.owner = Some(owner);
+        let receiver = state.declare(&Ident::new(String::from("self"), location));
+        state.used[receiver as usize] = true;
+        // `Self` names the object the method is called on, just like `self`.
+        state.scopes[0].push((String::from("Self"), receiver));
+        state
+    }
+
+    fn declare(&mut self, name: &Ident) -> u32 {
+        let slot = self.variables.len() as u32;
+        self.variables.push(name.data.clone());
+        self.locations.push(name.location);
+        self.used.push(false);
+        self.scopes.last_mut().unwrap().push((name.data.clone(), slot));
+        if self.captured.contains(&name.data) {
+            self.cells.insert(slot, self.upvalues);
+            self.upvalues += 1;
+        }
+        slot
+    }
+
+    /// Moves the variables named in `captured` into upvalues: the arguments, already declared,
+    /// right away and every other variable as it is declared.
+    fn capture(&mut self, captured: FxHashSet<String>, location: Location) {
+        let arguments = self.scopes[0].iter().filter(|(name, _)| captured.contains(name)).map(|(_, slot)| *slot).collect::<Vec<u32>>();
+        for slot in arguments {
+            if self.cells.contains_key(&slot) { continue; }
+            self.cells.insert(slot, self.upvalues);
+            self.upvalues += 1;
+            let index = self.slot(slot);
+            self.emit(LoadLocal, index, location);
+            self.bind(slot, location);
+        }
+        self.captured = captured;
+    }
+
+    /// Stores the value on top of the stack into a newly declared variable. A captured variable
+    /// gets a new upvalue each time, so closures created in earlier iterations of a loop keep theirs.
+    fn bind(&mut self, slot: u32, location: Location) {
+        match self.cells.get(&slot).copied() {
+            Some(upvalue) => self.emit(MakeUpvalue, upvalue, location),
+            None => {
+                let index = self.slot(slot);
+                self.emit(StoreLocal, index, location)
+            }
+        };
+    }
+
+    fn load(&mut self, variable: Variable, location: Location) {
+        match variable {
+            Variable::Slot(slot) => {
+                self.used[slot as usize] = true;
+                match self.cells.get(&slot).copied() {
+                    Some(upvalue) => self.emit(LoadUpvalue, upvalue, location),
+                    None => {
+                        let index = self.slot(slot);
+                        self.emit(LoadLocal, index, location)
+                    }
+                };
+            }
+            Variable::Upvalue(upvalue) => { self.emit(LoadUpvalue, upvalue, location); }
+        }
+    }
+
+    fn store(&mut self, variable: Variable, location: Location) {
+        match variable {
+            Variable::Slot(slot) => match self.cells.get(&slot).copied() {
+                Some(upvalue) => self.emit(StoreUpvalue, upvalue, location),
+                None => {
+                    let index = self.slot(slot);
+                    self.emit(StoreLocal, index, location)
+                }
+            },
+            Variable::Upvalue(upvalue) => self.emit(StoreUpvalue, upvalue, location)
+        };
+    }
+
+    /// The upvalue of an enclosing function's variable named `name`, recording that it is used.
+    fn upvalue(&mut self, name: &str) -> Option<u16> {
+        let upvalue = self.enclosing.iter().rev().find(|(declared, _)| declared == name).map(|(_, upvalue)| *upvalue)?;
+        self.referenced.insert(upvalue);
+        Some(upvalue)
+    }
+
+    fn is_variable(&self, name: &str) -> bool {
+        self.lookup(name).is_some() || self.enclosing.iter().any(|(declared, _)| declared == name)
+    }
+
+    /// The upvalues a lambda compiled at this point can use.
+    fn visible(&self) -> Vec<(String, u16)> {
+        let own = self.scopes.iter().flatten().filter_map(|(name, slot)| self.cells.get(slot).map(|upvalue| (name.clone(), *upvalue)));
+        self.enclosing.iter().cloned().chain(own).collect()
+    }
+
+    /// A slot for a value the compiler keeps around, e.g. the value being matched. Its name can't be
+    /// written in source, so it is never looked up by accident.
+    fn temporary(&mut self, location: Location) -> u16 {
+        let slot = self.declare(&Ident::new(String::from("<match>"), location));
+        self.used[slot as usize] = true;
+        self.slot(slot)
+    }
+
+    fn lookup(&self, name: &str) -> Option<u32> {
+        self.scopes.iter().rev().flat_map(|scope| scope.iter().rev()).find(|(declared, _)| declared == name).map(|(_, slot)| *slot)
+    }
+
+    fn constant(&mut self, constant: Constant) -> u16 {
+        let index = match self.constants.iter().position(|existing| *existing == constant && existing.leblanc_type().enum_id() == constant.leblanc_type().enum_id()) {
+            Some(index) => index,
+            None => {
+                self.constants.push(constant);
+                self.constants.len() - 1
+            }
+        };
+        self.index(index, "constants")
+    }
+
+    /// `slot` as the argument of an instruction.
+    fn slot(&mut self, slot: u32) -> u16 {
+        self.index(slot as usize, "local variables")
+    }
+
+    /// The index of the instruction at `index`, as the target of a jump or the bounds of a handler.
+    fn address(&mut self, index: usize) -> u16 {
+        self.index(index, "instructions")
+    }
+
+    /// `index` as the argument of an instruction. An index past what 16 bits hold marks `space` as
+    /// outgrown, which is reported once the function is finished.
+    fn index(&mut self, index: usize, space: &'static str) -> u16 {
+        u16::try_from(index).unwrap_or_else(|_| {
+            self.outgrown.get_or_insert(space);
+            u16::MAX
+        })
+    }
+
+    /// Appends an instruction and returns its index so jumps can be patched once their target is known.
+    fn emit(&mut self, instruct: InstructionBase, arg: u16, location: Location) -> usize {
+        self.instructions.push(Instruction::new(instruct, arg, location.line as u32, location.column as u16));
+        self.instructions.len() - 1
+    }
+
+    fn next_index(&mut self) -> u16 {
+        self.address(self.instructions.len())
+    }
+
+    /// Points the jump at `index` to the next instruction to be emitted.
+    fn patch(&mut self, index: usize) {
+        self.instructions[index].arg = self.next_index();
+    }
+
+    /// Adds handler entries jumping to `target` for `start..end`, split around `holes`.
+    fn cover(&mut self, start: u16, end: u16, holes: &[(u16, u16)], target: u16, exception: &str) {
+        let mut holes = holes.to_vec();
+        holes.sort_unstable();
+        let mut from = start;
+        for (hole_start, hole_end) in holes.into_iter().chain([(end, end)]) {
+            if hole_start > from && from < end {
+                self.handlers.push(ExceptionHandler::new(from, hole_start.min(end), target, self.depth, exception.to_string()));
+            }
+            from = from.max(hole_end);
+        }
+    }
+
+    fn bytecode(self) -> FunctionBytecode {
+        let mut bytecode = FunctionBytecode::new();
+        bytecode.set_name(self.name);
+        for argument in self.arguments {
+            bytecode.add_argument(argument);
+        }
+        for constant in &self.constants {
+            bytecode.add_constant(constant_bytes(constant), constant.leblanc_type().enum_id() as u16);
+        }
+        for table in &self.tables {
+            bytecode.add_constant(table.to_hex(), LeBlancType::Marker.enum_id() as u16);
+        }
+        for (slot, variable) in self.variables.into_iter().enumerate() {
+            bytecode.add_variable(variable, slot as u32);
+        }
+
+        let mut line: Option<(u32, InstructionBytecode)> = None;
+        let first_table = self.constants.len() as u16;
+        for mut instruction in self.instructions {
+            if instruction.instruct == InstructionBase::JumpTable {
+                instruction.arg += first_table;
+            }
+            match &mut line {
+                Some((number, _)) if *number == instruction.line_number => {}
+                _ => {
+                    if let Some((_, mut bytes)) = line.take() {
+                        bytecode.add_instruction_line(bytes.generate());
+                    }
+                    let mut bytes = InstructionBytecode::new();
+                    bytes.set_line_number(instruction.line_number);
+                    line = Some((instruction.line_number, bytes));
+                }
+            }
+            let (_, bytes) = line.as_mut().unwrap();
+            bytes.add_instruction(instruction.instruct.to_hex(2), instruction.arg.to_hex(2), instruction.column.to_hex(2));
+        }
+        if let Some((_, mut bytes)) = line {
+            bytecode.add_instruction_line(bytes.generate());
+        }
+        for handler in &self.handlers {
+            bytecode.add_handler(handler);
+        }
+        bytecode
+    }
+}
+
+/// Names for the disassembly of a function that is still being compiled.
+struct Listing<'a> {
+    state: &'a FunctionState,
+    globals: &'a [Global]
+}
+
+impl Symbols for Listing<'_> {
+    fn local(&self, slot: u16) -> String {
+        self.state.variables.get(slot as usize).cloned().unwrap_or_else(|| "?".to_string())
+    }
+
+    fn constant(&self, index: u16) -> String {
+        self.state.constants[index as usize].to_string()
+    }
+
+    fn table(&self, index: u16) -> String {
+        self.state.tables[index as usize].to_string()
+    }
+
+    fn function(&self, index: u16) -> String {
+        self.globals.get(index as usize).map_or_else(|| "?".to_string(), |global| global.name.clone())
+    }
+
+    fn instance(&self, index: u16) -> String {
+        self.function(index)
+    }
+}
+
+/// Whether running `statements` always ends in a `return` or a `throw`.
+fn diverges(statements: &[Statement]) -> bool {
+    statements.iter().any(|statement| match &statement.data {
+        Stmt::Return(_) | Stmt::Throw(_) => true,
+        Stmt::Conditional { branches, otherwise: Some(otherwise) } => {
+            branches.iter().all(|(_, body)| diverges(&body.data)) && diverges(&otherwise.data)
+        }
+        Stmt::Try { body, handlers, finally } => {
+            finally.as_ref().is_some_and(|finally| diverges(&finally.data))
+                || (diverges(&body.data) && handlers.iter().all(|handler| diverges(&handler.data.body.data)))
+        }
+        _ => false
+    })
+}
+
+/// The key of an arm that can go in a jump table: one without a guard whose pattern is a constant.
+fn table_key(arm: &Located<MatchArm>) -> Option<TableKey> {
+    if arm.data.guard.is_some() { return None; }
+    match &arm.data.pattern.data {
+        Pattern::Constant(Constant::Integer(value, _)) => Some(TableKey::Integer(*value)),
+        Pattern::Constant(Constant::Char(value)) => Some(TableKey::Char(*value)),
+        Pattern::Constant(Constant::Boolean(value)) => Some(TableKey::Boolean(*value)),
+        Pattern::Constant(Constant::String(value)) => Some(TableKey::String(value.clone())),
+        _ => None
+    }
+}
+
+/// `typing` if the compiler checks what values variables declared with it are given: the native
+/// types a literal can have, and the collections.
+fn checked_type(typing: LeBlancType) -> Option<LeBlancType> {
+    let checked = typing.is_numeric() || matches!(typing, LeBlancType::Char | LeBlancType::String
+        | LeBlancType::Derived(DerivedType::List | DerivedType::Dict | DerivedType::Tuple | DerivedType::Set));
+    checked.then_some(typing)
+}
+
+/// The type `value` is known to have before it runs: that of a literal or of a variable whose
+/// type is checked.
+fn static_type(state: &FunctionState, value: &Expr) -> Option<LeBlancType> {
+    match value {
+        Expr::Constant(constant) => Some(constant.leblanc_type()),
+        Expr::Unary { operator: LBOperator::Minus, operand } => match &operand.data {
+            Expr::Constant(constant) => Some(constant.leblanc_type()),
+            _ => None
+        },
+        Expr::Ident(name) => state.lookup(name).and_then(|slot| state.types.get(&slot)).map(|(typing, _)| *typing),
+        Expr::List(_) => Some(LeBlancType::Derived(DerivedType::List)),
+        Expr::Dict(_) => Some(LeBlancType::Derived(DerivedType::Dict)),
+        Expr::Tuple(_) => Some(LeBlancType::Derived(DerivedType::Tuple)),
+        Expr::Set(_) => Some(LeBlancType::Derived(DerivedType::Set)),
+        Expr::Block(_) => Some(LeBlancType::Block),
+        _ => None
+    }
+}
+
+/// Whether a variable of the checked type `declared` can hold a value of type `actual`: one of the
+/// same type, a narrower number or null.
+fn assignable(declared: LeBlancType, actual: LeBlancType) -> bool {
+    use LeBlancType::*;
+    let integer = |typing: LeBlancType| [Short, Int, Int64, Int128, BigInt].iter().position(|integer| *integer == typing);
+    if actual == Null || actual == declared { return true; }
+    match (integer(actual), integer(declared)) {
+        (Some(actual), Some(declared)) => actual <= declared,
+        (Some(_), None) => matches!(declared, Float | Double | Decimal),
+        (None, _) => matches!((actual, declared), (Float, Double) | (Float | Double, Decimal))
+    }
+}
+
+/// A numeric literal, with its sign.
+fn literal(value: &Expr) -> Option<Constant> {
+    match value {
+        Expr::Constant(constant) => Some(constant.clone()),
+        Expr::Unary { operator: LBOperator::Minus, operand } => match &operand.data {
+            Expr::Constant(Constant::Integer(value, typing)) => Some(Constant::Integer(-value, *typing)),
+            Expr::Constant(Constant::Decimal(value, typing)) => Some(Constant::Decimal(-value, *typing)),
+            Expr::Constant(Constant::BigInt(value)) => Some(Constant::BigInt(-value)),
+            Expr::Constant(Constant::BigDecimal(value)) => Some(Constant::BigDecimal(-value.clone())),
+            _ => None
+        },
+        _ => None
+    }
+}
+
+/// `literal` as a constant of the numeric type `typing`, or why it can't be one. `None` when
+/// either isn't a number, or the literal's type can't be converted to `typing` at all.
+fn coerce(literal: &Constant, typing: LeBlancType) -> Result<Option<Constant>, String> {
+    let fits = |fits: bool, constant: Constant| match fits {
+        true => Ok(Some(constant)),
+        false => Err(format!("{} does not fit in {}", literal, typing))
+    };
+    match (literal, typing) {
+        (Constant::Integer(value, _), LeBlancType::Short) => fits(i16::try_from(*value).is_ok(), Constant::Integer(*value, typing)),
+        (Constant::Integer(value, _), LeBlancType::Int) => fits(i32::try_from(*value).is_ok(), Constant::Integer(*value, typing)),
+        (Constant::Integer(value, _), LeBlancType::Int64) => fits(i64::try_from(*value).is_ok(), Constant::Integer(*value, typing)),
+        (Constant::Integer(value, _), LeBlancType::Arch) => fits(usize::try_from(*value).is_ok(), Constant::Integer(*value, typing)),
+        (Constant::Integer(value, _), LeBlancType::Int128) => Ok(Some(Constant::Integer(*value, typing))),
+        (Constant::Integer(value, _), LeBlancType::BigInt) => Ok(Some(Constant::BigInt(BigInt::from(*value)))),
+        (Constant::Integer(value, _), LeBlancType::Decimal) => Ok(Some(Constant::BigDecimal(LeblancDecimal::from_integer(BigInt::from(*value))))),
+        (Constant::Integer(value, _), LeBlancType::Float) => fits((*value as f32).is_finite(), Constant::Decimal(*value as f64, typing)),
+        (Constant::Integer(value, _), LeBlancType::Double) => Ok(Some(Constant::Decimal(*value as f64, typing))),
+        (Constant::Decimal(value, _), LeBlancType::Float) => fits((*value as f32).is_finite(), Constant::Decimal(*value, typing)),
+        (Constant::Decimal(value, _), LeBlancType::Double) => Ok(Some(Constant::Decimal(*value, typing))),
+        (Constant::Decimal(value, _), LeBlancType::Decimal) => Ok(LeblancDecimal::parse(&value.to_string()).map(Constant::BigDecimal)),
+        (Constant::BigInt(value), LeBlancType::Decimal) => Ok(Some(Constant::BigDecimal(LeblancDecimal::from_integer(value.clone())))),
+        _ => Ok(None)
+    }
+}
+
+/// A tuple or set literal made only of constants, as the constant it always evaluates to.
+fn folded(expression: &Expr) -> Option<Constant> {
+    let values = |items: &[Expression]| items.iter().map(|item| match &item.data {
+        Expr::Constant(constant) => Some(constant.clone()),
+        nested => folded(nested)
+    }).collect::<Option<Vec<Constant>>>();
+    match expression {
+        Expr::Tuple(items) => values(items).map(Constant::Tuple),
+        Expr::Set(items) => values(items).map(Constant::Set),
+        _ => None
+    }
+}
+
+/// Tuples and sets hold the type, length and bytes of each value in turn.
+fn constant_bytes(constant: &Constant) -> Hexadecimal {
+    match constant {
+        Constant::Integer(value, typing) => typing.transform(value.to_string()),
+        Constant::Decimal(value, typing) => typing.transform(value.to_string()),
+        Constant::BigInt(value) => value.to_hex(0),
+        Constant::BigDecimal(value) => value.to_hex(0),
+        Constant::String(value) => value.to_hex(128),
+        Constant::Char(value) => value.to_hex(128),
+        Constant::Boolean(value) => value.to_hex(128),
+        Constant::Null => Hexadecimal::empty(),
+        Constant::Tuple(values) | Constant::Set(values) => {
+            let mut hex = Hexadecimal::empty();
+            for value in values {
+                let bytes = constant_bytes(value);
+                hex += (value.leblanc_type().enum_id() as u16).to_hex(2) + (bytes.len() as u32).to_hex(4) + bytes;
+            }
+            hex
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::leblanc::compiler::compile::compile_source;
+    use crate::leblanc::compiler::diagnostic::DiagnosticCode;
+
+    /// The diagnostics of a `main` made of `statements` statements, the `i`th written by `statement`.
+    fn oversized(statements: usize, statement: fn(usize) -> String) -> Vec<(DiagnosticCode, String)> {
+        let body = (0..statements).map(statement).collect::<String>();
+        let (bytecode, diagnostics) = compile_source(&format!("func main() {{\n    int x = 0;\n{}    print(x);\n}}\n", body), "<test>");
+        assert!(bytecode.is_none());
+        diagnostics.into_iter().map(|diagnostic| (diagnostic.code, diagnostic.message)).collect()
+    }
+
+    #[test]
+    fn too_many_instructions_is_reported() {
+        let diagnostics = oversized(40_000, |_| String::from("    x = 1;\n"));
+        assert_eq!(diagnostics, vec![(DiagnosticCode::FunctionTooLarge, String::from("function 'main' needs more than 65536 instructions"))]);
+    }
+
+    #[test]
+    fn too_many_constants_is_reported() {
+        let diagnostics = oversized(70_000, |i| format!("    x = {};\n", i));
+        assert_eq!(diagnostics, vec![(DiagnosticCode::FunctionTooLarge, String::from("function 'main' needs more than 65536 constants"))]);
+    }
+
+    #[test]
+    fn extensions_cannot_replace_native_methods() {
+        let (bytecode, diagnostics) = compile_source("Extension Bad of string {\n    func to_string(Self) returns string {\n        return \"x\";\n    }\n}\n\nfunc main() {\n    print(\"a\".to_string());\n}\n", "<test>");
+        assert!(bytecode.is_none());
+        assert_eq!(diagnostics.into_iter().map(|diagnostic| (diagnostic.code, diagnostic.message)).collect::<Vec<_>>(), vec![
+            (DiagnosticCode::InvalidExtension, String::from("method 'to_string' of extension 'Bad' conflicts with the native method 'to_string' of string"))
+        ]);
+    }
+
+    #[test]
+    fn matches_missing_a_variant_are_warned_about() {
+        let (bytecode, diagnostics) = compile_source("Enum Shape { Circle(int), Empty }\n\nfunc main() {\n    Shape shape = Shape::Empty;\n    print(match shape { Circle(r) => r });\n}\n", "<test>");
+        assert!(bytecode.is_some());
+        assert_eq!(diagnostics.into_iter().map(|diagnostic| (diagnostic.code, diagnostic.message)).collect::<Vec<_>>(), vec![
+            (DiagnosticCode::NonExhaustiveMatch, String::from("match doesn't cover every value"))
+        ]);
+    }
+
+    #[test]
+    fn guard_arms_have_to_diverge() {
+        let (bytecode, diagnostics) = compile_source("func main() {\n    int x = 1;\n    int y = guard { x > 0 => print(\"no\"), else => 1 };\n    print(y);\n}\n", "<test>");
+        assert!(bytecode.is_none());
+        assert_eq!(diagnostics.into_iter().map(|diagnostic| (diagnostic.code, diagnostic.message)).collect::<Vec<_>>(), vec![
+            (DiagnosticCode::GuardFallthrough, String::from("guard arm doesn't leave the function"))
+        ]);
+    }
+
+    #[test]
+    fn sets_are_only_given_sets() {
+        let (bytecode, diagnostics) = compile_source("func main() {\n    Set<int> s = [1];\n    Set<int> t = {\"a\": 1};\n    print(s, t);\n}\n", "<test>");
+        assert!(bytecode.is_none());
+        assert_eq!(diagnostics.into_iter().map(|diagnostic| (diagnostic.code, diagnostic.message)).collect::<Vec<_>>(), vec![
+            (DiagnosticCode::IncompatibleType, String::from("cannot assign a value of type list to 's', which is declared set")),
+            (DiagnosticCode::IncompatibleType, String::from("cannot assign a value of type dict to 't', which is declared set"))
+        ]);
+    }
+
+    #[test]
+    fn trait_objects_need_implementing_classes() {
+        let source = "Trait Fruit {\n    func color() returns string;\n}\n\nClass Rock {}\n\nfunc main() {\n    Fruit! bad = 5;\n    Rock rock = Rock();\n    Fruit! again = rock;\n    List<Fruit!> fruits = [Rock()];\n    print(bad, again, fruits);\n}\n";
+        let (bytecode, diagnostics) = compile_source(source, "<test>");
+        assert!(bytecode.is_none());
+        assert_eq!(diagnostics.into_iter().map(|diagnostic| (diagnostic.code, diagnostic.message)).collect::<Vec<_>>(), vec![
+            (DiagnosticCode::IncompatibleType, String::from("a value of type int does not implement trait 'Fruit'")),
+            (DiagnosticCode::IncompatibleType, String::from("class 'Rock' does not implement trait 'Fruit'")),
+            (DiagnosticCode::IncompatibleType, String::from("class 'Rock' does not implement trait 'Fruit'"))
+        ]);
+    }
+
+    #[test]
+    fn blocks_are_not_dicts() {
+        let (bytecode, diagnostics) = compile_source("func main() {\n    Dict<string, int> d = { print(1); };\n    print(d);\n}\n", "<test>");
+        assert!(bytecode.is_none());
+        assert_eq!(diagnostics.into_iter().map(|diagnostic| (diagnostic.code, diagnostic.message)).collect::<Vec<_>>(),
+                   vec![(DiagnosticCode::IncompatibleType, String::from("cannot assign a value of type block to 'd', which is declared dict"))]);
+    }
+}
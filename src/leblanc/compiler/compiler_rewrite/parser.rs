@@ -0,0 +1,906 @@
+use std::cell::Cell;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use lazy_static::lazy_static;
+use num::BigInt;
+use pest::error::{Error, ErrorVariant, InputLocation, LineColLocation};
+use pest::iterators::Pair;
+use pest::prec_climber::{Assoc, Operator, PrecClimber};
+
+use crate::leblanc::compiler::compiler_rewrite::ast::{Block, Class, Constant, Enum, Expr, Expression, Extension, Field, File, Function, GuardArm, Handler, Ident, Import, Item, LambdaBody, Located, Location, MatchArm, Parameter, Pattern, Statement, Stmt, Trait, TraitMethod, TypeName, Variant};
+use crate::leblanc::compiler::compiler_rewrite::lexer::{lex, Rule};
+use crate::leblanc::compiler::lang::leblanc_constants::constant_type;
+use crate::leblanc::compiler::lang::leblanc_operators::LBOperator;
+use crate::leblanc::core::native_types::decimal_type::LeblancDecimal;
+use crate::leblanc::core::native_types::LeBlancType;
+
+lazy_static! {
+    static ref CLIMBER: PrecClimber<Rule> = PrecClimber::new(vec![
+        Operator::new(Rule::or, Assoc::Left),
+        Operator::new(Rule::xor, Assoc::Left),
+        Operator::new(Rule::and, Assoc::Left),
+        Operator::new(Rule::equals, Assoc::Left) | Operator::new(Rule::not_equals, Assoc::Left),
+        Operator::new(Rule::less, Assoc::Left) | Operator::new(Rule::greater, Assoc::Left)
+            | Operator::new(Rule::less_equal, Assoc::Left) | Operator::new(Rule::greater_equal, Assoc::Left),
+        Operator::new(Rule::lshift, Assoc::Left) | Operator::new(Rule::rshift, Assoc::Left),
+        Operator::new(Rule::add, Assoc::Left) | Operator::new(Rule::subtract, Assoc::Left),
+        Operator::new(Rule::multiply, Assoc::Left) | Operator::new(Rule::divide, Assoc::Left) | Operator::new(Rule::modulo, Assoc::Left),
+        Operator::new(Rule::power, Assoc::Right),
+    ]);
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub message: String,
+    pub location: Location
+}
+
+impl ParseError {
+    pub fn new(message: String, location: Location) -> ParseError {
+        ParseError { message, location }
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.location, self.message)
+    }
+}
+
+impl From<Box<Error<Rule>>> for ParseError {
+    fn from(error: Box<Error<Rule>>) -> Self {
+        let error = *error;
+        let (start, end) = match error.location {
+            InputLocation::Pos(pos) => (pos, pos),
+            InputLocation::Span(span) => span
+        };
+        let (line, column) = match error.line_col {
+            LineColLocation::Pos(line_col) => line_col,
+            LineColLocation::Span(line_col, _) => line_col
+        };
+        let message = match error.variant {
+            ErrorVariant::ParsingError { positives, negatives } => {
+                if !positives.is_empty() {
+                    format!("expected {}", describe_rules(&positives))
+                } else {
+                    format!("unexpected {}", describe_rules(&negatives))
+                }
+            }
+            ErrorVariant::CustomError { message } => message
+        };
+        ParseError::new(message, Location::new(start, end, line, column))
+    }
+}
+
+/// How deeply parentheses, brackets and braces can nest. The grammar and the AST builder recurse
+/// several frames per level, so much deeper input would overflow the native stack.
+pub const MAX_NESTING: usize = 128;
+
+/// How many operators, casts and postfix operations an expression can nest through. A chain such
+/// as `a + a + ...` or `- - a` makes an AST as deep as it is long, which everything walking the
+/// AST recurses through.
+pub const MAX_EXPRESSION_DEPTH: usize = 1000;
+
+/// Parses a LeBlanc source file into its AST.
+pub fn parse(source: &str) -> Result<File, ParseError> {
+    check_nesting(source)?;
+    let file = lex(source)?.next().unwrap();
+    AstBuilder::new(source).file(file)
+}
+
+/// Rejects source whose brackets nest deeper than [`MAX_NESTING`], pointing at the first bracket
+/// past the limit. Brackets in comments, strings and chars are skipped.
+fn check_nesting(source: &str) -> ParseResult<()> {
+    let mut chars = source.char_indices().peekable();
+    let mut depth: usize = 0;
+    while let Some((index, ch)) = chars.next() {
+        match ch {
+            '(' | '[' | '{' => {
+                depth += 1;
+                if depth > MAX_NESTING {
+                    let line_start = source[..index].rfind('\n').map_or(0, |newline| newline + 1);
+                    let line = source[..index].matches('\n').count() + 1;
+                    let column = source[line_start..index].chars().count() + 1;
+                    return Err(ParseError::new(format!("brackets nest more than {} deep", MAX_NESTING),
+                                               Location::new(index, index + 1, line, column)));
+                }
+            }
+            ')' | ']' | '}' => depth = depth.saturating_sub(1),
+            '"' | '\'' => {
+                while let Some((_, next)) = chars.next() {
+                    if next == '\\' {
+                        chars.next();
+                    } else if next == ch {
+                        break;
+                    }
+                }
+            }
+            '/' if matches!(chars.peek(), Some((_, '/'))) => {
+                chars.by_ref().find(|(_, next)| *next == '\n');
+            }
+            '/' if matches!(chars.peek(), Some((_, '*'))) => {
+                chars.next();
+                let mut previous = ' ';
+                for (_, next) in chars.by_ref() {
+                    if previous == '*' && next == '/' { break; }
+                    previous = next;
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+type ParseResult<T> = Result<T, ParseError>;
+type Members = (Vec<Located<Field>>, Vec<Located<Function>>);
+
+struct AstBuilder<'a> {
+    source: &'a str,
+    line_starts: Vec<usize>,
+    /// The operators, casts and postfix operations enclosing the expression being built.
+    depth: Cell<usize>
+}
+
+impl<'a> AstBuilder<'a> {
+    fn new(source: &'a str) -> AstBuilder<'a> {
+        let mut line_starts = vec![0];
+        line_starts.extend(source.match_indices('\n').map(|(index, _)| index + 1));
+        AstBuilder { source, line_starts, depth: Cell::new(0) }
+    }
+
+    fn location(&self, pair: &Pair<Rule>) -> Location {
+        let span = pair.as_span();
+        let line = self.line_starts.partition_point(|start| *start <= span.start());
+        let column = self.source[self.line_starts[line - 1]..span.start()].chars().count() + 1;
+        Location::new(span.start(), span.end(), line, column)
+    }
+
+    /// Builds an expression nested `levels` deeper than the current one, or reports `pair` if that
+    /// is past [`MAX_EXPRESSION_DEPTH`].
+    fn nested<T>(&self, levels: usize, pair: &Pair<Rule>, build: impl FnOnce() -> ParseResult<T>) -> ParseResult<T> {
+        let depth = self.depth.get() + levels;
+        if depth > MAX_EXPRESSION_DEPTH {
+            return Err(ParseError::new(format!("expression nests more than {} deep", MAX_EXPRESSION_DEPTH), self.location(pair)));
+        }
+        self.depth.set(depth);
+        let result = build();
+        self.depth.set(depth - levels);
+        result
+    }
+
+    fn ident(&self, pair: Pair<Rule>) -> Ident {
+        Located::new(pair.as_str().to_string(), self.location(&pair))
+    }
+
+    fn file(&self, pair: Pair<Rule>) -> ParseResult<File> {
+        let mut items = vec![];
+        for pair in pair.into_inner() {
+            let location = self.location(&pair);
+            let item = match pair.as_rule() {
+                Rule::import => Item::Import(self.import(pair)),
+                Rule::function => Item::Function(self.function(pair)?),
+                Rule::class => Item::Class(self.class(pair)?),
+                Rule::trait_item => Item::Trait(self.trait_item(pair)?),
+                Rule::enum_item => Item::Enum(self.enum_item(pair)),
+                Rule::extension => Item::Extension(self.extension(pair)?),
+                _ => continue
+            };
+            items.push(Located::new(item, location));
+        }
+        Ok(File { items })
+    }
+
+    fn import(&self, pair: Pair<Rule>) -> Import {
+        let import = inner(pair).next().unwrap();
+        let mut parts = inner(import.clone());
+        match import.as_rule() {
+            Rule::extension_import => Import::Extension {
+                name: self.ident(parts.next().unwrap()),
+                file: self.ident(parts.next().unwrap())
+            },
+            _ => Import::Module(self.ident(parts.next().unwrap()))
+        }
+    }
+
+    fn function(&self, pair: Pair<Rule>) -> ParseResult<Function> {
+        let mut parts = inner(pair);
+        let name = self.ident(parts.next().unwrap());
+        let parameters = inner(parts.next().unwrap()).map(|parameter| self.parameter(parameter)).collect();
+        let mut returns = vec![];
+        let mut body = None;
+        for part in parts {
+            match part.as_rule() {
+                Rule::returns => returns = inner(part).map(|type_name| self.type_name(type_name)).collect(),
+                Rule::block => body = Some(self.block(part)?),
+                _ => {}
+            }
+        }
+        Ok(Function { name, parameters, returns, body })
+    }
+
+    fn parameter(&self, pair: Pair<Rule>) -> Located<Parameter> {
+        let location = self.location(&pair);
+        let mut parts = inner(pair);
+        let first = parts.next().unwrap();
+        let parameter = match first.as_rule() {
+            Rule::self_parameter => Parameter {
+                type_name: Located::new(TypeName::new("Self"), location),
+                name: self.ident(first)
+            },
+            Rule::type_name => Parameter { type_name: self.type_name(first), name: self.ident(parts.next().unwrap()) },
+            _ => {
+                let name = self.ident(first);
+                Parameter { type_name: Located::new(TypeName::flex(), name.location), name }
+            }
+        };
+        Located::new(parameter, location)
+    }
+
+    fn class(&self, pair: Pair<Rule>) -> ParseResult<Class> {
+        let mut parts = inner(pair).peekable();
+        let name = self.ident(parts.next().unwrap());
+        let mut supertypes = vec![];
+        if parts.peek().map(|part| part.as_rule()) == Some(Rule::supertypes) {
+            supertypes = inner(parts.next().unwrap()).map(|supertype| self.ident(supertype)).collect();
+        }
+        let (fields, methods) = self.members(parts)?;
+        Ok(Class { name, supertypes, fields, methods })
+    }
+
+    fn trait_item(&self, pair: Pair<Rule>) -> ParseResult<Trait> {
+        let mut parts = inner(pair).peekable();
+        let name = self.ident(parts.next().unwrap());
+        let mut supertypes = vec![];
+        if parts.peek().map(|part| part.as_rule()) == Some(Rule::supertypes) {
+            supertypes = inner(parts.next().unwrap()).map(|supertype| self.ident(supertype)).collect();
+        }
+        let mut methods = vec![];
+        for method in parts {
+            let location = self.location(&method);
+            let mut method_parts = method.into_inner();
+            let first = method_parts.next().unwrap();
+            let (auto, function) = match first.as_rule() {
+                Rule::kw_auto => (true, method_parts.next().unwrap()),
+                _ => (false, first)
+            };
+            methods.push(Located::new(TraitMethod { auto, function: self.function(function)? }, location));
+        }
+        Ok(Trait { name, supertypes, methods })
+    }
+
+    fn enum_item(&self, pair: Pair<Rule>) -> Enum {
+        let mut parts = inner(pair).peekable();
+        let name = self.ident(parts.next().unwrap());
+        let type_parameters = match parts.next_if(|part| part.as_rule() == Rule::type_parameters) {
+            Some(parameters) => parameters.into_inner().map(|parameter| self.ident(parameter)).collect(),
+            None => vec![]
+        };
+        let variants = parts.map(|variant| {
+            let location = self.location(&variant);
+            let mut variant_parts = variant.into_inner();
+            let name = self.ident(variant_parts.next().unwrap());
+            Located::new(Variant { name, fields: variant_parts.map(|field| self.type_name(field)).collect() }, location)
+        }).collect();
+        Enum { name, type_parameters, variants }
+    }
+
+    fn extension(&self, pair: Pair<Rule>) -> ParseResult<Extension> {
+        let mut parts = inner(pair);
+        let name = self.ident(parts.next().unwrap());
+        let target = self.type_name(parts.next().unwrap());
+        let (fields, methods) = self.members(parts)?;
+        Ok(Extension { name, target, fields, methods })
+    }
+
+    fn members<'i>(&self, members: impl Iterator<Item=Pair<'i, Rule>>) -> ParseResult<Members> {
+        let mut fields = vec![];
+        let mut methods = vec![];
+        for member in members {
+            let location = self.location(&member);
+            match member.as_rule() {
+                Rule::function => methods.push(Located::new(self.function(member)?, location)),
+                _ => fields.push(Located::new(self.field(member)?, location))
+            }
+        }
+        Ok((fields, methods))
+    }
+
+    fn field(&self, pair: Pair<Rule>) -> ParseResult<Field> {
+        let property = pair.clone().into_inner().next().unwrap().as_rule() == Rule::kw_property;
+        let mut parts = inner(pair);
+        let type_name = self.type_name(parts.next().unwrap());
+        let name = self.ident(parts.next().unwrap());
+        let value = parts.next().map(|value| self.expression(value)).transpose()?;
+        Ok(Field { property, type_name, name, value })
+    }
+
+    fn type_name(&self, pair: Pair<Rule>) -> Located<TypeName> {
+        let location = self.location(&pair);
+        let mut parts = pair.into_inner().peekable();
+        let name = parts.next().unwrap().as_str().to_string();
+        let trait_object = parts.next_if(|part| part.as_rule() == Rule::trait_object).is_some();
+        let generics = match parts.next() {
+            Some(generics) => generics.into_inner().map(|generic| self.type_name(generic)).collect(),
+            None => vec![]
+        };
+        Located::new(TypeName { name, generics, trait_object }, location)
+    }
+
+    fn block(&self, pair: Pair<Rule>) -> ParseResult<Block> {
+        let location = self.location(&pair);
+        let statements = pair.into_inner().map(|statement| self.statement(statement)).collect::<ParseResult<Vec<Statement>>>()?;
+        Ok(Located::new(statements, location))
+    }
+
+    /// The body of a control statement: either a block or a single statement after a `:`.
+    fn body(&self, pair: Pair<Rule>) -> ParseResult<Block> {
+        let location = self.location(&pair);
+        let body = pair.into_inner().next().unwrap();
+        match body.as_rule() {
+            Rule::block => self.block(body),
+            _ => Ok(Located::new(vec![self.statement(body)?], location))
+        }
+    }
+
+    fn statement(&self, pair: Pair<Rule>) -> ParseResult<Statement> {
+        let location = self.location(&pair);
+        let rule = pair.as_rule();
+        let mut parts = inner(pair);
+        let statement = match rule {
+            Rule::if_statement => {
+                let mut branches = vec![(self.expression(parts.next().unwrap())?, self.body(parts.next().unwrap())?)];
+                let mut otherwise = None;
+                for clause in parts {
+                    let rule = clause.as_rule();
+                    let mut clause_parts = inner(clause);
+                    match rule {
+                        Rule::elif_clause => branches.push((self.expression(clause_parts.next().unwrap())?, self.body(clause_parts.next().unwrap())?)),
+                        _ => otherwise = Some(self.body(clause_parts.next().unwrap())?)
+                    }
+                }
+                Stmt::Conditional { branches, otherwise }
+            }
+            Rule::while_loop => Stmt::While {
+                condition: self.expression(parts.next().unwrap())?,
+                body: self.body(parts.next().unwrap())?
+            },
+            Rule::for_loop => Stmt::For {
+                type_name: self.type_name(parts.next().unwrap()),
+                variable: self.ident(parts.next().unwrap()),
+                iterable: self.expression(parts.next().unwrap())?,
+                body: self.body(parts.next().unwrap())?
+            },
+            Rule::try_statement => {
+                let body = self.block(parts.next().unwrap())?;
+                let mut handlers = vec![];
+                let mut finally = None;
+                for clause in parts {
+                    let clause_location = self.location(&clause);
+                    let rule = clause.as_rule();
+                    let mut clause_parts = inner(clause).collect::<Vec<Pair<Rule>>>();
+                    let block = self.block(clause_parts.pop().unwrap())?;
+                    match rule {
+                        Rule::except_clause => {
+                            let mut idents = clause_parts.into_iter();
+                            let mut exception_type = idents.next().unwrap().into_inner();
+                            let exception = self.ident(exception_type.next().unwrap());
+                            let family = exception_type.next().is_some();
+                            let name = idents.next().map(|name| self.ident(name));
+                            handlers.push(Located::new(Handler { exception, family, name, body: block }, clause_location));
+                        }
+                        _ => finally = Some(block)
+                    }
+                }
+                Stmt::Try { body, handlers, finally }
+            }
+            Rule::throw_statement => Stmt::Throw(self.expression(parts.next().unwrap())?),
+            Rule::yield_statement => Stmt::Yield(self.expression(parts.next().unwrap())?),
+            Rule::return_statement => {
+                let mut values = parts.map(|value| self.expression(value)).collect::<ParseResult<Vec<Expression>>>()?;
+                match values.len() {
+                    0 | 1 => Stmt::Return(values.pop()),
+                    _ => {
+                        let start = values[0].location;
+                        Stmt::Return(Some(Located::new(Expr::Tuple(values), start)))
+                    }
+                }
+            }
+            Rule::destructuring => {
+                let mut parts = parts.collect::<Vec<Pair<Rule>>>();
+                let value = self.expression(parts.pop().unwrap())?;
+                let targets = parts.chunks(2).map(|target| (self.type_name(target[0].clone()), self.ident(target[1].clone()))).collect();
+                Stmt::Destructuring { targets, value }
+            }
+            Rule::declaration => Stmt::Declaration {
+                type_name: self.type_name(parts.next().unwrap()),
+                name: self.ident(parts.next().unwrap()),
+                value: parts.next().map(|value| self.expression(value)).transpose()?
+            },
+            Rule::assignment => {
+                let target = self.postfix(parts.next().unwrap())?;
+                if !matches!(target.data, Expr::Ident(_) | Expr::Attribute { .. } | Expr::Index { .. }) {
+                    return Err(ParseError::new("invalid assignment target".to_string(), target.location));
+                }
+                let operator = match parts.next().unwrap().as_str() {
+                    "+=" => Some(LBOperator::Plus),
+                    "-=" => Some(LBOperator::Minus),
+                    "*=" => Some(LBOperator::Multiply),
+                    "/=" => Some(LBOperator::Divide),
+                    "%=" => Some(LBOperator::Modulo),
+                    _ => None
+                };
+                Stmt::Assignment { target, operator, value: self.expression(parts.next().unwrap())? }
+            }
+            Rule::match_statement | Rule::guard_statement => Stmt::Expression(self.primary(parts.next().unwrap())?),
+            Rule::when_statement => Stmt::When {
+                value: self.expression(parts.next().unwrap())?,
+                arms: parts.map(|arm| self.match_arm(arm)).collect::<ParseResult<Vec<Located<MatchArm>>>>()?
+            },
+            _ => Stmt::Expression(self.expression(parts.next().unwrap())?)
+        };
+        Ok(Located::new(statement, location))
+    }
+
+    fn expression(&self, pair: Pair<Rule>) -> ParseResult<Expression> {
+        let location = self.location(&pair);
+        match pair.as_rule() {
+            Rule::expression => self.expression(pair.into_inner().next().unwrap()),
+            Rule::lambda => {
+                let mut parameters = vec![];
+                let mut body = None;
+                for part in pair.into_inner() {
+                    match part.as_rule() {
+                        Rule::lambda_parameter => parameters.push(self.parameter(part)),
+                        Rule::block => body = Some(LambdaBody::Block(self.block(part)?)),
+                        _ => body = Some(LambdaBody::Expression(self.expression(part)?))
+                    }
+                }
+                Ok(Located::new(Expr::Lambda { parameters, body: Box::new(body.unwrap()) }, location))
+            }
+            Rule::groupment => {
+                let mut parts = pair.into_inner();
+                let function = self.expression(parts.next().unwrap())?;
+                match parts.next() {
+                    Some(group) => Ok(Located::new(Expr::Groupment {
+                        function: Box::new(function),
+                        group: Box::new(self.expression(group)?)
+                    }, location)),
+                    None => Ok(function)
+                }
+            }
+            Rule::range => {
+                let mut parts = inner(pair);
+                let start = self.expression(parts.next().unwrap())?;
+                match parts.next() {
+                    Some(end) => Ok(Located::new(Expr::Range {
+                        start: Box::new(start),
+                        end: Box::new(self.expression(end)?),
+                        step: parts.next().map(|step| self.expression(step).map(Box::new)).transpose()?
+                    }, location)),
+                    None => Ok(start)
+                }
+            }
+            // Each operand is at most as deep as there are operators.
+            Rule::binary => self.nested(pair.clone().into_inner().count() / 2, &pair, || CLIMBER.climb(pair.clone().into_inner(), |operand| self.unary(operand), |left, operator, right| {
+                let (left, right) = (left?, right?);
+                let location = left.location.to(&right.location);
+                Ok(Located::new(Expr::Binary {
+                    operator: binary_operator(operator.as_rule()),
+                    left: Box::new(left),
+                    right: Box::new(right)
+                }, location))
+            })),
+            _ => self.unary(pair)
+        }
+    }
+
+    fn unary(&self, pair: Pair<Rule>) -> ParseResult<Expression> {
+        let mut parts = pair.clone().into_inner().collect::<Vec<Pair<Rule>>>();
+        let operand = parts.pop().unwrap();
+        let mut expression = self.nested(parts.len(), &pair, || self.cast(operand))?;
+        for operator in parts.into_iter().rev() {
+            let location = self.location(&operator).to(&expression.location);
+            let operator = match operator.as_rule() {
+                Rule::not => LBOperator::Not,
+                Rule::inverse => LBOperator::Inverse,
+                _ => LBOperator::Minus
+            };
+            expression = Located::new(Expr::Unary { operator, operand: Box::new(expression) }, location);
+        }
+        Ok(expression)
+    }
+
+    fn cast(&self, pair: Pair<Rule>) -> ParseResult<Expression> {
+        let mut parts = inner(pair.clone()).collect::<Vec<Pair<Rule>>>();
+        let value = parts.remove(0);
+        let mut expression = self.nested(parts.len(), &pair, || self.postfix(value))?;
+        for type_name in parts {
+            let type_name = self.type_name(type_name);
+            let location = expression.location.to(&type_name.location);
+            expression = Located::new(Expr::Cast { value: Box::new(expression), type_name }, location);
+        }
+        Ok(expression)
+    }
+
+    fn postfix(&self, pair: Pair<Rule>) -> ParseResult<Expression> {
+        let suffixes = pair.clone().into_inner().count() - 1;
+        self.nested(suffixes, &pair, || self.suffixes(pair.clone()))
+    }
+
+    /// The primary expression of a `postfix`, with each call, attribute and index applied to it in turn.
+    fn suffixes(&self, pair: Pair<Rule>) -> ParseResult<Expression> {
+        let mut parts = pair.into_inner();
+        let mut expression = self.primary(parts.next().unwrap())?;
+        for suffix in parts {
+            let location = expression.location.to(&self.location(&suffix));
+            let data = match suffix.as_rule() {
+                Rule::call => {
+                    let arguments = suffix.into_inner().map(|argument| self.expression(argument)).collect::<ParseResult<Vec<Expression>>>()?;
+                    match expression.data {
+                        Expr::Attribute { object, attribute } => Expr::MethodCall { object, method: attribute, arguments },
+                        _ => Expr::Call { function: Box::new(expression), arguments }
+                    }
+                }
+                Rule::attribute => Expr::Attribute {
+                    object: Box::new(expression),
+                    attribute: self.ident(suffix.into_inner().next().unwrap())
+                },
+                _ => {
+                    let index = suffix.into_inner().next().unwrap();
+                    match index.as_rule() {
+                        Rule::slice => {
+                            let mut bounds = index.into_inner();
+                            let start = self.slice_bound(bounds.next().unwrap())?;
+                            let end = self.slice_bound(bounds.next().unwrap())?;
+                            Expr::Slice { object: Box::new(expression), start, end }
+                        }
+                        _ => Expr::Index { object: Box::new(expression), index: Box::new(self.expression(index)?) }
+                    }
+                }
+            };
+            expression = Located::new(data, location);
+        }
+        Ok(expression)
+    }
+
+    fn slice_bound(&self, pair: Pair<Rule>) -> ParseResult<Option<Box<Expression>>> {
+        pair.into_inner().next().map(|bound| self.expression(bound).map(Box::new)).transpose()
+    }
+
+    fn primary(&self, pair: Pair<Rule>) -> ParseResult<Expression> {
+        let location = self.location(&pair);
+        let data = match pair.as_rule() {
+            Rule::ident => Expr::Ident(pair.as_str().to_string()),
+            Rule::list => Expr::List(pair.into_inner().map(|element| self.expression(element)).collect::<ParseResult<Vec<Expression>>>()?),
+            Rule::dict => Expr::Dict(pair.into_inner().map(|entry| {
+                let mut parts = entry.into_inner();
+                Ok((self.expression(parts.next().unwrap())?, self.expression(parts.next().unwrap())?))
+            }).collect::<ParseResult<Vec<(Expression, Expression)>>>()?),
+            Rule::set => Expr::Set(pair.into_inner().map(|element| self.expression(element)).collect::<ParseResult<Vec<Expression>>>()?),
+            Rule::tuple => Expr::Tuple(pair.into_inner().map(|element| self.expression(element)).collect::<ParseResult<Vec<Expression>>>()?),
+            Rule::expression => return self.expression(pair),
+            Rule::block => Expr::Block(self.block(pair)?),
+            Rule::variant_path => {
+                let mut parts = pair.into_inner();
+                Expr::Variant { enum_name: self.ident(parts.next().unwrap()), variant: self.ident(parts.next().unwrap()) }
+            }
+            Rule::match_expression => {
+                let mut parts = inner(pair);
+                let value = self.expression(parts.next().unwrap())?;
+                let arms = parts.map(|arm| self.match_arm(arm)).collect::<ParseResult<Vec<Located<MatchArm>>>>()?;
+                Expr::Match { value: Box::new(value), arms }
+            }
+            Rule::guard_expression => {
+                let mut arms = vec![];
+                let mut otherwise = None;
+                for part in inner(pair) {
+                    match part.as_rule() {
+                        Rule::guard_arm => arms.push(self.guard_arm(part)?),
+                        _ => {
+                            let body = inner(part).next().unwrap();
+                            otherwise = Some(Box::new(match body.as_rule() {
+                                Rule::block => LambdaBody::Block(self.block(body)?),
+                                _ => LambdaBody::Expression(self.expression(body)?)
+                            }));
+                        }
+                    }
+                }
+                Expr::Guard { arms, otherwise }
+            }
+            _ => Expr::Constant(self.constant(pair, location)?)
+        };
+        Ok(Located::new(data, location))
+    }
+
+    fn match_arm(&self, pair: Pair<Rule>) -> ParseResult<Located<MatchArm>> {
+        let location = self.location(&pair);
+        let mut parts = pair.into_inner().peekable();
+        let pattern = self.pattern(parts.next().unwrap())?;
+        let guard = match parts.next_if(|part| part.as_rule() == Rule::guard) {
+            Some(guard) => Some(self.expression(inner(guard).next().unwrap())?),
+            None => None
+        };
+        let body = parts.next().unwrap();
+        let body = match body.as_rule() {
+            Rule::block => LambdaBody::Block(self.block(body)?),
+            _ => LambdaBody::Expression(self.expression(body)?)
+        };
+        Ok(Located::new(MatchArm { pattern, guard, body }, location))
+    }
+
+    fn guard_arm(&self, pair: Pair<Rule>) -> ParseResult<Located<GuardArm>> {
+        let location = self.location(&pair);
+        let mut parts = pair.into_inner();
+        let condition = self.expression(parts.next().unwrap())?;
+        let exit = parts.next().unwrap();
+        let exit_location = self.location(&exit);
+        // The keywords are needed to tell a `return` from a `throw`, so they aren't filtered out.
+        let mut exit_parts = exit.into_inner();
+        let first = exit_parts.next().unwrap();
+        let statement = match first.as_rule() {
+            Rule::block => return Ok(Located::new(GuardArm { condition, exit: self.block(first)? }, location)),
+            Rule::kw_return => Stmt::Return(exit_parts.next().map(|value| self.expression(value)).transpose()?),
+            Rule::kw_throw => Stmt::Throw(self.expression(exit_parts.next().unwrap())?),
+            _ => Stmt::Expression(self.expression(first)?)
+        };
+        let exit = Located::new(vec![Located::new(statement, exit_location)], exit_location);
+        Ok(Located::new(GuardArm { condition, exit }, location))
+    }
+
+    fn pattern(&self, pair: Pair<Rule>) -> ParseResult<Located<Pattern>> {
+        let location = self.location(&pair);
+        let mut parts = pair.into_inner();
+        let first = parts.next().unwrap();
+        let fields = parts.next().map(|fields| fields.into_inner().map(|field| self.pattern(field)).collect::<ParseResult<Vec<Located<Pattern>>>>()).transpose()?;
+        let pattern = match first.as_rule() {
+            Rule::literal_pattern => {
+                let mut literal = first.into_inner().collect::<Vec<Pair<Rule>>>();
+                let constant = literal.pop().unwrap();
+                let constant_location = self.location(&constant);
+                match (literal.is_empty(), self.constant(constant, constant_location)?) {
+                    (true, constant) => Pattern::Constant(constant),
+                    (false, Constant::Integer(value, typing)) => Pattern::Constant(Constant::Integer(-value, typing)),
+                    (false, Constant::Decimal(value, typing)) => Pattern::Constant(Constant::Decimal(-value, typing)),
+                    (false, Constant::BigInt(value)) => Pattern::Constant(Constant::BigInt(-value)),
+                    (false, Constant::BigDecimal(value)) => Pattern::Constant(Constant::BigDecimal(-value)),
+                    (false, constant) => return Err(ParseError::new(format!("cannot negate {}", constant), location))
+                }
+            }
+            Rule::variant_path => {
+                let mut path = first.into_inner();
+                Pattern::Variant { enum_name: Some(self.ident(path.next().unwrap())), variant: self.ident(path.next().unwrap()), fields }
+            }
+            Rule::default_pattern => Pattern::Wildcard,
+            _ if first.as_str() == "_" && fields.is_none() => Pattern::Wildcard,
+            _ => match fields {
+                Some(fields) => Pattern::Variant { enum_name: None, variant: self.ident(first), fields: Some(fields) },
+                None => Pattern::Name(first.as_str().to_string())
+            }
+        };
+        Ok(Located::new(pattern, location))
+    }
+
+    fn constant(&self, pair: Pair<Rule>, location: Location) -> ParseResult<Constant> {
+        let text = pair.as_str();
+        match pair.as_rule() {
+            Rule::number => number(text, location),
+            Rule::string => unescape(pair.into_inner().next().unwrap().as_str(), location).map(Constant::String),
+            Rule::char => {
+                let value = unescape(pair.into_inner().next().unwrap().as_str(), location)?;
+                let mut chars = value.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(ch), None) => Ok(Constant::Char(ch)),
+                    _ => Err(ParseError::new(format!("character literal {} must contain exactly one character", text), location))
+                }
+            }
+            Rule::boolean => Ok(Constant::Boolean(text == "true")),
+            _ => Ok(Constant::Null)
+        }
+    }
+}
+
+/// Children of `pair`, without the keyword tokens that only exist to anchor the grammar.
+fn inner(pair: Pair<Rule>) -> impl Iterator<Item=Pair<Rule>> {
+    pair.into_inner().filter(|part| !is_keyword(part.as_rule()))
+}
+
+fn is_keyword(rule: Rule) -> bool {
+    matches!(rule, Rule::kw_func | Rule::kw_returns | Rule::kw_return | Rule::kw_using | Rule::kw_ext | Rule::kw_from
+        | Rule::kw_class | Rule::kw_trait | Rule::kw_auto | Rule::kw_enum | Rule::kw_match | Rule::kw_when | Rule::kw_guard | Rule::kw_yield | Rule::kw_extension | Rule::kw_of | Rule::kw_property | Rule::kw_if | Rule::kw_elif | Rule::kw_else
+        | Rule::kw_while | Rule::kw_for | Rule::kw_in | Rule::kw_to | Rule::kw_by | Rule::kw_as | Rule::kw_try
+        | Rule::kw_except | Rule::kw_finally | Rule::kw_throw)
+}
+
+fn binary_operator(rule: Rule) -> LBOperator {
+    match rule {
+        Rule::or => LBOperator::Or,
+        Rule::and => LBOperator::And,
+        Rule::xor => LBOperator::Xor,
+        Rule::equals => LBOperator::Equals,
+        Rule::not_equals => LBOperator::NotEquals,
+        Rule::less => LBOperator::LessThan,
+        Rule::greater => LBOperator::GreaterThan,
+        Rule::less_equal => LBOperator::LessThanOrEqual,
+        Rule::greater_equal => LBOperator::GreaterThanOrEqual,
+        Rule::lshift => LBOperator::LShift,
+        Rule::rshift => LBOperator::RShift,
+        Rule::add => LBOperator::Plus,
+        Rule::subtract => LBOperator::Minus,
+        Rule::multiply => LBOperator::Multiply,
+        Rule::divide => LBOperator::Divide,
+        Rule::modulo => LBOperator::Modulo,
+        Rule::power => LBOperator::Power,
+        _ => LBOperator::NULL
+    }
+}
+
+/// Numeric literals follow the legacy typing rules of `constant_type`, except that unsuffixed
+/// integers are widened by value instead of by length.
+fn number(text: &str, location: Location) -> ParseResult<Constant> {
+    let typing = constant_type(text);
+    let digits = text.trim_end_matches(|ch: char| ch.is_ascii_alphabetic());
+    match typing {
+        LeBlancType::BigInt => BigInt::from_str(digits).map(Constant::BigInt)
+            .map_err(|_| ParseError::new(format!("invalid bigint literal {}, which can't have a fraction", text), location)),
+        LeBlancType::Decimal => LeblancDecimal::parse(digits).map(Constant::BigDecimal)
+            .ok_or_else(|| ParseError::new(format!("invalid decimal literal {}", text), location)),
+        LeBlancType::Float | LeBlancType::Double => digits.parse::<f64>()
+            .map(|value| Constant::Decimal(value, typing))
+            .map_err(|_| ParseError::new(format!("invalid decimal literal {}", text), location)),
+        _ => {
+            let value = digits.parse::<i128>()
+                .map_err(|_| ParseError::new(format!("invalid integer literal {}", text), location))?;
+            let typing = if digits.len() != text.len() {
+                LeBlancType::Int64
+            } else if i32::try_from(value).is_ok() {
+                LeBlancType::Int
+            } else if i64::try_from(value).is_ok() {
+                LeBlancType::Int64
+            } else {
+                LeBlancType::Int128
+            };
+            if typing == LeBlancType::Int64 && i64::try_from(value).is_err() {
+                return Err(ParseError::new(format!("integer literal {} does not fit in int64", text), location));
+            }
+            Ok(Constant::Integer(value, typing))
+        }
+    }
+}
+
+fn unescape(text: &str, location: Location) -> ParseResult<String> {
+    let mut result = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            result.push(ch);
+            continue;
+        }
+        let escaped = match chars.next() {
+            Some('n') => '\n',
+            Some('t') => '\t',
+            Some('r') => '\r',
+            Some('0') => '\0',
+            Some('\\') => '\\',
+            Some('"') => '"',
+            Some('\'') => '\'',
+            Some('x') => {
+                let code = chars.by_ref().take(2).collect::<String>();
+                u8::from_str_radix(&code, 16).ok().filter(|_| code.len() == 2).map(char::from)
+                    .ok_or_else(|| ParseError::new(format!("invalid escape \\x{}", code), location))?
+            }
+            other => return Err(ParseError::new(format!("unknown escape \\{}", other.map(String::from).unwrap_or_default()), location))
+        };
+        result.push(escaped);
+    }
+    Ok(result)
+}
+
+fn describe_rules(rules: &[Rule]) -> String {
+    let mut names: Vec<String> = vec![];
+    for name in rules.iter().map(|rule| describe_rule(*rule)) {
+        if !names.contains(&name) { names.push(name); }
+    }
+    match names.len() {
+        0 => "input".to_string(),
+        1 => names.remove(0),
+        _ => {
+            let last = names.pop().unwrap();
+            names.join(", ") + " or " + &last
+        }
+    }
+}
+
+fn describe_rule(rule: Rule) -> String {
+    let name = format!("{:?}", rule);
+    if let Some(keyword) = name.strip_prefix("kw_") {
+        return format!("`{}`", keyword);
+    }
+    if binary_operator(rule) != LBOperator::NULL {
+        return "operator".to_string();
+    }
+    match rule {
+        Rule::EOI => "end of file".to_string(),
+        Rule::file => "function, class, trait, enum, extension or import".to_string(),
+        Rule::trait_method => "method".to_string(),
+        Rule::match_arm | Rule::when_arm => "match arm".to_string(),
+        Rule::guard_arm => "guard arm".to_string(),
+        Rule::guard_else => "`else` arm".to_string(),
+        Rule::variant_path => "`Enum::Variant`".to_string(),
+        Rule::ident => "identifier".to_string(),
+        Rule::type_name => "type".to_string(),
+        Rule::exception_type => "exception type".to_string(),
+        Rule::postfix => "expression".to_string(),
+        Rule::assign_operator => "`=`".to_string(),
+        Rule::call => "`(`".to_string(),
+        Rule::attribute => "`.`".to_string(),
+        Rule::index => "`[`".to_string(),
+        _ => name.replace('_', " ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn nested(open: &str, close: &str, depth: usize) -> String {
+        format!("func main() {{\n\tint x = {}1{};\n}}", open.repeat(depth), close.repeat(depth))
+    }
+
+    #[test]
+    fn nesting_up_to_the_limit_parses() {
+        assert!(parse(&nested("(", ")", MAX_NESTING - 1)).is_ok());
+    }
+
+    #[test]
+    fn nesting_past_the_limit_is_a_parse_error() {
+        let error = parse(&nested("(", ")", 5000)).unwrap_err();
+        assert_eq!(error.message, format!("brackets nest more than {} deep", MAX_NESTING));
+        assert_eq!((error.location.line, error.location.column), (2, 10 + MAX_NESTING - 1));
+
+        assert!(parse(&nested("[", "]", 5000)).is_err());
+        assert!(parse(&nested("{", "}", 5000)).is_err());
+    }
+
+    fn chained(expression: String) -> String {
+        format!("func main() {{\n\tint a = 1;\n\tint x = {};\n}}", expression)
+    }
+
+    #[test]
+    fn chains_up_to_the_limit_parse() {
+        assert!(parse(&chained(vec!["a"; MAX_EXPRESSION_DEPTH + 1].join(" + "))).is_ok());
+        assert!(parse(&chained(vec!["a"; MAX_EXPRESSION_DEPTH + 1].join(" ** "))).is_ok());
+        assert!(parse(&chained("-".repeat(MAX_EXPRESSION_DEPTH) + "a")).is_ok());
+    }
+
+    #[test]
+    fn binary_chains_past_the_limit_are_a_parse_error() {
+        for operator in [" + ", " ** ", " == "] {
+            let error = parse(&chained(vec!["a"; 20_000].join(operator))).unwrap_err();
+            assert_eq!(error.message, format!("expression nests more than {} deep", MAX_EXPRESSION_DEPTH));
+            assert_eq!((error.location.line, error.location.column), (3, 10));
+        }
+    }
+
+    #[test]
+    fn unary_chains_past_the_limit_are_a_parse_error() {
+        for operator in ["-", "- ", "!", "~"] {
+            let error = parse(&chained(operator.repeat(20_000) + "a")).unwrap_err();
+            assert_eq!(error.message, format!("expression nests more than {} deep", MAX_EXPRESSION_DEPTH));
+        }
+    }
+
+    #[test]
+    fn cast_and_postfix_chains_past_the_limit_are_a_parse_error() {
+        assert!(parse(&chained("a".to_string() + &" as int".repeat(20_000))).is_err());
+        assert!(parse(&chained("a".to_string() + &".b".repeat(20_000))).is_err());
+    }
+
+    #[test]
+    fn brackets_in_strings_and_comments_do_not_nest() {
+        let brackets = "(".repeat(5000);
+        let source = format!("// {0}\n/* {0} */\nfunc main() {{\n\tstring s = \"\\\"{0}\";\n\tchar c = '(';\n}}", brackets);
+        assert!(parse(&source).is_ok());
+    }
+}
@@ -0,0 +1,619 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::leblanc::compiler::compiler_rewrite::ast::Constant;
+use crate::leblanc::core::bytecode::exception_table::ExceptionHandler;
+use crate::leblanc::core::interpreter::arithmetic::{self, Number, Operation};
+use crate::leblanc::core::interpreter::instructions::{Instruction, InstructionBase};
+use crate::leblanc::core::interpreter::instructions::InstructionBase::*;
+use crate::leblanc::core::interpreter::jump_table::JumpTable;
+use crate::leblanc::core::interpreter::value::Value;
+use crate::leblanc::core::native_types::LeBlancType;
+
+/// Whether the bytecode of every function is printed before and after it is optimized.
+static DUMP: AtomicBool = AtomicBool::new(false);
+
+pub fn set_dump(dump: bool) {
+    DUMP.store(dump, Ordering::Relaxed);
+}
+
+pub fn dumping() -> bool {
+    DUMP.load(Ordering::Relaxed)
+}
+
+/// Optimizes the bytecode of a function between code generation and serialization: arithmetic on
+/// constants is folded, jumps to unconditional jumps go straight to where those lead, stores to
+/// locals that are never read again are dropped, and common sequences are fused into
+/// superinstructions. Jump targets, jump tables (still indexed from 0, before they are moved
+/// after the constants) and exception handlers are kept pointing at the same code.
+///
+/// Nothing is folded, removed or fused across an instruction something jumps to, except at the
+/// first instruction of a sequence.
+pub fn optimize(instructions: &mut Vec<Instruction>, constants: &mut Vec<Constant>, handlers: &mut [ExceptionHandler], tables: &mut [JumpTable]) {
+    let mut peephole = Peephole { instructions, constants, handlers, tables };
+    peephole.fold_constants();
+    peephole.thread_jumps();
+    peephole.eliminate_dead_stores();
+    peephole.fuse();
+}
+
+struct Peephole<'a> {
+    instructions: &'a mut Vec<Instruction>,
+    constants: &'a mut Vec<Constant>,
+    handlers: &'a mut [ExceptionHandler],
+    tables: &'a mut [JumpTable]
+}
+
+/// Whether the argument of `instruct` is the index of an instruction.
+fn jumps(instruct: InstructionBase) -> bool {
+    matches!(instruct, Comparator_If | Comparator_ElseIf | Comparator_Else | WhileLoop | ForLoop | MakeBlock)
+}
+
+/// Whether execution can carry on with the instruction after `instruct`.
+fn falls_through(instruct: InstructionBase) -> bool {
+    !matches!(instruct, Return | Raise | Comparator_Else | JumpTable)
+}
+
+impl Peephole<'_> {
+    /// Which instructions execution can arrive at other than from the one before, indexed up to
+    /// and including the end of the function.
+    fn leaders(&self) -> Vec<bool> {
+        let count = self.instructions.len();
+        let mut leaders = vec![false; count + 1];
+        let mut mark = |index: usize| if index <= count { leaders[index] = true; };
+        for (index, instruction) in self.instructions.iter().enumerate() {
+            if jumps(instruction.instruct) {
+                mark(instruction.arg as usize);
+            }
+            match instruction.instruct {
+                // A block runs on its own from the instruction after this one.
+                MakeBlock => mark(index + 1),
+                // A generator carries on past the `Return` following this instruction.
+                MakeGenerator => mark(index + 2),
+                _ => {}
+            }
+        }
+        for table in self.tables.iter() {
+            mark(table.default as usize);
+            table.entries.iter().for_each(|(_, target)| mark(*target as usize));
+        }
+        for handler in self.handlers.iter() {
+            mark(handler.start as usize);
+            mark(handler.end as usize);
+            mark(handler.target as usize);
+        }
+        leaders
+    }
+
+    /// Drops the instructions marked in `removed`, moving everything that refers to an
+    /// instruction to where it ends up. A removed instruction's place goes to the next one kept.
+    fn compact(&mut self, removed: &[bool]) {
+        let mut moved: Vec<usize> = Vec::with_capacity(removed.len() + 1);
+        let mut kept: usize = 0;
+        for gone in removed {
+            moved.push(kept);
+            if !gone { kept += 1; }
+        }
+        moved.push(kept);
+        // Instructions only move back, so a target that fit in 16 bits still does.
+        let target = |old: u16| u16::try_from(moved[old as usize]).expect("an instruction moved past its old index");
+
+        let mut index = 0;
+        self.instructions.retain(|_| {
+            index += 1;
+            !removed[index - 1]
+        });
+        for instruction in self.instructions.iter_mut().filter(|instruction| jumps(instruction.instruct)) {
+            instruction.arg = target(instruction.arg);
+        }
+        for table in self.tables.iter_mut() {
+            table.retarget(target);
+        }
+        for handler in self.handlers.iter_mut() {
+            handler.start = target(handler.start);
+            handler.end = target(handler.end);
+            handler.target = target(handler.target);
+        }
+    }
+
+    /// The index of `constant`, adding it if there is no identical one yet.
+    fn constant(&mut self, constant: Constant) -> u16 {
+        let index = match self.constants.iter().position(|existing| identical(existing, &constant)) {
+            Some(index) => index,
+            None => {
+                self.constants.push(constant);
+                self.constants.len() - 1
+            }
+        };
+        // Past 16 bits, code generation reports the function as too large once this pass is done.
+        u16::try_from(index).unwrap_or(u16::MAX)
+    }
+
+    /// `LoadConstant a; LoadConstant b; <binary operation>` becomes `LoadConstant (a op b)`, as
+    /// long as the operation succeeds. One that raises is left to raise when it runs.
+    fn fold_constants(&mut self) {
+        loop {
+            let leaders = self.leaders();
+            let mut removed = vec![false; self.instructions.len()];
+            let mut index = 0;
+            while index + 2 < self.instructions.len() {
+                let [left, right, operator] = [0, 1, 2].map(|offset| self.instructions[index + offset]);
+                let folded = match (left.instruct, right.instruct, Operation::of(operator.instruct)) {
+                    (LoadConstant, LoadConstant, Some(operation)) if !leaders[index + 1] && !leaders[index + 2] => {
+                        fold(operation, &self.constants[left.arg as usize], &self.constants[right.arg as usize])
+                    }
+                    _ => None
+                };
+                match folded {
+                    Some(constant) => {
+                        self.instructions[index].arg = self.constant(constant);
+                        removed[index + 1] = true;
+                        removed[index + 2] = true;
+                        index += 3;
+                    }
+                    None => index += 1
+                }
+            }
+            if !removed.contains(&true) { return; }
+            self.compact(&removed);
+        }
+    }
+
+    /// Points jumps that land on a `Comparator_Else` at wherever that jumps to instead, then drops
+    /// the `Comparator_Else`s that only jump to the next instruction.
+    fn thread_jumps(&mut self) {
+        let blocks = self.blocks();
+        let destination = |instructions: &[Instruction], from: usize, target: u16| {
+            let mut target = target;
+            let mut seen = vec![];
+            while let Some(next) = instructions.get(target as usize).filter(|next| next.instruct == Comparator_Else) {
+                if seen.contains(&target) || block_of(&blocks, next.arg as usize) != block_of(&blocks, from) { break; }
+                seen.push(target);
+                target = next.arg;
+            }
+            target
+        };
+
+        for index in 0..self.instructions.len() {
+            let instruction = self.instructions[index];
+            if jumps(instruction.instruct) && instruction.instruct != MakeBlock {
+                self.instructions[index].arg = destination(self.instructions, index, instruction.arg);
+            }
+        }
+        for index in 0..self.tables.len() {
+            let Some(from) = self.instructions.iter().position(|instruction| instruction.instruct == JumpTable && instruction.arg as usize == index) else { continue };
+            let instructions = &*self.instructions;
+            self.tables[index].retarget(|target| destination(instructions, from, target));
+        }
+
+        let removed = self.instructions.iter().enumerate()
+            .map(|(index, instruction)| instruction.instruct == Comparator_Else && instruction.arg as usize == index + 1)
+            .collect::<Vec<bool>>();
+        if removed.contains(&true) {
+            self.compact(&removed);
+        }
+    }
+
+    /// The instructions each block runs, as the ranges between a `MakeBlock` and its end.
+    fn blocks(&self) -> Vec<(usize, usize)> {
+        self.instructions.iter().enumerate()
+            .filter(|(_, instruction)| instruction.instruct == MakeBlock)
+            .map(|(index, instruction)| (index + 1, instruction.arg as usize))
+            .collect()
+    }
+
+    /// Where execution can go after the instruction at `index`, including the handlers of errors
+    /// it raises. The end of the function is `instructions.len()`.
+    fn successors(&self, index: usize) -> Vec<usize> {
+        let instruction = self.instructions[index];
+        let mut successors = vec![];
+        if falls_through(instruction.instruct) {
+            successors.push(index + 1);
+        }
+        match instruction.instruct {
+            Comparator_If | Comparator_ElseIf | Comparator_Else | WhileLoop | ForLoop => successors.push(instruction.arg as usize),
+            JumpTable => {
+                let table = &self.tables[instruction.arg as usize];
+                successors.push(table.default as usize);
+                successors.extend(table.entries.iter().map(|(_, target)| *target as usize));
+            }
+            MakeGenerator => successors.push(index + 2),
+            _ => {}
+        }
+        successors.extend(self.handlers.iter().filter(|handler| handler.covers(index as u64)).map(|handler| handler.target as usize));
+        successors.into_iter().map(|successor| successor.min(self.instructions.len())).collect()
+    }
+
+    /// Replaces each `StoreLocal` whose value is never read with a `PopTop`, or drops it along
+    /// with the value when that was only just pushed. Functions with blocks are left alone, as a
+    /// block reads the locals of the function whenever it is called.
+    fn eliminate_dead_stores(&mut self) {
+        if self.instructions.iter().any(|instruction| instruction.instruct == MakeBlock) { return; }
+        let slots = self.instructions.iter()
+            .filter(|instruction| matches!(instruction.instruct, LoadLocal | StoreLocal))
+            .map(|instruction| instruction.arg as usize + 1)
+            .max().unwrap_or(0);
+        if slots == 0 { return; }
+
+        let count = self.instructions.len();
+        let successors = (0..count).map(|index| self.successors(index)).collect::<Vec<Vec<usize>>>();
+        // Which locals may be read before being stored again, from each instruction on.
+        let mut live = vec![vec![false; slots]; count + 1];
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for index in (0..count).rev() {
+                let mut after = vec![false; slots];
+                for successor in &successors[index] {
+                    after.iter_mut().zip(&live[*successor]).for_each(|(slot, live)| *slot |= *live);
+                }
+                let instruction = self.instructions[index];
+                match instruction.instruct {
+                    LoadLocal => after[instruction.arg as usize] = true,
+                    StoreLocal => after[instruction.arg as usize] = false,
+                    _ => {}
+                }
+                if after != live[index] {
+                    live[index] = after;
+                    changed = true;
+                }
+            }
+        }
+
+        let leaders = self.leaders();
+        let mut removed = vec![false; count];
+        for index in 0..count {
+            let instruction = self.instructions[index];
+            if instruction.instruct != StoreLocal || successors[index].iter().any(|successor| live[*successor][instruction.arg as usize]) {
+                continue;
+            }
+            let pushed = index > 0 && !removed[index - 1] && matches!(self.instructions[index - 1].instruct, LoadConstant | LoadLocal);
+            if pushed && !leaders[index] {
+                removed[index - 1] = true;
+                removed[index] = true;
+            } else {
+                self.instructions[index].instruct = PopTop;
+                self.instructions[index].arg = 0;
+            }
+        }
+        if removed.contains(&true) {
+            self.compact(&removed);
+        }
+    }
+
+    /// Turns the first instruction of common sequences into a superinstruction doing the work of
+    /// the whole sequence. The rest stay in place as its operands and are skipped over when it
+    /// runs, so nothing has to move. A sequence is only fused when it is on one line.
+    fn fuse(&mut self) {
+        let leaders = self.leaders();
+        let count = self.instructions.len();
+        let fusable = |instructions: &[Instruction], index: usize, length: usize| {
+            index + length <= count && (1..length).all(|offset| {
+                !leaders[index + offset] && instructions[index + offset].line_number == instructions[index].line_number
+            })
+        };
+
+        let mut fused = vec![false; count];
+        let mut index = 0;
+        while index < count {
+            if !fusable(self.instructions, index, 4) {
+                index += 1;
+                continue;
+            }
+            let [local, constant, operator, last] = [0, 1, 2, 3].map(|offset| self.instructions[index + offset]);
+            let superinstruction = match (local.instruct, constant.instruct, operator.instruct, last.instruct) {
+                (LoadLocal, LoadConstant, BinaryAdd, StoreLocal) if last.arg == local.arg => Some(IncrementLocal),
+                (LoadLocal, LoadConstant, Equality(_), Comparator_If | Comparator_ElseIf | WhileLoop) => Some(CompareLocalConstJump),
+                _ => None
+            };
+            match superinstruction {
+                Some(superinstruction) => {
+                    // Errors come from the operator, so that is where a traceback should point.
+                    self.instructions[index].instruct = superinstruction;
+                    self.instructions[index].column = operator.column;
+                    fused[index..index + 4].iter_mut().for_each(|fused| *fused = true);
+                    index += 4;
+                }
+                None => index += 1
+            }
+        }
+
+        let mut index = 0;
+        while index + 1 < count {
+            let pair = !fused[index] && !fused[index + 1] && fusable(self.instructions, index, 2)
+                && self.instructions[index].instruct == LoadLocal && self.instructions[index + 1].instruct == LoadLocal;
+            if pair {
+                self.instructions[index].instruct = LoadLocal2;
+                index += 2;
+            } else {
+                index += 1;
+            }
+        }
+    }
+}
+
+/// The innermost block `index` is in, if any.
+fn block_of(blocks: &[(usize, usize)], index: usize) -> Option<(usize, usize)> {
+    blocks.iter().filter(|(start, end)| *start <= index && index < *end).min_by_key(|(start, end)| end - start).copied()
+}
+
+/// `left <operation> right`, when both are constants the operation succeeds on.
+fn fold(operation: Operation, left: &Constant, right: &Constant) -> Option<Constant> {
+    let result = arithmetic::binary(operation, value(left)?, value(right)?).ok()?;
+    constant(result)
+}
+
+fn value(constant: &Constant) -> Option<Value> {
+    let number = match constant {
+        Constant::Boolean(boolean) => return Some(Value::Boolean(*boolean)),
+        Constant::Integer(value, LeBlancType::Short) => Number::Short(*value as i16),
+        Constant::Integer(value, LeBlancType::Int) => Number::Int(*value as i32),
+        Constant::Integer(value, LeBlancType::Arch) => Number::Arch(*value as isize),
+        Constant::Integer(value, LeBlancType::Int64) => Number::Int64(*value as i64),
+        Constant::Integer(value, LeBlancType::Int128) => Number::Int128(*value),
+        Constant::Decimal(value, LeBlancType::Float) => Number::Float(*value as f32),
+        Constant::Decimal(value, LeBlancType::Double) => Number::Double(*value),
+        Constant::BigInt(value) => Number::BigInt(value.clone()),
+        Constant::BigDecimal(value) => Number::Decimal(value.clone()),
+        _ => return None
+    };
+    Some(number.into_value())
+}
+
+/// The constant holding `value`. `None` for floats that aren't finite, which are left to be
+/// worked out when the code runs.
+fn constant(value: Value) -> Option<Constant> {
+    if let Value::Boolean(boolean) = value {
+        return Some(Constant::Boolean(boolean));
+    }
+    Some(match Number::of_value(&value)? {
+        Number::Short(short) => Constant::Integer(short as i128, LeBlancType::Short),
+        Number::Int(int) => Constant::Integer(int as i128, LeBlancType::Int),
+        Number::Arch(arch) => Constant::Integer(arch as i128, LeBlancType::Arch),
+        Number::Int64(int) => Constant::Integer(int as i128, LeBlancType::Int64),
+        Number::Int128(int) => Constant::Integer(int, LeBlancType::Int128),
+        Number::BigInt(int) => Constant::BigInt(int),
+        Number::Float(float) if float.is_finite() => Constant::Decimal(float as f64, LeBlancType::Float),
+        Number::Double(double) if double.is_finite() => Constant::Decimal(double, LeBlancType::Double),
+        Number::Decimal(decimal) => Constant::BigDecimal(decimal),
+        _ => return None
+    })
+}
+
+/// Whether two constants hold the same value of the same type. Unlike `==`, this tells `0.0` and
+/// `-0.0` apart, which a folded constant can't be merged across.
+fn identical(existing: &Constant, constant: &Constant) -> bool {
+    match (existing, constant) {
+        (Constant::Decimal(existing, existing_type), Constant::Decimal(constant, constant_type)) => {
+            existing.to_bits() == constant.to_bits() && existing_type.enum_id() == constant_type.enum_id()
+        }
+        _ => existing == constant && existing.leblanc_type().enum_id() == constant.leblanc_type().enum_id()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::leblanc::core::interpreter::jump_table::TableKey;
+
+    /// The parts of a function the passes work on.
+    struct Function {
+        instructions: Vec<Instruction>,
+        constants: Vec<Constant>,
+        handlers: Vec<ExceptionHandler>,
+        tables: Vec<JumpTable>
+    }
+
+    impl Function {
+        /// A function running `code` all on one line.
+        fn new(code: &[(InstructionBase, u16)], constants: Vec<Constant>) -> Function {
+            let instructions = code.iter().map(|(instruct, arg)| Instruction::new(*instruct, *arg, 1, 0)).collect();
+            Function { instructions, constants, handlers: vec![], tables: vec![] }
+        }
+
+        fn run(&mut self, pass: fn(&mut Peephole)) -> &mut Function {
+            let mut peephole = Peephole { instructions: &mut self.instructions, constants: &mut self.constants, handlers: &mut self.handlers, tables: &mut self.tables };
+            pass(&mut peephole);
+            self
+        }
+
+        fn code(&self) -> Vec<(InstructionBase, u16)> {
+            self.instructions.iter().map(|instruction| (instruction.instruct, instruction.arg)).collect()
+        }
+
+        fn constant(&self, index: usize) -> &Constant {
+            &self.constants[self.instructions[index].arg as usize]
+        }
+    }
+
+    fn int(value: i128) -> Constant {
+        Constant::Integer(value, LeBlancType::Int)
+    }
+
+    fn handler(start: u16, end: u16, target: u16) -> ExceptionHandler {
+        ExceptionHandler::new(start, end, target, 0, String::new())
+    }
+
+    #[test]
+    fn folds_arithmetic_on_constants() {
+        let mut function = Function::new(&[(LoadConstant, 0), (LoadConstant, 1), (BinaryMultiply, 0), (LoadConstant, 0), (BinaryAdd, 0), (Return, 0)], vec![int(6), int(7)]);
+        function.run(|peephole| peephole.fold_constants());
+        assert_eq!(function.code().len(), 2);
+        assert_eq!(function.constant(0), &int(48));
+    }
+
+    #[test]
+    fn folding_keeps_the_type_of_the_operands() {
+        let mut function = Function::new(&[(LoadConstant, 0), (LoadConstant, 1), (BinaryAdd, 0), (Return, 0)], vec![int(1), Constant::Decimal(0.5, LeBlancType::Double)]);
+        function.run(|peephole| peephole.fold_constants());
+        assert_eq!(function.constant(0), &Constant::Decimal(1.5, LeBlancType::Double));
+    }
+
+    #[test]
+    fn operations_that_raise_are_not_folded() {
+        let cases = [
+            (int(i32::MAX as i128), int(1), BinaryAdd),
+            (Constant::Integer(i64::MIN as i128, LeBlancType::Int64), Constant::Integer(-1, LeBlancType::Int64), BinaryMultiply),
+            (int(1), int(0), BinaryDivide),
+            (int(2), int(-1), BinaryPower),
+            (Constant::String("a".to_string()), int(1), BinarySubtract)
+        ];
+        for (left, right, operator) in cases {
+            let code = [(LoadConstant, 0), (LoadConstant, 1), (operator, 0), (Return, 0)];
+            let mut function = Function::new(&code, vec![left, right]);
+            assert_eq!(function.run(|peephole| peephole.fold_constants()).code(), code);
+        }
+    }
+
+    #[test]
+    fn floats_that_overflow_are_not_folded() {
+        let code = [(LoadConstant, 0), (LoadConstant, 1), (BinaryMultiply, 0), (Return, 0)];
+        let mut function = Function::new(&code, vec![Constant::Decimal(f64::MAX, LeBlancType::Double), Constant::Decimal(2.0, LeBlancType::Double)]);
+        assert_eq!(function.run(|peephole| peephole.fold_constants()).code(), code);
+    }
+
+    #[test]
+    fn nothing_is_folded_into_a_jump_target() {
+        let code = [(LoadConstant, 0), (Comparator_If, 3), (LoadConstant, 0), (LoadConstant, 1), (BinaryAdd, 0), (Return, 0)];
+        let mut function = Function::new(&code, vec![int(1), int(2)]);
+        // Instruction 3 is jumped to, so the sequence starting at 2 runs in two parts.
+        assert_eq!(function.run(|peephole| peephole.fold_constants()).code(), code);
+    }
+
+    #[test]
+    fn folding_moves_handlers_and_jumps() {
+        let mut function = Function::new(&[
+            (LoadConstant, 0), (LoadConstant, 1), (BinaryAdd, 0), (StoreLocal, 0),
+            (LoadLocal, 0), (Comparator_If, 8), (LoadLocal, 0), (Raise, 0),
+            (PopTop, 0), (LoadLocal, 0), (Return, 0)
+        ], vec![int(1), int(2)]);
+        function.handlers.push(handler(0, 8, 8));
+        function.run(|peephole| peephole.fold_constants());
+        assert_eq!(function.code(), [
+            (LoadConstant, 2), (StoreLocal, 0), (LoadLocal, 0), (Comparator_If, 6),
+            (LoadLocal, 0), (Raise, 0), (PopTop, 0), (LoadLocal, 0), (Return, 0)
+        ]);
+        let handler = &function.handlers[0];
+        assert_eq!((handler.start, handler.end, handler.target), (0, 6, 6));
+    }
+
+    #[test]
+    fn jumps_to_jumps_are_threaded() {
+        let mut function = Function::new(&[
+            (LoadConstant, 0), (Comparator_If, 3), (LoadConstant, 0), (Comparator_Else, 5), (LoadConstant, 0), (Return, 0)
+        ], vec![int(1)]);
+        function.run(|peephole| peephole.thread_jumps());
+        assert_eq!(function.code()[1], (Comparator_If, 5));
+    }
+
+    #[test]
+    fn jumps_to_the_next_instruction_are_dropped() {
+        let mut function = Function::new(&[
+            (LoadConstant, 0), (Comparator_If, 4), (Comparator_Else, 3), (LoadConstant, 0), (LoadConstant, 1), (Return, 0), (LoadConstant, 0), (Return, 0)
+        ], vec![int(1), int(2)]);
+        function.handlers.push(handler(0, 6, 6));
+        function.run(|peephole| peephole.thread_jumps());
+        assert_eq!(function.code(), [(LoadConstant, 0), (Comparator_If, 3), (LoadConstant, 0), (LoadConstant, 1), (Return, 0), (LoadConstant, 0), (Return, 0)]);
+        let handler = &function.handlers[0];
+        assert_eq!((handler.start, handler.end, handler.target), (0, 5, 5));
+    }
+
+    #[test]
+    fn large_functions_are_compacted() {
+        let mut code = vec![(Comparator_Else, 1)];
+        code.extend([(LoadConstant, 0), (PopTop, 0)].repeat(32_766));
+        code.extend([(LoadConstant, 0), (Comparator_If, u16::MAX), (LoadConstant, 0), (Return, 0)]);
+        assert_eq!(code.len(), u16::MAX as usize + 2);
+        let mut function = Function::new(&code, vec![int(1)]);
+        function.run(|peephole| peephole.thread_jumps());
+        let code = function.code();
+        assert_eq!(code.len(), u16::MAX as usize + 1);
+        assert_eq!((code[0], code[u16::MAX as usize - 2]), ((LoadConstant, 0), (Comparator_If, u16::MAX - 1)));
+    }
+
+    #[test]
+    fn jump_tables_are_threaded_and_moved() {
+        let mut table = JumpTable::new();
+        table.insert(TableKey::Integer(1), 2);
+        table.default = 4;
+        let mut function = Function::new(&[
+            (LoadLocal, 0), (JumpTable, 0), (Comparator_Else, 5), (LoadConstant, 0), (Comparator_Else, 5), (LoadConstant, 1), (Return, 0)
+        ], vec![int(1), int(2)]);
+        function.tables.push(table);
+        function.run(|peephole| peephole.thread_jumps());
+        assert_eq!(function.code(), [(LoadLocal, 0), (JumpTable, 0), (Comparator_Else, 4), (LoadConstant, 0), (LoadConstant, 1), (Return, 0)]);
+        assert_eq!(function.tables[0].entries, [(TableKey::Integer(1), 4)]);
+        assert_eq!(function.tables[0].default, 4);
+    }
+
+    #[test]
+    fn jumps_out_of_a_block_are_not_threaded() {
+        let code = [
+            (MakeBlock, 4), (Comparator_If, 3), (LoadConstant, 0), (Comparator_Else, 6),
+            (LoadConstant, 0), (StoreLocal, 0), (LoadConstant, 0), (Return, 0)
+        ];
+        let mut function = Function::new(&code, vec![int(1)]);
+        assert_eq!(function.run(|peephole| peephole.thread_jumps()).code(), code);
+    }
+
+    #[test]
+    fn dead_stores_are_removed() {
+        let mut function = Function::new(&[(LoadConstant, 0), (StoreLocal, 0), (LoadConstant, 1), (Return, 0)], vec![int(1), int(2)]);
+        assert_eq!(function.run(|peephole| peephole.eliminate_dead_stores()).code(), [(LoadConstant, 1), (Return, 0)]);
+    }
+
+    #[test]
+    fn dead_stores_of_computed_values_become_pops() {
+        let mut function = Function::new(&[(LoadLocal, 1), (LoadLocal, 1), (BinaryAdd, 0), (StoreLocal, 0), (LoadLocal, 1), (Return, 0)], vec![]);
+        assert_eq!(function.run(|peephole| peephole.eliminate_dead_stores()).code()[3], (PopTop, 0));
+    }
+
+    #[test]
+    fn stores_read_by_a_handler_are_kept() {
+        let code = [(LoadConstant, 0), (StoreLocal, 0), (LoadLocal, 1), (Raise, 0), (LoadLocal, 0), (Return, 0)];
+        let mut function = Function::new(&code, vec![int(1)]);
+        function.handlers.push(handler(2, 4, 4));
+        assert_eq!(function.run(|peephole| peephole.eliminate_dead_stores()).code(), code);
+    }
+
+    #[test]
+    fn functions_with_blocks_keep_their_stores() {
+        let code = [(MakeBlock, 3), (LoadLocal, 0), (Return, 0), (StoreLocal, 1), (LoadConstant, 0), (StoreLocal, 0), (LoadConstant, 0), (Return, 0)];
+        let mut function = Function::new(&code, vec![int(1)]);
+        assert_eq!(function.run(|peephole| peephole.eliminate_dead_stores()).code(), code);
+    }
+
+    #[test]
+    fn captured_locals_keep_their_stores() {
+        let code = [(LoadLocal, 0), (MakeUpvalue, 0), (LoadConstant, 0), (StoreUpvalue, 0), (LoadFunction, 0), (MakeClosure, 0), (Return, 0)];
+        let mut function = Function::new(&code, vec![int(1)]);
+        assert_eq!(function.run(|peephole| peephole.eliminate_dead_stores()).code(), code);
+    }
+
+    #[test]
+    fn common_sequences_are_fused() {
+        let mut function = Function::new(&[
+            (LoadLocal, 0), (LoadConstant, 0), (BinaryAdd, 0), (StoreLocal, 0),
+            (LoadLocal, 0), (LoadConstant, 0), (Equality(0), 0), (Comparator_If, 10),
+            (LoadLocal, 0), (LoadLocal, 1), (Return, 0)
+        ], vec![int(1)]);
+        function.run(|peephole| peephole.fuse());
+        let code = function.code();
+        assert_eq!(code[0], (IncrementLocal, 0));
+        assert_eq!(code[4], (CompareLocalConstJump, 0));
+        assert_eq!(code[8], (LoadLocal2, 0));
+        // The operands stay where they are.
+        assert_eq!(code[1..4], [(LoadConstant, 0), (BinaryAdd, 0), (StoreLocal, 0)]);
+    }
+
+    #[test]
+    fn sequences_are_not_fused_across_lines_or_jump_targets() {
+        let mut function = Function::new(&[
+            (LoadLocal, 0), (LoadConstant, 0), (BinaryAdd, 0), (StoreLocal, 0),
+            (LoadLocal, 0), (LoadConstant, 0), (BinaryAdd, 0), (StoreLocal, 0), (Return, 0)
+        ], vec![int(1)]);
+        function.instructions[3].line_number = 2;
+        function.handlers.push(handler(0, 5, 5));
+        let code = function.code();
+        assert_eq!(function.run(|peephole| peephole.fuse()).code(), code);
+    }
+}
@@ -76,6 +76,12 @@ pub struct  SimpleCounter {
     value: i64
 }
 
+impl Default for SimpleCounter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl SimpleCounter {
     pub fn new() -> SimpleCounter {
         SimpleCounter {
@@ -0,0 +1,249 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use crate::leblanc::compiler::compiler_rewrite::ast::Location;
+use crate::leblanc::compiler::compiler_rewrite::parser::ParseError;
+use crate::leblanc::rustblanc::json::Json;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Severity {
+    Error,
+    Warning
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Severity::Error => write!(f, "error"),
+            Severity::Warning => write!(f, "warning")
+        }
+    }
+}
+
+/// Every kind of problem the compiler reports. The codes are stable: new kinds get new numbers
+/// and retired ones are never reused. Errors are numbered from `LB0001`, warnings from `LB1001`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DiagnosticCode {
+    InvalidSyntax,
+    UnknownModule,
+    UndeclaredVariable,
+    VariableAlreadyDeclared,
+    UndefinedFunction,
+    ArgumentCount,
+    DuplicateFunction,
+    MissingBody,
+    Unsupported,
+    InvalidAssignment,
+    UnknownException,
+    InvalidOperator,
+    BuiltinRedeclared,
+    ExceptionArguments,
+    ImbalancedQuotation,
+    ImbalancedDelimiter,
+    MissingSemicolon,
+    InvalidGlobalDeclaration,
+    FlexReassignment,
+    IncompatibleType,
+    UnknownClass,
+    InvalidInheritance,
+    DuplicateMember,
+    UnknownMember,
+    MissingTraitMethod,
+    UnknownExtension,
+    InvalidExtension,
+    UnknownVariant,
+    GuardFallthrough,
+    FunctionTooLarge,
+
+    UnusedVariable,
+    ShadowedVariable,
+    UnreachableCode,
+    NonExhaustiveMatch
+}
+
+impl DiagnosticCode {
+    pub fn code(&self) -> &'static str {
+        match self {
+            DiagnosticCode::InvalidSyntax => "LB0001",
+            DiagnosticCode::UnknownModule => "LB0002",
+            DiagnosticCode::UndeclaredVariable => "LB0003",
+            DiagnosticCode::VariableAlreadyDeclared => "LB0004",
+            DiagnosticCode::UndefinedFunction => "LB0005",
+            DiagnosticCode::ArgumentCount => "LB0006",
+            DiagnosticCode::DuplicateFunction => "LB0007",
+            DiagnosticCode::MissingBody => "LB0008",
+            DiagnosticCode::Unsupported => "LB0009",
+            DiagnosticCode::InvalidAssignment => "LB0010",
+            DiagnosticCode::UnknownException => "LB0011",
+            DiagnosticCode::InvalidOperator => "LB0012",
+            DiagnosticCode::BuiltinRedeclared => "LB0013",
+            DiagnosticCode::ExceptionArguments => "LB0014",
+            DiagnosticCode::ImbalancedQuotation => "LB0015",
+            DiagnosticCode::ImbalancedDelimiter => "LB0016",
+            DiagnosticCode::MissingSemicolon => "LB0017",
+            DiagnosticCode::InvalidGlobalDeclaration => "LB0018",
+            DiagnosticCode::FlexReassignment => "LB0019",
+            DiagnosticCode::IncompatibleType => "LB0020",
+            DiagnosticCode::UnknownClass => "LB0021",
+            DiagnosticCode::InvalidInheritance => "LB0022",
+            DiagnosticCode::DuplicateMember => "LB0023",
+            DiagnosticCode::UnknownMember => "LB0024",
+            DiagnosticCode::MissingTraitMethod => "LB0025",
+            DiagnosticCode::UnknownExtension => "LB0026",
+            DiagnosticCode::InvalidExtension => "LB0027",
+            DiagnosticCode::UnknownVariant => "LB0028",
+            DiagnosticCode::GuardFallthrough => "LB0029",
+            DiagnosticCode::FunctionTooLarge => "LB0030",
+
+            DiagnosticCode::UnusedVariable => "LB1001",
+            DiagnosticCode::ShadowedVariable => "LB1002",
+            DiagnosticCode::UnreachableCode => "LB1003",
+            DiagnosticCode::NonExhaustiveMatch => "LB1004"
+        }
+    }
+
+    pub fn severity(&self) -> Severity {
+        match self {
+            DiagnosticCode::UnusedVariable | DiagnosticCode::ShadowedVariable | DiagnosticCode::UnreachableCode
+            | DiagnosticCode::NonExhaustiveMatch => Severity::Warning,
+            _ => Severity::Error
+        }
+    }
+}
+
+impl Display for DiagnosticCode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.code())
+    }
+}
+
+/// A span related to a diagnostic other than the one it points at, e.g. the earlier declaration
+/// of a redeclared variable.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Label {
+    pub location: Location,
+    pub message: String
+}
+
+/// A single error or warning found while compiling a file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub code: DiagnosticCode,
+    pub message: String,
+    pub location: Location,
+    pub labels: Vec<Label>,
+    pub notes: Vec<String>
+}
+
+impl Diagnostic {
+    pub fn new(code: DiagnosticCode, message: String, location: Location) -> Diagnostic {
+        Diagnostic { code, message, location, labels: vec![], notes: vec![] }
+    }
+
+    pub fn with_label(mut self, location: Location, message: &str) -> Diagnostic {
+        self.labels.push(Label { location, message: message.to_string() });
+        self
+    }
+
+    pub fn with_note(mut self, note: String) -> Diagnostic {
+        self.notes.push(note);
+        self
+    }
+
+    pub fn severity(&self) -> Severity {
+        self.code.severity()
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.severity() == Severity::Error
+    }
+
+    /// The diagnostic as a JSON object, for `--message-format=json` and the language server.
+    pub fn to_json(&self, path: &str) -> Json {
+        let mut spans = vec![json_span(path, &self.location, None, true)];
+        spans.extend(self.labels.iter().map(|label| json_span(path, &label.location, Some(&label.message), false)));
+        Json::object(vec![
+            ("code", self.code.code().into()),
+            ("severity", self.severity().to_string().into()),
+            ("message", self.message.clone().into()),
+            ("file", path.into()),
+            ("line", self.location.line.into()),
+            ("column", self.location.column.into()),
+            ("spans", Json::Array(spans)),
+            ("notes", self.notes.clone().into())
+        ])
+    }
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}[{}] {}: {}", self.severity(), self.code, self.location, self.message)
+    }
+}
+
+impl From<ParseError> for Diagnostic {
+    fn from(error: ParseError) -> Self {
+        Diagnostic::new(DiagnosticCode::InvalidSyntax, error.message, error.location)
+    }
+}
+
+/// How diagnostics are written to stderr: coloured source excerpts, or one JSON object per line.
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Default)]
+pub enum MessageFormat {
+    #[default]
+    Human,
+    Json
+}
+
+impl FromStr for MessageFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "human" => Ok(MessageFormat::Human),
+            "json" => Ok(MessageFormat::Json),
+            other => Err(format!("unknown message format '{}' (expected human or json)", other))
+        }
+    }
+}
+
+/// The candidate closest to `name`, if any is close enough to be a likely typo: one is a prefix
+/// of the other, or they are a few edits apart.
+pub fn similar_name<'a>(name: &str, candidates: impl IntoIterator<Item=&'a str>) -> Option<&'a str> {
+    let allowed = name.chars().count() / 3;
+    candidates.into_iter()
+        .filter(|candidate| *candidate != name && candidate.starts_with(|c: char| c.is_alphabetic() || c == '_'))
+        .filter_map(|candidate| {
+            let prefixed = candidate.starts_with(name) || name.starts_with(candidate);
+            let distance = edit_distance(name, candidate);
+            (prefixed || distance <= allowed).then_some((distance, candidate))
+        })
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<char>>();
+    let mut previous = (0..=b.len()).collect::<Vec<usize>>();
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != *b_char);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+fn json_span(path: &str, location: &Location, label: Option<&str>, primary: bool) -> Json {
+    Json::object(vec![
+        ("file", path.into()),
+        ("line", location.line.into()),
+        ("column", location.column.into()),
+        ("start", location.start.into()),
+        ("end", location.end.into()),
+        ("label", label.into()),
+        ("primary", primary.into())
+    ])
+}
@@ -0,0 +1,380 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::str::FromStr;
+use std::time::Instant;
+
+use crate::leblanc::compiler::compile::{compile_stub, compile_to};
+use crate::leblanc::compiler::compile_types::CompilationMode;
+use crate::leblanc::compiler::compile_types::full_reader::{read_bytecode, read_file};
+use crate::leblanc::compiler::compiler_rewrite::peephole;
+use crate::leblanc::compiler::diagnostic::MessageFormat;
+use crate::leblanc::core::bytecode::LeblancBytecode;
+use crate::leblanc::core::internal::methods::builtins::builtin_debug::builtin_disassemble::disassemble_handle;
+use crate::leblanc::core::interpreter::interactive::start;
+use crate::leblanc::core::interpreter::leblanc_runner::LeBlancRunner;
+use crate::leblanc::core::interpreter::{load_globals, run};
+use crate::leblanc::core::interpreter::vm_stack::{set_limits, StackLimits, MAX_CALLS_LIMIT};
+use crate::leblanc::lsp::server::serve;
+use crate::LeBlancType;
+
+pub const EXIT_SUCCESS: i32 = 0;
+pub const EXIT_FAILURE: i32 = 1;
+pub const EXIT_USAGE: i32 = 2;
+
+static USAGE: &str = "\
+Usage: leblanc [options] <command> [arguments]
+
+Commands:
+    compile <file.lb> [-o <out.lbbc>]    Compile a source file to bytecode, or to a .lbsf type
+                                         stub in stub mode
+    run <file.lb|file.lbbc> [args...]    Compile if needed, then run main
+    dis <file.lb|file.lbbc>              Disassemble every function in a file
+    repl                                 Start the interactive interpreter
+    lsp                                  Start a language server on stdin/stdout
+
+Options:
+    --mode <full|stub|bytecode|realtime> Compilation mode (default: full)
+    --message-format <human|json>        How compile diagnostics are printed to stderr; json
+                                         prints one object per line (default: human)
+    --stack-size <values>                How many values the VM stack can hold before a
+                                         StackOverflowException is raised (default: 1048576)
+    --max-depth <calls>                  How deep calls can nest before a StackOverflowException
+                                         is raised (default: 10000, at most 100000)
+    --time                               Print the elapsed time to stderr
+    --dump-bytecode                      Print the bytecode of every function compiled to stderr,
+                                         before and after it is optimized
+    -h, --help                           Print this message
+    -V, --version                        Print the version";
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Command {
+    Compile { input: String, output: Option<String> },
+    Run { input: String, args: Vec<String> },
+    Disassemble { input: String },
+    Repl,
+    Lsp,
+    Help,
+    Version
+}
+
+#[derive(PartialEq, Eq, Clone)]
+pub struct DriverOptions {
+    pub command: Command,
+    pub mode: CompilationMode,
+    pub format: MessageFormat,
+    pub time: bool,
+    /// Print the bytecode of every function compiled, before and after it is optimized.
+    pub dump: bool,
+    pub limits: StackLimits,
+}
+
+/// Parses the arguments following the binary name. Everything after the input file of `run`
+/// (or after a `--`) is handed to the program untouched.
+pub fn parse_arguments(args: &[String]) -> Result<DriverOptions, String> {
+    let mut mode = CompilationMode::Full;
+    let mut format = MessageFormat::Human;
+    let mut time = false;
+    let mut dump = false;
+    let mut limits = StackLimits::default();
+    let mut output = None;
+    let mut subcommand: Option<String> = None;
+    let mut positionals: Vec<String> = vec![];
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if subcommand.as_deref() == Some("run") && !positionals.is_empty() {
+            if arg != "--" || positionals.len() > 1 {
+                positionals.push(arg.clone());
+            }
+            continue;
+        }
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(DriverOptions { command: Command::Help, mode, format, time, dump, limits }),
+            "-V" | "--version" => return Ok(DriverOptions { command: Command::Version, mode, format, time, dump, limits }),
+            "--time" => time = true,
+            "--dump-bytecode" => dump = true,
+            "--mode" => {
+                let value = iter.next().ok_or("--mode expects a value")?;
+                mode = CompilationMode::from_str(value)?;
+            }
+            "--message-format" => {
+                let value = iter.next().ok_or("--message-format expects a value")?;
+                format = MessageFormat::from_str(value)?;
+            }
+            "--stack-size" => {
+                limits.values = parse_limit(arg, iter.next().map(String::as_str), usize::MAX)?;
+            }
+            "--max-depth" => {
+                limits.calls = parse_limit(arg, iter.next().map(String::as_str), MAX_CALLS_LIMIT)?;
+            }
+            "-o" | "--output" => {
+                output = Some(iter.next().ok_or_else(|| format!("{} expects a path", arg))?.clone());
+            }
+            "--" if subcommand.is_some() => {
+                positionals.extend(iter.by_ref().cloned());
+            }
+            other => {
+                if let Some(value) = other.strip_prefix("--mode=") {
+                    mode = CompilationMode::from_str(value)?;
+                } else if let Some(value) = other.strip_prefix("--message-format=") {
+                    format = MessageFormat::from_str(value)?;
+                } else if let Some(value) = other.strip_prefix("--stack-size=") {
+                    limits.values = parse_limit("--stack-size", Some(value), usize::MAX)?;
+                } else if let Some(value) = other.strip_prefix("--max-depth=") {
+                    limits.calls = parse_limit("--max-depth", Some(value), MAX_CALLS_LIMIT)?;
+                } else if other.starts_with('-') && other.len() > 1 {
+                    return Err(format!("unknown option '{}'", other));
+                } else if subcommand.is_none() {
+                    subcommand = Some(other.to_string());
+                } else {
+                    positionals.push(other.to_string());
+                }
+            }
+        }
+    }
+
+    let subcommand = match subcommand {
+        Some(subcommand) => subcommand,
+        None => return Ok(DriverOptions { command: Command::Help, mode, format, time, dump, limits })
+    };
+    if output.is_some() && subcommand != "compile" {
+        return Err(format!("-o is only accepted by 'compile', not '{}'", subcommand));
+    }
+
+    let mut positionals = positionals.into_iter();
+    let command = match subcommand.as_str() {
+        "compile" => Command::Compile { input: expect_input(&mut positionals, "compile")?, output },
+        "run" => Command::Run { input: expect_input(&mut positionals, "run")?, args: positionals.by_ref().collect() },
+        "dis" | "disassemble" => Command::Disassemble { input: expect_input(&mut positionals, "dis")? },
+        "repl" => Command::Repl,
+        "lsp" => Command::Lsp,
+        "help" => Command::Help,
+        other => return Err(format!("unknown command '{}'", other))
+    };
+    if let Some(extra) = positionals.next() {
+        return Err(format!("unexpected argument '{}'", extra));
+    }
+
+    Ok(DriverOptions { command, mode, format, time, dump, limits })
+}
+
+fn parse_limit(option: &str, value: Option<&str>, max: usize) -> Result<usize, String> {
+    let value = value.ok_or_else(|| format!("{} expects a value", option))?;
+    match value.parse::<usize>() {
+        Ok(limit) if limit > max => Err(format!("{} can be at most {}, not {}", option, max, limit)),
+        Ok(limit) if limit > 0 => Ok(limit),
+        _ => Err(format!("{} expects a positive number, not '{}'", option, value))
+    }
+}
+
+fn expect_input(positionals: &mut impl Iterator<Item=String>, subcommand: &str) -> Result<String, String> {
+    positionals.next().ok_or_else(|| format!("'{}' expects an input file", subcommand))
+}
+
+/// Entry point used by `main`: parses `args` and runs the selected command, returning the exit code.
+pub fn drive_from_args(args: &[String]) -> i32 {
+    match parse_arguments(args) {
+        Ok(options) => drive(options),
+        Err(message) => {
+            eprintln!("error: {}\n\n{}", message, USAGE);
+            EXIT_USAGE
+        }
+    }
+}
+
+pub fn drive(options: DriverOptions) -> i32 {
+    let now = Instant::now();
+    set_limits(options.limits);
+    peephole::set_dump(options.dump);
+    let code = match options.command {
+        Command::Compile { input, output } => drive_compile(input, output, options.mode, options.format),
+        Command::Run { input, args } => drive_run(input, args, options.mode, options.format),
+        Command::Disassemble { input } => drive_disassemble(input, options.mode, options.format),
+        Command::Repl => { start(); EXIT_SUCCESS }
+        Command::Lsp => serve(&mut io::stdin().lock(), &mut io::stdout().lock()),
+        Command::Help => { println!("{}", USAGE); EXIT_SUCCESS }
+        Command::Version => { println!("leblanc {}", env!("CARGO_PKG_VERSION")); EXIT_SUCCESS }
+    };
+    if options.time {
+        eprintln!("Total Elapsed: {}", now.elapsed().as_secs_f64());
+    }
+    code
+}
+
+fn drive_compile(input: String, output: Option<String>, mode: CompilationMode, format: MessageFormat) -> i32 {
+    if let Err(message) = check_source(&input) {
+        eprintln!("error: {}", message);
+        return EXIT_FAILURE;
+    }
+    if mode == CompilationMode::StubFile {
+        let output = output.unwrap_or_else(|| default_output(&input, ".lbsf"));
+        if let Err(message) = compile_stub(&input, &output, format) {
+            eprintln!("error: {}", message);
+            return EXIT_FAILURE;
+        }
+        return EXIT_SUCCESS;
+    }
+    let output = output.unwrap_or_else(|| default_output(&input, ".lbbc"));
+    let string = match mode {
+        CompilationMode::Realtime => match fs::read_to_string(&input) {
+            Ok(source) => source,
+            Err(err) => { eprintln!("error: cannot read '{}': {}", input, err); return EXIT_FAILURE; }
+        },
+        _ => input
+    };
+    if compile_to(string, Some(output), mode, format).bytecode.is_empty() {
+        return EXIT_FAILURE;
+    }
+    EXIT_SUCCESS
+}
+
+fn drive_run(input: String, args: Vec<String>, mode: CompilationMode, format: MessageFormat) -> i32 {
+    let bytecode = match load_bytecode(input, mode, format) {
+        Ok(bytecode) => bytecode,
+        Err(message) => { eprintln!("error: {}", message); return EXIT_FAILURE; }
+    };
+    match run(bytecode, args) {
+        Ok(result) if result.lock().typing == LeBlancType::Exception => EXIT_FAILURE,
+        Ok(_) => EXIT_SUCCESS,
+        Err(message) => { eprintln!("error: {}", message); EXIT_FAILURE }
+    }
+}
+
+fn drive_disassemble(input: String, mode: CompilationMode, format: MessageFormat) -> i32 {
+    let bytecode = match load_bytecode(input, mode, format) {
+        Ok(bytecode) => bytecode,
+        Err(message) => { eprintln!("error: {}", message); return EXIT_FAILURE; }
+    };
+    let runner = LeBlancRunner::new(load_globals(bytecode));
+    runner.install_globals();
+    for global in runner.globals() {
+        let method = match global.lock().data.get_inner_method() {
+            Some(method) if !method.is_internal_method() => method.clone(),
+            _ => continue
+        };
+        println!("Disassembly of {}:", method.context.name);
+        println!("{}", disassemble_handle(&method.leblanc_handle.lock()));
+    }
+    EXIT_SUCCESS
+}
+
+/// Loads `input` as bytecode, compiling it in memory first when it is a source file. Only
+/// `compile` writes bytecode files.
+fn load_bytecode(input: String, mode: CompilationMode, format: MessageFormat) -> Result<LeblancBytecode, String> {
+    if input.ends_with(".lbbc") {
+        if !Path::new(&input).is_file() {
+            return Err(format!("cannot read '{}': no such file", input));
+        }
+        return read_file(input);
+    }
+    check_source(&input)?;
+    let string = match mode {
+        CompilationMode::StubFile => return Err("stub mode only produces type stubs and cannot be run".to_string()),
+        CompilationMode::Realtime => fs::read_to_string(&input).map_err(|err| format!("cannot read '{}': {}", input, err))?,
+        _ => input.clone()
+    };
+    let fabric = compile_to(string, None, mode, format);
+    if fabric.bytecode.is_empty() {
+        return Err(format!("could not compile '{}'", input));
+    }
+    Ok(read_bytecode(fabric.bytecode))
+}
+
+fn check_source(input: &str) -> Result<(), String> {
+    if !input.ends_with(".lb") {
+        return Err(format!("'{}' is not a LeBlanc source file (expected .lb)", input));
+    }
+    if !Path::new(input).is_file() {
+        return Err(format!("cannot read '{}': no such file", input));
+    }
+    Ok(())
+}
+
+fn default_output(input: &str, extension: &str) -> String {
+    input.strip_suffix(".lb").unwrap_or(input).to_string() + extension
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn arguments(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    fn options(args: &[&str]) -> DriverOptions {
+        parse_arguments(&arguments(args)).unwrap_or_else(|message| panic!("{:?} is rejected: {}", args, message))
+    }
+
+    fn error(args: &[&str]) -> String {
+        match parse_arguments(&arguments(args)) {
+            Ok(_) => panic!("{:?} is accepted", args),
+            Err(message) => message
+        }
+    }
+
+    #[test]
+    fn commands_take_their_inputs() {
+        assert_eq!(options(&["compile", "a.lb"]).command, Command::Compile { input: "a.lb".to_string(), output: None });
+        assert_eq!(options(&["compile", "a.lb", "-o", "b.lbbc"]).command, Command::Compile { input: "a.lb".to_string(), output: Some("b.lbbc".to_string()) });
+        assert_eq!(options(&["dis", "a.lbbc"]).command, Command::Disassemble { input: "a.lbbc".to_string() });
+        assert_eq!(options(&["disassemble", "a.lb"]).command, Command::Disassemble { input: "a.lb".to_string() });
+        assert_eq!(options(&["repl"]).command, Command::Repl);
+        assert_eq!(options(&["lsp"]).command, Command::Lsp);
+    }
+
+    #[test]
+    fn help_and_version() {
+        assert_eq!(options(&[]).command, Command::Help);
+        assert_eq!(options(&["help"]).command, Command::Help);
+        assert_eq!(options(&["run", "a.lb", "--help"]).command, Command::Run { input: "a.lb".to_string(), args: arguments(&["--help"]) });
+        assert_eq!(options(&["--help", "run", "a.lb"]).command, Command::Help);
+        assert_eq!(options(&["-V"]).command, Command::Version);
+    }
+
+    #[test]
+    fn everything_after_the_program_of_run_is_its_arguments() {
+        assert_eq!(options(&["run", "a.lb", "x", "--time", "-o", "y"]).command, Command::Run { input: "a.lb".to_string(), args: arguments(&["x", "--time", "-o", "y"]) });
+        assert_eq!(options(&["run", "a.lb", "--", "--time"]).command, Command::Run { input: "a.lb".to_string(), args: arguments(&["--time"]) });
+        assert_eq!(options(&["run", "a.lb", "x", "--"]).command, Command::Run { input: "a.lb".to_string(), args: arguments(&["x", "--"]) });
+        assert_eq!(options(&["run", "--", "a.lb", "x"]).command, Command::Run { input: "a.lb".to_string(), args: arguments(&["x"]) });
+        assert!(!options(&["run", "a.lb", "--time"]).time);
+    }
+
+    #[test]
+    fn options_are_read_before_and_after_the_command() {
+        let parsed = options(&["--time", "compile", "--mode", "realtime", "a.lb", "--message-format=json", "--dump-bytecode"]);
+        assert!(parsed.time && parsed.dump);
+        assert!(parsed.mode == CompilationMode::Realtime);
+        assert!(parsed.format == MessageFormat::Json);
+
+        let parsed = options(&["--mode=stub", "--message-format", "human", "compile", "a.lb"]);
+        assert!(parsed.mode == CompilationMode::StubFile);
+        assert!(parsed.format == MessageFormat::Human);
+    }
+
+    #[test]
+    fn stack_limits_are_checked() {
+        assert_eq!(options(&["repl"]).limits, StackLimits::default());
+        assert_eq!(options(&["--stack-size", "64", "--max-depth=100", "repl"]).limits, StackLimits { values: 64, calls: 100 });
+        assert_eq!(options(&["--max-depth", &MAX_CALLS_LIMIT.to_string(), "repl"]).limits.calls, MAX_CALLS_LIMIT);
+        assert_eq!(error(&["--max-depth", &(MAX_CALLS_LIMIT + 1).to_string(), "repl"]), format!("--max-depth can be at most {}, not {}", MAX_CALLS_LIMIT, MAX_CALLS_LIMIT + 1));
+        assert_eq!(error(&["--stack-size=0", "repl"]), "--stack-size expects a positive number, not '0'");
+        assert_eq!(error(&["--stack-size", "lots", "repl"]), "--stack-size expects a positive number, not 'lots'");
+        assert_eq!(error(&["repl", "--max-depth"]), "--max-depth expects a value");
+    }
+
+    #[test]
+    fn mistakes_are_reported() {
+        assert_eq!(error(&["frobnicate"]), "unknown command 'frobnicate'");
+        assert_eq!(error(&["--frobnicate", "repl"]), "unknown option '--frobnicate'");
+        assert_eq!(error(&["compile"]), "'compile' expects an input file");
+        assert_eq!(error(&["run"]), "'run' expects an input file");
+        assert_eq!(error(&["compile", "a.lb", "b.lb"]), "unexpected argument 'b.lb'");
+        assert_eq!(error(&["repl", "a.lb"]), "unexpected argument 'a.lb'");
+        assert_eq!(error(&["dis", "a.lb", "-o", "b"]), "-o is only accepted by 'compile', not 'dis'");
+        assert_eq!(error(&["compile", "a.lb", "-o"]), "-o expects a path");
+        assert_eq!(error(&["--mode"]), "--mode expects a value");
+    }
+}
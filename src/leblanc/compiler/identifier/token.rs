@@ -73,7 +73,7 @@ impl Token {
     }
 
     pub fn symbol(&self, index: usize) -> Symbol {
-        return match self.symbols.get(index) {
+        match self.symbols.get(index) {
             None => {
                 LeblancBaseException::new(&format!("Error indexing symbol in parser ({} not in array of size {})", index, self.symbols.len())
                                           , true, 5009002).handle().unwrap();
@@ -18,6 +18,8 @@ use crate::leblanc::rustblanc::exception::error_stubbing::ErrorStub::InvalidSynt
 use crate::leblanc::rustblanc::relationship::Node;
 use crate::LeBlancType::{Class, Exception, Flex, Null};
 
+static DEBUG: bool = false;
+
 pub fn identify(mut typed_tokens: Vec<TypedToken>, mut import_tokens: Vec<Node<TypedToken>>, type_map: &mut HashMap<String, Vec<Vec<CompileVocab>>>, mut func_matcher: HashMap<PartialToken, Vec<Vec<LeBlancType>>>, errors: &mut Vec<ErrorStub>, mode: CompilationMode) -> Vec<Node<TypedToken>>{
 
     identify_unknown(&mut typed_tokens, type_map);
@@ -48,7 +50,7 @@ pub fn identify(mut typed_tokens: Vec<TypedToken>, mut import_tokens: Vec<Node<T
 
 
     for token in &typed_tokens {
-        println!("Before owwnership: {:?}", token);
+        if DEBUG { println!("Before owwnership: {:?}", token); }
     }
 
     create_ownership(typed_tokens, mode)
@@ -173,7 +175,7 @@ pub fn identify_functions(typed_tokens: &mut Vec<TypedToken>, func_matcher: &mut
         if token.lang_type() == CompileVocab::KEYWORD(Func) {
             arg_types = vec![];
             if i+1 > typed_tokens.len() {
-                println!("My error was here");
+                if DEBUG { println!("My error was here"); }
                 errors.append_item( InvalidSyntax(token.clone()));
             } else {
                 if ndi != 0 {
@@ -302,17 +304,17 @@ pub fn create_anno_functions(typed_tokens: &mut Vec<TypedToken>, _errors: &mut V
         i += 1;
     }
     /*for token in &final_appends {
-        println!("FINAL APPEND TOKEN: {:?}", token);
+        if DEBUG { println!("FINAL APPEND TOKEN: {:?}", token); }
     }*/
     typed_tokens.append(&mut final_appends);
 }
 
 pub fn identify_unknown(typed_tokens: &mut Vec<TypedToken>, type_map: &mut HashMap<String, Vec<Vec<CompileVocab>>>) {
     for token in typed_tokens.iter_mut().filter(|t| t.lang_type().matches("unknown")) {
-        if let Class(class_value) = token.lang_type().extract_native_type() {
+        if let Class(_class_value) = token.lang_type().extract_native_type() {
             let optional_scopes = type_map.get_mut(&token.as_string());
             if optional_scopes.is_some() {
-                let global_value = optional_scopes.as_ref().unwrap().get(0);
+                let global_value = optional_scopes.as_ref().unwrap().first();
                 if global_value.is_some() && !global_value.unwrap().is_empty() {
                     token.set_scope(0);
                     token.set_type(global_value.unwrap()[0]);
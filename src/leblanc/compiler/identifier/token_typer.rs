@@ -25,10 +25,12 @@ use crate::leblanc::core::internal::methods::builtins::create_partial_functions;
 use crate::leblanc::rustblanc::Appendable;
 use crate::leblanc::include::lib::get_core_modules;
 use crate::leblanc::rustblanc::copystring::CopyStringable;
-use crate::LeBlancType::{Dynamic, Exception, Null};
+use crate::LeBlancType::{Dynamic, Null};
 
+static DEBUG: bool = false;
 
-pub fn create_typed_tokens<'a>(mut tokens: Vec<Token>, mut errors: Vec<ErrorStub>, mode: CompilationMode) -> Fabric {
+
+pub fn create_typed_tokens(mut tokens: Vec<Token>, mut errors: Vec<ErrorStub>, mode: CompilationMode) -> Fabric {
     let UNKNOWN_TYPE: LeBlancType = Null;
     let UNKNOWN_VOCAB: CompileVocab = UNKNOWN(UNKNOWN_TYPE);
 
@@ -38,10 +40,10 @@ pub fn create_typed_tokens<'a>(mut tokens: Vec<Token>, mut errors: Vec<ErrorStub
     let mut typed_tokens: Vec<TypedToken> = Vec::new();
     let mut type_map: HashMap<String, Vec<Vec<CompileVocab>>> = HashMap::new();
 
-    let mut new_class_counter = 0;
+    let mut _new_class_counter = 0;
     let mut brace_counter = 0;
     let mut scope_value = 0;
-    let mut class_scope: u32 = 0;
+    let mut _class_scope: u32 = 0;
     let mut global_scope = NotGlobal;
     let lock_global_scope = false;
 
@@ -126,7 +128,7 @@ pub fn create_typed_tokens<'a>(mut tokens: Vec<Token>, mut errors: Vec<ErrorStub
                     '}' => {
                         brace_counter -= 1;
                         if brace_counter == 0 {
-                            class_scope = 0;
+                            _class_scope = 0;
                         }
                     },
                     '[' => {
@@ -200,7 +202,7 @@ pub fn create_typed_tokens<'a>(mut tokens: Vec<Token>, mut errors: Vec<ErrorStub
                             EXTENSION(ExtensionTypeExport(index as u32))
                         }
                         LBKeyword::Class => {
-                            new_class_counter += 1;
+                            _new_class_counter += 1;
                             CLASS(Class(token_string.clone().to_cstring()))
                         }
                         _ => UNKNOWN_VOCAB
@@ -214,7 +216,7 @@ pub fn create_typed_tokens<'a>(mut tokens: Vec<Token>, mut errors: Vec<ErrorStub
             } else if is_native_type(token_string.as_str()) {
                 let vocab_type = TYPE(type_value(token_string.as_str()));
                 vocab_type
-            } else if let TYPE(inner_type) = typed_tokens.get((typed_tokens.len() as i32 - 1) as usize).unwrap_or(&TypedToken::empty()).lang_type() {
+            } else if let Some(TYPE(inner_type)) = typed_tokens.last().map(|token| token.lang_type()) {
                 VARIABLE(inner_type)
             } else {
                 let optional_scope = type_map.get(token_string.as_str());
@@ -231,12 +233,10 @@ pub fn create_typed_tokens<'a>(mut tokens: Vec<Token>, mut errors: Vec<ErrorStub
                     } else {
                         UNKNOWN_VOCAB
                     }
-                } else if is_constant(next_token.as_string().as_str()) {
-                    UNKNOWN_VOCAB
-                } else if next_token.as_string() == "=" || next_token.as_string() == "->" {
+                } else if is_constant(next_token.as_string().as_str()) || next_token.as_string() == "=" || next_token.as_string() == "->" {
                     UNKNOWN_VOCAB
                 } else {
-                    new_class_counter += 1;
+                    _new_class_counter += 1;
                     TYPE(Class(token_string.clone().to_cstring()))
                 }
             };
@@ -318,7 +318,7 @@ pub fn create_typed_tokens<'a>(mut tokens: Vec<Token>, mut errors: Vec<ErrorStub
                     }
                 }
                 FUNCTION(_) => {
-                    println!("LAST TYPE!!: {:?}", typed_tokens[typed_tokens.len() - 1].lang_type());
+                    if DEBUG { println!("LAST TYPE!!: {:?}", typed_tokens[typed_tokens.len() - 1].lang_type()); }
                     if typed_tokens[typed_tokens.len() - 1].lang_type() != CompileVocab::KEYWORD(Func) {
                         errors.append_item(ErrorStub::InvalidGlobalVariableDeclaration(typed_token.clone()))
                     }
@@ -332,7 +332,7 @@ pub fn create_typed_tokens<'a>(mut tokens: Vec<Token>, mut errors: Vec<ErrorStub
 
         if vocab.matches("class") {
             global_scope = NotGlobal;
-            class_scope = type_map.get("Class").unwrap().len() as u32;
+            _class_scope = type_map.get("Class").unwrap().len() as u32;
         }
         last_vocab = vocab;
 
@@ -100,7 +100,7 @@ impl TypedToken {
         } else {
             "0".to_string()
         };
-        let typings = self.a_typing.iter().map(|t| t[1].to_string() + "|").collect::<String>();
+        let typings = self.a_typing[1].iter().map(|t| t.to_string() + "|").collect::<String>();
 
 
 
@@ -18,7 +18,8 @@ pub fn constant_type(string: &str) -> LeBlancType {
         match last_char.to_ascii_uppercase() {
             'L' => return LeBlancType::Int64,
             'F' => return LeBlancType::Float,
-            'D' => return LeBlancType::Double,
+            'D' => return LeBlancType::Decimal,
+            'N' => return LeBlancType::BigInt,
             _ => {}
         }
 
@@ -19,6 +19,13 @@ pub enum LBKeyword {
     Else,
     SelfRT,// Reference and Type
     Class,
+    Trait,
+    Auto,
+    Enum,
+    Match,
+    When,
+    Guard,
+    Yield,
     Null
 }
 
@@ -38,6 +45,13 @@ pub fn keyword_value(value: &str) -> LBKeyword {
         "extension" => LBKeyword::ExtensionImport,
         "ext" => LBKeyword::ExtensionImport,
         "Class" => LBKeyword::Class,
+        "Trait" => LBKeyword::Trait,
+        "auto" => LBKeyword::Auto,
+        "Enum" => LBKeyword::Enum,
+        "match" => LBKeyword::Match,
+        "when" => LBKeyword::When,
+        "guard" => LBKeyword::Guard,
+        "yield" => LBKeyword::Yield,
         "of" => LBKeyword::Of,
         "Self" => LBKeyword::SelfRT,
         "if" => LBKeyword::If,
@@ -67,6 +81,13 @@ impl Display for LBKeyword {
             LBKeyword::Else => "else",
             LBKeyword::SelfRT => "selfrt",
             LBKeyword::Class => "class",
+            LBKeyword::Trait => "Trait",
+            LBKeyword::Auto => "auto",
+            LBKeyword::Enum => "Enum",
+            LBKeyword::Match => "match",
+            LBKeyword::When => "when",
+            LBKeyword::Guard => "guard",
+            LBKeyword::Yield => "yield",
             LBKeyword::Null => "null"
         };
         write!(f, "{}", s)
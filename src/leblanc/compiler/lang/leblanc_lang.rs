@@ -105,7 +105,7 @@ pub fn boundary_value(ch: &char) -> BoundaryType {
 
 impl CompileVocab {
     pub fn extract_native_type(&self) -> &LeBlancType {
-        return match self {
+        match self {
             CompileVocab::CONSTANT(native_type) => native_type,
             CompileVocab::VARIABLE(native_type) => native_type,
             CompileVocab::CONSTRUCTOR(native_type) => native_type,
@@ -146,7 +146,7 @@ impl CompileVocab {
                     LBOperator::AssignEach => 5,
                     LBOperator::Increment => 9,
                     LBOperator::QuickList => 8,
-                    LBOperator::Or | LBOperator::And => 5,
+                    LBOperator::Or | LBOperator::Xor | LBOperator::And => 5,
                     LBOperator::Index => 11,
                     _ => 10
                 }
@@ -10,6 +10,7 @@ pub enum LBOperator {
     Modulo,
     Or,
     And,
+    Xor,
     Not,
     Assign,
     AssignEach,
@@ -46,6 +47,7 @@ pub fn operator_type(value: &str) -> LBOperator {
         "%" => LBOperator::Modulo,
         "or" => LBOperator::Or,
         "and" => LBOperator::And,
+        "xor" => LBOperator::Xor,
         "!" => LBOperator::Not,
         "~" => LBOperator::Inverse,
         "=" => LBOperator::Assign,
@@ -78,6 +80,7 @@ impl Display for LBOperator {
             LBOperator::Modulo => "%",
             LBOperator::Or => "or",
             LBOperator::And => "and",
+            LBOperator::Xor => "xor",
             LBOperator::Not => "!",
             LBOperator::Assign => "=",
             LBOperator::Inverse => "~",
@@ -62,13 +62,13 @@ impl Symbol {
     }
 
     pub fn as_string(&self) -> String {
-        return self.char().to_string();
+        self.char().to_string()
     }
 }
 
 impl PartialEq for Symbol {
     fn eq(&self, other: &Self) -> bool {
-        return self.char() == other.char();
+        self.char() == other.char()
     }
 }
 
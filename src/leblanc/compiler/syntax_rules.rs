@@ -40,6 +40,12 @@ impl ExtraTypeSymbol {
     }
 }
 
+impl Default for RuleAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl RuleAnalyzer {
     pub fn new() -> RuleAnalyzer {
         RuleAnalyzer {
@@ -86,7 +92,7 @@ impl RuleAnalyzer {
                     match next_token.lang_type() {
                         CompileVocab::CONSTANT(token_type) => { if match_type != token_type { errors.append_item(ErrorStub::IncompatibleType(prior_token.clone())) } }
                         CompileVocab::VARIABLE(token_type) => { if match_type != token_type { errors.append_item(ErrorStub::IncompatibleType(prior_token.clone())) } }
-                        CompileVocab::CONSTRUCTOR(token_type) => { if match_type != token_type { errors.append_item(ErrorStub::IncompatibleType(prior_token.clone())) } }
+                        CompileVocab::CONSTRUCTOR(token_type) if match_type != token_type => { errors.append_item(ErrorStub::IncompatibleType(prior_token.clone())) }
                         _ => {}
                     }
                 }
@@ -12,6 +12,8 @@ use crate::leblanc::compiler::symbols::{Symbol, SymbolType};
 use crate::leblanc::compiler::symbols::SymbolType::Whitespace;
 use crate::leblanc::rustblanc::exception::error_stubbing::ErrorStub;
 
+static DEBUG: bool = false;
+
 
 pub fn create_tokens(char_reader: &mut CharReader, mode: CompilationMode) -> Fabric {
     let mut partial_errors: Vec<ErrorStub> = Vec::new();
@@ -90,7 +92,7 @@ pub fn create_tokens(char_reader: &mut CharReader, mode: CompilationMode) -> Fab
             // If the last token is only an operator then we can add the new operator to it
             if *current_symbol.char() == '.' {
                 if constant_type(&(token.as_string() + &current_symbol.as_string())).is_numeric() {
-                    println!("Is constant");
+                    if DEBUG { println!("Is constant"); }
                     token.add_symbol(current_symbol);
                 } else {
                     add_token(&mut tokens, token);
@@ -132,15 +134,20 @@ pub fn create_tokens(char_reader: &mut CharReader, mode: CompilationMode) -> Fab
     }
 
 
+    // The unclosed string runs to the end of the file, which leaves nothing that can be typed.
+    if !partial_errors.is_empty() {
+        return Fabric::new(char_reader.path().clone(), vec![], vec![], vec![], vec![], partial_errors);
+    }
+
     tokens.reverse();
-    println!("Typing");
+    if DEBUG { println!("Typing"); }
     let mut fabric = create_typed_tokens(tokens, partial_errors, mode);
     fabric.path = char_reader.path().clone();
     fabric
 
 }
 
-fn get_next_symbol(char_reader: &mut CharReader, last_symbol: Symbol, mut quote_marker: &mut CharMarker) -> Symbol {
+fn get_next_symbol(char_reader: &mut CharReader, last_symbol: Symbol, quote_marker: &mut CharMarker) -> Symbol {
     let c = char_reader.advance(1);
     let cfuture = char_reader.char_next();
 
@@ -163,10 +170,10 @@ fn get_next_symbol(char_reader: &mut CharReader, last_symbol: Symbol, mut quote_
     }
 
 
-    return Symbol::new(c, cfuture.is_whitespace(),
+    Symbol::new(c, cfuture.is_whitespace(),
                        last_symbol.get_type() == &SymbolType::Whitespace,
                        quote_start, quote_end,
-                       SymbolType::of(c), char_reader.symbol_number(), char_reader.line_number());
+                       SymbolType::of(c), char_reader.symbol_number(), char_reader.line_number())
 }
 
 fn check_start_quote(ch: char, quote_marker: char) -> bool {
@@ -12,4 +12,5 @@ pub mod interpreter;
 pub mod bytecode;
 #[macro_use]
 pub mod leblanc_handle;
-pub mod leblanc_default_data;
\ No newline at end of file
+pub mod leblanc_default_data;
+pub mod extension;
\ No newline at end of file
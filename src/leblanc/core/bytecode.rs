@@ -6,6 +6,8 @@ pub mod instruction_line_bytes;
 pub mod precompiled;
 pub mod decompiled_constant;
 pub mod extension_bytes;
+pub mod class_bytes;
+pub mod exception_table;
 
 use crate::leblanc::core::bytecode::file_body::FileBodyBytecode;
 use crate::leblanc::core::bytecode::file_header::FileHeaderBytecode;
@@ -31,7 +33,6 @@ use crate::leblanc::rustblanc::hex::Hexadecimal;
 ///
 ///
 ///
-
 pub trait ToBytecode {
     fn generate(&mut self) -> Hexadecimal;
 }
@@ -31,6 +31,7 @@ impl ByteRestriction {
         ByteRestriction::new(limit, true)
     }
 
+    #[allow(clippy::result_unit_err)]
     pub fn add_bytes(&mut self, mut bytes: Hexadecimal) -> Result<usize, ()> {
         let size_comparison = if !self.repeated {
             (self.bytes.len() + bytes.len()) as u64
@@ -57,6 +58,7 @@ impl ByteRestriction {
         }
     }
 
+    #[allow(clippy::result_unit_err)]
     pub fn consume_bytes(&mut self, mut bytes: Hexadecimal) -> Result<usize, ()> {
         let size_comparison = if !self.repeated {
             (self.bytes.len() + bytes.len()) as u64
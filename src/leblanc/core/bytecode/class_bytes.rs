@@ -0,0 +1,133 @@
+use crate::leblanc::core::bytecode::byte_limiter::ByteLimit::{Limited, Undefined};
+use crate::leblanc::core::bytecode::byte_limiter::ByteRestriction;
+use crate::leblanc::core::bytecode::ToBytecode;
+use crate::leblanc::rustblanc::hex::Hexadecimal;
+use crate::leblanc::rustblanc::Hexable;
+
+///     name_length | name | total_supertype_length | supertype_length | supertype | ....... |
+///       4 bytes   |  X   |         4 bytes        |      4 bytes     |     X     | ....... |
+///
+///     total_field_length | field_length | field | ....... | total_method_length | method_index | ....... |
+///          4 bytes       |    4 bytes   |   X   | ....... |       4 bytes       |    4 bytes   | ....... |
+///
+/// Only the fields and methods a class declares itself are stored, inherited ones are resolved
+/// through its supertypes when the file is loaded. Method indices point into the file's functions.
+#[derive(Debug)]
+pub struct ClassBytecode {
+    name_length: ByteRestriction,
+    name: ByteRestriction,
+    total_supertype_length: ByteRestriction,
+    supertype_length: ByteRestriction,
+    supertype: ByteRestriction,
+    total_field_length: ByteRestriction,
+    field_length: ByteRestriction,
+    field: ByteRestriction,
+    total_method_length: ByteRestriction,
+    method_index: ByteRestriction,
+}
+
+impl Default for ClassBytecode {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ClassBytecode {
+    pub fn new() -> ClassBytecode {
+        ClassBytecode {
+            name_length: ByteRestriction::once(Limited(4)),
+            name: ByteRestriction::once(Undefined),
+            total_supertype_length: ByteRestriction::once(Limited(4)),
+            supertype_length: ByteRestriction::repeated(Limited(4)),
+            supertype: ByteRestriction::repeated(Undefined),
+            total_field_length: ByteRestriction::once(Limited(4)),
+            field_length: ByteRestriction::repeated(Limited(4)),
+            field: ByteRestriction::repeated(Undefined),
+            total_method_length: ByteRestriction::once(Limited(4)),
+            method_index: ByteRestriction::repeated(Limited(4)),
+        }
+    }
+
+    pub fn set_name(&mut self, name: String) {
+        self.name_length.consume_bytes(self.name.consume_bytes(name.to_hex(0)).expect("name too long.").to_hex(128)).expect("name too long.");
+    }
+
+    pub fn name(&self) -> String {
+        self.name.bytes().to_hexable::<String>()
+    }
+
+    pub fn add_supertype(&mut self, name: String) {
+        self.supertype_length.consume_bytes(self.supertype.consume_bytes(name.to_hex(0)).expect("supertype name too long").to_hex(128)).expect("supertype name too long");
+    }
+
+    pub fn add_field(&mut self, name: String) {
+        self.field_length.consume_bytes(self.field.consume_bytes(name.to_hex(0)).expect("field name too long").to_hex(128)).expect("field name too long");
+    }
+
+    pub fn add_method(&mut self, function_index: u32) {
+        self.method_index.consume_bytes(function_index.to_hex(4)).expect("method index too long");
+    }
+
+    pub fn supertypes(&self) -> Vec<String> {
+        self.supertype.iter().unwrap().map(|hex| hex.to_hexable::<String>()).collect()
+    }
+
+    pub fn fields(&self) -> Vec<String> {
+        self.field.iter().unwrap().map(|hex| hex.to_hexable::<String>()).collect()
+    }
+
+    pub fn methods(&self) -> Vec<u32> {
+        self.method_index.iter().unwrap().map(|hex| hex.to_hexable::<u32>()).collect()
+    }
+
+    pub fn from(hex: &mut Hexadecimal) -> ClassBytecode {
+        let mut bytecode = ClassBytecode::new();
+        let name_length = hex.scrape(bytecode.name_length.unpack().unwrap() as usize);
+        let name = hex.scrape(name_length.to_hexable::<u32>() as usize);
+
+        let total_supertype_length = hex.scrape(bytecode.total_supertype_length.unpack().unwrap() as usize);
+        let mut supertype_bytes = hex.scrape(total_supertype_length.to_hexable::<u32>() as usize);
+        while !supertype_bytes.is_empty() {
+            let supertype_length = supertype_bytes.scrape(bytecode.supertype_length.unpack().unwrap() as usize);
+            let supertype = supertype_bytes.scrape(supertype_length.to_hexable::<u32>() as usize);
+            bytecode.supertype_length.consume_bytes(supertype_length).unwrap();
+            bytecode.supertype.consume_bytes(supertype).unwrap();
+        }
+
+        let total_field_length = hex.scrape(bytecode.total_field_length.unpack().unwrap() as usize);
+        let mut field_bytes = hex.scrape(total_field_length.to_hexable::<u32>() as usize);
+        while !field_bytes.is_empty() {
+            let field_length = field_bytes.scrape(bytecode.field_length.unpack().unwrap() as usize);
+            let field = field_bytes.scrape(field_length.to_hexable::<u32>() as usize);
+            bytecode.field_length.consume_bytes(field_length).unwrap();
+            bytecode.field.consume_bytes(field).unwrap();
+        }
+
+        let total_method_length = hex.scrape(bytecode.total_method_length.unpack().unwrap() as usize);
+        let mut method_bytes = hex.scrape(total_method_length.to_hexable::<u32>() as usize);
+        while !method_bytes.is_empty() {
+            let method_index = method_bytes.scrape(bytecode.method_index.unpack().unwrap() as usize);
+            bytecode.method_index.consume_bytes(method_index).unwrap();
+        }
+
+        bytecode.name_length.consume_bytes(name_length).unwrap();
+        bytecode.name.consume_bytes(name).unwrap();
+        bytecode
+    }
+}
+
+impl ToBytecode for ClassBytecode {
+    fn generate(&mut self) -> Hexadecimal {
+        let supertypes = self.supertype_length.join(&self.supertype);
+        self.total_supertype_length.consume_bytes(supertypes.len().to_hex(128)).expect("Supertypes too long");
+
+        let fields = self.field_length.join(&self.field);
+        self.total_field_length.consume_bytes(fields.len().to_hex(128)).expect("Fields too long");
+
+        let methods = self.method_index.bytes();
+        self.total_method_length.consume_bytes(methods.len().to_hex(128)).expect("Methods too long");
+
+        self.name_length.bytes() + self.name.bytes() + self.total_supertype_length.bytes() + supertypes
+            + self.total_field_length.bytes() + fields + self.total_method_length.bytes() + methods
+    }
+}
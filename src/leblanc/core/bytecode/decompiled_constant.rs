@@ -1,7 +1,15 @@
+use crate::leblanc::core::interpreter::jump_table::JumpTable;
+use std::sync::Arc;
+use num::BigInt;
 use crate::leblanc::core::leblanc_object::LeBlancObject;
 use crate::leblanc::core::native_types::arch_type::leblanc_object_arch;
 use crate::leblanc::core::native_types::boolean_type::leblanc_object_boolean;
+use crate::leblanc::core::native_types::bigint_type::leblanc_object_bigint;
 use crate::leblanc::core::native_types::char_type::leblanc_object_char;
+use crate::leblanc::core::native_types::decimal_type::{leblanc_object_decimal, LeblancDecimal};
+use crate::leblanc::core::native_types::derived::DerivedType;
+use crate::leblanc::core::native_types::derived::set_type::{leblanc_object_set, LeblancSet};
+use crate::leblanc::core::native_types::derived::tuple_type::{leblanc_object_tuple, LeblancTuple};
 use crate::leblanc::core::native_types::double_type::leblanc_object_double;
 use crate::leblanc::core::native_types::float_type::leblanc_object_float;
 use crate::leblanc::core::native_types::int128_type::leblanc_object_int128;
@@ -10,6 +18,7 @@ use crate::leblanc::core::native_types::int_type::leblanc_object_int;
 use crate::leblanc::core::native_types::short_type::leblanc_object_short;
 use crate::leblanc::core::native_types::string_type::leblanc_object_string;
 use crate::leblanc::rustblanc::hex::Hexadecimal;
+use crate::leblanc::rustblanc::strawberry::Strawberry;
 use crate::leblanc::rustblanc::Hexable;
 use crate::LeBlancType;
 
@@ -27,6 +36,24 @@ impl DecompiledConstant {
         }
     }
 
+    /// The jump table of a `match` or `when`, which is stored as a `marker` constant.
+    pub fn jump_table(&self) -> Option<JumpTable> {
+        (self.constant_type == LeBlancType::Marker).then(|| JumpTable::from_hex(&self.constant_data))
+    }
+
+    /// The values of a tuple or set, each stored as its type, length and bytes.
+    fn values(&self) -> Vec<Arc<Strawberry<LeBlancObject>>> {
+        let mut hex = self.constant_data.clone();
+        let mut values = vec![];
+        while !hex.is_empty() {
+            let typing = LeBlancType::from_enum_id(hex.scrape(2).to_hexable::<u16>());
+            let length = hex.scrape(4).to_hexable::<u32>();
+            let value = DecompiledConstant::new(hex.scrape(length as usize), typing);
+            values.push(value.to_leblanc_object().to_mutex());
+        }
+        values
+    }
+
     pub fn to_leblanc_object(self) -> LeBlancObject {
         match self.constant_type {
             LeBlancType::Char => leblanc_object_char(char::from_hex(&self.constant_data)),
@@ -35,10 +62,15 @@ impl DecompiledConstant {
             LeBlancType::Int64 => leblanc_object_int64(i64::from_hex(&self.constant_data)),
             LeBlancType::Int128 => leblanc_object_int128(i128::from_hex(&self.constant_data)),
             LeBlancType::Arch => leblanc_object_arch(isize::from_hex(&self.constant_data)),
+            LeBlancType::BigInt => leblanc_object_bigint(BigInt::from_hex(&self.constant_data)),
             LeBlancType::Float => leblanc_object_float(f32::from_hex(&self.constant_data)),
             LeBlancType::Double => leblanc_object_double(f64::from_hex(&self.constant_data)),
+            LeBlancType::Decimal => leblanc_object_decimal(LeblancDecimal::from_hex(&self.constant_data)),
             LeBlancType::Boolean => leblanc_object_boolean(bool::from_hex(&self.constant_data)),
             LeBlancType::String => leblanc_object_string(String::from_hex(&self.constant_data)),
+            LeBlancType::Null => LeBlancObject::null(),
+            LeBlancType::Derived(DerivedType::Tuple) => leblanc_object_tuple(LeblancTuple::new(self.values())),
+            LeBlancType::Derived(DerivedType::Set) => leblanc_object_set(LeblancSet::new(self.values())),
             _ => LeBlancObject::error()
         }
     }
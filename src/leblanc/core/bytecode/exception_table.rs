@@ -0,0 +1,81 @@
+use crate::leblanc::core::native_types::error_type::LeblancError;
+use crate::leblanc::rustblanc::hex::Hexadecimal;
+use crate::leblanc::rustblanc::Hexable;
+
+/// One entry of a function's exception table. While an instruction in `start..end` fails with an
+/// error named `exception` (any error when `exception` is empty), the stack is cut back to `depth`
+/// values, the error is pushed and execution resumes at `target`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ExceptionHandler {
+    pub start: u16,
+    pub end: u16,
+    pub target: u16,
+    pub depth: u16,
+    pub exception: String
+}
+
+impl ExceptionHandler {
+    pub fn new(start: u16, end: u16, target: u16, depth: u16, exception: String) -> ExceptionHandler {
+        ExceptionHandler { start, end, target, depth, exception }
+    }
+
+    pub fn covers(&self, instruction: u64) -> bool {
+        self.start as u64 <= instruction && instruction < self.end as u64
+    }
+
+    /// `Name` only catches errors of exactly that type, `Name!` catches its whole family.
+    pub fn catches(&self, error: &LeblancError) -> bool {
+        match self.exception.strip_suffix('!') {
+            _ if self.exception.is_empty() => true,
+            Some(family) => error.is_a(family),
+            None => error.name() == self.exception
+        }
+    }
+
+    /// The fixed size part of an entry: start, end, target and depth as two bytes each.
+    pub fn bounds(&self) -> Hexadecimal {
+        self.start.to_hex(2) + self.end.to_hex(2) + self.target.to_hex(2) + self.depth.to_hex(2)
+    }
+
+    pub fn from_bytes(mut bounds: Hexadecimal, exception: Hexadecimal) -> ExceptionHandler {
+        let start = bounds.scrape(2).to_hexable::<u16>();
+        let end = bounds.scrape(2).to_hexable::<u16>();
+        let target = bounds.scrape(2).to_hexable::<u16>();
+        let depth = bounds.scrape(2).to_hexable::<u16>();
+        ExceptionHandler::new(start, end, target, depth, exception.to_hexable::<String>())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::leblanc::core::native_types::error_type::LeblancError;
+    use crate::leblanc::rustblanc::Hexable;
+    use super::ExceptionHandler;
+
+    fn error(name: &str) -> LeblancError {
+        LeblancError::new(name.to_string(), String::new(), vec![])
+    }
+
+    #[test]
+    fn handlers_cover_their_range_only() {
+        let handler = ExceptionHandler::new(2, 5, 9, 1, String::new());
+        assert_eq!((0..7).filter(|&instruction| handler.covers(instruction)).collect::<Vec<_>>(), vec![2, 3, 4]);
+    }
+
+    #[test]
+    fn handlers_catch_by_name_or_family() {
+        let derived = LeblancError::derived("NotFound".to_string(), vec!["HttpError".to_string(), "Exception".to_string()], String::new());
+        let exact = ExceptionHandler::new(0, 1, 1, 0, "HttpError".to_string());
+        let family = ExceptionHandler::new(0, 1, 1, 0, "HttpError!".to_string());
+        let any = ExceptionHandler::new(0, 1, 1, 0, String::new());
+        assert!(!exact.catches(&derived) && family.catches(&derived) && any.catches(&derived));
+        assert!(!family.catches(&error("TypeException")) && any.catches(&error("TypeException")));
+    }
+
+    #[test]
+    fn handlers_round_trip_through_bytes() {
+        let handler = ExceptionHandler::new(3, 40, 41, 2, "IndexException!".to_string());
+        let decoded = ExceptionHandler::from_bytes(handler.bounds(), handler.exception.to_hex(0));
+        assert_eq!(decoded, handler);
+    }
+}
@@ -1,19 +1,39 @@
 use crate::leblanc::core::bytecode::byte_limiter::ByteLimit::{Limited, Undefined};
 use crate::leblanc::core::bytecode::byte_limiter::ByteRestriction;
 use crate::leblanc::core::bytecode::ToBytecode;
+use crate::leblanc::core::native_types::LeBlancType;
 use crate::leblanc::rustblanc::hex::Hexadecimal;
 use crate::leblanc::rustblanc::Hexable;
 
+///     name_length | name | target | total_property_length | property_name_length | property_name | property_type | ....... |
+///       4 bytes   |  X   | 2 bytes |        4 bytes        |        4 bytes       |       X       |    2 bytes    | ....... |
+///
+///     initializer | total_method_length | owned_method_index | ....... | source_length | source |
+///       4 bytes   |       4 bytes       |       4 bytes      | ....... |    4 bytes    |   X    |
+///
+/// `initializer` is one more than the index of the function setting the properties of an object
+/// to their default values, or 0 if no property has one. Method indices point into the file's functions.
+/// `source` is the file an imported extension was declared in, empty for the file's own extensions.
 #[derive(Debug)]
 pub struct ExtensionBytecode {
     name_length: ByteRestriction,
     name: ByteRestriction,
-    total_parameter_length: ByteRestriction,
-    parameter_name_length: ByteRestriction, // for adding variables to objects
-    parameter_name: ByteRestriction,
-    parameter_type: ByteRestriction,
+    target: ByteRestriction,
+    total_property_length: ByteRestriction,
+    property_name_length: ByteRestriction, // for adding variables to objects
+    property_name: ByteRestriction,
+    property_type: ByteRestriction,
+    initializer: ByteRestriction,
     total_method_length: ByteRestriction,
     owned_method_index: ByteRestriction,
+    source_length: ByteRestriction,
+    source: ByteRestriction,
+}
+
+impl Default for ExtensionBytecode {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl ExtensionBytecode {
@@ -21,12 +41,16 @@ impl ExtensionBytecode {
         ExtensionBytecode {
             name_length: ByteRestriction::once(Limited(4)),
             name: ByteRestriction::once(Undefined),
-            total_parameter_length: ByteRestriction::once(Limited(4)),
-            parameter_name_length: ByteRestriction::repeated(Limited(4)),
-            parameter_name: ByteRestriction::repeated(Undefined),
-            parameter_type: ByteRestriction::repeated(Limited(2)),
+            target: ByteRestriction::once(Limited(2)),
+            total_property_length: ByteRestriction::once(Limited(4)),
+            property_name_length: ByteRestriction::repeated(Limited(4)),
+            property_name: ByteRestriction::repeated(Undefined),
+            property_type: ByteRestriction::repeated(Limited(2)),
+            initializer: ByteRestriction::once(Limited(4)),
             total_method_length: ByteRestriction::once(Limited(4)),
-            owned_method_index: ByteRestriction::repeated(Limited(4))
+            owned_method_index: ByteRestriction::repeated(Limited(4)),
+            source_length: ByteRestriction::once(Limited(4)),
+            source: ByteRestriction::once(Undefined)
         }
     }
 
@@ -34,13 +58,52 @@ impl ExtensionBytecode {
         self.name_length.consume_bytes(self.name.consume_bytes(name.to_hex(0)).expect("name too long.").to_hex(128)).expect("name too long.");
     }
 
-    pub fn add_parameter(&mut self, name: String, type_hex: Hexadecimal) {
-        self.parameter_name_length.consume_bytes(self.parameter_name.consume_bytes(name.to_hex(0)).expect("name too long").to_hex(128)).expect("name too long");
-        self.parameter_type.consume_bytes(type_hex).expect("type hex too much");
+    pub fn name(&self) -> String {
+        self.name.bytes().to_hexable::<String>()
+    }
+
+    pub fn set_target(&mut self, target: LeBlancType) {
+        self.target.consume_bytes(target.enum_id().to_hex(2)).expect("target too long");
+    }
+
+    pub fn target(&self) -> LeBlancType {
+        LeBlancType::from_enum_id(self.target.bytes().to_hexable::<u16>())
+    }
+
+    pub fn add_property(&mut self, name: String, typing: LeBlancType) {
+        self.property_name_length.consume_bytes(self.property_name.consume_bytes(name.to_hex(0)).expect("name too long").to_hex(128)).expect("name too long");
+        self.property_type.consume_bytes(typing.enum_id().to_hex(2)).expect("type hex too much");
+    }
+
+    pub fn properties(&self) -> Vec<(String, LeBlancType)> {
+        let names = self.property_name.iter().unwrap().map(|hex| hex.to_hexable::<String>());
+        let types = self.property_type.iter().unwrap().map(|hex| LeBlancType::from_enum_id(hex.to_hexable::<u16>()));
+        names.zip(types).collect()
+    }
+
+    pub fn set_initializer(&mut self, function_index: Option<u32>) {
+        self.initializer.consume_bytes(function_index.map_or(0, |index| index + 1).to_hex(4)).expect("initializer index too long");
+    }
+
+    pub fn initializer(&self) -> Option<u32> {
+        self.initializer.bytes().to_hexable::<u32>().checked_sub(1)
     }
 
     pub fn add_reference_method(&mut self, method_index: u32) {
-        self.total_method_length.consume_bytes(self.owned_method_index.consume_bytes(method_index.to_hex(4)).expect("method bytes too long").to_hex(128)).expect("method bytes too long");
+        self.owned_method_index.consume_bytes(method_index.to_hex(4)).expect("method bytes too long");
+    }
+
+    pub fn methods(&self) -> Vec<u32> {
+        self.owned_method_index.iter().unwrap().map(|hex| hex.to_hexable::<u32>()).collect()
+    }
+
+    pub fn set_source(&mut self, source: String) {
+        self.source_length.consume_bytes(self.source.consume_bytes(source.to_hex(0)).expect("source too long.").to_hex(128)).expect("source too long.");
+    }
+
+    /// The file the extension was declared in, if it was imported from another one.
+    pub fn source(&self) -> Option<String> {
+        Some(self.source.bytes().to_hexable::<String>()).filter(|source| !source.is_empty())
     }
 
     pub fn from(hex: &mut Hexadecimal) -> ExtensionBytecode {
@@ -48,18 +111,20 @@ impl ExtensionBytecode {
         let name_length = hex.scrape(bytecode.name_length.unpack().unwrap() as usize);
         let name_length_u32 = name_length.to_hexable::<u32>();
         let name = hex.scrape(name_length_u32 as usize);
-        let total_parameter_length = hex.scrape(bytecode.total_parameter_length.unpack().unwrap() as usize);
-        let total_parameter_length_u32 = total_parameter_length.to_hexable::<u32>();
-        let mut parameter_bytes = hex.scrape(total_parameter_length_u32 as usize);
-        while !parameter_bytes.is_empty() {
-            let parameter_name_length = parameter_bytes.scrape(bytecode.parameter_name_length.unpack().unwrap() as usize);
-            let parameter_name_length_u32 = parameter_name_length.to_hexable::<u32>();
-            let parameter_name = parameter_bytes.scrape(parameter_name_length_u32 as usize);
-            let parameter_type = parameter_bytes.scrape(bytecode.parameter_type.unpack().unwrap() as usize);
-            bytecode.parameter_name_length.consume_bytes(parameter_name_length).unwrap();
-            bytecode.parameter_name.consume_bytes(parameter_name).unwrap();
-            bytecode.parameter_type.consume_bytes(parameter_type).unwrap();
+        let target = hex.scrape(bytecode.target.unpack().unwrap() as usize);
+        let total_property_length = hex.scrape(bytecode.total_property_length.unpack().unwrap() as usize);
+        let total_property_length_u32 = total_property_length.to_hexable::<u32>();
+        let mut property_bytes = hex.scrape(total_property_length_u32 as usize);
+        while !property_bytes.is_empty() {
+            let property_name_length = property_bytes.scrape(bytecode.property_name_length.unpack().unwrap() as usize);
+            let property_name_length_u32 = property_name_length.to_hexable::<u32>();
+            let property_name = property_bytes.scrape(property_name_length_u32 as usize);
+            let property_type = property_bytes.scrape(bytecode.property_type.unpack().unwrap() as usize);
+            bytecode.property_name_length.consume_bytes(property_name_length).unwrap();
+            bytecode.property_name.consume_bytes(property_name).unwrap();
+            bytecode.property_type.consume_bytes(property_type).unwrap();
         }
+        let initializer = hex.scrape(bytecode.initializer.unpack().unwrap() as usize);
         let total_method_length = hex.scrape(bytecode.total_method_length.unpack().unwrap() as usize);
         let total_method_length_u32 = total_method_length.to_hexable::<u32>();
         let mut method_bytes = hex.scrape(total_method_length_u32 as usize);
@@ -67,11 +132,16 @@ impl ExtensionBytecode {
             let method_index = method_bytes.scrape(bytecode.owned_method_index.unpack().unwrap() as usize);
             bytecode.owned_method_index.consume_bytes(method_index).unwrap();
         }
+        let source_length = hex.scrape(bytecode.source_length.unpack().unwrap() as usize);
+        let source_length_u32 = source_length.to_hexable::<u32>();
+        let source = hex.scrape(source_length_u32 as usize);
 
         bytecode.name_length.consume_bytes(name_length).unwrap();
         bytecode.name.consume_bytes(name).unwrap();
-        bytecode.total_parameter_length.consume_bytes(total_parameter_length).unwrap();
-        bytecode.total_method_length.consume_bytes(total_method_length).unwrap();
+        bytecode.target.consume_bytes(target).unwrap();
+        bytecode.initializer.consume_bytes(initializer).unwrap();
+        bytecode.source_length.consume_bytes(source_length).unwrap();
+        bytecode.source.consume_bytes(source).unwrap();
 
         bytecode
     }
@@ -80,10 +150,13 @@ impl ExtensionBytecode {
 
 impl ToBytecode for ExtensionBytecode {
     fn generate(&mut self) -> Hexadecimal {
-        let parameter_bytes = self.parameter_name_length.join_thrice(&self.parameter_name, &self.parameter_type);
-        self.total_parameter_length.consume_bytes(parameter_bytes.len().to_hex(128)).expect("Parameter too long");
+        let property_bytes = self.property_name_length.join_thrice(&self.property_name, &self.property_type);
+        self.total_property_length.consume_bytes(property_bytes.len().to_hex(128)).expect("Properties too long");
 
-        self.name_length.bytes() + self.name.bytes() + self.total_parameter_length.bytes() + parameter_bytes + self.total_method_length.bytes() + self.owned_method_index.bytes()
+        let methods = self.owned_method_index.bytes();
+        self.total_method_length.consume_bytes(methods.len().to_hex(128)).expect("Methods too long");
 
+        self.name_length.bytes() + self.name.bytes() + self.target.bytes() + self.total_property_length.bytes() + property_bytes
+            + self.initializer.bytes() + self.total_method_length.bytes() + methods + self.source_length.bytes() + self.source.bytes()
     }
-}
\ No newline at end of file
+}
@@ -1,6 +1,7 @@
 
 use crate::leblanc::core::bytecode::byte_limiter::ByteLimit::{Limited, Undefined};
 use crate::leblanc::core::bytecode::byte_limiter::ByteRestriction;
+use crate::leblanc::core::bytecode::class_bytes::ClassBytecode;
 use crate::leblanc::core::bytecode::extension_bytes::ExtensionBytecode;
 use crate::leblanc::core::bytecode::function_bytes::FunctionBytecode;
 use crate::leblanc::core::bytecode::ToBytecode;
@@ -15,6 +16,15 @@ pub struct FileBodyBytecode {
     total_extension_size: ByteRestriction,
     extension_size: ByteRestriction,
     extension: ByteRestriction,
+    total_class_size: ByteRestriction,
+    class_size: ByteRestriction,
+    class: ByteRestriction,
+}
+
+impl Default for FileBodyBytecode {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl FileBodyBytecode {
@@ -25,7 +35,10 @@ impl FileBodyBytecode {
             function: ByteRestriction::repeated(Undefined),
             total_extension_size: ByteRestriction::once(Limited(8)),
             extension_size: ByteRestriction::repeated(Limited(8)),
-            extension: ByteRestriction::repeated(Undefined)
+            extension: ByteRestriction::repeated(Undefined),
+            total_class_size: ByteRestriction::once(Limited(8)),
+            class_size: ByteRestriction::repeated(Limited(8)),
+            class: ByteRestriction::repeated(Undefined)
         }
     }
 
@@ -37,6 +50,10 @@ impl FileBodyBytecode {
         self.extension_size.consume_bytes(self.extension.consume_bytes(extension.generate()).expect("Extension too large").to_hex(128)).expect("Extension too large");
     }
 
+    pub fn add_class(&mut self, mut class: ClassBytecode) {
+        self.class_size.consume_bytes(self.class.consume_bytes(class.generate()).expect("Class too large").to_hex(128)).expect("Class too large");
+    }
+
     pub fn from(hex: &mut Hexadecimal) -> FileBodyBytecode {
         let mut body = FileBodyBytecode::new();
 
@@ -62,16 +79,31 @@ impl FileBodyBytecode {
             body.extension.consume_bytes(extension).unwrap();
         }
 
+        let total_class_size = hex.scrape(body.total_class_size.unpack().unwrap() as usize);
+        let total_class_size_u64 = total_class_size.to_hexable::<u64>();
+        let mut class_bytes = hex.scrape(total_class_size_u64 as usize);
+        while !class_bytes.is_empty() {
+            let class_size = class_bytes.scrape(body.class_size.unpack().unwrap() as usize);
+            let class_size_u64 = class_size.to_hexable::<u64>();
+            let class = class_bytes.scrape(class_size_u64 as usize);
+            body.class_size.consume_bytes(class_size).unwrap();
+            body.class.consume_bytes(class).unwrap();
+        }
+
         body
 
     }
 
     pub fn functions(&mut self) -> Vec<FunctionBytecode> {
-        return self.function.iter_mut().unwrap().map(FunctionBytecode::from).collect::<Vec<FunctionBytecode>>();
+        self.function.iter_mut().unwrap().map(FunctionBytecode::from).collect::<Vec<FunctionBytecode>>()
     }
 
     pub fn extensions(&mut self) -> Vec<ExtensionBytecode> {
-        return self.extension.iter_mut().unwrap().map(ExtensionBytecode::from).collect::<Vec<ExtensionBytecode>>();
+        self.extension.iter_mut().unwrap().map(ExtensionBytecode::from).collect::<Vec<ExtensionBytecode>>()
+    }
+
+    pub fn classes(&mut self) -> Vec<ClassBytecode> {
+        self.class.iter_mut().unwrap().map(ClassBytecode::from).collect::<Vec<ClassBytecode>>()
     }
 
 
@@ -86,10 +118,15 @@ impl ToBytecode for FileBodyBytecode {
         let extensions = self.extension_size.join_uncloned(&mut self.extension);
         self.total_extension_size.consume_bytes(extensions.len().to_hex(128)).unwrap();
 
+        let classes = self.class_size.join_uncloned(&mut self.class);
+        self.total_class_size.consume_bytes(classes.len().to_hex(128)).unwrap();
+
         let mut final_bytes = self.total_function_size.bytes();
         final_bytes.consume(functions);
         final_bytes.consume(self.total_extension_size.bytes());
         final_bytes.consume(extensions);
+        final_bytes.consume(self.total_class_size.bytes());
+        final_bytes.consume(classes);
 
         //self.total_body_size.consume_bytes(functions).unwrap(); // This looks weird but we do it so that we no longer clone the functions obj saving on some storage space
         final_bytes
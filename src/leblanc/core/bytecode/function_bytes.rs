@@ -2,6 +2,7 @@ use fxhash::{FxHashMap};
 use crate::leblanc::core::bytecode::byte_limiter::ByteLimit::{Limited, Undefined};
 use crate::leblanc::core::bytecode::byte_limiter::ByteRestriction;
 use crate::leblanc::core::bytecode::decompiled_constant::DecompiledConstant;
+use crate::leblanc::core::bytecode::exception_table::ExceptionHandler;
 use crate::leblanc::core::bytecode::instruction_line_bytes::InstructionBytecode;
 use crate::leblanc::core::bytecode::ToBytecode;
 use crate::leblanc::core::leblanc_context::VariableContext;
@@ -29,6 +30,16 @@ pub struct FunctionBytecode {
     instructions_total_size: ByteRestriction,
     instruction_line_length: ByteRestriction,
     instruction_line: ByteRestriction,
+    handlers_total_length: ByteRestriction,
+    handler_bounds: ByteRestriction,
+    handler_name_length: ByteRestriction,
+    handler_name: ByteRestriction,
+}
+
+impl Default for FunctionBytecode {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl FunctionBytecode {
@@ -52,6 +63,10 @@ impl FunctionBytecode {
             instructions_total_size: ByteRestriction::repeated(Limited(6)),
             instruction_line_length: ByteRestriction::repeated(Limited(4)),
             instruction_line: ByteRestriction::repeated(Undefined),
+            handlers_total_length: ByteRestriction::once(Limited(6)),
+            handler_bounds: ByteRestriction::repeated(Limited(8)),
+            handler_name_length: ByteRestriction::repeated(Limited(4)),
+            handler_name: ByteRestriction::repeated(Undefined),
         }
     }
 
@@ -88,8 +103,13 @@ impl FunctionBytecode {
 
     }
 
+    pub fn add_handler(&mut self, handler: &ExceptionHandler) {
+        self.handler_bounds.consume_bytes(handler.bounds()).expect("Handler bounds too long");
+        self.handler_name_length.consume_bytes(self.handler_name.consume_bytes(handler.exception.to_hex(0)).expect("Handler name too long").to_hex(128)).expect("Handler name too long");
+    }
+
     pub fn instruction_lines(&mut self) -> Vec<InstructionBytecode> {
-        return self.instruction_line.iter_mut().unwrap().map(InstructionBytecode::from).collect::<Vec<InstructionBytecode>>();
+        self.instruction_line.iter_mut().unwrap().map(InstructionBytecode::from).collect::<Vec<InstructionBytecode>>()
     }
 
     pub fn constants(&mut self) -> Vec<DecompiledConstant> {
@@ -114,6 +134,12 @@ impl FunctionBytecode {
         variables
     }
 
+    pub fn handlers(&mut self) -> Vec<ExceptionHandler> {
+        let bounds = self.handler_bounds.segments().unwrap();
+        let names = self.handler_name.segments().unwrap();
+        bounds.into_iter().zip(names).map(|(bounds, name)| ExceptionHandler::from_bytes(bounds, name)).collect()
+    }
+
     pub fn arguments(&mut self) -> Vec<LeBlancType> {
         self.arguments.iter().unwrap().map(|hex| LeBlancType::from_enum_id(hex.to_hexable::<u16>())).collect()
     }
@@ -184,6 +210,20 @@ impl FunctionBytecode {
             fb.instruction_line.consume_bytes(instruction_line).unwrap();
         }
 
+        let mut handlers_total_length = hex.scrape(fb.handlers_total_length.unpack().unwrap() as usize);
+        handlers_total_length.extend_to_length(8);
+        let handlers_total_length_u64 = handlers_total_length.to_hexable::<u64>();
+        let mut handlers = hex.scrape(handlers_total_length_u64 as usize);
+        while !handlers.is_empty() {
+            let handler_bounds = handlers.scrape(fb.handler_bounds.unpack().unwrap() as usize);
+            let handler_name_length = handlers.scrape(fb.handler_name_length.unpack().unwrap() as usize);
+            let handler_name_length_u32 = handler_name_length.to_hexable::<u32>();
+            let handler_name = handlers.scrape(handler_name_length_u32 as usize);
+            fb.handler_bounds.consume_bytes(handler_bounds).unwrap();
+            fb.handler_name_length.consume_bytes(handler_name_length).unwrap();
+            fb.handler_name.consume_bytes(handler_name).unwrap();
+        }
+
         fb.argument_length.consume_bytes(argument_length).unwrap();
         fb.name.consume_bytes(name).unwrap();
         fb.name_length.consume_bytes(name_length).unwrap();
@@ -194,6 +234,9 @@ impl FunctionBytecode {
 
 impl ToBytecode for FunctionBytecode {
     fn generate(&mut self) -> Hexadecimal {
+        let handler_bytes = self.handler_bounds.join_thrice(&self.handler_name_length, &self.handler_name);
+        self.handlers_total_length.consume_bytes(handler_bytes.len().to_hex(128)).expect("Handlers too long");
+
         let instruction_bytes = self.instruction_line_length.join(&self.instruction_line);
         self.instructions_total_size.consume_bytes(instruction_bytes.len().to_hex(128)).expect("Instructions too long");
 
@@ -210,6 +253,7 @@ impl ToBytecode for FunctionBytecode {
         self.argument_length.consume_bytes(self.arguments.bytes().len().to_hex(4)).expect("arguments too long");
 
         self.name_length.bytes() + self.name.bytes() + self.argument_length.bytes() + self.arguments.bytes() + self.constants_total_length.bytes() + constants +
-            self.variable_total_length.bytes() + variables + self.precompiled_total_length.bytes() + precompile + self.instructions_total_size.bytes() + instruction_bytes
+            self.variable_total_length.bytes() + variables + self.precompiled_total_length.bytes() + precompile + self.instructions_total_size.bytes() + instruction_bytes +
+            self.handlers_total_length.bytes() + handler_bytes
     }
 }
\ No newline at end of file
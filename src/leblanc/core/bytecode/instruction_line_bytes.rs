@@ -9,7 +9,14 @@ use crate::leblanc::rustblanc::Hexable;
 pub struct InstructionBytecode {
     line_number: ByteRestriction,
     instructions: ByteRestriction,
-    instruction_arguments: ByteRestriction
+    instruction_arguments: ByteRestriction,
+    instruction_columns: ByteRestriction
+}
+
+impl Default for InstructionBytecode {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl InstructionBytecode {
@@ -17,7 +24,8 @@ impl InstructionBytecode {
         InstructionBytecode {
             line_number: ByteRestriction::once(Limited(4)),
             instructions: ByteRestriction::repeated(Limited(2)),
-            instruction_arguments: ByteRestriction::repeated(Limited(2))
+            instruction_arguments: ByteRestriction::repeated(Limited(2)),
+            instruction_columns: ByteRestriction::repeated(Limited(2))
         }
     }
 
@@ -25,9 +33,10 @@ impl InstructionBytecode {
         self.line_number.consume_bytes(line_number.to_hex(128)).expect("Line number too many bytes");
     }
 
-    pub fn add_instruction(&mut self, instruction: Hexadecimal, instruction_argument: Hexadecimal) {
+    pub fn add_instruction(&mut self, instruction: Hexadecimal, instruction_argument: Hexadecimal, column: Hexadecimal) {
         self.instructions.consume_bytes(instruction).expect("instruction too many bytes");
         self.instruction_arguments.consume_bytes(instruction_argument).expect("instruction arg too many bytes");
+        self.instruction_columns.consume_bytes(column).expect("instruction column too many bytes");
     }
 
     pub fn from(hex: &mut Hexadecimal) -> InstructionBytecode {
@@ -37,8 +46,10 @@ impl InstructionBytecode {
         while !hex.is_empty() {
             let instruction = hex.scrape(bytecode.instructions.unpack().unwrap() as usize);
             let instruction_arg = hex.scrape(bytecode.instruction_arguments.unpack().unwrap() as usize);
+            let instruction_column = hex.scrape(bytecode.instruction_columns.unpack().unwrap() as usize);
             bytecode.instructions.consume_bytes(instruction).unwrap();
             bytecode.instruction_arguments.consume_bytes(instruction_arg).unwrap();
+            bytecode.instruction_columns.consume_bytes(instruction_column).unwrap();
         }
 
         bytecode.line_number.consume_bytes(line_number).unwrap();
@@ -49,10 +60,11 @@ impl InstructionBytecode {
     pub fn to_instructions(mut self) -> Vec<Instruction> {
         let mut instructions = self.instructions.segments().unwrap();
         let mut instruction_args = self.instruction_arguments.segments().unwrap();
+        let mut instruction_columns = self.instruction_columns.segments().unwrap();
         let line_number = self.line_number.bytes().to_hexable::<u32>();
         let mut mapped = vec![];
         while !instructions.is_empty() {
-            mapped.push(Instruction::new(InstructionBase::from_hex(&instructions.remove(0)), instruction_args.remove(0).to_hexable::<u16>(), line_number));
+            mapped.push(Instruction::new(InstructionBase::from_hex(&instructions.remove(0)), instruction_args.remove(0).to_hexable::<u16>(), line_number, instruction_columns.remove(0).to_hexable::<u16>()));
         }
         mapped
     }
@@ -60,13 +72,14 @@ impl InstructionBytecode {
     pub fn remove(&mut self) -> (Hexadecimal, Hexadecimal) {
         let instruction = self.instructions.pop().unwrap();
         let arg = self.instruction_arguments.pop().unwrap();
+        self.instruction_columns.pop();
         (instruction, arg)
     }
 }
 
 impl ToBytecode for InstructionBytecode {
     fn generate(&mut self) -> Hexadecimal {
-        let instruction_bytes = self.instructions.join(&self.instruction_arguments);
+        let instruction_bytes = self.instructions.join_thrice(&self.instruction_arguments, &self.instruction_columns);
 
         self.line_number.bytes() + instruction_bytes
     }
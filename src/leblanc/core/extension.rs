@@ -0,0 +1,80 @@
+use std::sync::Arc;
+
+use crate::leblanc::core::leblanc_argument::LeBlancArgument;
+use crate::leblanc::core::leblanc_object::LeBlancObject;
+use crate::leblanc::core::method::Method;
+use crate::leblanc::core::native_types::LeBlancType;
+use crate::leblanc::rustblanc::strawberry::Strawberry;
+
+static mut EXTENSIONS: Vec<Extension> = vec![];
+
+/// Methods and properties added to every object of `target` by `Extension X of target { ... }`.
+#[derive(Debug, Clone)]
+pub struct Extension {
+    pub name: String,
+    pub target: LeBlancType,
+    pub properties: Vec<String>,
+    /// Called with an object once it is given the properties, to set them to their default values.
+    pub initializer: Option<Method>,
+    pub methods: Vec<Method>
+}
+
+/// Replaces the extensions consulted when an object lacks a method or member.
+pub fn install_extensions(extensions: Vec<Extension>) {
+    unsafe { EXTENSIONS = extensions; }
+}
+
+fn extensions_of(typing: LeBlancType) -> impl Iterator<Item=&'static Extension> {
+    unsafe { (&raw const EXTENSIONS).as_ref().unwrap().iter() }.filter(move |extension| extension.target == typing)
+}
+
+/// Whether an extension gives objects of `typing` properties. They are kept on the object, so
+/// values of the type can't be unboxed without losing them.
+pub fn has_properties(typing: LeBlancType) -> bool {
+    extensions_of(typing).any(|extension| !extension.properties.is_empty())
+}
+
+/// An extension method of `typing` named `name` accepting `arguments`. Only consulted after the
+/// object's own methods, so native methods always win.
+pub fn extension_method(typing: LeBlancType, name: &str, arguments: &Vec<LeBlancArgument>) -> Option<Method> {
+    extensions_of(typing).flat_map(|extension| extension.methods.iter())
+        .find(|method| method.matches(name.to_string(), arguments))
+        .cloned()
+}
+
+/// An extension method of `typing` named `name` that takes no arguments.
+pub fn extension_method_no_args(typing: LeBlancType, name: &str) -> Option<Method> {
+    extensions_of(typing).flat_map(|extension| extension.methods.iter())
+        .find(|method| method.context.name == name && method.context.arguments.is_empty())
+        .cloned()
+}
+
+/// Gives `object` the properties of every extension of its type and runs their initializers, the
+/// first time one of them is used. Objects of the same type often share their members, so the
+/// object gets a map of its own. Returns whether an extension of its type declares `name`.
+pub fn attach_properties(object: &Arc<Strawberry<LeBlancObject>>, name: &str) -> Result<bool, Arc<Strawberry<LeBlancObject>>> {
+    let typing = object.lock().typing;
+    if !extensions_of(typing).any(|extension| extension.properties.iter().any(|property| property == name)) {
+        return Ok(false);
+    }
+
+    let members = object.lock().members.clone();
+    let mut attached = members.lock().clone();
+    let mut initializers = vec![];
+    for extension in extensions_of(typing) {
+        if extension.properties.iter().all(|property| attached.contains_key(property)) { continue; }
+        for property in &extension.properties {
+            attached.insert(property.clone(), LeBlancObject::null().to_mutex());
+        }
+        initializers.extend(extension.initializer.iter().cloned());
+    }
+    object.lock().members = Arc::new(Strawberry::new(attached));
+
+    for mut initializer in initializers {
+        let result = initializer.run(object.clone(), &mut [object.clone()]);
+        if result.lock().typing == LeBlancType::Exception {
+            return Err(result);
+        }
+    }
+    Ok(true)
+}
@@ -1,4 +1,5 @@
 pub mod methods;
 pub mod internal_range_generator;
 pub mod transformed_iterator;
-pub mod internal_list_iterator;
\ No newline at end of file
+pub mod internal_list_iterator;
+pub mod combined_iterators;
\ No newline at end of file
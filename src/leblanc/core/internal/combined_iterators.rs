@@ -0,0 +1,108 @@
+use crate::leblanc::rustblanc::strawberry::Strawberry;
+use std::sync::Arc;
+use crate::leblanc::core::internal::transformed_iterator::{pair, TransformedIterator};
+use crate::leblanc::core::leblanc_object::LeBlancObject;
+use crate::leblanc::core::native_types::derived::iterator_type::LeblancIterable;
+use crate::leblanc::core::native_types::derived::list_type::LeblancList;
+
+/// The values of two iterators in pairs, until either runs out.
+#[derive(Clone, Debug)]
+pub struct ZippedIterator {
+    first: Box<dyn LeblancIterable>,
+    second: Box<dyn LeblancIterable>
+}
+
+impl ZippedIterator {
+    pub fn new(first: Box<dyn LeblancIterable>, second: Box<dyn LeblancIterable>) -> ZippedIterator {
+        ZippedIterator { first, second }
+    }
+}
+
+impl LeblancIterable for ZippedIterator {
+    fn lb_next(&mut self) -> Arc<Strawberry<LeBlancObject>> {
+        pair(self.first.lb_next(), self.second.lb_next())
+    }
+
+    fn has_next(&mut self) -> bool {
+        self.first.has_next() && self.second.has_next()
+    }
+
+    fn reverse(&mut self) {
+        self.first.reverse();
+        self.second.reverse();
+    }
+
+    fn to_list(&mut self) -> LeblancList {
+        LeblancList::new(self.collect())
+    }
+
+    fn to_rust_iter(&mut self) -> Box<dyn Iterator<Item=Arc<Strawberry<LeBlancObject>>>> {
+        Box::new(self.clone())
+    }
+
+    fn transformed(&mut self) -> Option<&mut TransformedIterator> { None }
+}
+
+impl Iterator for ZippedIterator {
+    type Item = Arc<Strawberry<LeBlancObject>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.has_next() {
+            true => Some(self.lb_next()),
+            false => None
+        }
+    }
+}
+
+/// The values of one iterator, then those of another.
+#[derive(Clone, Debug)]
+pub struct ChainedIterator {
+    first: Box<dyn LeblancIterable>,
+    second: Box<dyn LeblancIterable>
+}
+
+impl ChainedIterator {
+    pub fn new(first: Box<dyn LeblancIterable>, second: Box<dyn LeblancIterable>) -> ChainedIterator {
+        ChainedIterator { first, second }
+    }
+}
+
+impl LeblancIterable for ChainedIterator {
+    fn lb_next(&mut self) -> Arc<Strawberry<LeBlancObject>> {
+        match self.first.has_next() {
+            true => self.first.lb_next(),
+            false => self.second.lb_next()
+        }
+    }
+
+    fn has_next(&mut self) -> bool {
+        self.first.has_next() || self.second.has_next()
+    }
+
+    fn reverse(&mut self) {
+        self.first.reverse();
+        self.second.reverse();
+        std::mem::swap(&mut self.first, &mut self.second);
+    }
+
+    fn to_list(&mut self) -> LeblancList {
+        LeblancList::new(self.collect())
+    }
+
+    fn to_rust_iter(&mut self) -> Box<dyn Iterator<Item=Arc<Strawberry<LeBlancObject>>>> {
+        Box::new(self.clone())
+    }
+
+    fn transformed(&mut self) -> Option<&mut TransformedIterator> { None }
+}
+
+impl Iterator for ChainedIterator {
+    type Item = Arc<Strawberry<LeBlancObject>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.has_next() {
+            true => Some(self.lb_next()),
+            false => None
+        }
+    }
+}
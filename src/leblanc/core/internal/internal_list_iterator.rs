@@ -1,7 +1,5 @@
-use alloc::rc::Rc;
-use std::cell::RefCell;
 use crate::leblanc::rustblanc::strawberry::Strawberry;
-use std::sync::{Arc, Mutex};
+use std::sync::Arc;
 use crate::leblanc::core::internal::transformed_iterator::TransformedIterator;
 use crate::leblanc::core::leblanc_object::LeBlancObject;
 use crate::leblanc::core::native_types::derived::iterator_type::LeblancIterable;
@@ -28,7 +26,7 @@ impl LeblancIterable for LeblancVecIterator {
         self.vec[self.index - 1].clone()
     }
 
-    fn has_next(&self) -> bool {
+    fn has_next(&mut self) -> bool {
         self.index < self.vec.len()
     }
 
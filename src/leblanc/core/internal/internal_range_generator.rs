@@ -1,13 +1,11 @@
-use alloc::rc::Rc;
 
 use core::fmt::{Debug, Formatter};
-use std::cell::RefCell;
 use std::mem::swap;
 use crate::leblanc::rustblanc::strawberry::Strawberry;
-use std::sync::{Arc, Mutex};
+use std::sync::Arc;
 use crate::leblanc::core::internal::internal_range_generator::RangeGeneratorStepType::{ConditionalStep, FunctionStep, NegativeStep, PositiveStep};
 use crate::leblanc::core::internal::transformed_iterator::TransformedIterator;
-use crate::leblanc::core::leblanc_object::{LBODOperation, LeBlancObject, Reflect, Stringify};
+use crate::leblanc::core::leblanc_object::{LBODOperation, LeBlancObject, Reflect};
 use crate::leblanc::core::method::Method;
 use crate::leblanc::core::native_types::derived::iterator_type::{leblanc_object_iterator, LeblancIterable};
 use crate::leblanc::core::native_types::derived::list_type::LeblancList;
@@ -40,7 +38,7 @@ impl LeblancIterable for LeblancInternalRangeGenerator {
         self.value.clone().to_mutex()
     }
 
-    fn has_next(&self) -> bool {
+    fn has_next(&mut self) -> bool {
         match self.step_type {
             PositiveStep => self.next_value.data < self.boundary.data,
             NegativeStep => self.boundary.data < self.next_value.data,
@@ -58,7 +56,6 @@ impl LeblancIterable for LeblancInternalRangeGenerator {
     }
 
     fn to_list(&mut self) -> LeblancList {
-        println!("Range generator to list");
         LeblancList::new(self.collect() )
     }
 
@@ -68,6 +65,7 @@ impl LeblancIterable for LeblancInternalRangeGenerator {
 }
 
 impl LeblancInternalRangeGenerator {
+    #[allow(clippy::new_ret_no_self)]
     pub fn new(value: Arc<Strawberry<LeBlancObject>>, boundary: Arc<Strawberry<LeBlancObject>>, step: Arc<Strawberry<LeBlancObject>>) -> Result<Arc<Strawberry<LeBlancObject>>, Arc<Strawberry<LeBlancObject>>> {
         let value = value.lock().clone();
         let boundary = boundary.lock().clone();
@@ -77,13 +75,13 @@ impl LeblancInternalRangeGenerator {
             LeBlancType::Double | LeBlancType::Arch => if step.data.as_i128() >= 0 { PositiveStep } else { NegativeStep },
             LeBlancType::String => PositiveStep,
             LeBlancType::Boolean => ConditionalStep,
-            LeBlancType::Function => FunctionStep(step.data.get_mut_inner_method().unwrap().clone()),
-            LeBlancType::Class(_) => { let matched_method = step.methods.iter().filter(|m| {
+            LeBlancType::Function => FunctionStep(Box::new(step.data.get_mut_inner_method().unwrap().clone())),
+            LeBlancType::Class(_) => { let matched_method = step.methods.iter().find(|m| {
                 m.matches("_".to_string(), &vec![value.to_leblanc_arg(0)])
-                }).next().cloned();
+                }).cloned();
                 match matched_method {
                     None => { return Err(step.to_mutex()) }
-                    Some(method) => FunctionStep(method)
+                    Some(method) => FunctionStep(Box::new(method))
                  }
             },
             _ => return Err(step.to_mutex())
@@ -102,25 +100,29 @@ impl LeblancInternalRangeGenerator {
         })).to_mutex())
     }
 
+    #[allow(dead_code)]
     fn conditional_step_fn(&self) -> bool {
         *self.step.reflect().downcast_ref::<bool>().unwrap()
     }
 
+    #[allow(dead_code)]
     fn positive_step_fn(&self) -> bool {
         self.next_value.data < self.boundary.data
     }
 
+    #[allow(dead_code)]
     fn negative_step_fn(&self) -> bool {
         self.boundary.data < self.next_value.data
     }
 
 }
 
+#[allow(clippy::enum_variant_names)]
 #[derive(PartialEq, Clone, Debug)]
 enum RangeGeneratorStepType {
     PositiveStep,
     NegativeStep,
-    FunctionStep(Method),
+    FunctionStep(Box<Method>),
     ConditionalStep,
 }
 
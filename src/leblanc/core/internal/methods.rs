@@ -4,5 +4,9 @@ pub mod builtins;
 pub mod internal_string;
 pub mod internal_iterator;
 pub mod internal_list;
+pub mod internal_dict;
+pub mod internal_set;
+pub mod internal_tuple;
 pub mod internal_promise;
 pub mod internal_group;
+pub mod internal_error;
@@ -1,9 +1,7 @@
 
 
-use alloc::rc::Rc;
-use std::cell::RefCell;
 use crate::leblanc::rustblanc::strawberry::Strawberry;
-use std::sync::{Arc, Mutex};
+use std::sync::Arc;
 use crate::leblanc::rustblanc::hex::Hexadecimal;
 use crate::leblanc::rustblanc::Hexable;
 use crate::leblanc::rustblanc::utils::{decode_hex, encode_hex};
@@ -16,11 +14,13 @@ use crate::leblanc::compiler::compile_types::partial_function::PartialFunction;
 use crate::leblanc::core::internal::methods::builtins::builtin_debug::{_BUILTIN_DEBUG_METHOD_, _BUILTIN_DEBUG_OBJECT_};
 use crate::leblanc::core::internal::methods::builtins::builtin_debug::builtin_disassemble::{_BUILTIN_DISASSEMBLE_METHOD_, _BUILTIN_DISASSEMBLE_OBJECT_};
 use crate::leblanc::core::internal::methods::builtins::builtin_type::{_BUILTIN_TYPE_METHOD_, _BUILTIN_TYPE_OBJECT_};
+use crate::leblanc::core::internal::methods::builtins::builtin_execute::{_BUILTIN_EXECUTE_METHOD_, _BUILTIN_EXECUTE_OBJECT_};
 use crate::LeBlancType;
 
 pub mod builtin_print;
 pub mod builtin_debug;
 pub mod builtin_type;
+pub mod builtin_execute;
 
 #[derive(Debug, PartialEq, Eq, EnumVariantNames, strum_macros::Display, EnumIter)]
 pub enum BuiltinFunctions {
@@ -33,7 +33,8 @@ pub fn create_partial_functions() -> Vec<PartialFunction> {
         PartialFunction::from_method(_BUILTIN_PRINT_METHOD_(), vec![LeBlancType::Null]),
         PartialFunction::from_method(_BUILTIN_DISASSEMBLE_METHOD_(), vec![LeBlancType::Null]),
         PartialFunction::from_method(_BUILTIN_DEBUG_METHOD_(), vec![LeBlancType::Null]),
-        PartialFunction::from_method(_BUILTIN_TYPE_METHOD_(), vec![LeBlancType::Null])
+        PartialFunction::from_method(_BUILTIN_TYPE_METHOD_(), vec![LeBlancType::Null]),
+        PartialFunction::from_method(_BUILTIN_EXECUTE_METHOD_(), vec![LeBlancType::Null])
     ]
 }
 
@@ -41,7 +42,8 @@ pub fn create_builtin_function_objects() -> Vec<Arc<Strawberry<LeBlancObject>>>
     vec![_BUILTIN_PRINT_OBJECT_().to_mutex(),
          _BUILTIN_DISASSEMBLE_OBJECT_().to_mutex(),
          _BUILTIN_DEBUG_OBJECT_().to_mutex(),
-         _BUILTIN_TYPE_OBJECT_().to_mutex()]
+         _BUILTIN_TYPE_OBJECT_().to_mutex(),
+         _BUILTIN_EXECUTE_OBJECT_().to_mutex()]
 }
 
 impl Hexable for BuiltinFunctions {
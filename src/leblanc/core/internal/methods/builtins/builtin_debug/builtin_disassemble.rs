@@ -1,19 +1,20 @@
 
 use std::collections::BTreeSet;
+use std::fmt::Write;
 use std::io;
 
 
 
-use alloc::rc::Rc;
-use std::cell::RefCell;
 use crate::leblanc::rustblanc::strawberry::Strawberry;
-use std::sync::{Arc, Mutex};
+use std::sync::Arc;
 
 use prettytable::{Cell, format, Row, Table};
-use crate::leblanc::core::interpreter::instructions::InstructionBase;
+use crate::leblanc::core::bytecode::exception_table::ExceptionHandler;
+use crate::leblanc::core::interpreter::instructions::{Instruction, InstructionBase};
 use crate::leblanc::core::interpreter::leblanc_runner::get_globals;
 use crate::leblanc::core::leblanc_argument::LeBlancArgument;
-use crate::leblanc::core::leblanc_object::{LeBlancObject, Reflect, Stringify};
+use crate::leblanc::core::leblanc_handle::LeblancHandle;
+use crate::leblanc::core::leblanc_object::{LeBlancObject, Reflect};
 use crate::leblanc::core::method::Method;
 use crate::leblanc::core::method_store::MethodStore;
 use crate::leblanc::core::native_types::base_type::internal_method;
@@ -21,8 +22,6 @@ use crate::leblanc::core::native_types::base_type::internal_method;
 
 use crate::LeBlancType;
 
-static mut STDOUT: Option<io::Stdout> = None;
-
 fn _BUILTIN_DISASSEMBLE(_self: Arc<Strawberry<LeBlancObject>>, args: &mut [Arc<Strawberry<LeBlancObject>>]) -> Arc<Strawberry<LeBlancObject>> {
     let method = args[0].lock().data.get_inner_method().unwrap().clone();
     let dis_rust_func = if args.len() > 1 {
@@ -37,45 +36,10 @@ fn _BUILTIN_DISASSEMBLE(_self: Arc<Strawberry<LeBlancObject>>, args: &mut [Arc<S
             println!("Disassembling builtin");
         }
     } else {
-        let leblanc_handle = method.leblanc_handle;
-        let instructions = leblanc_handle.lock().instructions.clone();
-        let mut prev_line_number = 0;
-        let mut line_number_format = grow_to_size("", 8);
-        let mut instruct_count = 0;
-        let mut table = Table::new();
-        table.set_format(*format::consts::FORMAT_CLEAN);
-        for instruction in instructions.iter() {
-            if instruction.line_number != prev_line_number {
-                line_number_format = grow_to_size(&instruction.line_number.to_string(), 8);
-                prev_line_number = instruction.line_number;
-                table.add_row(Row::new(vec![Cell::new("").with_hspan(5)]));
-            } else {line_number_format = grow_to_size("", 8)}
-
-            let arg_string = match instruction.instruct {
-                InstructionBase::LoadLocal => format!("({})", leblanc_handle.lock().variable_context.values().find(|context| context.relationship == instruction.arg as u32).unwrap().name),
-                InstructionBase::LoadConstant => format!("({})", leblanc_handle.lock().constants[instruction.arg as usize].lock().data),
-                InstructionBase::LoadFunction => format!("({})", unsafe {get_globals()[instruction.arg as usize].lock().data.get_inner_method().unwrap().context.name.clone()}),
-                InstructionBase::Equality(_) => format!("({})", recover_equality_op(instruction.arg as u8)),
-                _ => "".to_string()
-            };
-            table.add_row(Row::new(vec![
-                Cell::new(&line_number_format),
-                Cell::new(&instruct_count.to_string()),
-                Cell::new(&(instruction.instruct.to_string())),
-                Cell::new(&instruction.arg.to_string()),
-                Cell::new(&arg_string)
-            ]));
-            instruct_count += 2;
-        }
-        output = table.to_string();
+        output = disassemble_handle(method.leblanc_handle.underlying_pointer());
     }
 
-    unsafe {
-        if STDOUT.is_none() {
-            STDOUT = Some(io::stdout());
-        }
-        io::copy(&mut output.as_bytes(), &mut STDOUT.as_mut().unwrap()).unwrap();
-    }
+    io::copy(&mut output.as_bytes(), &mut io::stdout()).unwrap();
     LeBlancObject::unsafe_null()
 }
 
@@ -94,6 +58,91 @@ pub fn _BUILTIN_DISASSEMBLE_OBJECT_() -> LeBlancObject {
     internal_method(_BUILTIN_DISASSEMBLE_METHOD_())
 }
 
+/// Names for the arguments of instructions, from a loaded function or from one still being compiled.
+pub trait Symbols {
+    fn local(&self, slot: u16) -> String;
+    fn constant(&self, index: u16) -> String;
+    fn table(&self, index: u16) -> String;
+    fn function(&self, index: u16) -> String;
+    fn instance(&self, index: u16) -> String;
+}
+
+impl Symbols for LeblancHandle {
+    fn local(&self, slot: u16) -> String {
+        self.variable_context.values().find(|context| context.relationship == slot as u32).map_or_else(|| "?".to_string(), |context| context.name.to_string())
+    }
+
+    fn constant(&self, index: u16) -> String {
+        self.constants[index as usize].to_string()
+    }
+
+    /// Tables are kept in the constants as their description.
+    fn table(&self, index: u16) -> String {
+        self.constant(index)
+    }
+
+    fn function(&self, index: u16) -> String {
+        unsafe {get_globals()[index as usize].lock().data.get_inner_method().unwrap().context.name.clone()}
+    }
+
+    fn instance(&self, index: u16) -> String {
+        unsafe {get_globals()[index as usize].lock().name_of()}
+    }
+}
+
+pub fn disassemble_handle(leblanc_handle: &LeblancHandle) -> String {
+    disassemble(&leblanc_handle.instructions, &leblanc_handle.handlers, leblanc_handle)
+}
+
+pub fn disassemble(instructions: &[Instruction], handlers: &[ExceptionHandler], symbols: &dyn Symbols) -> String {
+    let mut prev_line_number = 0;
+    let mut line_number_format;
+    let mut instruct_count = 0;
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_CLEAN);
+    for (index, instruction) in instructions.iter().enumerate() {
+        if instruction.line_number != prev_line_number {
+            line_number_format = grow_to_size(&instruction.line_number.to_string(), 8);
+            prev_line_number = instruction.line_number;
+            table.add_row(Row::new(vec![Cell::new("").with_hspan(5)]));
+        } else {line_number_format = grow_to_size("", 8)}
+
+        let operand = |offset: usize| instructions.get(index + offset).map_or(0, |operand| operand.arg);
+        let arg_string = match instruction.instruct {
+            InstructionBase::LoadLocal | InstructionBase::StoreLocal => format!("({})", symbols.local(instruction.arg)),
+            InstructionBase::LoadConstant | InstructionBase::AttributeAccess | InstructionBase::AttributeStore
+            | InstructionBase::MapMatch | InstructionBase::CheckTrait => format!("({})", symbols.constant(instruction.arg)),
+            InstructionBase::JumpTable => format!("({})", symbols.table(instruction.arg)),
+            InstructionBase::MakeInstance | InstructionBase::ExtendException => format!("({})", symbols.instance(instruction.arg)),
+            InstructionBase::LoadFunction => format!("({})", symbols.function(instruction.arg)),
+            InstructionBase::Equality(_) => format!("({})", recover_equality_op(instruction.arg as u8)),
+            InstructionBase::Comparator_If | InstructionBase::Comparator_ElseIf | InstructionBase::Comparator_Else
+            | InstructionBase::WhileLoop | InstructionBase::ForLoop | InstructionBase::MakeBlock => format!("(to {})", instruction.arg as u32 * 2),
+            InstructionBase::LoadLocal2 => format!("({}, {})", symbols.local(instruction.arg), symbols.local(operand(1))),
+            InstructionBase::IncrementLocal => format!("({} += {})", symbols.local(instruction.arg), symbols.constant(operand(1))),
+            InstructionBase::CompareLocalConstJump => format!("({} {} {})", symbols.local(instruction.arg), recover_equality_op(operand(2) as u8), symbols.constant(operand(1))),
+            _ => "".to_string()
+        };
+        table.add_row(Row::new(vec![
+            Cell::new(&line_number_format),
+            Cell::new(&instruct_count.to_string()),
+            Cell::new(&(instruction.instruct.to_string())),
+            Cell::new(&instruction.arg.to_string()),
+            Cell::new(&arg_string)
+        ]));
+        instruct_count += 2;
+    }
+    let mut output = table.to_string();
+    if !handlers.is_empty() {
+        output += "\nException table:\n";
+        for handler in handlers {
+            let exception = if handler.exception.is_empty() { "any" } else { handler.exception.as_str() };
+            writeln!(output, "    {} to {} -> {} depth {} ({})", handler.start as u32 * 2, handler.end as u32 * 2, handler.target as u32 * 2, handler.depth, exception).unwrap();
+        }
+    }
+    output
+}
+
 fn grow_to_size(string: &str, number: usize) -> String {
     let mut new_string = string.to_string();
     while new_string.len() < number {
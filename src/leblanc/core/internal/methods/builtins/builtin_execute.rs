@@ -0,0 +1,48 @@
+use crate::leblanc::rustblanc::strawberry::Strawberry;
+use std::sync::Arc;
+use crate::leblanc::core::leblanc_argument::LeBlancArgument;
+use crate::leblanc::core::leblanc_handle::LeblancHandle;
+use crate::leblanc::core::leblanc_object::{LeBlancObject, LeBlancObjectData};
+use crate::leblanc::core::method::Method;
+use crate::leblanc::core::method_tag::MethodTag;
+use crate::leblanc::core::method_store::MethodStore;
+use crate::leblanc::core::native_types::base_type::internal_method;
+use crate::leblanc::core::native_types::base_type::ToLeblanc;
+use crate::leblanc::core::native_types::error_type::LeblancError;
+use crate::LeBlancType;
+
+/// Runs the block in `args` in `handle`, the running call. See `MethodTag::ExecuteBlock`.
+pub fn execute_block(handle: &mut LeblancHandle, args: &[Arc<Strawberry<LeBlancObject>>]) -> Arc<Strawberry<LeBlancObject>> {
+    let block = match args.first().map(|arg| arg.lock()) {
+        Some(borrowed) => match &borrowed.data {
+            LeBlancObjectData::Block(block) => *block,
+            _ => return LeblancError::new("TypeException".to_string(), format!("Expected a block but got: {}", borrowed.typing), vec![]).create_mutex()
+        },
+        None => return LeblancError::new("TypeException".to_string(), "Expected a block but got nothing".to_string(), vec![]).create_mutex()
+    };
+    handle.execute_block(block)
+}
+
+/// Only reached when `execute` is called from outside a LeBlanc call, which no block belongs to.
+fn _BUILTIN_EXECUTE_(_self: Arc<Strawberry<LeBlancObject>>, _args: &mut [Arc<Strawberry<LeBlancObject>>]) -> Arc<Strawberry<LeBlancObject>> {
+    outside_block()
+}
+
+pub fn outside_block() -> Arc<Strawberry<LeBlancObject>> {
+    LeblancError::new("BlockException".to_string(), "Cannot execute a block outside the call that created it".to_string(), vec![]).create_mutex()
+}
+
+pub fn _BUILTIN_EXECUTE_METHOD_() -> Method {
+    Method::new(
+        MethodStore::new(
+            "execute".to_string(),
+            vec![LeBlancArgument::default(LeBlancType::Block, 0)]
+        ),
+        _BUILTIN_EXECUTE_,
+        MethodTag::ExecuteBlock.singleton()
+    )
+}
+
+pub fn _BUILTIN_EXECUTE_OBJECT_() -> LeBlancObject {
+    internal_method(_BUILTIN_EXECUTE_METHOD_())
+}
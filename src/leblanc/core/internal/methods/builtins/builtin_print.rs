@@ -2,16 +2,15 @@ use std::collections::BTreeSet;
 use std::io;
 
 
-use alloc::rc::Rc;
-use std::cell::RefCell;
 use std::io::Write;
 use crate::leblanc::rustblanc::strawberry::Strawberry;
-use std::sync::{Arc, Mutex};
+use std::sync::Arc;
 use crate::leblanc::core::leblanc_argument::LeBlancArgument;
 use crate::leblanc::core::leblanc_object::{Callable, LeBlancObject, Stringify};
 use crate::leblanc::core::method::Method;
 use crate::leblanc::core::method_store::MethodStore;
 use crate::leblanc::core::native_types::base_type::internal_method;
+use crate::leblanc::include::lib::io::io_exception;
 
 use crate::LeBlancType;
 
@@ -24,7 +23,9 @@ fn _BUILTIN_PRINT_(_self: Arc<Strawberry<LeBlancObject>>, args: &mut [Arc<Strawb
             Ok(r) => r.to_string() + sep,
             Err(err) => return err
         };
-        write!(io::stdout().lock(), "{}", result).unwrap();
+        if let Err(err) = write!(io::stdout().lock(), "{}", result) {
+            return io_exception(format!("Cannot write to stdout: {}", err));
+        }
         //io::stdout().write(result.as_bytes()).unwrap();
     }
 
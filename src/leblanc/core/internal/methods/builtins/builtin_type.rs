@@ -1,8 +1,6 @@
-use alloc::rc::Rc;
-use std::cell::RefCell;
 use std::collections::BTreeSet;
 use crate::leblanc::rustblanc::strawberry::Strawberry;
-use std::sync::{Arc, Mutex};
+use std::sync::Arc;
 use crate::leblanc::core::leblanc_argument::LeBlancArgument;
 use crate::leblanc::core::leblanc_object::LeBlancObject;
 use crate::leblanc::core::method::Method;
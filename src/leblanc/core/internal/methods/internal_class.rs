@@ -1,11 +1,8 @@
 use fxhash::{FxHashMap};
 
 
-use alloc::rc::Rc;
-use std::cell::RefCell;
 use std::sync::Arc;
 use crate::leblanc::rustblanc::strawberry::Strawberry;
-use std::sync::Mutex;
 
 use crate::leblanc::core::leblanc_context::VariableContext;
 use crate::leblanc::core::leblanc_object::{LeBlancObject, LeBlancObjectData, Reflect, Stringify};
@@ -15,9 +12,9 @@ use crate::leblanc::core::native_types::LeBlancType;
 use crate::leblanc::core::native_types::string_type::leblanc_object_string;
 
 pub fn _internal_field_(_self: Arc<Strawberry<LeBlancObject>>, arguments: &mut [Arc<Strawberry<LeBlancObject>>]) -> Arc<Strawberry<LeBlancObject>> {
-    let string: String = unsafe {arguments[0].reflect().downcast_ref_unchecked::<String>()}.clone();
+    let string: String = unsafe {arguments[0].reflect().downcast_unchecked_ref::<String>()}.clone();
 
-    return _self.lock().members.lock().get(string.as_str()).unwrap_or(&LeBlancObject::null()).clone().to_mutex();
+    return _self.lock().members.lock().get(string.as_str()).cloned().unwrap_or_else(LeBlancObject::unsafe_null);
 }
 
 pub fn _internal_expose_(_self: Arc<Strawberry<LeBlancObject>>, _arguments: &mut [Arc<Strawberry<LeBlancObject>>]) -> Arc<Strawberry<LeBlancObject>> {
@@ -30,7 +27,7 @@ pub fn _internal_expose_(_self: Arc<Strawberry<LeBlancObject>>, _arguments: &mut
         VariableContext::empty(),
     );
 
-    expose_object.members.lock().insert("name".to_string(), leblanc_object_string(_self.lock().name_of()));
+    expose_object.members.lock().insert("name".to_string(), leblanc_object_string(_self.lock().name_of()).to_mutex());
 
     let variable_class_meta = ClassMeta::default("VariableContext".to_string(), 1);
     let variable_state = LeBlancObject::new(
@@ -41,12 +38,12 @@ pub fn _internal_expose_(_self: Arc<Strawberry<LeBlancObject>>, _arguments: &mut
         VariableContext::empty()
     );
 
-    variable_state.members.lock().insert("name".to_string(), leblanc_object_string(_self.lock().context.name.to_string()));
-    variable_state.members.lock().insert("state".to_string(), leblanc_object_string(_self.lock().context.state.to_string()));
-    variable_state.members.lock().insert("lineNumber".to_string(), leblanc_object_string(_self.lock().context.line_number.to_string()));
-    variable_state.members.lock().insert("file".to_string(), leblanc_object_string(_self.lock().context.file.to_string()));
+    variable_state.members.lock().insert("name".to_string(), leblanc_object_string(_self.lock().context.name.to_string()).to_mutex());
+    variable_state.members.lock().insert("state".to_string(), leblanc_object_string(_self.lock().context.state.to_string()).to_mutex());
+    variable_state.members.lock().insert("lineNumber".to_string(), leblanc_object_string(_self.lock().context.line_number.to_string()).to_mutex());
+    variable_state.members.lock().insert("file".to_string(), leblanc_object_string(_self.lock().context.file.to_string()).to_mutex());
 
-    expose_object.members.lock().insert("variableContext".to_string(), variable_state);
+    expose_object.members.lock().insert("variableContext".to_string(), variable_state.to_mutex());
 
 
     expose_object.to_mutex()
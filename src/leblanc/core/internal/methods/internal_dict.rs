@@ -0,0 +1,57 @@
+use crate::leblanc::rustblanc::strawberry::Strawberry;
+use std::sync::Arc;
+use crate::leblanc::core::internal::internal_list_iterator::LeblancVecIterator;
+use crate::leblanc::core::internal::transformed_iterator::pair;
+use crate::leblanc::core::leblanc_object::{LeBlancObject, RustDataCast};
+use crate::leblanc::core::native_types::boolean_type::leblanc_object_boolean;
+use crate::leblanc::core::native_types::derived::dict_type::LeblancDict;
+use crate::leblanc::core::native_types::derived::iterator_type::leblanc_object_iterator;
+use crate::leblanc::core::native_types::int_type::leblanc_object_int;
+
+
+/// The value of a key, or the second argument (null if not given) when the dictionary doesn't have it.
+pub fn _internal_dict_get_(_self: Arc<Strawberry<LeBlancObject>>, arguments: &mut [Arc<Strawberry<LeBlancObject>>]) -> Arc<Strawberry<LeBlancObject>> {
+    let borrowed = _self.lock();
+    let dict: &LeblancDict = borrowed.data.ref_data().unwrap();
+    dict.get(&arguments[0]).or_else(|| arguments.get(1).cloned()).unwrap_or_else(LeBlancObject::unsafe_null)
+}
+
+/// Removes a key, giving the value it had, or null if there was none.
+pub fn _internal_dict_remove_(_self: Arc<Strawberry<LeBlancObject>>, arguments: &mut [Arc<Strawberry<LeBlancObject>>]) -> Arc<Strawberry<LeBlancObject>> {
+    let mut borrowed = _self.lock();
+    let dict: &mut LeblancDict = borrowed.data.mut_data().unwrap();
+    dict.remove(&arguments[0]).unwrap_or_else(LeBlancObject::unsafe_null)
+}
+
+pub fn _internal_dict_contains_(_self: Arc<Strawberry<LeBlancObject>>, arguments: &mut [Arc<Strawberry<LeBlancObject>>]) -> Arc<Strawberry<LeBlancObject>> {
+    let borrowed = _self.lock();
+    let dict: &LeblancDict = borrowed.data.ref_data().unwrap();
+    leblanc_object_boolean(dict.contains(&arguments[0])).to_mutex()
+}
+
+pub fn _internal_dict_length_(_self: Arc<Strawberry<LeBlancObject>>, _arguments: &mut [Arc<Strawberry<LeBlancObject>>]) -> Arc<Strawberry<LeBlancObject>> {
+    let borrowed = _self.lock();
+    let dict: &LeblancDict = borrowed.data.ref_data().unwrap();
+    leblanc_object_int(dict.len() as i32).to_mutex()
+}
+
+/// An iterator over the keys, which is also what iterating over the dictionary itself gives.
+pub fn _internal_dict_keys_(_self: Arc<Strawberry<LeBlancObject>>, _arguments: &mut [Arc<Strawberry<LeBlancObject>>]) -> Arc<Strawberry<LeBlancObject>> {
+    let borrowed = _self.lock();
+    let dict: &LeblancDict = borrowed.data.ref_data().unwrap();
+    leblanc_object_iterator(Box::new(LeblancVecIterator::new(dict.keys()))).to_mutex()
+}
+
+pub fn _internal_dict_values_(_self: Arc<Strawberry<LeBlancObject>>, _arguments: &mut [Arc<Strawberry<LeBlancObject>>]) -> Arc<Strawberry<LeBlancObject>> {
+    let borrowed = _self.lock();
+    let dict: &LeblancDict = borrowed.data.ref_data().unwrap();
+    leblanc_object_iterator(Box::new(LeblancVecIterator::new(dict.values()))).to_mutex()
+}
+
+/// An iterator over `(key, value)` pairs.
+pub fn _internal_dict_items_(_self: Arc<Strawberry<LeBlancObject>>, _arguments: &mut [Arc<Strawberry<LeBlancObject>>]) -> Arc<Strawberry<LeBlancObject>> {
+    let borrowed = _self.lock();
+    let dict: &LeblancDict = borrowed.data.ref_data().unwrap();
+    let items = dict.entries.iter().map(|(key, value)| pair(key.clone(), value.clone())).collect();
+    leblanc_object_iterator(Box::new(LeblancVecIterator::new(items))).to_mutex()
+}
@@ -0,0 +1,29 @@
+use crate::leblanc::rustblanc::strawberry::Strawberry;
+use std::sync::Arc;
+use crate::leblanc::core::leblanc_object::{LeBlancObject, RustDataCast};
+use crate::leblanc::core::native_types::base_type::ToLeblanc;
+use crate::leblanc::core::native_types::derived::list_type::LeblancList;
+use crate::leblanc::core::native_types::error_type::LeblancError;
+use crate::leblanc::core::native_types::string_type::leblanc_object_string;
+
+pub fn _internal_error_name_(_self: Arc<Strawberry<LeBlancObject>>, _arguments: &mut [Arc<Strawberry<LeBlancObject>>]) -> Arc<Strawberry<LeBlancObject>> {
+    let borrowed = _self.lock();
+    let error: &LeblancError = borrowed.data.ref_data().unwrap();
+    leblanc_object_string(error.name().to_string()).to_mutex()
+}
+
+pub fn _internal_error_message_(_self: Arc<Strawberry<LeBlancObject>>, _arguments: &mut [Arc<Strawberry<LeBlancObject>>]) -> Arc<Strawberry<LeBlancObject>> {
+    let borrowed = _self.lock();
+    let error: &LeblancError = borrowed.data.ref_data().unwrap();
+    leblanc_object_string(error.message().to_string()).to_mutex()
+}
+
+/// The recorded frames of the error as a list of strings, outermost first.
+pub fn _internal_error_trace_(_self: Arc<Strawberry<LeBlancObject>>, _arguments: &mut [Arc<Strawberry<LeBlancObject>>]) -> Arc<Strawberry<LeBlancObject>> {
+    let borrowed = _self.lock();
+    let error: &LeblancError = borrowed.data.ref_data().unwrap();
+    let frames = error.stack_trace().iter()
+        .map(|frame| leblanc_object_string(frame.to_string()).to_mutex())
+        .collect();
+    LeblancList::new(frames).create_mutex()
+}
@@ -1,8 +1,6 @@
-use alloc::rc::Rc;
-use std::cell::RefCell;
 use crate::leblanc::rustblanc::strawberry::Strawberry;
-use std::sync::{Arc, Mutex};
-use crate::leblanc::core::leblanc_object::{LeBlancObject, RustDataCast, Stringify};
+use std::sync::Arc;
+use crate::leblanc::core::leblanc_object::{LeBlancObject, RustDataCast};
 use crate::leblanc::core::native_types::base_type::ToLeblanc;
 use crate::leblanc::core::native_types::group_type::LeblancGroup;
 
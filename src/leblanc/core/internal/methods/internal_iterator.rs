@@ -1,13 +1,20 @@
-use alloc::rc::Rc;
-use std::cell::RefCell;
 use crate::leblanc::rustblanc::strawberry::Strawberry;
-use std::sync::{Arc, Mutex};
-use crate::leblanc::core::internal::transformed_iterator::TransformedIterator;
+use std::sync::Arc;
+use crate::leblanc::core::internal::combined_iterators::{ChainedIterator, ZippedIterator};
+use crate::leblanc::core::internal::transformed_iterator::{call, is_error, is_true, iterable, IterMutation, TransformedIterator};
+use crate::leblanc::core::interpreter::instruction_execution::add;
 
 use crate::leblanc::core::leblanc_object::{LeBlancObject, LeBlancObjectData, RustDataCast};
+use crate::leblanc::core::native_types::base_type::ToLeblanc;
+use crate::leblanc::core::native_types::boolean_type::leblanc_object_boolean;
+use crate::leblanc::core::native_types::error_type::LeblancError;
+use crate::leblanc::core::native_types::int_type::leblanc_object_int;
 
+use crate::leblanc::core::native_types::derived::dict_type::{leblanc_object_dict, LeblancDict};
 use crate::leblanc::core::native_types::derived::iterator_type::{leblanc_object_iterator, LeblancIterator};
-use crate::leblanc::core::native_types::derived::list_type::{leblanc_object_list};
+use crate::leblanc::core::native_types::derived::list_type::{leblanc_object_list, LeblancList};
+use crate::leblanc::core::native_types::derived::set_type::LeblancSet;
+use crate::leblanc::core::native_types::derived::tuple_type::LeblancTuple;
 
 
 pub fn _internal_iterator_next(_self: Arc<Strawberry<LeBlancObject>>, _arguments: &mut [Arc<Strawberry<LeBlancObject>>]) -> Arc<Strawberry<LeBlancObject>> {
@@ -17,27 +24,83 @@ pub fn _internal_iterator_next(_self: Arc<Strawberry<LeBlancObject>>, _arguments
 }
 
 pub fn _internal_iterator_to_list_(_self: Arc<Strawberry<LeBlancObject>>, _arguments: &mut [Arc<Strawberry<LeBlancObject>>]) -> Arc<Strawberry<LeBlancObject>> {
-    Arc::new(Strawberry::new(leblanc_object_list(<LeBlancObjectData as RustDataCast<LeblancIterator>>::mut_data(&mut _self.lock().data).unwrap().to_list())))
+    collect_list(&_self).unwrap_or_else(|error| error)
 }
 
-pub fn _internal_iterator_filter_(_self: Arc<Strawberry<LeBlancObject>>, _arguments: &mut [Arc<Strawberry<LeBlancObject>>]) -> Arc<Strawberry<LeBlancObject>> {
+/// Adds `transformation` to the iterator, which becomes a transformed iterator over a copy of
+/// itself if it isn't one already.
+fn transform(_self: Arc<Strawberry<LeBlancObject>>, transformation: IterMutation) -> Arc<Strawberry<LeBlancObject>> {
     let mut borrowed = _self.lock();
     let iterator: &mut LeblancIterator = borrowed.data.mut_data().unwrap();
 
     match iterator.transformed() {
         Some(trans_iter) => {
-            trans_iter.filter(_arguments[0].lock().data.get_inner_method().unwrap().leblanc_handle.lock().clone());
+            trans_iter.transform(transformation);
             drop(borrowed);
             _self
         },
         None => {
             let mut new_iter = TransformedIterator::new(iterator.iterator.clone());
-            new_iter.filter(_arguments[0].lock().data.get_inner_method().unwrap().leblanc_handle.lock().clone());
+            new_iter.transform(transformation);
             leblanc_object_iterator(Box::new(new_iter)).to_mutex()
         }
     }
 }
 
+/// Gives each value of the iterator to `visit` until it returns false or the values run out. An
+/// error the iterator gives instead of a value stops it and is returned.
+fn each(_self: &Arc<Strawberry<LeBlancObject>>, mut visit: impl FnMut(Arc<Strawberry<LeBlancObject>>) -> Result<bool, Arc<Strawberry<LeBlancObject>>>) -> Result<(), Arc<Strawberry<LeBlancObject>>> {
+    let mut borrowed = _self.lock();
+    let iterator: &mut LeblancIterator = borrowed.data.mut_data().unwrap();
+    while iterator.has_next() {
+        let value = iterator.next();
+        if is_error(&value) { return Err(value); }
+        if !visit(value)? { break; }
+    }
+    Ok(())
+}
+
+fn collect_values(_self: &Arc<Strawberry<LeBlancObject>>) -> Result<Vec<Arc<Strawberry<LeBlancObject>>>, Arc<Strawberry<LeBlancObject>>> {
+    let mut values = vec![];
+    each(_self, |value| {
+        values.push(value);
+        Ok(true)
+    })?;
+    Ok(values)
+}
+
+fn collect_list(_self: &Arc<Strawberry<LeBlancObject>>) -> Result<Arc<Strawberry<LeBlancObject>>, Arc<Strawberry<LeBlancObject>>> {
+    Ok(leblanc_object_list(LeblancList::new(collect_values(_self)?)).to_mutex())
+}
+
+/// A dict of `(key, value)` pairs, like the items of a dict or the values of `zip`. Lists of
+/// two values are taken as pairs too.
+fn collect_dict(_self: &Arc<Strawberry<LeBlancObject>>) -> Result<Arc<Strawberry<LeBlancObject>>, Arc<Strawberry<LeBlancObject>>> {
+    let mut dict = LeblancDict::empty();
+    each(_self, |value| {
+        let entry = match &value.lock().data {
+            LeBlancObjectData::Tuple(pair) if pair.len() == 2 => (pair.values[0].clone(), pair.values[1].clone()),
+            LeBlancObjectData::List(pair) if pair.internal_vec.len() == 2 => (pair.internal_vec[0].clone(), pair.internal_vec[1].clone()),
+            other => return Err(LeblancError::new("TypeException".to_string(), format!("Cannot collect {} into a dict, expected a (key, value) pair", other), vec![]).create_mutex())
+        };
+        dict.insert(entry.0, entry.1);
+        Ok(true)
+    })?;
+    Ok(leblanc_object_dict(dict).to_mutex())
+}
+
+/// The result of a function, or the error it raised.
+fn checked(value: Arc<Strawberry<LeBlancObject>>) -> Result<Arc<Strawberry<LeBlancObject>>, Arc<Strawberry<LeBlancObject>>> {
+    match is_error(&value) {
+        true => Err(value),
+        false => Ok(value)
+    }
+}
+
+pub fn _internal_iterator_filter_(_self: Arc<Strawberry<LeBlancObject>>, _arguments: &mut [Arc<Strawberry<LeBlancObject>>]) -> Arc<Strawberry<LeBlancObject>> {
+    transform(_self, IterMutation::Filter(_arguments[0].clone()))
+}
+
 pub fn _internal_iterator_reverse_(_self: Arc<Strawberry<LeBlancObject>>, _arguments: &mut [Arc<Strawberry<LeBlancObject>>]) -> Arc<Strawberry<LeBlancObject>> {
     let mut borrowed = _self.lock();
     let iterator: &mut LeblancIterator = borrowed.data.mut_data().unwrap();
@@ -47,19 +110,153 @@ pub fn _internal_iterator_reverse_(_self: Arc<Strawberry<LeBlancObject>>, _argum
 }
 
 pub fn _internal_iterator_map_(_self: Arc<Strawberry<LeBlancObject>>, _arguments: &mut [Arc<Strawberry<LeBlancObject>>]) -> Arc<Strawberry<LeBlancObject>> {
-    let mut borrowed = _self.lock();
-    let iterator: &mut LeblancIterator = borrowed.data.mut_data().unwrap();
+    transform(_self, IterMutation::Map(_arguments[0].clone()))
+}
 
-    match iterator.transformed() {
-        Some(trans_iter) => {
-            trans_iter.map(_arguments[0].lock().data.get_inner_method().unwrap().leblanc_handle.lock().clone());
-            drop(borrowed);
-            _self
-        },
-        None => {
-            let mut new_iter = TransformedIterator::new(iterator.iterator.clone());
-            new_iter.map(_arguments[0].lock().data.get_inner_method().unwrap().leblanc_handle.lock().clone());
-            leblanc_object_iterator(Box::new(new_iter)).to_mutex()
-        }
+pub fn _internal_iterator_take_(_self: Arc<Strawberry<LeBlancObject>>, _arguments: &mut [Arc<Strawberry<LeBlancObject>>]) -> Arc<Strawberry<LeBlancObject>> {
+    let count = _arguments[0].lock().data.as_i128().max(0) as i64;
+    transform(_self, IterMutation::Take(count))
+}
+
+pub fn _internal_iterator_skip_(_self: Arc<Strawberry<LeBlancObject>>, _arguments: &mut [Arc<Strawberry<LeBlancObject>>]) -> Arc<Strawberry<LeBlancObject>> {
+    let count = _arguments[0].lock().data.as_i128().max(0) as i64;
+    transform(_self, IterMutation::Skip(count))
+}
+
+pub fn _internal_iterator_take_while_(_self: Arc<Strawberry<LeBlancObject>>, _arguments: &mut [Arc<Strawberry<LeBlancObject>>]) -> Arc<Strawberry<LeBlancObject>> {
+    transform(_self, IterMutation::TakeWhile(_arguments[0].clone(), false))
+}
+
+pub fn _internal_iterator_enumerate_(_self: Arc<Strawberry<LeBlancObject>>, _arguments: &mut [Arc<Strawberry<LeBlancObject>>]) -> Arc<Strawberry<LeBlancObject>> {
+    transform(_self, IterMutation::Enumerate(0))
+}
+
+pub fn _internal_iterator_flat_map_(_self: Arc<Strawberry<LeBlancObject>>, _arguments: &mut [Arc<Strawberry<LeBlancObject>>]) -> Arc<Strawberry<LeBlancObject>> {
+    transform(_self, IterMutation::FlatMap(_arguments[0].clone(), None))
+}
+
+pub fn _internal_iterator_zip_(_self: Arc<Strawberry<LeBlancObject>>, _arguments: &mut [Arc<Strawberry<LeBlancObject>>]) -> Arc<Strawberry<LeBlancObject>> {
+    let first = iterable(_self);
+    match first.and_then(|first| Ok(ZippedIterator::new(first, iterable(_arguments[0].clone())?))) {
+        Ok(zipped) => leblanc_object_iterator(Box::new(zipped)).to_mutex(),
+        Err(error) => error
     }
-}
\ No newline at end of file
+}
+
+pub fn _internal_iterator_chain_(_self: Arc<Strawberry<LeBlancObject>>, _arguments: &mut [Arc<Strawberry<LeBlancObject>>]) -> Arc<Strawberry<LeBlancObject>> {
+    let first = iterable(_self);
+    match first.and_then(|first| Ok(ChainedIterator::new(first, iterable(_arguments[0].clone())?))) {
+        Ok(chained) => leblanc_object_iterator(Box::new(chained)).to_mutex(),
+        Err(error) => error
+    }
+}
+
+pub fn _internal_iterator_fold_(_self: Arc<Strawberry<LeBlancObject>>, _arguments: &mut [Arc<Strawberry<LeBlancObject>>]) -> Arc<Strawberry<LeBlancObject>> {
+    let function = _arguments[1].clone();
+    let mut accumulator = _arguments[0].clone();
+    if let Err(error) = each(&_self, |value| {
+        accumulator = checked(call(&function, &mut [accumulator.clone(), value]))?;
+        Ok(true)
+    }) { return error; }
+    accumulator
+}
+
+/// `fold` starting from the first value. An empty iterator has no first value, so it raises an
+/// `IndexException`; `fold` takes a starting value for iterators that may be empty.
+pub fn _internal_iterator_reduce_(_self: Arc<Strawberry<LeBlancObject>>, _arguments: &mut [Arc<Strawberry<LeBlancObject>>]) -> Arc<Strawberry<LeBlancObject>> {
+    let function = _arguments[0].clone();
+    let mut accumulator: Option<Arc<Strawberry<LeBlancObject>>> = None;
+    if let Err(error) = each(&_self, |value| {
+        accumulator = Some(match accumulator.take() {
+            Some(accumulator) => checked(call(&function, &mut [accumulator, value]))?,
+            None => value
+        });
+        Ok(true)
+    }) { return error; }
+    accumulator.unwrap_or_else(|| empty("reduce"))
+}
+
+/// Adds the values up the way `+` does. The sum of no values is 0.
+pub fn _internal_iterator_sum_(_self: Arc<Strawberry<LeBlancObject>>, _arguments: &mut [Arc<Strawberry<LeBlancObject>>]) -> Arc<Strawberry<LeBlancObject>> {
+    let mut total: Option<Arc<Strawberry<LeBlancObject>>> = None;
+    if let Err(error) = each(&_self, |value| {
+        total = Some(match total.take() {
+            Some(total) => checked(add(total, value)?)?,
+            None => value
+        });
+        Ok(true)
+    }) { return error; }
+    total.unwrap_or_else(|| leblanc_object_int(0).to_mutex())
+}
+
+/// The value `replaces` picks over every other. An empty iterator has none, so `operation` raises
+/// an `IndexException` rather than giving null, which could be mistaken for a value.
+fn extreme(_self: Arc<Strawberry<LeBlancObject>>, operation: &str, replaces: fn(&LeBlancObjectData, &LeBlancObjectData) -> bool) -> Arc<Strawberry<LeBlancObject>> {
+    let mut best: Option<Arc<Strawberry<LeBlancObject>>> = None;
+    if let Err(error) = each(&_self, |value| {
+        let better = match &best {
+            Some(best) => !Arc::ptr_eq(best, &value) && replaces(&value.lock().data, &best.lock().data),
+            None => true
+        };
+        if better { best = Some(value); }
+        Ok(true)
+    }) { return error; }
+    best.unwrap_or_else(|| empty(operation))
+}
+
+/// The error `operation` raises for an iterator without values.
+fn empty(operation: &str) -> Arc<Strawberry<LeBlancObject>> {
+    LeblancError::new("IndexException".to_string(), format!("Cannot {} an empty iterator", operation), vec![]).create_mutex()
+}
+
+pub fn _internal_iterator_min_(_self: Arc<Strawberry<LeBlancObject>>, _arguments: &mut [Arc<Strawberry<LeBlancObject>>]) -> Arc<Strawberry<LeBlancObject>> {
+    extreme(_self, "take the min of", |value, best| value < best)
+}
+
+pub fn _internal_iterator_max_(_self: Arc<Strawberry<LeBlancObject>>, _arguments: &mut [Arc<Strawberry<LeBlancObject>>]) -> Arc<Strawberry<LeBlancObject>> {
+    extreme(_self, "take the max of", |value, best| value > best)
+}
+
+/// Whether the function returns true for any value, stopping at the first that it does.
+pub fn _internal_iterator_any_(_self: Arc<Strawberry<LeBlancObject>>, _arguments: &mut [Arc<Strawberry<LeBlancObject>>]) -> Arc<Strawberry<LeBlancObject>> {
+    let function = _arguments[0].clone();
+    let mut found = false;
+    if let Err(error) = each(&_self, |value| {
+        found = is_true(&checked(call(&function, &mut [value]))?);
+        Ok(!found)
+    }) { return error; }
+    leblanc_object_boolean(found).to_mutex()
+}
+
+/// Whether the function returns true for every value, stopping at the first that it doesn't.
+pub fn _internal_iterator_all_(_self: Arc<Strawberry<LeBlancObject>>, _arguments: &mut [Arc<Strawberry<LeBlancObject>>]) -> Arc<Strawberry<LeBlancObject>> {
+    let function = _arguments[0].clone();
+    let mut holds = true;
+    if let Err(error) = each(&_self, |value| {
+        holds = is_true(&checked(call(&function, &mut [value]))?);
+        Ok(holds)
+    }) { return error; }
+    leblanc_object_boolean(holds).to_mutex()
+}
+
+pub fn _internal_iterator_count_(_self: Arc<Strawberry<LeBlancObject>>, _arguments: &mut [Arc<Strawberry<LeBlancObject>>]) -> Arc<Strawberry<LeBlancObject>> {
+    let mut count = 0;
+    if let Err(error) = each(&_self, |_| {
+        count += 1;
+        Ok(true)
+    }) { return error; }
+    leblanc_object_int(count).to_mutex()
+}
+
+/// Collects the values into the kind of collection named by the argument.
+pub fn _internal_iterator_collect_(_self: Arc<Strawberry<LeBlancObject>>, _arguments: &mut [Arc<Strawberry<LeBlancObject>>]) -> Arc<Strawberry<LeBlancObject>> {
+    let kind = _arguments[0].lock().data.to_string();
+    let collected = match kind.as_str() {
+        "list" => collect_list(&_self),
+        "dict" => collect_dict(&_self),
+        "tuple" => collect_values(&_self).map(|values| LeblancTuple::new(values).create_mutex()),
+        "set" => collect_values(&_self).map(|values| LeblancSet::new(values).create_mutex()),
+        _ => Err(LeblancError::new("TypeException".to_string(), format!("Cannot collect an iterator into {}", kind), vec![]).create_mutex())
+    };
+    collected.unwrap_or_else(|error| error)
+}
@@ -1,4 +1,3 @@
-use core::borrow::BorrowMut;
 use crate::leblanc::rustblanc::strawberry::Strawberry;
 use std::sync::{Arc};
 use crate::leblanc::core::internal::internal_list_iterator::LeblancVecIterator;
@@ -1,9 +1,7 @@
 
 
-use alloc::rc::Rc;
-use std::cell::RefCell;
 use crate::leblanc::rustblanc::strawberry::Strawberry;
-use std::sync::{Arc, Mutex};
+use std::sync::Arc;
 use crate::leblanc::core::leblanc_object::{LeBlancObject, LeBlancObjectData, Reflect};
 use crate::leblanc::core::native_types::base_type::ToLeblanc;
 
@@ -14,8 +12,8 @@ pub fn _internal_add_number_(_self: Arc<Strawberry<LeBlancObject>>, arguments: &
 }
 
 pub fn _internal_add_double_(_self: Arc<Strawberry<LeBlancObject>>, arguments: &mut [Arc<Strawberry<LeBlancObject>>]) -> Arc<Strawberry<LeBlancObject>> {
-    let n1: f64 = unsafe {*(_self.reflect().downcast_ref_unchecked())};
-    let n2: f64 = unsafe {*(arguments[0].reflect().downcast_ref_unchecked())};
+    let n1: f64 = unsafe {*(_self.reflect().downcast_unchecked_ref())};
+    let n2: f64 = unsafe {*(arguments[0].reflect().downcast_unchecked_ref())};
 
     let result = n1 + n2;
 
@@ -23,8 +21,8 @@ pub fn _internal_add_double_(_self: Arc<Strawberry<LeBlancObject>>, arguments: &
 }
 
 pub fn _internal_add_float_(_self: Arc<Strawberry<LeBlancObject>>, arguments: &mut [Arc<Strawberry<LeBlancObject>>]) -> Arc<Strawberry<LeBlancObject>> {
-    let n1: f32 = unsafe {*(_self.reflect().downcast_ref_unchecked())};
-    let n2: f32 = unsafe {*(arguments[0].reflect().downcast_ref_unchecked())};
+    let n1: f32 = unsafe {*(_self.reflect().downcast_unchecked_ref())};
+    let n2: f32 = unsafe {*(arguments[0].reflect().downcast_unchecked_ref())};
 
     let result = n1 + n2;
 
@@ -32,9 +30,8 @@ pub fn _internal_add_float_(_self: Arc<Strawberry<LeBlancObject>>, arguments: &m
 }
 
 pub fn _internal_inplace_add_(_self: Arc<Strawberry<LeBlancObject>>, arguments: &mut [Arc<Strawberry<LeBlancObject>>]) -> Arc<Strawberry<LeBlancObject>> {
-    let n1: i64 = unsafe {*(_self.reflect().downcast_ref_unchecked())};
-    let n2: i64 = unsafe {*(arguments[0].reflect().downcast_ref_unchecked())};
-    println!("n1: {} | n2: {}", n1, n2);
+    let n1: i64 = unsafe {*(_self.reflect().downcast_unchecked_ref())};
+    let n2: i64 = unsafe {*(arguments[0].reflect().downcast_unchecked_ref())};
 
     _self.lock().data = LeBlancObjectData::Int64(n1 + n2);
     arguments[0].clone()
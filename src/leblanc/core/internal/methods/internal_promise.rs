@@ -1,7 +1,5 @@
-use alloc::rc::Rc;
-use std::cell::RefCell;
 use crate::leblanc::rustblanc::strawberry::Strawberry;
-use std::sync::{Arc, Mutex};
+use std::sync::Arc;
 use crate::leblanc::core::leblanc_object::{LeBlancObject, RustDataCast};
 use crate::leblanc::core::native_types::promise_type::ArcLeblancPromise;
 
@@ -0,0 +1,69 @@
+use crate::leblanc::rustblanc::strawberry::Strawberry;
+use std::sync::Arc;
+use crate::leblanc::core::internal::internal_list_iterator::LeblancVecIterator;
+use crate::leblanc::core::internal::transformed_iterator::iterable;
+use crate::leblanc::core::leblanc_object::{LeBlancObject, RustDataCast};
+use crate::leblanc::core::native_types::base_type::ToLeblanc;
+use crate::leblanc::core::native_types::boolean_type::leblanc_object_boolean;
+use crate::leblanc::core::native_types::derived::iterator_type::leblanc_object_iterator;
+use crate::leblanc::core::native_types::derived::set_type::LeblancSet;
+use crate::leblanc::core::native_types::int_type::leblanc_object_int;
+
+
+pub fn _internal_set_iterate_(_self: Arc<Strawberry<LeBlancObject>>, _arguments: &mut [Arc<Strawberry<LeBlancObject>>]) -> Arc<Strawberry<LeBlancObject>> {
+    let borrowed = _self.lock();
+    let set: &LeblancSet = borrowed.data.ref_data().unwrap();
+    leblanc_object_iterator(Box::new(LeblancVecIterator::new(set.values.clone()))).to_mutex()
+}
+
+pub fn _internal_set_contains_(_self: Arc<Strawberry<LeBlancObject>>, arguments: &mut [Arc<Strawberry<LeBlancObject>>]) -> Arc<Strawberry<LeBlancObject>> {
+    let borrowed = _self.lock();
+    let set: &LeblancSet = borrowed.data.ref_data().unwrap();
+    leblanc_object_boolean(set.contains(&arguments[0])).to_mutex()
+}
+
+pub fn _internal_set_length_(_self: Arc<Strawberry<LeBlancObject>>, _arguments: &mut [Arc<Strawberry<LeBlancObject>>]) -> Arc<Strawberry<LeBlancObject>> {
+    let borrowed = _self.lock();
+    let set: &LeblancSet = borrowed.data.ref_data().unwrap();
+    leblanc_object_int(set.len() as i32).to_mutex()
+}
+
+pub fn _internal_set_union_(_self: Arc<Strawberry<LeBlancObject>>, arguments: &mut [Arc<Strawberry<LeBlancObject>>]) -> Arc<Strawberry<LeBlancObject>> {
+    combine(_self, &arguments[0], |set, other| set.union(other).create_mutex())
+}
+
+pub fn _internal_set_intersection_(_self: Arc<Strawberry<LeBlancObject>>, arguments: &mut [Arc<Strawberry<LeBlancObject>>]) -> Arc<Strawberry<LeBlancObject>> {
+    combine(_self, &arguments[0], |set, other| set.intersection(other).create_mutex())
+}
+
+pub fn _internal_set_difference_(_self: Arc<Strawberry<LeBlancObject>>, arguments: &mut [Arc<Strawberry<LeBlancObject>>]) -> Arc<Strawberry<LeBlancObject>> {
+    combine(_self, &arguments[0], |set, other| set.difference(other).create_mutex())
+}
+
+pub fn _internal_set_is_subset_(_self: Arc<Strawberry<LeBlancObject>>, arguments: &mut [Arc<Strawberry<LeBlancObject>>]) -> Arc<Strawberry<LeBlancObject>> {
+    combine(_self, &arguments[0], |set, other| leblanc_object_boolean(set.is_subset(other)).to_mutex())
+}
+
+pub fn _internal_set_is_superset_(_self: Arc<Strawberry<LeBlancObject>>, arguments: &mut [Arc<Strawberry<LeBlancObject>>]) -> Arc<Strawberry<LeBlancObject>> {
+    combine(_self, &arguments[0], |set, other| leblanc_object_boolean(other.is_subset(set)).to_mutex())
+}
+
+/// Applies `operation` to the set and the other value, which is made into a set first if it is
+/// another iterable. The other value is read before the set is locked, since it can be the set itself.
+fn combine(_self: Arc<Strawberry<LeBlancObject>>, other: &Arc<Strawberry<LeBlancObject>>, operation: fn(&LeblancSet, &LeblancSet) -> Arc<Strawberry<LeBlancObject>>) -> Arc<Strawberry<LeBlancObject>> {
+    let other = match as_set(other) {
+        Ok(other) => other,
+        Err(error) => return error
+    };
+    let borrowed = _self.lock();
+    let set: &LeblancSet = borrowed.data.ref_data().unwrap();
+    operation(set, &other)
+}
+
+fn as_set(object: &Arc<Strawberry<LeBlancObject>>) -> Result<LeblancSet, Arc<Strawberry<LeBlancObject>>> {
+    let set: Option<LeblancSet> = object.lock().data.clone_data();
+    match set {
+        Some(set) => Ok(set),
+        None => Ok(LeblancSet::new(iterable(object.clone())?.to_rust_iter().collect()))
+    }
+}
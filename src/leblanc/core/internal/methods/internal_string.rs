@@ -1,9 +1,7 @@
 
 
-use alloc::rc::Rc;
-use std::cell::RefCell;
 use crate::leblanc::rustblanc::strawberry::Strawberry;
-use std::sync::{Arc, Mutex};
+use std::sync::Arc;
 use crate::leblanc::core::leblanc_object::{LeBlancObject, Stringify};
 use crate::leblanc::core::native_types::base_type::ToLeblanc;
 
@@ -0,0 +1,20 @@
+use crate::leblanc::rustblanc::strawberry::Strawberry;
+use std::sync::Arc;
+use crate::leblanc::core::internal::internal_list_iterator::LeblancVecIterator;
+use crate::leblanc::core::leblanc_object::{LeBlancObject, RustDataCast};
+use crate::leblanc::core::native_types::derived::iterator_type::leblanc_object_iterator;
+use crate::leblanc::core::native_types::derived::tuple_type::LeblancTuple;
+use crate::leblanc::core::native_types::int_type::leblanc_object_int;
+
+
+pub fn _internal_tuple_iterate_(_self: Arc<Strawberry<LeBlancObject>>, _arguments: &mut [Arc<Strawberry<LeBlancObject>>]) -> Arc<Strawberry<LeBlancObject>> {
+    let borrowed = _self.lock();
+    let tuple: &LeblancTuple = borrowed.data.ref_data().unwrap();
+    leblanc_object_iterator(Box::new(LeblancVecIterator::new(tuple.values.clone()))).to_mutex()
+}
+
+pub fn _internal_tuple_length_(_self: Arc<Strawberry<LeBlancObject>>, _arguments: &mut [Arc<Strawberry<LeBlancObject>>]) -> Arc<Strawberry<LeBlancObject>> {
+    let borrowed = _self.lock();
+    let tuple: &LeblancTuple = borrowed.data.ref_data().unwrap();
+    leblanc_object_int(tuple.len() as i32).to_mutex()
+}
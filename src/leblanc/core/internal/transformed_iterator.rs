@@ -1,79 +1,234 @@
-use alloc::rc::Rc;
 use core::fmt::Debug;
-use std::cell::RefCell;
 use crate::leblanc::rustblanc::strawberry::Strawberry;
-use std::sync::{Arc, Mutex};
-use crate::leblanc::core::internal::transformed_iterator::IterMutation::{Filter, Map};
-use crate::leblanc::core::leblanc_handle::LeblancHandle;
-use crate::leblanc::core::leblanc_object::{LeBlancObject, RustDataCast};
+use std::sync::Arc;
+use crate::leblanc::core::internal::internal_list_iterator::LeblancVecIterator;
+use crate::leblanc::core::internal::transformed_iterator::IterMutation::{Enumerate, Filter, FlatMap, Map, Skip, Take, TakeWhile};
+use crate::leblanc::core::leblanc_object::{Callable, LeBlancObject, LeBlancObjectData, RustDataCast};
 
-use crate::leblanc::core::native_types::derived::iterator_type::{LeblancIterable};
+use crate::leblanc::core::native_types::derived::DerivedType;
+use crate::leblanc::core::native_types::derived::iterator_type::{LeblancIterable, LeblancIterator};
+use crate::leblanc::core::native_types::base_type::ToLeblanc;
 use crate::leblanc::core::native_types::derived::list_type::LeblancList;
+use crate::leblanc::core::native_types::derived::tuple_type::LeblancTuple;
+use crate::leblanc::core::native_types::int_type::leblanc_object_int;
+use crate::LeBlancType;
 
+/// An iterator with transformations applied to it. Each value of the inner iterator goes through
+/// every transformation before the next one is taken, so nothing is computed until it is asked for
+/// and no list is built in between.
 #[derive(Debug, Clone)]
 pub struct TransformedIterator {
     inner_iterator: Box<dyn LeblancIterable>,
-    transformations: Vec<IterMutation>
+    transformations: Vec<IterMutation>,
+    /// The value `has_next` had to compute to find out whether there is one.
+    peeked: Option<Option<Arc<Strawberry<LeBlancObject>>>>,
+    finished: bool
 }
 
-#[derive(Debug, Clone, PartialEq)]
+/// A transformation, holding the function it applies and whatever it counts.
+#[derive(Debug, Clone)]
 pub enum IterMutation {
-    Filter(LeblancHandle),
-    Map(LeblancHandle)
+    Filter(Arc<Strawberry<LeBlancObject>>),
+    Map(Arc<Strawberry<LeBlancObject>>),
+    /// The values still to be let through.
+    Take(i64),
+    /// The values still to be dropped.
+    Skip(i64),
+    /// Whether the function has returned false yet.
+    TakeWhile(Arc<Strawberry<LeBlancObject>>, bool),
+    /// The index of the next value.
+    Enumerate(i32),
+    /// The values the function returned for the current value, until they run out.
+    FlatMap(Arc<Strawberry<LeBlancObject>>, Option<Box<dyn LeblancIterable>>)
+}
+
+impl IterMutation {
+    /// Whether no more values can get past this transformation.
+    fn closed(&self) -> bool {
+        matches!(self, Take(0) | TakeWhile(_, true))
+    }
 }
 
 impl TransformedIterator {
     pub fn new(inner_iterator: Box<dyn LeblancIterable>) -> TransformedIterator {
         TransformedIterator {
             inner_iterator,
-            transformations: vec![]
+            transformations: vec![],
+            peeked: None,
+            finished: false
+        }
+    }
+
+    /// Adds a transformation to the end. A value already taken from the inner iterator has been
+    /// through the others, so then they are kept together as the inner iterator instead.
+    pub fn transform(&mut self, transformation: IterMutation) {
+        if self.peeked.is_some() {
+            let transformed = std::mem::replace(self, TransformedIterator::new(Box::new(LeblancVecIterator::new(vec![]))));
+            self.inner_iterator = Box::new(transformed);
+        }
+        self.transformations.push(transformation)
+    }
+
+    /// The next value to go through the transformations, with the index of the first one it has
+    /// to go through. That is after the last `flat_map` with values left, or the inner iterator's
+    /// next value when there is none. Nothing is taken from past a closed `take` or `take_while`.
+    fn source(&mut self) -> Option<(Arc<Strawberry<LeBlancObject>>, usize)> {
+        for index in (0..self.transformations.len()).rev() {
+            match &mut self.transformations[index] {
+                transformation if transformation.closed() => return None,
+                FlatMap(_, current) => {
+                    if let Some(values) = current {
+                        if values.has_next() { return Some((values.lb_next(), index + 1)); }
+                    }
+                    *current = None;
+                }
+                _ => {}
+            }
+        }
+        match self.inner_iterator.has_next() {
+            true => Some((self.inner_iterator.lb_next(), 0)),
+            false => None
         }
     }
 
-    pub fn filter(&mut self, handle: LeblancHandle) {
-        self.transformations.push(Filter(handle))
+    /// Runs values through the transformations until one comes out the other end. An error
+    /// raised by a function ends the iterator, with the error as its last value.
+    fn produce(&mut self) -> Option<Arc<Strawberry<LeBlancObject>>> {
+        if self.finished { return None; }
+        'values: loop {
+            let (mut value, start) = match self.source() {
+                Some(source) => source,
+                None => {
+                    self.finished = true;
+                    return None;
+                }
+            };
+            for transformation in &mut self.transformations[start..] {
+                match transformation {
+                    Filter(function) => {
+                        let keep = call(function, &mut [value.clone()]);
+                        if is_error(&keep) { return self.fail(keep); }
+                        if !is_true(&keep) { continue 'values; }
+                    }
+                    Map(function) => {
+                        value = call(function, &mut [value]);
+                        if is_error(&value) { return self.fail(value); }
+                    }
+                    Take(remaining) => *remaining -= 1,
+                    Skip(remaining) => if *remaining > 0 {
+                        *remaining -= 1;
+                        continue 'values;
+                    }
+                    TakeWhile(function, closed) => {
+                        let keep = call(function, &mut [value.clone()]);
+                        if is_error(&keep) { return self.fail(keep); }
+                        if !is_true(&keep) {
+                            *closed = true;
+                            continue 'values;
+                        }
+                    }
+                    Enumerate(index) => {
+                        value = pair(leblanc_object_int(*index).to_mutex(), value);
+                        *index += 1;
+                    }
+                    FlatMap(function, current) => {
+                        let values = call(function, &mut [value]);
+                        match iterable(values) {
+                            Ok(values) => *current = Some(values),
+                            Err(error) => return self.fail(error)
+                        }
+                        continue 'values;
+                    }
+                }
+            }
+            return Some(value);
+        }
     }
 
-    pub fn map(&mut self, handle: LeblancHandle) {
-        self.transformations.push(Map(handle))
+    fn fail(&mut self, error: Arc<Strawberry<LeBlancObject>>) -> Option<Arc<Strawberry<LeBlancObject>>> {
+        self.finished = true;
+        Some(error)
     }
 }
 
+/// Calls a function object, or an object with a `call` method.
+pub fn call(function: &Arc<Strawberry<LeBlancObject>>, arguments: &mut [Arc<Strawberry<LeBlancObject>>]) -> Arc<Strawberry<LeBlancObject>> {
+    let method = function.lock().data.get_inner_method().cloned();
+    match method {
+        Some(mut method) => method.run(function.clone(), arguments),
+        None => function.clone().call("call", arguments).unwrap_or_else(|error| error)
+    }
+}
+
+pub fn is_error(value: &Arc<Strawberry<LeBlancObject>>) -> bool {
+    value.underlying_pointer().typing == LeBlancType::Exception
+}
+
+pub fn is_true(value: &Arc<Strawberry<LeBlancObject>>) -> bool {
+    matches!(value.lock().data, LeBlancObjectData::Boolean(true))
+}
+
+/// Two values together, as `enumerate` and `zip` give them.
+pub fn pair(first: Arc<Strawberry<LeBlancObject>>, second: Arc<Strawberry<LeBlancObject>>) -> Arc<Strawberry<LeBlancObject>> {
+    LeblancTuple::new(vec![first, second]).create_mutex()
+}
+
+/// The iterator over `object`, which is either an iterator already or has an `iterate` method.
+pub fn iterable(mut object: Arc<Strawberry<LeBlancObject>>) -> Result<Box<dyn LeblancIterable>, Arc<Strawberry<LeBlancObject>>> {
+    if is_error(&object) { return Err(object); }
+    if object.lock().typing != LeBlancType::Derived(DerivedType::Iterator) {
+        object = object.call_name("iterate")?;
+    }
+    let borrowed = object.lock();
+    let iterator: &LeblancIterator = borrowed.data.ref_data().unwrap();
+    Ok(iterator.iterator.clone())
+}
+
 impl LeblancIterable for TransformedIterator {
     fn lb_next(&mut self) -> Arc<Strawberry<LeBlancObject>> {
-        self.inner_iterator.lb_next()
+        let next = match self.peeked.take() {
+            Some(peeked) => peeked,
+            None => self.produce()
+        };
+        next.unwrap_or_else(LeBlancObject::unsafe_null)
     }
 
-    fn has_next(&self) -> bool {
-        self.inner_iterator.has_next()
+    fn has_next(&mut self) -> bool {
+        if self.peeked.is_none() {
+            self.peeked = Some(self.produce());
+        }
+        matches!(self.peeked, Some(Some(_)))
     }
 
     fn reverse(&mut self) {
-        self.inner_iterator.reverse()
+        let mut values = vec![];
+        while self.has_next() {
+            values.push(self.lb_next());
+        }
+        values.reverse();
+        *self = TransformedIterator::new(Box::new(LeblancVecIterator::new(values)));
     }
 
     fn to_list(&mut self) -> LeblancList {
-        let mut iterator = self.inner_iterator.to_rust_iter();
-
-        for transformation in &mut self.transformations {
-            match transformation {
-                Filter(handle) => {
-                    iterator = Box::new(iterator.filter(|i| *handle.execute_lambda(&mut [i.clone()]).lock().data.ref_data().unwrap()));
-                }
-                Map(handle) => {
-                    iterator = Box::new(iterator.map(|i| handle.execute_lambda(&mut [i])));
-                }
-            }
-        }
-
-        LeblancList::new(iterator.collect::<Vec<Arc<Strawberry<LeBlancObject>>>>())
+        LeblancList::new(self.collect())
     }
 
     fn to_rust_iter(&mut self) -> Box<dyn Iterator<Item=Arc<Strawberry<LeBlancObject>>>> {
-        todo!()
+        Box::new(self.clone())
     }
 
     fn transformed(&mut self) -> Option<&mut TransformedIterator> {
         Some(self)
     }
-}
\ No newline at end of file
+}
+
+impl Iterator for TransformedIterator {
+    type Item = Arc<Strawberry<LeBlancObject>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.has_next() {
+            true => Some(self.lb_next()),
+            false => None
+        }
+    }
+}
@@ -1,25 +1,70 @@
 use std::collections::BTreeSet;
+use std::sync::Arc;
+use std::thread;
+use fxhash::{FxHashMap, FxHashSet};
 
 
 
 use crate::leblanc::core::bytecode::LeblancBytecode;
+use crate::leblanc::core::extension::{install_extensions, Extension};
 use crate::leblanc::core::internal::methods::builtins::create_builtin_function_objects;
 use crate::leblanc::core::interpreter::leblanc_runner::LeBlancRunner;
+use crate::leblanc::core::interpreter::vm_stack::native_stack_size;
 use crate::leblanc::core::leblanc_argument::LeBlancArgument;
 use crate::leblanc::core::leblanc_handle::LeblancHandle;
+use crate::leblanc::core::leblanc_context::VariableContext;
+use crate::leblanc::core::leblanc_object::{LeBlancObject, LeBlancObjectData};
 use crate::leblanc::core::method::Method;
 use crate::leblanc::core::method_store::MethodStore;
-use crate::leblanc::core::native_types::base_type::internal_method;
+use crate::leblanc::core::native_types::base_type::{base_methods, internal_method};
+use crate::leblanc::core::native_types::class_type::ClassMeta;
+use crate::leblanc::core::native_types::LeBlancType;
 use crate::leblanc::rustblanc::copystring::CopyString;
 use crate::leblanc::include::lib::get_core_modules;
+use crate::leblanc::rustblanc::strawberry::Strawberry;
 
+pub mod arithmetic;
 pub mod instructions;
 pub mod interactive;
 pub mod instruction_execution;
+pub mod jump_table;
 pub mod leblanc_runner;
+pub mod value;
+pub mod vm_stack;
 
 
-pub fn run(mut bytecode: LeblancBytecode) {
+/// Runs the `main` function of a loaded bytecode file, passing `args` through as a list of strings
+/// when `main` declares a parameter. Returns whatever `main` returned, which is an exception object
+/// if execution failed, or why the program's thread could not be started or run to the end.
+pub fn run(bytecode: LeblancBytecode, args: Vec<String>) -> Result<Arc<Strawberry<LeBlancObject>>, String> {
+    on_program_thread(move || LeBlancRunner::new(load_globals(bytecode)).run_main(args))
+}
+
+/// Like [`run`], but calls the function `name` with `args` as they are, and leaves printing the
+/// stack trace of an error it raises to the caller.
+pub fn run_function(bytecode: LeblancBytecode, name: &str, mut args: Vec<Arc<Strawberry<LeBlancObject>>>) -> Result<Arc<Strawberry<LeBlancObject>>, String> {
+    let name = name.to_string();
+    on_program_thread(move || LeBlancRunner::new(load_globals(bytecode)).run_function(&name, &mut args))
+}
+
+fn on_program_thread<F>(program: F) -> Result<Arc<Strawberry<LeBlancObject>>, String>
+    where F: FnOnce() -> Arc<Strawberry<LeBlancObject>> + Send + 'static {
+    // Every call also nests on the native stack, so the program gets a thread with enough of it.
+    let stack_size = native_stack_size();
+    let program = thread::Builder::new().name("main".to_string()).stack_size(stack_size).spawn(program)
+        .map_err(|err| format!("cannot start the program with a {} byte stack: {}", stack_size, err))?;
+    program.join().map_err(|panic| {
+        let message = panic.downcast_ref::<&str>().map(|message| message.to_string())
+            .or_else(|| panic.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "unknown cause".to_string());
+        format!("the program stopped unexpectedly: {}", message)
+    })
+}
+
+/// Builds the global function table for a bytecode file: builtins, any imported core modules, the
+/// prototype of every class and every function defined in the file, in the order `LoadFunction`
+/// indexes them. The file's extensions are installed along the way.
+pub fn load_globals(mut bytecode: LeblancBytecode) -> Vec<Arc<Strawberry<LeBlancObject>>> {
     let mut globals = create_builtin_function_objects();
 
     let core_modules = get_core_modules();
@@ -29,23 +74,97 @@ pub fn run(mut bytecode: LeblancBytecode) {
         }
     }
 
-    for mut function in bytecode.body().functions() {
+    let file = CopyString::new(bytecode.file_header().get_file_name());
+    let extension_bytecode = bytecode.body().extensions();
+    let mut sources = FxHashMap::default();
+    for extension in &extension_bytecode {
+        if let Some(source) = extension.source() {
+            let source = CopyString::new(source);
+            sources.extend(extension.initializer().into_iter().chain(extension.methods()).map(|index| (index as usize, source)));
+        }
+    }
+
+    let mut functions = vec![];
+    for (index, mut function) in bytecode.body().functions().into_iter().enumerate() {
         let arguments = &function.arguments();
         let name = function.name();
-        let leblanc_handle = LeblancHandle::from_function_bytecode(function);
+        let mut leblanc_handle = LeblancHandle::from_function_bytecode(function);
+        leblanc_handle.file = sources.get(&index).copied().unwrap_or(file);
         let method_store = MethodStore::new(name.clone(), LeBlancArgument::from_positional(arguments));
         let method = Method::of_leblanc_handle(method_store, leblanc_handle, BTreeSet::new());
-        let mut lbo = internal_method(method);
-        lbo.context.file = CopyString::new(bytecode.file_header().get_file_name());
         if name != "__GLOBAL__" {
-            globals.push(lbo.to_mutex());
+            functions.push(method);
+        }
+    }
+
+    let mut classes: Vec<(ClassMeta, Arc<FxHashSet<Method>>, Vec<String>)> = vec![];
+    for class in bytecode.body().classes() {
+        let name = class.name();
+        let supertypes = class.supertypes().iter()
+            .filter_map(|supertype| classes.iter().find(|(meta, _, _)| meta.name == supertype))
+            .cloned()
+            .collect::<Vec<(ClassMeta, Arc<FxHashSet<Method>>, Vec<String>)>>();
+
+        let mut methods = base_methods().iter().cloned().map(|method| (method_key(&method), method)).collect::<FxHashMap<(String, usize), Method>>();
+        for (_, inherited, _) in supertypes.iter().rev() {
+            methods.extend(inherited.iter().cloned().map(|method| (method_key(&method), method)));
+        }
+        for index in class.methods() {
+            let method = receiver_method(&functions[index as usize]);
+            methods.insert(method_key(&method), method);
         }
+
+        let mut fields = supertypes.iter().flat_map(|(_, _, fields)| fields.iter().cloned()).collect::<Vec<String>>();
+        fields.extend(class.fields());
+        fields.dedup();
+
+        let meta = ClassMeta::new(CopyString::new(&name), supertypes.into_iter().map(|(meta, _, _)| meta).collect(), classes.len() as u32);
+        classes.push((meta, Arc::new(methods.into_values().collect()), fields));
     }
 
-    let mut runner = LeBlancRunner::new(globals);
+    let extensions: Vec<Extension> = extension_bytecode.into_iter().map(|extension| Extension {
+        name: extension.name(),
+        target: extension.target(),
+        properties: extension.properties().into_iter().map(|(name, _)| name).collect(),
+        initializer: extension.initializer().map(|index| functions[index as usize].clone()),
+        methods: extension.methods().into_iter().map(|index| receiver_method(&functions[index as usize])).collect()
+    }).collect();
+    install_extensions(extensions);
 
-    runner.run_main();
+    for (meta, methods, fields) in classes {
+        let members = fields.into_iter().map(|field| (field, LeBlancObject::null().to_mutex())).collect::<FxHashMap<String, Arc<Strawberry<LeBlancObject>>>>();
+        let name = meta.name;
+        let mut prototype = LeBlancObject::new(
+            LeBlancObjectData::Class(Box::new(meta)),
+            LeBlancType::Class(name),
+            methods,
+            Arc::new(Strawberry::new(members)),
+            VariableContext::empty()
+        );
+        prototype.context.file = file;
+        globals.push(prototype.to_mutex());
+    }
+
+    for method in functions {
+        let mut lbo = internal_method(method);
+        lbo.context.file = file;
+        globals.push(lbo.to_mutex());
+    }
 
+    globals
+}
 
+/// The method an object gets from `function`, a method compiled with the object it is called on
+/// as its first argument. Methods are named after the class, trait or extension declaring them.
+fn receiver_method(function: &Method) -> Method {
+    let name = function.context.name.rsplit_once('.').map_or(function.context.name.as_str(), |(_, method)| method).to_string();
+    let arguments = function.context.arguments.iter().skip(1).map(|argument| match argument.typing {
+        LeBlancType::Class(_) => LeBlancType::Flex,
+        typing => typing
+    }).collect::<Vec<LeBlancType>>();
+    Method::of_class(MethodStore::new(name, LeBlancArgument::from_positional(&arguments)), function.leblanc_handle.clone())
+}
 
-}
\ No newline at end of file
+fn method_key(method: &Method) -> (String, usize) {
+    (method.context.name.clone(), method.context.arguments.len())
+}
@@ -0,0 +1,800 @@
+use std::cmp::Ordering;
+use std::fmt::Display;
+use std::sync::Arc;
+
+use num::traits::{CheckedNeg, CheckedRem, Pow, WrappingAdd, WrappingMul, WrappingNeg, WrappingSub};
+use num::{BigInt, Float, FromPrimitive, One, PrimInt, Signed, ToPrimitive, Zero};
+
+use crate::leblanc::core::interpreter::instructions::InstructionBase;
+use crate::leblanc::core::interpreter::value::Value;
+use crate::leblanc::core::leblanc_object::{LeBlancObject, LeBlancObjectData};
+use crate::leblanc::core::native_types::base_type::ToLeblanc;
+use crate::leblanc::core::native_types::decimal_type::LeblancDecimal;
+use crate::leblanc::core::native_types::error_type::LeblancError;
+use crate::leblanc::core::native_types::LeBlancType;
+use crate::leblanc::rustblanc::strawberry::Strawberry;
+
+/// Whether integer operations that overflow their type wrap around instead of raising an
+/// `OverflowException`. Chosen per build with the `wrapping-arithmetic` feature.
+pub const WRAPPING: bool = cfg!(feature = "wrapping-arithmetic");
+
+/// The numeric types from narrowest to widest. Mixing two of them promotes both to the wider one,
+/// so an integer mixed with a float gives a float and anything mixed with a decimal gives a
+/// decimal.
+const TOWER: [LeBlancType; 9] = [LeBlancType::Short, LeBlancType::Int, LeBlancType::Arch, LeBlancType::Int64,
+    LeBlancType::Int128, LeBlancType::BigInt, LeBlancType::Float, LeBlancType::Double, LeBlancType::Decimal];
+
+/// An operator of the arithmetic and bitwise instructions.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Operation {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Power,
+    Modulo,
+    LShift,
+    RShift,
+    And,
+    Or,
+    Xor
+}
+
+impl Operation {
+    pub fn of(instruct: InstructionBase) -> Option<Operation> {
+        Some(match instruct {
+            InstructionBase::BinaryAdd => Operation::Add,
+            InstructionBase::BinarySubtract => Operation::Subtract,
+            InstructionBase::BinaryMultiply => Operation::Multiply,
+            InstructionBase::BinaryDivide => Operation::Divide,
+            InstructionBase::BinaryPower => Operation::Power,
+            InstructionBase::BinaryModulo => Operation::Modulo,
+            InstructionBase::BinaryLShift => Operation::LShift,
+            InstructionBase::BinaryRShift => Operation::RShift,
+            InstructionBase::BinaryAnd => Operation::And,
+            InstructionBase::BinaryOr => Operation::Or,
+            InstructionBase::BinaryXor => Operation::Xor,
+            _ => return None
+        })
+    }
+
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Operation::Add => "+",
+            Operation::Subtract => "-",
+            Operation::Multiply => "*",
+            Operation::Divide => "/",
+            Operation::Power => "**",
+            Operation::Modulo => "%",
+            Operation::LShift => "<<",
+            Operation::RShift => ">>",
+            Operation::And => "and",
+            Operation::Or => "or",
+            Operation::Xor => "xor"
+        }
+    }
+}
+
+/// A value of one of the numeric types, see `TOWER`.
+#[derive(Clone, Debug, PartialEq, PartialOrd)]
+pub enum Number {
+    Short(i16),
+    Int(i32),
+    Arch(isize),
+    Int64(i64),
+    Int128(i128),
+    BigInt(BigInt),
+    Float(f32),
+    Double(f64),
+    Decimal(LeblancDecimal)
+}
+
+impl Number {
+    pub fn of(data: &LeBlancObjectData) -> Option<Number> {
+        Some(match data {
+            LeBlancObjectData::Short(short) => Number::Short(*short),
+            LeBlancObjectData::Int(int) => Number::Int(*int),
+            LeBlancObjectData::Arch(arch) => Number::Arch(*arch),
+            LeBlancObjectData::Int64(int) => Number::Int64(*int),
+            LeBlancObjectData::Int128(int) => Number::Int128(*int),
+            LeBlancObjectData::BigInt(int) => Number::BigInt(int.clone()),
+            LeBlancObjectData::Float(float) => Number::Float(*float),
+            LeBlancObjectData::Double(double) => Number::Double(*double),
+            LeBlancObjectData::Decimal(decimal) => Number::Decimal(decimal.clone()),
+            _ => return None
+        })
+    }
+
+    #[inline(always)]
+    pub fn of_value(value: &Value) -> Option<Number> {
+        match value {
+            Value::Int(int) => Some(Number::Int(*int)),
+            Value::Int64(int) => Some(Number::Int64(*int)),
+            Value::Double(double) => Some(Number::Double(*double)),
+            Value::Object(object) => Number::of(&object.lock().data),
+            _ => None
+        }
+    }
+
+    #[inline(always)]
+    pub fn into_value(self) -> Value {
+        match self {
+            Number::Int(int) => Value::Int(int),
+            Number::Int64(int) => Value::Int64(int),
+            Number::Double(double) => Value::Double(double),
+            number => Value::Object(number.into_object())
+        }
+    }
+
+    pub fn into_object(self) -> Arc<Strawberry<LeBlancObject>> {
+        match self {
+            Number::Short(short) => short.create_mutex(),
+            Number::Int(int) => int.create_mutex(),
+            Number::Arch(arch) => arch.create_mutex(),
+            Number::Int64(int) => int.create_mutex(),
+            Number::Int128(int) => int.create_mutex(),
+            Number::BigInt(int) => int.create_mutex(),
+            Number::Float(float) => float.create_mutex(),
+            Number::Double(double) => double.create_mutex(),
+            Number::Decimal(decimal) => decimal.create_mutex()
+        }
+    }
+
+    pub fn typing(&self) -> LeBlancType {
+        match self {
+            Number::Short(_) => LeBlancType::Short,
+            Number::Int(_) => LeBlancType::Int,
+            Number::Arch(_) => LeBlancType::Arch,
+            Number::Int64(_) => LeBlancType::Int64,
+            Number::Int128(_) => LeBlancType::Int128,
+            Number::BigInt(_) => LeBlancType::BigInt,
+            Number::Float(_) => LeBlancType::Float,
+            Number::Double(_) => LeBlancType::Double,
+            Number::Decimal(_) => LeBlancType::Decimal
+        }
+    }
+
+    /// The position of the number's type in `TOWER`.
+    fn rank(&self) -> usize {
+        match self {
+            Number::Short(_) => 0,
+            Number::Int(_) => 1,
+            Number::Arch(_) => 2,
+            Number::Int64(_) => 3,
+            Number::Int128(_) => 4,
+            Number::BigInt(_) => 5,
+            Number::Float(_) => 6,
+            Number::Double(_) => 7,
+            Number::Decimal(_) => 8
+        }
+    }
+
+    /// The value as an integer, truncating a fraction. `None` for a value that is out of range.
+    pub fn integer(&self) -> Option<i128> {
+        match self {
+            Number::Short(short) => Some(*short as i128),
+            Number::Int(int) => Some(*int as i128),
+            Number::Arch(arch) => Some(*arch as i128),
+            Number::Int64(int) => Some(*int as i128),
+            Number::Int128(int) => Some(*int),
+            Number::BigInt(int) => int.to_i128(),
+            Number::Float(float) => float_to_integer(*float as f64),
+            Number::Double(double) => float_to_integer(*double),
+            Number::Decimal(decimal) => decimal.trunc().to_i128()
+        }
+    }
+
+    /// The value as a `bigint`, truncating a fraction. `None` for a float that isn't finite.
+    fn big(&self) -> Option<BigInt> {
+        match self {
+            Number::BigInt(int) => Some(int.clone()),
+            Number::Float(float) => BigInt::from_f32(float.trunc()),
+            Number::Double(double) => BigInt::from_f64(double.trunc()),
+            Number::Decimal(decimal) => Some(decimal.trunc()),
+            integer => integer.integer().map(BigInt::from)
+        }
+    }
+
+    /// The value as a `decimal`. A float becomes the shortest decimal that reads back as the same
+    /// float, so `0.1` is `0.1` rather than the digits of its binary fraction. `None` for a float
+    /// that isn't finite.
+    fn decimal(&self) -> Option<LeblancDecimal> {
+        match self {
+            Number::Decimal(decimal) => Some(decimal.clone()),
+            Number::Float(float) if float.is_finite() => LeblancDecimal::parse(&float.to_string()),
+            Number::Double(double) if double.is_finite() => LeblancDecimal::parse(&double.to_string()),
+            Number::Float(_) | Number::Double(_) => None,
+            integer => integer.big().map(LeblancDecimal::from_integer)
+        }
+    }
+
+    fn float(&self) -> f64 {
+        match self {
+            Number::Float(float) => *float as f64,
+            Number::Double(double) => *double,
+            Number::BigInt(int) => int.to_f64().unwrap_or(f64::NAN),
+            Number::Decimal(decimal) => decimal.to_f64(),
+            integer => integer.integer().unwrap() as f64
+        }
+    }
+
+    fn is_integer(&self) -> bool {
+        self.rank() <= 5
+    }
+
+    /// The value as a `typing`, or `None` when it doesn't fit.
+    fn convert(&self, typing: LeBlancType) -> Option<Number> {
+        match typing {
+            LeBlancType::Float => return Some(Number::Float(self.float() as f32)),
+            LeBlancType::Double => return Some(Number::Double(self.float())),
+            LeBlancType::BigInt => return self.big().map(Number::BigInt),
+            LeBlancType::Decimal => return self.decimal().map(Number::Decimal),
+            _ => {}
+        }
+        let integer = self.integer()?;
+        match typing {
+            LeBlancType::Short => i16::try_from(integer).ok().map(Number::Short),
+            LeBlancType::Int => i32::try_from(integer).ok().map(Number::Int),
+            LeBlancType::Arch => isize::try_from(integer).ok().map(Number::Arch),
+            LeBlancType::Int64 => i64::try_from(integer).ok().map(Number::Int64),
+            LeBlancType::Int128 => Some(Number::Int128(integer)),
+            _ => None
+        }
+    }
+
+    /// The value as a `typing`, keeping its low bits when it doesn't fit.
+    fn wrapped(&self, typing: LeBlancType) -> Option<Number> {
+        let integer = match self {
+            Number::Float(float) => *float as i128,
+            Number::Double(double) => *double as i128,
+            Number::BigInt(_) | Number::Decimal(_) => (self.big()? & BigInt::from(u128::MAX)).to_u128()? as i128,
+            integer => integer.integer().unwrap()
+        };
+        match typing {
+            LeBlancType::Short => Some(Number::Short(integer as i16)),
+            LeBlancType::Int => Some(Number::Int(integer as i32)),
+            LeBlancType::Arch => Some(Number::Arch(integer as isize)),
+            LeBlancType::Int64 => Some(Number::Int64(integer as i64)),
+            LeBlancType::Int128 => Some(Number::Int128(integer)),
+            typing => self.convert(typing)
+        }
+    }
+
+    /// The value as a `typing` for `as`. A value that doesn't fit raises an `OverflowException`,
+    /// or wraps with the `wrapping-arithmetic` feature.
+    pub fn cast(&self, typing: LeBlancType) -> Result<Number, Arc<Strawberry<LeBlancObject>>> {
+        match self.convert(typing) {
+            Some(number) => Ok(number),
+            None if WRAPPING => self.wrapped(typing).ok_or_else(|| cast_error(self.typing(), typing)),
+            None if TOWER.contains(&typing) => Err(arithmetic_error("OverflowException", format!("{} does not fit in {}", self, typing))),
+            None => Err(cast_error(self.typing(), typing))
+        }
+    }
+
+    /// `self <operation> other`, after promoting both to the wider of their types. A shift keeps
+    /// the type of the number shifted.
+    pub fn apply(self, operation: Operation, other: Number) -> Result<Number, Arc<Strawberry<LeBlancObject>>> {
+        if matches!(operation, Operation::LShift | Operation::RShift) {
+            return self.shift(operation, other);
+        }
+        let typing = TOWER[self.rank().max(other.rank())];
+        match self.promoted(other)? {
+            (Number::Short(left), Number::Short(right)) => integer(operation, left, right, typing).map(Number::Short),
+            (Number::Int(left), Number::Int(right)) => integer(operation, left, right, typing).map(Number::Int),
+            (Number::Arch(left), Number::Arch(right)) => integer(operation, left, right, typing).map(Number::Arch),
+            (Number::Int64(left), Number::Int64(right)) => integer(operation, left, right, typing).map(Number::Int64),
+            (Number::Int128(left), Number::Int128(right)) => integer(operation, left, right, typing).map(Number::Int128),
+            (Number::BigInt(left), Number::BigInt(right)) => big_integer(operation, left, right).map(Number::BigInt),
+            (Number::Float(left), Number::Float(right)) => float(operation, left, right, typing).map(Number::Float),
+            (Number::Double(left), Number::Double(right)) => float(operation, left, right, typing).map(Number::Double),
+            (Number::Decimal(left), Number::Decimal(right)) => decimal(operation, left, right).map(Number::Decimal),
+            _ => unreachable!("both sides are promoted to {}", typing)
+        }
+    }
+
+    /// Both numbers as the wider of their types. Only a float that isn't finite can fail to
+    /// promote, when it meets a decimal.
+    #[inline(always)]
+    fn promoted(self, other: Number) -> Result<(Number, Number), Arc<Strawberry<LeBlancObject>>> {
+        if self.rank() == other.rank() {
+            return Ok((self, other));
+        }
+        let typing = TOWER[self.rank().max(other.rank())];
+        match (self.convert(typing), other.convert(typing)) {
+            (Some(left), Some(right)) => Ok((left, right)),
+            _ => Err(arithmetic_error("ArithmeticException", format!("{} and {} have no common {} value", self, other, typing)))
+        }
+    }
+
+    /// How `self` orders against `other` once both are promoted, `None` if either is NaN.
+    /// Decimals compare by value, so `1.0d` equals `1.00d`.
+    pub fn compare(self, other: Number) -> Option<Ordering> {
+        match self.promoted(other).ok()? {
+            (Number::Decimal(left), Number::Decimal(right)) => Some(left.compare(&right)),
+            (left, right) => left.partial_cmp(&right)
+        }
+    }
+
+    fn shift(self, operation: Operation, by: Number) -> Result<Number, Arc<Strawberry<LeBlancObject>>> {
+        if !self.is_integer() || !by.is_integer() {
+            return Err(unsupported(operation, self.typing(), by.typing()));
+        }
+        let count = match by.integer() {
+            Some(count) => count,
+            None => return Err(arithmetic_error("OverflowException", format!("{} {} {} shifts too far", self, operation.symbol(), by)))
+        };
+        match self {
+            Number::Short(short) => shift(operation, short, count).map(Number::Short),
+            Number::Int(int) => shift(operation, int, count).map(Number::Int),
+            Number::Arch(arch) => shift(operation, arch, count).map(Number::Arch),
+            Number::Int64(int) => shift(operation, int, count).map(Number::Int64),
+            Number::Int128(int) => shift(operation, int, count).map(Number::Int128),
+            Number::BigInt(int) => big_shift(operation, int, count).map(Number::BigInt),
+            _ => unreachable!()
+        }
+    }
+
+    /// `-self`. `None` when it overflows, which only the smallest value of an integer type does.
+    fn negate(self) -> Option<Number> {
+        Some(match self {
+            Number::Short(short) => Number::Short(negate_integer(short)?),
+            Number::Int(int) => Number::Int(negate_integer(int)?),
+            Number::Arch(arch) => Number::Arch(negate_integer(arch)?),
+            Number::Int64(int) => Number::Int64(negate_integer(int)?),
+            Number::Int128(int) => Number::Int128(negate_integer(int)?),
+            Number::BigInt(int) => Number::BigInt(-int),
+            Number::Float(float) => Number::Float(-float),
+            Number::Double(double) => Number::Double(-double),
+            Number::Decimal(decimal) => Number::Decimal(-decimal)
+        })
+    }
+
+    /// `~self`, flipping every bit of an integer. `None` for a float or decimal.
+    fn inverse(self) -> Option<Number> {
+        Some(match self {
+            Number::Short(short) => Number::Short(!short),
+            Number::Int(int) => Number::Int(!int),
+            Number::Arch(arch) => Number::Arch(!arch),
+            Number::Int64(int) => Number::Int64(!int),
+            Number::Int128(int) => Number::Int128(!int),
+            Number::BigInt(int) => Number::BigInt(!int),
+            _ => return None
+        })
+    }
+}
+
+impl Display for Number {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Number::Short(short) => write!(f, "{}", short),
+            Number::Int(int) => write!(f, "{}", int),
+            Number::Arch(arch) => write!(f, "{}", arch),
+            Number::Int64(int) => write!(f, "{}", int),
+            Number::Int128(int) => write!(f, "{}", int),
+            Number::BigInt(int) => write!(f, "{}", int),
+            Number::Float(float) => write!(f, "{}", float),
+            Number::Double(double) => write!(f, "{}", double),
+            Number::Decimal(decimal) => write!(f, "{}", decimal)
+        }
+    }
+}
+
+/// `left <operation> right` for the binary instructions. Numbers go through the numeric tower and
+/// booleans support `and`, `or` and `xor`.
+#[inline(always)]
+pub fn binary(operation: Operation, left: Value, right: Value) -> Result<Value, Arc<Strawberry<LeBlancObject>>> {
+    if let (Value::Int(left), Value::Int(right)) = (&left, &right) {
+        if let Some(result) = int_fast_path(operation, *left, *right) {
+            return Ok(Value::Int(result));
+        }
+    }
+    if let (Value::Boolean(left), Value::Boolean(right)) = (&left, &right) {
+        return match operation {
+            Operation::And => Ok(Value::Boolean(*left && *right)),
+            Operation::Or => Ok(Value::Boolean(*left || *right)),
+            Operation::Xor => Ok(Value::Boolean(*left != *right)),
+            _ => Err(unsupported(operation, LeBlancType::Boolean, LeBlancType::Boolean))
+        };
+    }
+    match (Number::of_value(&left), Number::of_value(&right)) {
+        (Some(left), Some(right)) => left.apply(operation, right).map(Number::into_value),
+        _ => Err(unsupported(operation, left.typing(), right.typing()))
+    }
+}
+
+/// `-value`, for the unary minus.
+pub fn negate(value: Value) -> Result<Value, Arc<Strawberry<LeBlancObject>>> {
+    if let Value::Int(int) = value {
+        if let Some(negated) = int.checked_neg() {
+            return Ok(Value::Int(negated));
+        }
+    }
+    match Number::of_value(&value) {
+        Some(number) => {
+            let typing = number.typing();
+            let shown = number.to_string();
+            number.negate().map(Number::into_value)
+                .ok_or_else(|| arithmetic_error("OverflowException", format!("-({}) overflows {}", shown, typing)))
+        }
+        None => Err(arithmetic_error("TypeException", format!("Unsupported operand type for -: {}", value.typing())))
+    }
+}
+
+/// `~value`, which negates a boolean and flips every bit of an integer.
+pub fn inverse(value: Value) -> Result<Value, Arc<Strawberry<LeBlancObject>>> {
+    if let Value::Boolean(boolean) = value {
+        return Ok(Value::Boolean(!boolean));
+    }
+    Number::of_value(&value).and_then(Number::inverse).map(Number::into_value)
+        .ok_or_else(|| arithmetic_error("TypeException", format!("Unsupported operand type for ~: {}", value.typing())))
+}
+
+/// The result of the common operations on two ints when it neither overflows nor divides by
+/// zero, which are then left to `Number::apply`.
+#[inline(always)]
+pub fn int_fast_path(operation: Operation, left: i32, right: i32) -> Option<i32> {
+    match operation {
+        Operation::Add => left.checked_add(right),
+        Operation::Subtract => left.checked_sub(right),
+        Operation::Multiply => left.checked_mul(right),
+        Operation::Divide => left.checked_div(right),
+        Operation::Modulo => left.checked_rem(right),
+        _ => None
+    }
+}
+
+fn integer<T: PrimInt + CheckedRem + WrappingAdd + WrappingSub + WrappingMul + Display>(operation: Operation, left: T, right: T, typing: LeBlancType) -> Result<T, Arc<Strawberry<LeBlancObject>>> {
+    let result = match operation {
+        Operation::Add if WRAPPING => Some(left.wrapping_add(&right)),
+        Operation::Add => left.checked_add(&right),
+        Operation::Subtract if WRAPPING => Some(left.wrapping_sub(&right)),
+        Operation::Subtract => left.checked_sub(&right),
+        Operation::Multiply if WRAPPING => Some(left.wrapping_mul(&right)),
+        Operation::Multiply => left.checked_mul(&right),
+        Operation::Divide | Operation::Modulo if right.is_zero() => return Err(divide_by_zero(operation, left, right)),
+        // Only the smallest value divided by -1 overflows, which wraps back to itself.
+        Operation::Divide if WRAPPING => Some(left.checked_div(&right).unwrap_or(left)),
+        Operation::Divide => left.checked_div(&right),
+        Operation::Modulo => Some(left.checked_rem(&right).unwrap_or_else(T::zero)),
+        Operation::Power if right < T::zero() => {
+            return Err(arithmetic_error("ArithmeticException", format!("{} ** {} has a negative exponent, which {} can't hold", left, right, typing)));
+        }
+        Operation::Power => power(left, right),
+        Operation::And => Some(left & right),
+        Operation::Or => Some(left | right),
+        Operation::Xor => Some(left ^ right),
+        Operation::LShift | Operation::RShift => unreachable!("shifts are applied by Number::shift")
+    };
+    result.ok_or_else(|| overflow(operation, left, right, typing))
+}
+
+/// `base ** exponent` by squaring, wrapping or returning `None` on overflow.
+fn power<T: PrimInt + WrappingMul>(base: T, exponent: T) -> Option<T> {
+    let mut result = T::one();
+    let mut base = base;
+    let mut exponent = exponent;
+    while exponent > T::zero() {
+        if exponent & T::one() == T::one() {
+            result = if WRAPPING { result.wrapping_mul(&base) } else { result.checked_mul(&base)? };
+        }
+        exponent = exponent >> 1;
+        if exponent > T::zero() {
+            base = if WRAPPING { base.wrapping_mul(&base) } else { base.checked_mul(&base)? };
+        }
+    }
+    Some(result)
+}
+
+fn negate_integer<T: PrimInt + WrappingNeg + CheckedNeg>(value: T) -> Option<T> {
+    match WRAPPING {
+        true => Some(value.wrapping_neg()),
+        false => value.checked_neg()
+    }
+}
+
+fn shift<T: PrimInt + Display>(operation: Operation, value: T, by: i128) -> Result<T, Arc<Strawberry<LeBlancObject>>> {
+    let bits = T::zero().count_zeros() as i128;
+    let by = match (0..bits).contains(&by) {
+        true => by as usize,
+        false if by < 0 => return Err(arithmetic_error("ArithmeticException", format!("{} {} {} shifts by a negative count", value, operation.symbol(), by))),
+        false if WRAPPING => by.rem_euclid(bits) as usize,
+        false => return Err(arithmetic_error("OverflowException", format!("{} {} {} shifts by more than the {} bits of the value", value, operation.symbol(), by, bits)))
+    };
+    Ok(match operation {
+        Operation::LShift => value << by,
+        _ => value >> by
+    })
+}
+
+/// `left <operation> right` on bigints, which never overflow. Division truncates like it does for
+/// the fixed width integers.
+fn big_integer(operation: Operation, left: BigInt, right: BigInt) -> Result<BigInt, Arc<Strawberry<LeBlancObject>>> {
+    Ok(match operation {
+        Operation::Add => left + right,
+        Operation::Subtract => left - right,
+        Operation::Multiply => left * right,
+        Operation::Divide | Operation::Modulo if right.is_zero() => return Err(divide_by_zero(operation, left, right)),
+        Operation::Divide => left / right,
+        Operation::Modulo => left % right,
+        Operation::Power => match right.to_u32() {
+            Some(exponent) => Pow::pow(left, exponent),
+            None if right.is_negative() => {
+                return Err(arithmetic_error("ArithmeticException", format!("{} ** {} has a negative exponent, which bigint can't hold", left, right)));
+            }
+            None => return Err(overflow(operation, left, right, LeBlancType::BigInt))
+        },
+        Operation::And => left & right,
+        Operation::Or => left | right,
+        Operation::Xor => left ^ right,
+        Operation::LShift | Operation::RShift => unreachable!("shifts are applied by Number::shift")
+    })
+}
+
+fn big_shift(operation: Operation, value: BigInt, by: i128) -> Result<BigInt, Arc<Strawberry<LeBlancObject>>> {
+    let by = match u32::try_from(by) {
+        Ok(by) => by,
+        Err(_) if by < 0 => return Err(arithmetic_error("ArithmeticException", format!("{} {} {} shifts by a negative count", value, operation.symbol(), by))),
+        Err(_) => return Err(arithmetic_error("OverflowException", format!("{} {} {} shifts too far", value, operation.symbol(), by)))
+    };
+    Ok(match operation {
+        Operation::LShift => value << by,
+        _ => value >> by
+    })
+}
+
+/// `left <operation> right` on decimals. Only a whole number can be an exponent, and a negative
+/// one divides, so `2d ** -2` is `0.25`.
+fn decimal(operation: Operation, left: LeblancDecimal, right: LeblancDecimal) -> Result<LeblancDecimal, Arc<Strawberry<LeBlancObject>>> {
+    Ok(match operation {
+        Operation::Add => left.add(&right),
+        Operation::Subtract => left.sub(&right),
+        Operation::Multiply => left.mul(&right),
+        Operation::Divide => left.div(&right).ok_or_else(|| divide_by_zero(operation, &left, &right))?,
+        Operation::Modulo => left.rem(&right).ok_or_else(|| divide_by_zero(operation, &left, &right))?,
+        Operation::Power if !right.is_integer() => {
+            return Err(arithmetic_error("ArithmeticException", format!("{} ** {} has a fractional exponent, which decimal can't hold", left, right)));
+        }
+        Operation::Power => {
+            let exponent = right.trunc();
+            let power = match exponent.magnitude().to_u32() {
+                Some(magnitude) => left.pow(magnitude),
+                None => return Err(overflow(operation, left, right, LeBlancType::Decimal))
+            };
+            match exponent.is_negative() {
+                true => LeblancDecimal::from_integer(BigInt::one()).div(&power).ok_or_else(|| divide_by_zero(operation, &left, &right))?,
+                false => power
+            }
+        }
+        _ => return Err(unsupported(operation, LeBlancType::Decimal, LeBlancType::Decimal))
+    })
+}
+
+fn float<T: Float + Display>(operation: Operation, left: T, right: T, typing: LeBlancType) -> Result<T, Arc<Strawberry<LeBlancObject>>> {
+    Ok(match operation {
+        Operation::Add => left + right,
+        Operation::Subtract => left - right,
+        Operation::Multiply => left * right,
+        Operation::Divide | Operation::Modulo if right.is_zero() => return Err(divide_by_zero(operation, left, right)),
+        Operation::Divide => left / right,
+        Operation::Modulo => left % right,
+        Operation::Power => left.powf(right),
+        _ => return Err(unsupported(operation, typing, typing))
+    })
+}
+
+fn float_to_integer(float: f64) -> Option<i128> {
+    match float.is_finite() {
+        true => float.trunc().to_i128(),
+        false => None
+    }
+}
+
+fn arithmetic_error(name: &str, message: String) -> Arc<Strawberry<LeBlancObject>> {
+    LeblancError::new(name.to_string(), message, vec![]).create_mutex()
+}
+
+fn overflow<T: Display>(operation: Operation, left: T, right: T, typing: LeBlancType) -> Arc<Strawberry<LeBlancObject>> {
+    arithmetic_error("OverflowException", format!("{} {} {} overflows {}", left, operation.symbol(), right, typing))
+}
+
+fn divide_by_zero<T: Display>(operation: Operation, left: T, right: T) -> Arc<Strawberry<LeBlancObject>> {
+    arithmetic_error("DivideByZeroException", format!("{} {} {} divides by zero", left, operation.symbol(), right))
+}
+
+pub fn unsupported(operation: Operation, left: LeBlancType, right: LeBlancType) -> Arc<Strawberry<LeBlancObject>> {
+    arithmetic_error("TypeException", format!("Unsupported operand types for {}: {} and {}", operation.symbol(), left, right))
+}
+
+pub fn cast_error(from: LeBlancType, to: LeBlancType) -> Arc<Strawberry<LeBlancObject>> {
+    arithmetic_error("TypeException", format!("Cannot cast {} to {}", from, to))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::leblanc::core::leblanc_object::RustDataCast;
+
+    /// One of every numeric type, in the order of `TOWER`.
+    fn ones() -> [Number; 9] {
+        [Number::Short(1), Number::Int(1), Number::Arch(1), Number::Int64(1), Number::Int128(1), Number::BigInt(BigInt::one()),
+            Number::Float(1.0), Number::Double(1.0), Number::Decimal(LeblancDecimal::from_integer(BigInt::one()))]
+    }
+
+    fn big(value: i128) -> Number {
+        Number::BigInt(BigInt::from(value))
+    }
+
+    fn decimal(text: &str) -> Number {
+        Number::Decimal(LeblancDecimal::parse(text).unwrap())
+    }
+
+    fn string(text: &str) -> Value {
+        Value::Object(text.to_string().create_mutex())
+    }
+
+    /// The name of the error `result` failed with.
+    fn error<T: std::fmt::Debug>(result: Result<T, Arc<Strawberry<LeBlancObject>>>) -> String {
+        let error = result.expect_err("expected an error");
+        let error = error.lock();
+        let error: &LeblancError = error.data.ref_data().unwrap();
+        error.name().to_string()
+    }
+
+    #[test]
+    fn mixed_types_promote_to_the_wider_one() {
+        for left in ones() {
+            for right in ones() {
+                let expected = TOWER[left.rank().max(right.rank())];
+                let sum = left.clone().apply(Operation::Add, right.clone()).unwrap();
+                assert_eq!(sum.typing(), expected, "{} + {}", left.typing(), right.typing());
+                assert_eq!(sum.integer(), Some(2), "{} + {}", left.typing(), right.typing());
+            }
+        }
+    }
+
+    #[test]
+    fn results_that_fit() {
+        let cases = [
+            (Number::Int(7), Operation::Divide, Number::Int(2), Number::Int(3)),
+            (Number::Int(-7), Operation::Divide, Number::Int(2), Number::Int(-3)),
+            (Number::Int(-7), Operation::Modulo, Number::Int(2), Number::Int(-1)),
+            (Number::Int(3), Operation::Power, Number::Int(4), Number::Int(81)),
+            (Number::Int(6), Operation::Xor, Number::Int(3), Number::Int(5)),
+            (Number::Int(i32::MAX), Operation::Add, Number::Int64(1), Number::Int64(i32::MAX as i64 + 1)),
+            (Number::Int64(i64::MAX), Operation::Multiply, Number::Int128(2), Number::Int128(i64::MAX as i128 * 2)),
+            (Number::Int128(i128::MAX), Operation::Add, big(1), Number::BigInt(BigInt::from(i128::MAX) + 1)),
+            (big(2), Operation::Power, big(100), Number::BigInt(BigInt::from(2).pow(100u32))),
+            (Number::Int(1), Operation::Divide, Number::Double(4.0), Number::Double(0.25)),
+            (decimal("0.1"), Operation::Add, Number::Double(0.2), decimal("0.3")),
+            (decimal("2"), Operation::Power, Number::Int(-2), decimal("0.25"))
+        ];
+        for (left, operation, right, expected) in cases {
+            let shown = format!("{} {} {}", left, operation.symbol(), right);
+            let result = left.apply(operation, right).unwrap();
+            assert_eq!(result.typing(), expected.typing(), "{}", shown);
+            assert_eq!(result.compare(expected), Some(Ordering::Equal), "{}", shown);
+        }
+    }
+
+    #[test]
+    #[cfg(not(feature = "wrapping-arithmetic"))]
+    fn overflowing_a_type_raises() {
+        let cases = [
+            (Number::Short(i16::MAX), Operation::Add, Number::Short(1)),
+            (Number::Int(i32::MAX), Operation::Multiply, Number::Int(2)),
+            (Number::Int(i32::MIN), Operation::Divide, Number::Int(-1)),
+            (Number::Int(2), Operation::Power, Number::Int(31)),
+            (Number::Arch(isize::MIN), Operation::Subtract, Number::Arch(1)),
+            (Number::Int64(i64::MAX), Operation::Add, Number::Int(1)),
+            (Number::Int128(i128::MIN), Operation::Subtract, Number::Short(1)),
+            (big(2), Operation::Power, Number::Int128(u32::MAX as i128 + 1))
+        ];
+        for (left, operation, right) in cases {
+            let shown = format!("{} {} {}", left, operation.symbol(), right);
+            assert_eq!(error(left.apply(operation, right)), "OverflowException", "{}", shown);
+        }
+        assert_eq!(error(binary(Operation::Add, Value::Int(i32::MAX), Value::Int(1))), "OverflowException");
+        assert_eq!(error(Number::Int(70000).cast(LeBlancType::Short)), "OverflowException");
+    }
+
+    #[test]
+    #[cfg(feature = "wrapping-arithmetic")]
+    fn overflowing_a_type_wraps() {
+        assert_eq!(Number::Int(i32::MAX).apply(Operation::Add, Number::Int(1)).unwrap(), Number::Int(i32::MIN));
+        assert_eq!(Number::Short(i16::MIN).apply(Operation::Divide, Number::Short(-1)).unwrap(), Number::Short(i16::MIN));
+    }
+
+    #[test]
+    fn shifts_keep_the_type_of_the_value() {
+        let cases = [
+            (Number::Short(1), Operation::LShift, Number::Int64(15), Number::Short(i16::MIN)),
+            (Number::Int(-16), Operation::RShift, Number::Short(2), Number::Int(-4)),
+            (Number::Int64(1), Operation::LShift, big(40), Number::Int64(1 << 40)),
+            (big(1), Operation::LShift, Number::Int(100), Number::BigInt(BigInt::one() << 100u32))
+        ];
+        for (value, operation, by, expected) in cases {
+            assert_eq!(value.apply(operation, by).unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn negative_shift_counts_raise() {
+        for value in ones().into_iter().take(6) {
+            for operation in [Operation::LShift, Operation::RShift] {
+                assert_eq!(error(value.clone().apply(operation, Number::Int(-1))), "ArithmeticException", "{} {} -1", value.typing(), operation.symbol());
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(not(feature = "wrapping-arithmetic"))]
+    fn shifting_past_the_width_raises() {
+        assert_eq!(error(Number::Int(1).apply(Operation::LShift, Number::Int(32))), "OverflowException");
+        assert_eq!(error(Number::Short(1).apply(Operation::RShift, Number::Int(16))), "OverflowException");
+        assert_eq!(error(big(1).apply(Operation::LShift, Number::Int128(u32::MAX as i128 + 1))), "OverflowException");
+    }
+
+    #[test]
+    fn only_integers_shift() {
+        assert_eq!(error(Number::Double(1.0).apply(Operation::LShift, Number::Int(1))), "TypeException");
+        assert_eq!(error(Number::Int(1).apply(Operation::RShift, decimal("1"))), "TypeException");
+    }
+
+    #[test]
+    fn negate() {
+        let cases = [
+            (Value::Int(5), Number::Int(-5)),
+            (Value::Int64(i64::MAX), Number::Int64(-i64::MAX)),
+            (Value::Double(0.5), Number::Double(-0.5)),
+            (Value::Object(Number::Short(-3).into_object()), Number::Short(3)),
+            (Value::Object(big(i128::MIN).into_object()), Number::BigInt(-BigInt::from(i128::MIN))),
+            (Value::Object(decimal("1.50").into_object()), decimal("-1.50"))
+        ];
+        for (value, expected) in cases {
+            let negated = super::negate(value).unwrap();
+            assert_eq!(Number::of_value(&negated), Some(expected));
+        }
+    }
+
+    #[test]
+    #[cfg(not(feature = "wrapping-arithmetic"))]
+    fn negating_the_smallest_integer_raises() {
+        assert_eq!(error(super::negate(Value::Int(i32::MIN))), "OverflowException");
+        assert_eq!(error(super::negate(Value::Object(Number::Short(i16::MIN).into_object()))), "OverflowException");
+        assert_eq!(error(super::negate(Value::Object(Number::Int128(i128::MIN).into_object()))), "OverflowException");
+    }
+
+    #[test]
+    fn only_numbers_negate() {
+        assert_eq!(error(super::negate(Value::Boolean(true))), "TypeException");
+        assert_eq!(error(super::negate(string("a"))), "TypeException");
+    }
+
+    #[test]
+    fn error_paths() {
+        let cases = [
+            (Number::Int(1), Operation::Divide, Number::Int(0), "DivideByZeroException"),
+            (Number::Int64(1), Operation::Modulo, Number::Int64(0), "DivideByZeroException"),
+            (big(1), Operation::Divide, big(0), "DivideByZeroException"),
+            (Number::Double(1.0), Operation::Modulo, Number::Double(0.0), "DivideByZeroException"),
+            (decimal("1"), Operation::Divide, decimal("0.0"), "DivideByZeroException"),
+            (decimal("0"), Operation::Power, Number::Int(-1), "DivideByZeroException"),
+            (Number::Int(2), Operation::Power, Number::Int(-1), "ArithmeticException"),
+            (big(2), Operation::Power, big(-1), "ArithmeticException"),
+            (decimal("2"), Operation::Power, decimal("0.5"), "ArithmeticException"),
+            (Number::Double(f64::INFINITY), Operation::Add, decimal("1"), "ArithmeticException"),
+            (Number::Double(1.0), Operation::And, Number::Double(1.0), "TypeException"),
+            (decimal("1"), Operation::Xor, decimal("1"), "TypeException")
+        ];
+        for (left, operation, right, expected) in cases {
+            let shown = format!("{} {} {}", left, operation.symbol(), right);
+            assert_eq!(error(left.apply(operation, right)), expected, "{}", shown);
+        }
+
+        assert_eq!(error(binary(Operation::Add, Value::Boolean(true), Value::Boolean(false))), "TypeException");
+        assert_eq!(error(binary(Operation::Multiply, string("a"), Value::Int(2))), "TypeException");
+        assert_eq!(error(binary(Operation::Add, Value::Int(1), Value::Null)), "TypeException");
+        assert_eq!(error(inverse(Value::Double(1.0))), "TypeException");
+        assert_eq!(error(Number::Int(1).cast(LeBlancType::String)), "TypeException");
+    }
+}
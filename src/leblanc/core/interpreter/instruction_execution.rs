@@ -3,48 +3,80 @@
 
 
 
-use alloc::rc::Rc;
-use std::cell::{BorrowError, Ref, RefCell};
 use crate::leblanc::rustblanc::strawberry::Strawberry;
-use std::sync::{Arc, Mutex};
-use arrayvec::ArrayVec;
+use std::cmp::Ordering;
+use std::sync::Arc;
+use crate::leblanc::core::interpreter::arithmetic;
+use crate::leblanc::core::interpreter::arithmetic::{cast_error, unsupported, Number, Operation};
+use crate::leblanc::core::interpreter::value::Value;
+use crate::leblanc::core::interpreter::vm_stack::Stack;
+use fxhash::FxHashMap;
 use crate::leblanc::core::internal::internal_range_generator::LeblancInternalRangeGenerator;
 
 use crate::leblanc::core::interpreter::instructions::{Instruction, InstructionBase};
 use crate::leblanc::core::interpreter::instructions::InstructionBase::{Comparator_Else, Comparator_ElseIf, Comparator_If};
 use crate::leblanc::core::interpreter::leblanc_runner::get_globals;
-use crate::leblanc::core::leblanc_object::{Callable, LeBlancObject, QuickUnwrap, RustDataCast};
+use crate::leblanc::core::leblanc_object::{Callable, LeBlancObject, LeBlancObjectData, QuickUnwrap, RustDataCast, Stringify};
+use crate::leblanc::core::extension::{attach_properties, has_properties};
 use crate::leblanc::core::leblanc_handle::LeblancHandle;
+use crate::leblanc::core::leblanc_context::VariableContext;
 
 use crate::leblanc::core::method_tag::MethodTag;
-use crate::leblanc::core::native_types::attributes::can_add_self;
+use crate::leblanc::core::internal::methods::builtins::builtin_execute::execute_block;
 use crate::leblanc::core::native_types::base_type::ToLeblanc;
+use crate::leblanc::core::native_types::block_type::NativeBlock;
 use crate::leblanc::core::native_types::derived::DerivedType;
+use crate::leblanc::core::native_types::derived::dict_type::LeblancDict;
+use crate::leblanc::core::native_types::derived::generator_type::leblanc_object_generator;
 use crate::leblanc::core::native_types::derived::iterator_type::LeblancIterator;
 use crate::leblanc::core::native_types::derived::list_type::LeblancList;
+use crate::leblanc::core::native_types::derived::set_type::LeblancSet;
+use crate::leblanc::core::native_types::derived::tuple_type::LeblancTuple;
+use crate::leblanc::core::native_types::enum_type::LeblancEnum;
 use crate::leblanc::core::native_types::error_type::LeblancError;
-use crate::leblanc::core::native_types::int_type::leblanc_object_int;
 
 
 use crate::{LeBlancType};
-use crate::leblanc::core::native_types::double_type::leblanc_object_double;
-use crate::leblanc::core::native_types::float_type::leblanc_object_float;
+use crate::leblanc::rustblanc::copystring::CopyString;
 use crate::leblanc::core::native_types::group_type::{leblanc_object_group, LeblancGroup};
 
 
-pub fn execute_instruction(instruct: InstructionBase) -> fn(&mut LeblancHandle, &Instruction, &mut ArrayVec<Arc<Strawberry<LeBlancObject>>, 80>) -> Result<(), Arc<Strawberry<LeBlancObject>>> {
+/// Runs one instruction on the stack of the call it belongs to, failing with the error it raises.
+pub type InstructionHandler = fn(&mut LeblancHandle, &Instruction, &mut Stack) -> Result<(), Arc<Strawberry<LeBlancObject>>>;
+
+pub fn execute_instruction(instruct: InstructionBase) -> InstructionHandler {
     match instruct {
         InstructionBase::InstructionMarker => _INSTRUCT_MARKER_,
         InstructionBase::BinaryAdd => _INSTRUCT_BINARY_ADD_,
-        InstructionBase::BinarySubtract => _INSTRUCT_BINARY_SUBTRACT_,
-        InstructionBase::BinaryModulo => _INSTRUCT_BINARY_MODULO_,
-        InstructionBase::BinaryAnd => _INSTRUCT_BINARY_AND_,
-        InstructionBase::BinaryOr => _INSTRUCT_BINARY_OR_,
+        InstructionBase::BinarySubtract | InstructionBase::BinaryMultiply | InstructionBase::BinaryDivide
+        | InstructionBase::BinaryPower | InstructionBase::BinaryModulo | InstructionBase::BinaryLShift
+        | InstructionBase::BinaryRShift | InstructionBase::BinaryAnd | InstructionBase::BinaryOr
+        | InstructionBase::BinaryXor => _INSTRUCT_BINARY_ARITHMETIC_,
+        InstructionBase::BinaryNot => _INSTRUCT_BINARY_NOT_,
+        InstructionBase::BinaryInverse => _INSTRUCT_BINARY_INVERSE_,
+        InstructionBase::BinaryNegate => _INSTRUCT_BINARY_NEGATE_,
+        InstructionBase::Cast => _INSTRUCT_CAST_,
         InstructionBase::InPlaceAdd => _INSTRUCT_INPLACE_ADD_,
         InstructionBase::LoadLocal => _INSTRUCT_LOAD_LOCAL_,
         InstructionBase::LoadConstant => _INSTRUCT_LOAD_CONSTANT_,
         InstructionBase::LoadFunction => _INSTRUCT_LOAD_FUNCTION_,
         InstructionBase::StoreLocal => _INSTRUCT_STORE_LOCAL_,
+        InstructionBase::PopTop => _INSTRUCT_POP_TOP_,
+        InstructionBase::Raise => _INSTRUCT_RAISE_,
+        InstructionBase::MakeException => _INSTRUCT_MAKE_EXCEPTION_,
+        InstructionBase::MakeInstance => _INSTRUCT_MAKE_INSTANCE_,
+        InstructionBase::ExtendException => _INSTRUCT_EXTEND_EXCEPTION_,
+        InstructionBase::MakeVariant => _INSTRUCT_MAKE_VARIANT_,
+        InstructionBase::MapMatch => _INSTRUCT_MAP_MATCH_,
+        InstructionBase::JumpTable => _INSTRUCT_JUMP_TABLE_,
+        InstructionBase::MakeBlock => _INSTRUCT_MAKE_BLOCK_,
+        InstructionBase::MakeUpvalue => _INSTRUCT_MAKE_UPVALUE_,
+        InstructionBase::LoadUpvalue => _INSTRUCT_LOAD_UPVALUE_,
+        InstructionBase::StoreUpvalue => _INSTRUCT_STORE_UPVALUE_,
+        InstructionBase::MakeClosure => _INSTRUCT_MAKE_CLOSURE_,
+        InstructionBase::MakeGenerator => _INSTRUCT_MAKE_GENERATOR_,
+        InstructionBase::AttributeAccess => _INSTRUCT_ATTRIBUTE_ACCESS_,
+        InstructionBase::AttributeStore => _INSTRUCT_ATTRIBUTE_STORE_,
         InstructionBase::CallFunction => _CALL_FUNCTION_,
         InstructionBase::CallClassMethod => _INSTRUCT_CALL_CLASS_METHOD_,
         InstructionBase::IteratorSetup(_) => _INSTRUCT_CREATE_RANGE_,
@@ -55,26 +87,34 @@ pub fn execute_instruction(instruct: InstructionBase) -> fn(&mut LeblancHandle,
         Comparator_ElseIf => _INSTRUCT_COMPARATOR_,
         Comparator_Else => _INSTRUCT_COMPARATOR_,
         InstructionBase::ListSetup => _INSTRUCT_LIST_SETUP_,
+        InstructionBase::DictSetup => _INSTRUCT_DICT_SETUP_,
+        InstructionBase::TupleSetup => _INSTRUCT_TUPLE_SETUP_,
+        InstructionBase::SetSetup => _INSTRUCT_SET_SETUP_,
+        InstructionBase::Unpack => _INSTRUCT_UNPACK_,
+        InstructionBase::CheckTrait => _INSTRUCT_CHECK_TRAIT_,
         InstructionBase::ElementAccess => _INSTRUCT_ELEMENT_ACCESS_,
         InstructionBase::ElementStore => _INSTRUCT_ELEMENT_STORE_,
         InstructionBase::Group => _INSTRUCT_GROUP_,
+        InstructionBase::LoadLocal2 => _INSTRUCT_LOAD_LOCAL2_,
+        InstructionBase::IncrementLocal => _INSTRUCT_INCREMENT_LOCAL_,
+        InstructionBase::CompareLocalConstJump => _INSTRUCT_COMPARE_LOCAL_CONST_JUMP_,
         _ => _INSTRUCT_BASE_
     }
 }
 
 
-fn deprecated_safe_stack_pop(stack: &mut ArrayVec<Arc<Strawberry<LeBlancObject>>, 80>, mut error: bool) -> Arc<Strawberry<LeBlancObject>> {
-    match stack.pop() {
-        None => {
-            error = true;
-            LeBlancObject::unsafe_error()
-        }
-        Some(result) => result
-    }
+fn deprecated_safe_stack_pop(stack: &mut Stack, _error: bool) -> Arc<Strawberry<LeBlancObject>> {
+    stack.pop().unwrap_or_else(LeBlancObject::unsafe_error)
 }
 
-fn safe_stack_pop(stack: &mut ArrayVec<Arc<Strawberry<LeBlancObject>>, 80>) -> Result<Arc<Strawberry<LeBlancObject>>, Arc<Strawberry<LeBlancObject>>> {
-    match stack.pop() {
+fn safe_stack_pop(stack: &mut Stack) -> Result<Arc<Strawberry<LeBlancObject>>, Arc<Strawberry<LeBlancObject>>> {
+    safe_stack_pop_value(stack).map(Value::into_object)
+}
+
+/// Pops the top value without boxing it, for instructions with a fast path for primitives.
+#[inline(always)]
+fn safe_stack_pop_value(stack: &mut Stack) -> Result<Value, Arc<Strawberry<LeBlancObject>>> {
+    match stack.pop_value() {
         None => {
             println!("Hit stack error");
             Err(LeblancError::new("UnknownStackException".to_string(), "Internal stack pop returned a none value".to_string(), vec![]).create_mutex())
@@ -83,17 +123,24 @@ fn safe_stack_pop(stack: &mut ArrayVec<Arc<Strawberry<LeBlancObject>>, 80>) -> R
     }
 }
 
-fn _INSTRUCT_BASE_(_handle: &mut LeblancHandle, _arg: &Instruction, _stack: &mut ArrayVec<Arc<Strawberry<LeBlancObject>>, 80>) -> Result<(), Arc<Strawberry<LeBlancObject>>> {
-    println!("I don't exist :)");
-    Err(LeblancError::new("Instruction Doesn't Exist".to_string(), "".to_string(), vec![]).create_mutex())
+/// Binary instructions lock both operands at once, so an object on both sides (`a + a`) is copied first.
+fn unaliased(first: &Arc<Strawberry<LeBlancObject>>, second: Arc<Strawberry<LeBlancObject>>) -> Arc<Strawberry<LeBlancObject>> {
+    match Arc::ptr_eq(first, &second) {
+        true => second.lock().clone().to_mutex(),
+        false => second
+    }
 }
 
-fn _INSTRUCT_MARKER_(_handle: &mut LeblancHandle, _arg: &Instruction, stack: &mut ArrayVec<Arc<Strawberry<LeBlancObject>>, 80>) -> Result<(), Arc<Strawberry<LeBlancObject>>> {
+fn _INSTRUCT_BASE_(_handle: &mut LeblancHandle, arg: &Instruction, _stack: &mut Stack) -> Result<(), Arc<Strawberry<LeBlancObject>>> {
+    Err(LeblancError::new("InstructionNotFoundException".to_string(), format!("No executor for instruction {}", arg.instruct), vec![]).create_mutex())
+}
+
+fn _INSTRUCT_MARKER_(_handle: &mut LeblancHandle, _arg: &Instruction, stack: &mut Stack) -> Result<(), Arc<Strawberry<LeBlancObject>>> {
     stack.push(LeBlancObject::unsafe_marker());
     Ok(())
 }
 
-fn _INSTRUCT_INPLACE_ADD_(_handle: &mut LeblancHandle, _arg: &Instruction, stack: &mut ArrayVec<Arc<Strawberry<LeBlancObject>>, 80>) -> Result<(), Arc<Strawberry<LeBlancObject>>> {
+fn _INSTRUCT_INPLACE_ADD_(_handle: &mut LeblancHandle, _arg: &Instruction, stack: &mut Stack) -> Result<(), Arc<Strawberry<LeBlancObject>>> {
     let error = false;
     let target: Arc<Strawberry<LeBlancObject>> = deprecated_safe_stack_pop(stack, error);
     if error { return Err(target); }
@@ -101,7 +148,7 @@ fn _INSTRUCT_INPLACE_ADD_(_handle: &mut LeblancHandle, _arg: &Instruction, stack
     if error { return Err(arg1); }
 
 
-    let result = target.lock().methods.iter().cloned().find(|m| m.has_tag(MethodTag::InPlaceAddition));
+    let result = target.lock().methods.iter().find(|&m| m.has_tag(MethodTag::InPlaceAddition)).cloned();
         //.filter(|m| m.matches("_".to_string(), vec![tos2.lock().to_leblanc_arg(0)]))
         //.next().unwrap_or(Method::error()).run(tos1.clone(), &mut [tos2.clone()]);
 
@@ -111,120 +158,121 @@ fn _INSTRUCT_INPLACE_ADD_(_handle: &mut LeblancHandle, _arg: &Instruction, stack
     Ok(())
 }
 
-fn _INSTRUCT_BINARY_ADD_(_handle: &mut LeblancHandle, _arg: &Instruction, stack: &mut ArrayVec<Arc<Strawberry<LeBlancObject>>, 80>) -> Result<(), Arc<Strawberry<LeBlancObject>>> {
-    let targeter =  match safe_stack_pop(stack) { Ok(res) => res, Err(err) => return Err(err) };
-    let target = match safe_stack_pop(stack) { Ok(res) => res, Err(err) => return Err(err) };
-
-    let ntargeter = targeter.lock();
-    let ntarget = target.lock();
+fn _INSTRUCT_BINARY_ADD_(_handle: &mut LeblancHandle, _arg: &Instruction, stack: &mut Stack) -> Result<(), Arc<Strawberry<LeBlancObject>>> {
+    let targeter = safe_stack_pop_value(stack)?.unboxed();
+    let target = safe_stack_pop_value(stack)?.unboxed();
+    stack.push_value(add_values(target, targeter)?);
+    Ok(())
+}
 
+/// `target + targeter` on unboxed values, as `BinaryAdd` and `IncrementLocal` add them.
+#[inline(always)]
+fn add_values(target: Value, targeter: Value) -> Result<Value, Arc<Strawberry<LeBlancObject>>> {
+    if let (Value::Int(target), Value::Int(targeter)) = (&target, &targeter) {
+        if let Some(sum) = target.checked_add(*targeter) {
+            return Ok(Value::Int(sum));
+        }
+    }
+    match (Number::of_value(&target), Number::of_value(&targeter)) {
+        (Some(target), Some(targeter)) => Ok(target.apply(Operation::Add, targeter)?.into_value()),
+        _ => Ok(Value::Object(add(target.into_object(), targeter.into_object())?))
+    }
+}
 
-    if can_add_self(&ntargeter.typing) && can_add_self(&ntarget.typing) {
-        stack.push(leblanc_object_int((ntarget.data.as_i128() + ntargeter.data.as_i128()) as i32).to_mutex());
-        return Ok(());
+/// `target + targeter`, for the `+` operator and anything else adding values the same way.
+/// Numbers are added through the numeric tower, anything else by its addition method.
+pub fn add(target: Arc<Strawberry<LeBlancObject>>, targeter: Arc<Strawberry<LeBlancObject>>) -> Result<Arc<Strawberry<LeBlancObject>>, Arc<Strawberry<LeBlancObject>>> {
+    let target_number = Number::of(&target.lock().data);
+    let targeter_number = Number::of(&targeter.lock().data);
+    if let (Some(target), Some(targeter)) = (target_number, targeter_number) {
+        return target.apply(Operation::Add, targeter).map(Number::into_object);
     }
+    let target = unaliased(&targeter, target);
+
+    let ntargeter = targeter.lock();
+    let ntarget = target.lock();
 
     let arguments = vec![ntargeter.to_leblanc_arg(0)];
-    let matched_method = ntarget.methods.iter().filter(|m| {
-        m.matches("_".to_string(), &arguments)
-    }).next().cloned();
+    let matched_method = ntarget.methods.iter().find(|m| {
+        m.has_tag(MethodTag::Addition) && m.matches("_".to_string(), &arguments)
+    }).cloned();
     match matched_method {
-        None => {
-            let arguments = vec![ntarget.to_leblanc_arg(0)];
-            let matched_method = ntargeter.methods.iter().filter(|m| {
-                m.matches("_".to_string(), &arguments)
-            }).next().cloned();
-            if matched_method.is_none() {
-                return Err(LeBlancObject::unsafe_error());
-            }
+        // Anything can be put in front of a string, as a string can be in front of anything.
+        None if ntargeter.typing == LeBlancType::String => {
+            let string = ntargeter.data.to_string();
             drop(ntargeter);
             drop(ntarget);
-            stack.push(matched_method.unwrap().run(targeter, &mut [target]));
+            let mut target = target;
+            let shown = target.call_name("to_string")?.to_string();
+            Ok((shown + &string).create_mutex())
         }
+        None => Err(unsupported(Operation::Add, ntarget.typing, ntargeter.typing)),
         Some(mut method) => {
             drop(ntargeter);
             drop(ntarget);
-            stack.push(method.run(target, &mut [targeter]));
+            Ok(method.run(target, &mut [targeter]))
         }
     }
+}
 
+/// Every binary arithmetic and bitwise instruction but `BinaryAdd`, see `arithmetic::binary`.
+fn _INSTRUCT_BINARY_ARITHMETIC_(_handle: &mut LeblancHandle, arg: &Instruction, stack: &mut Stack) -> Result<(), Arc<Strawberry<LeBlancObject>>> {
+    let targeter = safe_stack_pop_value(stack)?.unboxed();
+    let target = safe_stack_pop_value(stack)?.unboxed();
+    let operation = Operation::of(arg.instruct).unwrap();
+    stack.push_value(arithmetic::binary(operation, target, targeter)?);
     Ok(())
 }
 
-fn _INSTRUCT_BINARY_SUBTRACT_(_handle: &mut LeblancHandle, _arg: &Instruction, stack: &mut ArrayVec<Arc<Strawberry<LeBlancObject>>, 80>) -> Result<(), Arc<Strawberry<LeBlancObject>>> {
-    let target = match safe_stack_pop(stack) { Ok(res) => res, Err(err) => return Err(err) };
-    let targeter =  match safe_stack_pop(stack) { Ok(res) => res, Err(err) => return Err(err) };
-
-    let ntargeter = targeter.lock();
-    let ntarget = target.lock();
-
-    if can_add_self(&ntargeter.typing) && can_add_self(&ntarget.typing) {
-        stack.push(leblanc_object_int((ntarget.data.as_i128() - ntargeter.data.as_i128()) as i32).to_mutex());
-        return Ok(());
-    } else {
-        match ntarget.typing {
-            LeBlancType::Float | LeBlancType::Double => {
-                let double1: &f64 = ntarget.data.ref_data().unwrap();
-                let double2: &f64 = ntargeter.data.ref_data().unwrap();
-                if ntarget.typing == LeBlancType::Float {
-                    stack.push(leblanc_object_float((double1 - double2) as f32).to_mutex());
-                } else {
-                    stack.push(leblanc_object_double(double1 - double2).to_mutex());
-                }
-                return Ok(());
-            }
-            _ => {}
-        }
-    }
+fn _INSTRUCT_BINARY_INVERSE_(_handle: &mut LeblancHandle, _arg: &Instruction, stack: &mut Stack) -> Result<(), Arc<Strawberry<LeBlancObject>>> {
+    let target = safe_stack_pop_value(stack)?.unboxed();
+    stack.push_value(arithmetic::inverse(target)?);
     Ok(())
 }
 
-fn _INSTRUCT_BINARY_MODULO_(_handle: &mut LeblancHandle, _arg: &Instruction, stack: &mut ArrayVec<Arc<Strawberry<LeBlancObject>>, 80>) -> Result<(), Arc<Strawberry<LeBlancObject>>> {
-    let target = match safe_stack_pop(stack) { Ok(res) => res, Err(err) => return Err(err) };
-    let targeter =  match safe_stack_pop(stack) { Ok(res) => res, Err(err) => return Err(err) };
-
-    let ntargeter = targeter.lock();
-    let ntarget = target.lock();
-
-    if can_add_self(&ntargeter.typing) && can_add_self(&ntarget.typing) {
-        stack.push(leblanc_object_int((ntarget.data.as_i128() % ntargeter.data.as_i128()) as i32).to_mutex());
-        return Ok(());
-    } else {
-        match ntarget.typing {
-            LeBlancType::Float | LeBlancType::Double => {
-                let double1: &f64 = ntarget.data.ref_data().unwrap();
-                let double2: &f64 = ntargeter.data.ref_data().unwrap();
-                if ntarget.typing == LeBlancType::Float {
-                    stack.push(leblanc_object_float((double1 % double2) as f32).to_mutex());
-                } else {
-                    stack.push(leblanc_object_double(double1 % double2).to_mutex());
-                }
-                return Ok(());
-            }
-            _ => {}
-        }
-    }
+fn _INSTRUCT_BINARY_NEGATE_(_handle: &mut LeblancHandle, _arg: &Instruction, stack: &mut Stack) -> Result<(), Arc<Strawberry<LeBlancObject>>> {
+    let target = safe_stack_pop_value(stack)?.unboxed();
+    stack.push_value(arithmetic::negate(target)?);
     Ok(())
 }
 
-fn _INSTRUCT_BINARY_AND_(_handle: &mut LeblancHandle, _arg: &Instruction, stack: &mut ArrayVec<Arc<Strawberry<LeBlancObject>>, 80>) -> Result<(), Arc<Strawberry<LeBlancObject>>> {
-    let target = match safe_stack_pop(stack) { Ok(res) => res, Err(err) => return Err(err) };
-    let targeter =  match safe_stack_pop(stack) { Ok(res) => res, Err(err) => return Err(err) };
-
-    stack.push((*target.lock().data.ref_data().unwrap() && *targeter.lock().data.ref_data().unwrap()).create_mutex());
+/// `value as typing`. Numbers are converted through the numeric tower, chars to and from their
+/// code point, and anything can be made a string.
+fn _INSTRUCT_CAST_(_handle: &mut LeblancHandle, arg: &Instruction, stack: &mut Stack) -> Result<(), Arc<Strawberry<LeBlancObject>>> {
+    let typing = LeBlancType::from_enum_id(arg.arg);
+    let value = safe_stack_pop_value(stack)?.unboxed();
+    let from = value.typing();
+    if from == typing || matches!(typing, LeBlancType::Flex | LeBlancType::Dynamic) {
+        stack.push_value(value);
+        return Ok(());
+    }
+    let number = match value {
+        Value::Char(ch) => Some(Number::Int(ch as i32)),
+        ref value => Number::of_value(value)
+    };
+    let result = match (typing, number) {
+        (LeBlancType::String, _) => Value::Object(value.to_string().create_mutex()),
+        (LeBlancType::Char, Some(number)) => {
+            let code = number.cast(LeBlancType::Int)?;
+            let ch = code.integer().and_then(|code| u32::try_from(code).ok()).and_then(char::from_u32);
+            Value::Char(ch.ok_or_else(|| LeblancError::new("TypeException".to_string(), format!("{} is not a valid char", code), vec![]).create_mutex())?)
+        }
+        (_, Some(number)) => number.cast(typing)?.into_value(),
+        (_, None) => return Err(cast_error(from, typing))
+    };
+    stack.push_value(result);
     Ok(())
 }
 
-fn _INSTRUCT_BINARY_OR_(_handle: &mut LeblancHandle, _arg: &Instruction, stack: &mut ArrayVec<Arc<Strawberry<LeBlancObject>>, 80>) -> Result<(), Arc<Strawberry<LeBlancObject>>> {
-    let target = match safe_stack_pop(stack) { Ok(res) => res, Err(err) => return Err(err) };
-    let targeter =  match safe_stack_pop(stack) { Ok(res) => res, Err(err) => return Err(err) };
-
-    stack.push((*target.lock().data.ref_data().unwrap() || *targeter.lock().data.ref_data().unwrap()).create_mutex());
+fn _INSTRUCT_BINARY_NOT_(_handle: &mut LeblancHandle, _arg: &Instruction, stack: &mut Stack) -> Result<(), Arc<Strawberry<LeBlancObject>>> {
+    let target = safe_stack_pop_value(stack)?;
+    let truth = truthy(target)?;
+    stack.push_value(Value::Boolean(!truth));
     Ok(())
 }
 
 #[inline(always)]
-fn _INSTRUCT_LOAD_FUNCTION_(_handle: &mut LeblancHandle, arg: &Instruction, stack: &mut ArrayVec<Arc<Strawberry<LeBlancObject>>, 80>) -> Result<(), Arc<Strawberry<LeBlancObject>>> {
+fn _INSTRUCT_LOAD_FUNCTION_(_handle: &mut LeblancHandle, arg: &Instruction, stack: &mut Stack) -> Result<(), Arc<Strawberry<LeBlancObject>>> {
     let result= unsafe { get_globals() }[arg.arg as usize].clone();
     /*if result.is_none() { LeBlancObject::error().to_mutex(); }
     let result = result.unwrap().force_unwrap().to_mutex();*/
@@ -234,12 +282,19 @@ fn _INSTRUCT_LOAD_FUNCTION_(_handle: &mut LeblancHandle, arg: &Instruction, stac
 }
 
 #[inline(always)]
-fn _INSTRUCT_LOAD_CONSTANT_(handle: &mut LeblancHandle, arg: &Instruction, stack: &mut ArrayVec<Arc<Strawberry<LeBlancObject>>, 80>) -> Result<(), Arc<Strawberry<LeBlancObject>>> {
+fn _INSTRUCT_LOAD_CONSTANT_(handle: &mut LeblancHandle, arg: &Instruction, stack: &mut Stack) -> Result<(), Arc<Strawberry<LeBlancObject>>> {
     let result= handle.constants.get(arg.arg as usize);
     match result {
         None => Err(LeBlancObject::error().to_mutex()),
+        // Extension properties are written on the object itself, so each load gets a copy of its
+        // own and a call can't leave them changed for the next one.
+        Some(Value::Object(constant)) if has_properties(constant.lock().typing) => {
+            let copy = constant.lock().clone();
+            stack.push(copy.to_mutex());
+            Ok(())
+        }
         Some(constant) => {
-            stack.push(constant.clone());
+            stack.push_value(constant.clone());
             Ok(())
         }
     }
@@ -247,79 +302,356 @@ fn _INSTRUCT_LOAD_CONSTANT_(handle: &mut LeblancHandle, arg: &Instruction, stack
 }
 
 #[inline(always)]
-fn _INSTRUCT_LOAD_LOCAL_(handle: &mut LeblancHandle, arg: &Instruction, stack: &mut ArrayVec<Arc<Strawberry<LeBlancObject>>, 80>) -> Result<(), Arc<Strawberry<LeBlancObject>>> {
-    let result= handle.variables.get(arg.arg as usize);
-    match result {
+fn _INSTRUCT_LOAD_LOCAL_(handle: &mut LeblancHandle, arg: &Instruction, stack: &mut Stack) -> Result<(), Arc<Strawberry<LeBlancObject>>> {
+    stack.push_value(load_local(handle, arg.arg));
+    Ok(())
+}
+
+/// The local in `slot`, which is null until something is stored in it.
+#[inline(always)]
+fn load_local(handle: &mut LeblancHandle, slot: u16) -> Value {
+    match handle.variables.get(slot as usize) {
         None => {
             let null = LeBlancObject::null().to_mutex();
-            if arg.arg as usize >= handle.variables.len() {
-                handle.variables.push(null.clone());
-            } else {
-                handle.variables[arg.arg as usize] = null.clone();
-            }
-            stack.push(null);
+            handle.variables.resize_with(slot as usize, || Value::Object(LeBlancObject::null().to_mutex()));
+            handle.variables.push(Value::Object(null.clone()));
+            Value::Object(null)
         },
-        Some(res) => {
-            match res.underlying_pointer().typing.is_numeric() {
-                true => stack.push(res.clone()),
-                false => stack.push(res.clone())
-            }
-        }
+        Some(value) => value.clone()
     }
+}
+
+#[inline(always)]
+fn _INSTRUCT_STORE_LOCAL_(handle: &mut LeblancHandle, arg: &Instruction, stack: &mut Stack) -> Result<(), Arc<Strawberry<LeBlancObject>>> {
+    let result = safe_stack_pop_value(stack)?;
+    store_local(handle, arg.arg, result);
     Ok(())
 }
 
 #[inline(always)]
-fn _INSTRUCT_STORE_LOCAL_(handle: &mut LeblancHandle, arg: &Instruction, stack: &mut ArrayVec<Arc<Strawberry<LeBlancObject>>, 80>) -> Result<(), Arc<Strawberry<LeBlancObject>>> {
-    let result = match safe_stack_pop(stack) { Ok(res) => res, Err(err) => return Err(err) };
-    if arg.arg as usize >= handle.variables.len() {
-        handle.variables.push(result);
+fn store_local(handle: &mut LeblancHandle, slot: u16, value: Value) {
+    let value = value.into_variable();
+    if slot as usize >= handle.variables.len() {
+        handle.variables.resize_with(slot as usize, || Value::Object(LeBlancObject::null().to_mutex()));
+        handle.variables.push(value);
     } else {
-        handle.variables[arg.arg as usize] = result;
+        handle.variables[slot as usize] = value;
+    }
+}
+
+/// The instruction `offset` words after the one running, i.e. an operand of a superinstruction.
+#[inline(always)]
+fn operand(handle: &LeblancHandle, offset: u64) -> Instruction {
+    handle.instructions[(handle.current_instruct + offset) as usize]
+}
+
+/// `LoadLocal a; LoadLocal b`, pushing both locals. The second load is skipped over.
+fn _INSTRUCT_LOAD_LOCAL2_(handle: &mut LeblancHandle, arg: &Instruction, stack: &mut Stack) -> Result<(), Arc<Strawberry<LeBlancObject>>> {
+    let second = operand(handle, 1).arg;
+    stack.push_value(load_local(handle, arg.arg));
+    stack.push_value(load_local(handle, second));
+    handle.current_instruct += 1;
+    Ok(())
+}
+
+/// `LoadLocal x; LoadConstant c; BinaryAdd; StoreLocal x`, adding the constant to the local
+/// without going through the stack. The three instructions after it are skipped over.
+fn _INSTRUCT_INCREMENT_LOCAL_(handle: &mut LeblancHandle, arg: &Instruction, _stack: &mut Stack) -> Result<(), Arc<Strawberry<LeBlancObject>>> {
+    let constant = operand(handle, 1).arg;
+    let target = load_local(handle, arg.arg).unboxed();
+    let targeter = handle.constants[constant as usize].clone().unboxed();
+    let sum = add_values(target, targeter)?;
+    store_local(handle, arg.arg, sum);
+    handle.current_instruct += 3;
+    Ok(())
+}
+
+/// `LoadLocal x; LoadConstant c; Equality; <jump>`, comparing the local to the constant and jumping
+/// the way the `Comparator_If`, `Comparator_ElseIf` or `WhileLoop` after it would. The three
+/// instructions after it are skipped over when it doesn't jump.
+fn _INSTRUCT_COMPARE_LOCAL_CONST_JUMP_(handle: &mut LeblancHandle, arg: &Instruction, _stack: &mut Stack) -> Result<(), Arc<Strawberry<LeBlancObject>>> {
+    let constant = operand(handle, 1).arg;
+    let operator = operand(handle, 2).arg;
+    let jump = operand(handle, 3);
+    let tos1 = load_local(handle, arg.arg).unboxed();
+    let tos2 = handle.constants[constant as usize].clone().unboxed();
+    let truth = equality(operator, tos1, tos2)?;
+    let jumps = match jump.instruct {
+        InstructionBase::WhileLoop => truth,
+        _ => !truth
+    };
+    match jumps {
+        true => jump_to(handle, jump.arg),
+        false => handle.current_instruct += 3
     }
     Ok(())
 }
 
 #[inline(always)]
-fn _CALL_FUNCTION_(_handle: &mut LeblancHandle, arg: &Instruction, stack: &mut ArrayVec<Arc<Strawberry<LeBlancObject>>, 80>) -> Result<(), Arc<Strawberry<LeBlancObject>>> {
-    let func = match safe_stack_pop(stack) { Ok(res) => res, Err(err) => return Err(err) };
-    let mut arguments = match arg.arg {
-        0 => { Vec::new() }
-        1 => { vec![match safe_stack_pop(stack) { Ok(res) => res, Err(err) => return Err(err) }] }
-        _ => {
-            let mut vector = Vec::new();
-            for _ in 0..arg.arg as usize {
-                let tos = match safe_stack_pop(stack) { Ok(res) => res, Err(err) => return Err(err) };
-                vector.push(tos);
+fn _INSTRUCT_POP_TOP_(_handle: &mut LeblancHandle, _arg: &Instruction, stack: &mut Stack) -> Result<(), Arc<Strawberry<LeBlancObject>>> {
+    safe_stack_pop_value(stack)?;
+    Ok(())
+}
+
+/// Rethrows the exception on top of the stack, e.g. at the end of a `finally` that was entered by an error.
+fn _INSTRUCT_RAISE_(_handle: &mut LeblancHandle, _arg: &Instruction, stack: &mut Stack) -> Result<(), Arc<Strawberry<LeBlancObject>>> {
+    let error = safe_stack_pop(stack)?;
+    let typing = error.underlying_pointer().typing;
+    match typing {
+        LeBlancType::Exception => Err(error),
+        other => Err(LeblancError::new("TypeException".to_string(), format!("Cannot raise a value of type {}", other), vec![]).create_mutex())
+    }
+}
+
+/// Builds an error from its lineage (`Name:Parent:...`) and, with an arg of 1, a message below it.
+fn _INSTRUCT_MAKE_EXCEPTION_(_handle: &mut LeblancHandle, arg: &Instruction, stack: &mut Stack) -> Result<(), Arc<Strawberry<LeBlancObject>>> {
+    let lineage = safe_stack_pop(stack)?.lock().data.to_string();
+    let message = match arg.arg {
+        0 => String::new(),
+        _ => safe_stack_pop(stack)?.lock().data.to_string()
+    };
+    let mut family = lineage.split(':').map(str::to_string);
+    let name = family.next().unwrap();
+    stack.push(LeblancError::derived(name, family.collect(), message).create_mutex());
+    Ok(())
+}
+
+/// Creates an instance of the class whose prototype is the global at `arg`. Every field starts
+/// out null, ready for the field initializers and the constructor the caller runs next.
+fn _INSTRUCT_MAKE_INSTANCE_(_handle: &mut LeblancHandle, arg: &Instruction, stack: &mut Stack) -> Result<(), Arc<Strawberry<LeBlancObject>>> {
+    let prototype = unsafe { get_globals() }[arg.arg as usize].lock();
+    let members = prototype.members.lock().keys().map(|field| (field.clone(), LeBlancObject::null().to_mutex())).collect::<FxHashMap<String, Arc<Strawberry<LeBlancObject>>>>();
+    stack.push(LeBlancObject::new(
+        prototype.data.clone(),
+        prototype.typing,
+        prototype.methods.clone(),
+        Arc::new(Strawberry::new(members)),
+        VariableContext::empty()
+    ).to_mutex());
+    Ok(())
+}
+
+/// Gives the error on top of the stack the methods and fields of the exception class whose
+/// prototype is the global at `arg`. Methods the class declares replace the error's own.
+fn _INSTRUCT_EXTEND_EXCEPTION_(_handle: &mut LeblancHandle, arg: &Instruction, stack: &mut Stack) -> Result<(), Arc<Strawberry<LeBlancObject>>> {
+    let error = safe_stack_pop(stack)?;
+    let prototype = unsafe { get_globals() }[arg.arg as usize].lock();
+    {
+        let mut error = error.lock();
+        let mut methods = prototype.methods.as_ref().clone();
+        methods.extend(error.methods.iter().cloned());
+        error.methods = Arc::new(methods);
+        let members = prototype.members.lock().keys().map(|field| (field.clone(), LeBlancObject::null().to_mutex())).collect::<FxHashMap<String, Arc<Strawberry<LeBlancObject>>>>();
+        error.members = Arc::new(Strawberry::new(members));
+    }
+    stack.push(error);
+    Ok(())
+}
+
+/// Builds a value of the variant named by the constant on top of the stack (`Enum::Variant`),
+/// taking the `arg` values below it as its payload.
+fn _INSTRUCT_MAKE_VARIANT_(_handle: &mut LeblancHandle, arg: &Instruction, stack: &mut Stack) -> Result<(), Arc<Strawberry<LeBlancObject>>> {
+    let path = safe_stack_pop(stack)?.lock().data.to_string();
+    let mut payload = (0..arg.arg).map(|_| safe_stack_pop(stack)).collect::<Result<Vec<Arc<Strawberry<LeBlancObject>>>, Arc<Strawberry<LeBlancObject>>>>()?;
+    payload.reverse();
+    let (name, variant) = path.split_once("::").unwrap_or(("", &path));
+    stack.push(LeblancEnum::new(CopyString::new(name), CopyString::new(variant), payload).create_mutex());
+    Ok(())
+}
+
+/// Pops a value and pushes whether it is the variant named by the constant at `arg`. When it is,
+/// its payload is pushed first, underneath the result, so a matching arm can destructure it.
+fn _INSTRUCT_MAP_MATCH_(handle: &mut LeblancHandle, arg: &Instruction, stack: &mut Stack) -> Result<(), Arc<Strawberry<LeBlancObject>>> {
+    let value = safe_stack_pop(stack)?;
+    let path = handle.constants[arg.arg as usize].to_string();
+    let payload = {
+        let borrowed = value.lock();
+        let value: Option<&LeblancEnum> = borrowed.data.ref_data();
+        value.filter(|value| value.is(&path)).map(|value| value.payload.clone())
+    };
+    match payload {
+        Some(payload) => {
+            for field in payload {
+                stack.push(field);
             }
-            vector.reverse();
-            vector
+            stack.push(true.create_mutex());
         }
+        None => stack.push(false.create_mutex())
+    }
+    Ok(())
+}
+
+/// Pops a value and jumps to the arm the jump table at constant `arg` has for it.
+fn _INSTRUCT_JUMP_TABLE_(handle: &mut LeblancHandle, arg: &Instruction, stack: &mut Stack) -> Result<(), Arc<Strawberry<LeBlancObject>>> {
+    let value = safe_stack_pop(stack)?;
+    let target = handle.jump_tables[&arg.arg].target(&value.lock().data);
+    jump_to(handle, target);
+    Ok(())
+}
+
+/// Pushes a block of the instructions between this one and `arg`, then jumps over them.
+fn _INSTRUCT_MAKE_BLOCK_(handle: &mut LeblancHandle, arg: &Instruction, stack: &mut Stack) -> Result<(), Arc<Strawberry<LeBlancObject>>> {
+    let block = NativeBlock { frame: handle.frame, start: handle.current_instruct + 1, end: arg.arg as u64 };
+    stack.push(block.create_mutex());
+    jump_to(handle, arg.arg);
+    Ok(())
+}
+
+/// Pops a value into a new upvalue `arg`. Closures created before keep the upvalue they had.
+fn _INSTRUCT_MAKE_UPVALUE_(handle: &mut LeblancHandle, arg: &Instruction, stack: &mut Stack) -> Result<(), Arc<Strawberry<LeBlancObject>>> {
+    let upvalue = Arc::new(Strawberry::new(safe_stack_pop(stack)?));
+    let index = arg.arg as usize;
+    if index >= handle.upvalues.len() {
+        handle.upvalues.resize_with(index, || Arc::new(Strawberry::new(LeBlancObject::null().to_mutex())));
+        handle.upvalues.push(upvalue);
+    } else {
+        handle.upvalues[index] = upvalue;
+    }
+    Ok(())
+}
+
+fn _INSTRUCT_LOAD_UPVALUE_(handle: &mut LeblancHandle, arg: &Instruction, stack: &mut Stack) -> Result<(), Arc<Strawberry<LeBlancObject>>> {
+    match handle.upvalues.get(arg.arg as usize) {
+        Some(upvalue) => stack.push(upvalue.lock().clone()),
+        None => stack.push(LeBlancObject::null().to_mutex())
+    }
+    Ok(())
+}
+
+fn _INSTRUCT_STORE_UPVALUE_(handle: &mut LeblancHandle, arg: &Instruction, stack: &mut Stack) -> Result<(), Arc<Strawberry<LeBlancObject>>> {
+    let value = safe_stack_pop(stack)?;
+    match handle.upvalues.get(arg.arg as usize) {
+        Some(upvalue) => *upvalue.lock() = value,
+        None => return Err(LeblancError::new("StackException".to_string(), format!("Upvalue {} doesn't exist", arg.arg), vec![]).create_mutex())
+    }
+    Ok(())
+}
+
+/// Replaces the function on top of the stack with a closure over the upvalues of this call.
+fn _INSTRUCT_MAKE_CLOSURE_(handle: &mut LeblancHandle, _arg: &Instruction, stack: &mut Stack) -> Result<(), Arc<Strawberry<LeBlancObject>>> {
+    let mut closure = safe_stack_pop(stack)?.lock().clone();
+    if let LeBlancObjectData::Function(method) = &mut closure.data {
+        let mut inner = method.leblanc_handle.lock().clone();
+        inner.upvalues = handle.upvalues.clone();
+        method.leblanc_handle = Arc::new(Strawberry::new(inner));
+    }
+    stack.push(closure.to_mutex());
+    Ok(())
+}
+
+/// Starts a generator: pushes one over a copy of the running call, which carries on after the
+/// `Return` following this instruction once a value is asked for.
+fn _INSTRUCT_MAKE_GENERATOR_(handle: &mut LeblancHandle, _arg: &Instruction, stack: &mut Stack) -> Result<(), Arc<Strawberry<LeBlancObject>>> {
+    let mut frame = handle.clone();
+    frame.variables = handle.variables.clone();
+    frame.current_instruct = handle.current_instruct + 2;
+    stack.push(leblanc_object_generator(frame).to_mutex());
+    Ok(())
+}
+
+/// Replaces the object on top of the stack with its field named by the constant at `arg`. Every
+/// error also has a `message` field.
+fn _INSTRUCT_ATTRIBUTE_ACCESS_(handle: &mut LeblancHandle, arg: &Instruction, stack: &mut Stack) -> Result<(), Arc<Strawberry<LeBlancObject>>> {
+    let object = safe_stack_pop(stack)?;
+    let name = handle.constants[arg.arg as usize].to_string();
+    let mut field = object.lock().members.lock().get(&name).cloned();
+    if field.is_none() && attach_properties(&object, &name)? {
+        field = object.lock().members.lock().get(&name).cloned();
+    }
+    if field.is_none() && name == "message" {
+        let message = object.lock().data.ref_data().map(|error: &LeblancError| error.message().to_string());
+        field = message.map(|message| message.create_mutex());
+    }
+    match field {
+        Some(field) => {
+            stack.push(field);
+            Ok(())
+        }
+        None => Err(field_not_found(&object, &name))
+    }
+}
+
+/// Pops an object, then the value to store in its field named by the constant at `arg`. Only
+/// fields the object's class declares, properties an extension of its type adds, or the message
+/// of an error can be assigned.
+fn _INSTRUCT_ATTRIBUTE_STORE_(handle: &mut LeblancHandle, arg: &Instruction, stack: &mut Stack) -> Result<(), Arc<Strawberry<LeBlancObject>>> {
+    let object = safe_stack_pop(stack)?;
+    let value = safe_stack_pop(stack)?;
+    let name = handle.constants[arg.arg as usize].to_string();
+    let missing = !object.lock().members.lock().contains_key(&name);
+    if missing && name == "message" {
+        if let Some(error) = object.lock().data.mut_data() {
+            let error: &mut LeblancError = error;
+            error.set_message(value.lock().data.to_string());
+            return Ok(());
+        }
+    }
+    if missing {
+        attach_properties(&object, &name)?;
+    }
+    let members = object.lock().members.clone();
+    let mut members = members.lock();
+    match members.get_mut(&name) {
+        Some(field) => {
+            *field = value;
+            Ok(())
+        }
+        None => {
+            drop(members);
+            Err(field_not_found(&object, &name))
+        }
+    }
+}
+
+fn field_not_found(object: &Arc<Strawberry<LeBlancObject>>, name: &str) -> Arc<Strawberry<LeBlancObject>> {
+    let object = object.lock();
+    let owner = match object.typing {
+        LeBlancType::Class(_) => object.name_of(),
+        other => other.to_string()
     };
+    LeblancError::new("FieldNotFoundException".to_string(), format!("{} has no field named '{}'", owner, name), vec![]).create_mutex()
+}
+
+#[inline(always)]
+fn _CALL_FUNCTION_(handle: &mut LeblancHandle, arg: &Instruction, stack: &mut Stack) -> Result<(), Arc<Strawberry<LeBlancObject>>> {
+    let func = safe_stack_pop(stack)?;
+    let mut arguments = Vec::with_capacity(arg.arg as usize);
+    for _ in 0..arg.arg as usize {
+        arguments.push(safe_stack_pop_value(stack)?);
+    }
+    arguments.reverse();
 
     //let func = func.clone_if_locked();
-    let is_internal = func.underlying_pointer().data.get_inner_method().unwrap().is_internal_method();
-    let result = match is_internal {
+    let method = match func.underlying_pointer().data.get_inner_method() {
+        Some(method) => method,
+        None => return Err(not_callable(func.underlying_pointer().typing))
+    };
+    let result = match method.is_internal_method() {
+        true if method.has_tag(MethodTag::ExecuteBlock) => {
+            let arguments = arguments.into_iter().map(Value::into_object).collect::<Vec<Arc<Strawberry<LeBlancObject>>>>();
+            Value::Object(execute_block(handle, &arguments))
+        }
         true => {
-            let handle = func.underlying_pointer().data.get_inner_method().unwrap().handle;
-            (handle)(func, &mut arguments)
+            let native = method.handle;
+            let mut arguments = arguments.into_iter().map(Value::into_object).collect::<Vec<Arc<Strawberry<LeBlancObject>>>>();
+            Value::Object((native)(func, &mut arguments))
         },
-        false => //.execute(&mut arguments)//.execute(&mut arguments)
-            func.underlying_pointer().data.get_inner_method().unwrap().leblanc_handle.clone_if_locked().lock().execute(&mut arguments)
-        };
+        // A LeBlanc function takes and gives values as they are, without boxing them.
+        false => method.leblanc_handle.clone_if_locked().lock().execute_values(arguments)
+    };
 
-    let typing = result.underlying_pointer().typing;
-    match typing {
-        LeBlancType::Exception => return Err(result),
-        _ => stack.push(result)
+    match result {
+        Value::Object(result) if result.underlying_pointer().typing == LeBlancType::Exception => Err(result),
+        result => {
+            stack.push_value(result);
+            Ok(())
+        }
     }
-
-    Ok(())
 }
 
-fn _INSTRUCT_CALL_CLASS_METHOD_(_handle: &mut LeblancHandle, arg: &Instruction, stack: &mut ArrayVec<Arc<Strawberry<LeBlancObject>>, 80>) -> Result<(), Arc<Strawberry<LeBlancObject>>> {
+fn _INSTRUCT_CALL_CLASS_METHOD_(_handle: &mut LeblancHandle, arg: &Instruction, stack: &mut Stack) -> Result<(), Arc<Strawberry<LeBlancObject>>> {
     let error = false;
-    let method_name = match safe_stack_pop(stack) { Ok(res) => res, Err(err) => return Err(err) };
+    let method_name = safe_stack_pop(stack)?;
     let mut arguments = match arg.arg {
         0 => { Vec::new() }
         1 => { vec![deprecated_safe_stack_pop(stack, error)] }
@@ -337,34 +669,35 @@ fn _INSTRUCT_CALL_CLASS_METHOD_(_handle: &mut LeblancHandle, arg: &Instruction,
     let mut object = deprecated_safe_stack_pop(stack, error);
     //println!("object: {:#?}", object);
     if error { return Err(object); }
-    match object.call(method_name.lock().data.to_string().as_str(), &mut arguments) {
-        Ok(result) => stack.push(result),
-        Err(err) => return Err(err)
+    {
+        let result = object.call(method_name.lock().data.to_string().as_str(), &mut arguments)?;
+        if result.underlying_pointer().typing == LeBlancType::Exception { return Err(result); }
+        stack.push(result)
     }
 
     Ok(())
 }
 
-fn _INSTRUCT_CREATE_RANGE_(_handle: &mut LeblancHandle, _arg: &Instruction, stack: &mut ArrayVec<Arc<Strawberry<LeBlancObject>>, 80>) -> Result<(), Arc<Strawberry<LeBlancObject>>> {
-    let increment = match safe_stack_pop(stack) { Ok(res) => res, Err(err) => return Err(err) };
-    let bound = match safe_stack_pop(stack) { Ok(res) => res, Err(err) => return Err(err) };
-    let operand = match safe_stack_pop(stack) { Ok(res) => res, Err(err) => return Err(err) };
+fn _INSTRUCT_CREATE_RANGE_(_handle: &mut LeblancHandle, _arg: &Instruction, stack: &mut Stack) -> Result<(), Arc<Strawberry<LeBlancObject>>> {
+    let increment = safe_stack_pop(stack)?;
+    let bound = safe_stack_pop(stack)?;
+    let operand = safe_stack_pop(stack)?;
 
-    match LeblancInternalRangeGenerator::new(operand, bound, increment) {
-        Ok(value) => stack.push(value),
-        Err(value) => return Err(value)
+    {
+        let value = LeblancInternalRangeGenerator::new(operand, bound, increment)?;
+        stack.push(value)
     }
 
     Ok(())
 }
 
-fn _INSTRUCT_LIST_SETUP_(_handle: &mut LeblancHandle, _arg: &Instruction, stack: &mut ArrayVec<Arc<Strawberry<LeBlancObject>>, 80>) -> Result<(), Arc<Strawberry<LeBlancObject>>> {
-    let mut item = match safe_stack_pop(stack) { Ok(res) => res, Err(err) => return Err(err) };
+fn _INSTRUCT_LIST_SETUP_(_handle: &mut LeblancHandle, _arg: &Instruction, stack: &mut Stack) -> Result<(), Arc<Strawberry<LeBlancObject>>> {
+    let mut item = safe_stack_pop(stack)?;
     let mut typing = item.lock().typing;
     let mut item_list = vec![];
     while typing != LeBlancType::Marker {
         item_list.push(item);
-        item = match safe_stack_pop(stack) { Ok(res) => res, Err(err) => return Err(err) };
+        item = safe_stack_pop(stack)?;
         typing = item.lock().typing;
     }
     item_list.reverse();
@@ -372,131 +705,237 @@ fn _INSTRUCT_LIST_SETUP_(_handle: &mut LeblancHandle, _arg: &Instruction, stack:
     Ok(())
 }
 
-fn _INSTRUCT_WHILE_LOOP(handle: &mut LeblancHandle, arg: &Instruction, stack: &mut ArrayVec<Arc<Strawberry<LeBlancObject>>, 80>) -> Result<(), Arc<Strawberry<LeBlancObject>>> {
-    let mut instructs = vec![];
-    let mut instruct = *handle.instructions.get((handle.current_instruct+1) as usize).unwrap();
-    let mut i = 1;
-    let loop_start = handle.current_instruct;
-    while instruct.instruct != InstructionBase::InstructionMarker {
-        instructs.push(instruct);
-        i += 1;
-        instruct = *handle.instructions.get((handle.current_instruct+i) as usize).unwrap();
+/// Pops keys and values, pushed in turn after a marker, into a new dict.
+fn _INSTRUCT_DICT_SETUP_(_handle: &mut LeblancHandle, _arg: &Instruction, stack: &mut Stack) -> Result<(), Arc<Strawberry<LeBlancObject>>> {
+    let mut entries = vec![];
+    loop {
+        let value = safe_stack_pop(stack)?;
+        if value.lock().typing == LeBlancType::Marker { break; }
+        let key = safe_stack_pop(stack)?;
+        entries.push((key, value));
     }
-    let jump = arg.arg - ((instructs.len()-1) as u16);
+    entries.reverse();
+    stack.push(LeblancDict::new(entries).create_mutex());
+    Ok(())
+}
 
-    let truth = handle.execute_instructions(&instructs, stack);
-    let mut boolean: bool = *truth.lock().data.ref_data().unwrap();
-    while boolean {
-        let _loop_result = handle.execute_range(loop_start+1, loop_start+1 + arg.arg as u64 );
-        let truth = handle.execute_instructions(&instructs, stack);
-        boolean = *truth.lock().data.ref_data().unwrap();
+fn _INSTRUCT_TUPLE_SETUP_(_handle: &mut LeblancHandle, _arg: &Instruction, stack: &mut Stack) -> Result<(), Arc<Strawberry<LeBlancObject>>> {
+    let values = pop_to_marker(stack)?;
+    stack.push(LeblancTuple::new(values).create_mutex());
+    Ok(())
+}
 
-    }
+fn _INSTRUCT_SET_SETUP_(_handle: &mut LeblancHandle, _arg: &Instruction, stack: &mut Stack) -> Result<(), Arc<Strawberry<LeBlancObject>>> {
+    let values = pop_to_marker(stack)?;
+    stack.push(LeblancSet::new(values).create_mutex());
+    Ok(())
+}
 
-    handle.current_instruct = loop_start;
-    handle.current_instruct += jump as u64;
+/// The values pushed after the last marker, in the order they were pushed.
+fn pop_to_marker(stack: &mut Stack) -> Result<Vec<Arc<Strawberry<LeBlancObject>>>, Arc<Strawberry<LeBlancObject>>> {
+    let mut values = vec![];
+    loop {
+        let value = safe_stack_pop(stack)?;
+        if value.lock().typing == LeBlancType::Marker { break; }
+        values.push(value);
+    }
+    values.reverse();
+    Ok(values)
+}
 
+/// Replaces a tuple or list of exactly `arg` values with its values, the first on top, so they
+/// can be stored in the order they are declared.
+fn _INSTRUCT_UNPACK_(_handle: &mut LeblancHandle, arg: &Instruction, stack: &mut Stack) -> Result<(), Arc<Strawberry<LeBlancObject>>> {
+    let value = safe_stack_pop(stack)?;
+    let borrowed = value.lock();
+    let values = match &borrowed.data {
+        LeBlancObjectData::Tuple(tuple) => &tuple.values,
+        LeBlancObjectData::List(list) => &list.internal_vec,
+        _ => return Err(LeblancError::new("TypeException".to_string(), format!("Cannot unpack {} into {} variables", borrowed.typing, arg.arg), vec![]).create_mutex())
+    };
+    if values.len() != arg.arg as usize {
+        return Err(LeblancError::new("TypeException".to_string(), format!("Expected {} values to unpack but got {}", arg.arg, values.len()), vec![]).create_mutex());
+    }
+    for value in values.iter().rev() {
+        stack.push(value.clone());
+    }
     Ok(())
+}
 
+/// Raises a `TypeException` unless the value on top of the stack, which stays there, is null or an
+/// instance of a class implementing a trait. The constant at `arg` is a tuple of the name of the
+/// trait followed by the names of the classes implementing it.
+fn _INSTRUCT_CHECK_TRAIT_(handle: &mut LeblancHandle, arg: &Instruction, stack: &mut Stack) -> Result<(), Arc<Strawberry<LeBlancObject>>> {
+    let value = safe_stack_pop_value(stack)?;
+    let typing = value.typing();
+    stack.push_value(value);
+    let constant = handle.constants[arg.arg as usize].clone().into_object();
+    let constant = constant.lock();
+    let names = match constant.data.ref_data() {
+        Some(LeblancTuple { values }) => values.iter().map(|name| name.lock().data.to_string()).collect::<Vec<String>>(),
+        None => vec![]
+    };
+    let (implements, kind) = match typing {
+        LeBlancType::Null => (true, String::new()),
+        LeBlancType::Class(name) => {
+            let name = name.to_string();
+            (names.iter().skip(1).any(|class| *class == name), format!("class {}", name))
+        }
+        other => (false, other.to_string())
+    };
+    match implements {
+        true => Ok(()),
+        false => Err(LeblancError::new("TypeException".to_string(), format!("{} does not implement trait {}", kind, names.first().map_or("", String::as_str)), vec![]).create_mutex())
+    }
 }
 
-fn _INSTRUCT_FOR_LOOP_(handle: &mut LeblancHandle, arg: &Instruction, stack: &mut ArrayVec<Arc<Strawberry<LeBlancObject>>, 80>) -> Result<(), Arc<Strawberry<LeBlancObject>>> {
-    let iter_variable = match safe_stack_pop(stack) { Ok(res) => res, Err(err) => return Err(err) };
-    let mut iterable = match safe_stack_pop(stack) { Ok(res) => res, Err(err) => return Err(err) };
+/// Sits after the loop body: jumps back to the start of the body at `arg` while the condition holds.
+fn _INSTRUCT_WHILE_LOOP(handle: &mut LeblancHandle, arg: &Instruction, stack: &mut Stack) -> Result<(), Arc<Strawberry<LeBlancObject>>> {
+    let truth = safe_stack_pop_value(stack)?;
+    if truthy(truth)? {
+        jump_to(handle, arg.arg);
+    }
+    Ok(())
+}
 
-    let loop_start = handle.current_instruct;
+/// Advances the iterator on top of the stack, pushing its next element. Once the iterator is
+/// exhausted it is popped and execution continues at the loop exit in `arg`.
+fn _INSTRUCT_FOR_LOOP_(handle: &mut LeblancHandle, arg: &Instruction, stack: &mut Stack) -> Result<(), Arc<Strawberry<LeBlancObject>>> {
+    let mut iterable = safe_stack_pop(stack)?;
 
     if iterable.lock().typing != LeBlancType::Derived(DerivedType::Iterator) {
-        iterable = match iterable.call_name("iterate") {
-            Ok(iter) => iter,
-            Err(err) => return Err(err)
-        }
+        iterable = iterable.call_name("iterate")?
     }
 
-    let mut borrowed_iterable = iterable.lock();
-    let inner_iterator: &mut LeblancIterator = borrowed_iterable.data.mut_data().unwrap();
+    let next = {
+        let mut borrowed_iterable = iterable.lock();
+        let inner_iterator: &mut LeblancIterator = borrowed_iterable.data.mut_data().unwrap();
+        match inner_iterator.has_next() {
+            true => Some(inner_iterator.next()),
+            false => None
+        }
+    };
 
-    while inner_iterator.has_next() {
-        let variable = inner_iterator.next();
-        iter_variable.lock().move_data(variable.arc_unwrap());
-        let _loop_result = handle.execute_range(loop_start+1, loop_start+1 + arg.arg as u64 );
-        //println!("Hahahaha");
-        //variable.lock().swap_rc(&mut iter_variable.lock());
-        //println!("I'm bad");
+    match next {
+        // A generator gives the error that ended its call as its last value.
+        Some(variable) if variable.underlying_pointer().typing == LeBlancType::Exception => return Err(variable),
+        Some(variable) => {
+            stack.push(iterable);
+            stack.push(variable);
+        }
+        None => jump_to(handle, arg.arg)
     }
 
-    handle.current_instruct = loop_start;
-    handle.current_instruct += arg.arg as u64;
-
     Ok(())
 }
 
-fn _INSTRUCT_EQUALITY_(_handle: &mut LeblancHandle, arg: &Instruction, stack: &mut ArrayVec<Arc<Strawberry<LeBlancObject>>, 80>) -> Result<(), Arc<Strawberry<LeBlancObject>>> {
-    //println!("Error?");
-    let tos1 = match safe_stack_pop(stack) { Ok(res) => res, Err(err) => return Err(err) };
-    let tos2 = match safe_stack_pop(stack) { Ok(res) => res, Err(err) => return Err(err) };
-    //println!("Good bye cruel world");
+fn _INSTRUCT_EQUALITY_(_handle: &mut LeblancHandle, arg: &Instruction, stack: &mut Stack) -> Result<(), Arc<Strawberry<LeBlancObject>>> {
+    let tos2 = safe_stack_pop_value(stack)?.unboxed();
+    let tos1 = safe_stack_pop_value(stack)?.unboxed();
+    stack.push_value(Value::Boolean(equality(arg.arg, tos1, tos2)?));
+    Ok(())
+}
 
-    let tos1 = tos1.lock();
-    let tos2_borrow = tos2.lock();
+/// `tos1 <operator> tos2` on unboxed values, with the operators numbered as in `Equality`.
+#[inline(always)]
+fn equality(operator: u16, tos1: Value, tos2: Value) -> Result<bool, Arc<Strawberry<LeBlancObject>>> {
+    match (tos1.primitive_data(), tos2.primitive_data()) {
+        (Some(LeBlancObjectData::Int(tos1)), Some(LeBlancObjectData::Int(tos2))) => ordered(operator, tos1.partial_cmp(&tos2)),
+        (Some(tos1), Some(tos2)) => compare(operator, &tos1, &tos2),
+        _ => {
+            let tos2 = tos2.into_object();
+            let tos1 = unaliased(&tos2, tos1.into_object());
+            let tos1 = tos1.lock();
+            let tos2_borrow = tos2.lock();
+            compare(operator, &tos1.data, &tos2_borrow.data)
+        }
+    }
+}
 
-    stack.push(match arg.arg {
-        0 => (tos1.data == tos2_borrow.data),
-        1 => (tos1.data != tos2_borrow.data),
-        2 => (tos1.data > tos2_borrow.data),
-        3 => (tos1.data < tos2_borrow.data),
-        4 => (tos1.data >= tos2_borrow.data),
-        5 => (tos1.data <= tos2_borrow.data),
+fn compare(operator: u16, tos1: &LeBlancObjectData, tos2: &LeBlancObjectData) -> Result<bool, Arc<Strawberry<LeBlancObject>>> {
+    if let (Some(first), Some(second)) = (Number::of(tos1), Number::of(tos2)) {
+        return ordered(operator, first.compare(second));
+    }
+    Ok(match operator {
+        0 => tos1 == tos2,
+        1 => tos1 != tos2,
+        2 => tos1 > tos2,
+        3 => tos1 < tos2,
+        4 => tos1 >= tos2,
+        5 => tos1 <= tos2,
         _ => { return Err(LeBlancObject::unsafe_error()); }
-    }.create_mutex());
-
-    Ok(())
+    })
 }
 
-fn _INSTRUCT_COMPARATOR_(handle: &mut LeblancHandle, arg: &Instruction, stack: &mut ArrayVec<Arc<Strawberry<LeBlancObject>>, 80>) -> Result<(), Arc<Strawberry<LeBlancObject>>> {
-    let truth = if arg.instruct != Comparator_Else {
-        match safe_stack_pop(stack) { Ok(res) => res, Err(err) => return Err(err) }
-    } else {
-        LeBlancObject::unsafe_error()
-    };
+/// Whether two values that order as `ordering` satisfy the comparison `operator`. Values that
+/// can't be ordered, like NaN, are only unequal.
+#[inline(always)]
+fn ordered(operator: u16, ordering: Option<Ordering>) -> Result<bool, Arc<Strawberry<LeBlancObject>>> {
+    Ok(match operator {
+        0 => ordering == Some(Ordering::Equal),
+        1 => ordering != Some(Ordering::Equal),
+        2 => ordering == Some(Ordering::Greater),
+        3 => ordering == Some(Ordering::Less),
+        4 => matches!(ordering, Some(Ordering::Greater | Ordering::Equal)),
+        5 => matches!(ordering, Some(Ordering::Less | Ordering::Equal)),
+        _ => { return Err(LeBlancObject::unsafe_error()); }
+    })
+}
 
-    let block_start = handle.current_instruct;
-    if arg.instruct == Comparator_Else || *truth.lock().data.ref_data().unwrap() {
-        let jump_result = handle.execute_range(block_start + 1, block_start + 1 + arg.arg as u64);
-        stack.push(jump_result);
-        if arg.instruct == Comparator_Else {
-            handle.current_instruct -= 1;
-        } else {
-            let mut jump = 0;
-            while handle.current_instruct + jump < handle.instructions.len() as u64 {
-                let instruct: Instruction = handle.instructions[(handle.current_instruct + jump) as usize];
-                match instruct.instruct {
-                    Comparator_If => jump += instruct.arg as u64,
-                    Comparator_ElseIf => jump += instruct.arg as u64,
-                    Comparator_Else => {
-                        jump += instruct.arg as u64;
-                        break;
-                    }
-                    _ => { jump += 1; }
-                }
-            }
-            handle.current_instruct += jump;
-        };
+/// `Comparator_If` and `Comparator_ElseIf` jump to `arg` when their condition is false, `Comparator_Else`
+/// always jumps (it skips the remaining branches once one has run).
+fn _INSTRUCT_COMPARATOR_(handle: &mut LeblancHandle, arg: &Instruction, stack: &mut Stack) -> Result<(), Arc<Strawberry<LeBlancObject>>> {
+    if arg.instruct == Comparator_Else {
+        jump_to(handle, arg.arg);
+        return Ok(());
+    }
 
-    } else {
-        handle.current_instruct += arg.arg as u64;
+    let truth = safe_stack_pop_value(stack)?;
+    if !truthy(truth)? {
+        jump_to(handle, arg.arg);
     }
 
     Ok(())
 
 }
 
-fn _INSTRUCT_ELEMENT_ACCESS_(_handle: &mut LeblancHandle, _arg: &Instruction, stack: &mut ArrayVec<Arc<Strawberry<LeBlancObject>>, 80>) -> Result<(), Arc<Strawberry<LeBlancObject>>> {
-    let list_like = match safe_stack_pop(stack) { Ok(res) => res, Err(err) => return Err(err) };
-    let accessor = match safe_stack_pop(stack) { Ok(res) => res, Err(err) => return Err(err) };
+/// Sets the next instruction to run to `target`, accounting for the increment after every instruction.
+#[inline(always)]
+fn jump_to(handle: &mut LeblancHandle, target: u16) {
+    handle.current_instruct = target as u64 - 1;
+}
+
+fn truthy(value: Value) -> Result<bool, Arc<Strawberry<LeBlancObject>>> {
+    if let Value::Boolean(truth) = value {
+        return Ok(truth);
+    }
+    let object = value.into_object();
+    let borrowed = object.lock();
+    let truth: Option<&bool> = borrowed.data.ref_data();
+    match truth {
+        Some(truth) => Ok(*truth),
+        None => Err(LeblancError::new("TypeException".to_string(), format!("Expected a boolean condition but got: {}", borrowed.typing), vec![]).create_mutex())
+    }
+}
+
+fn _INSTRUCT_ELEMENT_ACCESS_(_handle: &mut LeblancHandle, _arg: &Instruction, stack: &mut Stack) -> Result<(), Arc<Strawberry<LeBlancObject>>> {
+    let accessor = safe_stack_pop(stack)?;
+    let list_like = safe_stack_pop(stack)?;
 
     let mut borrowed = list_like.lock();
-    let list: &mut LeblancList = borrowed.data.mut_data().unwrap();
+    let list: &mut LeblancList = match &mut borrowed.data {
+        LeBlancObjectData::List(list) => list,
+        LeBlancObjectData::Dict(dict) => {
+            stack.push(dict.get(&accessor).ok_or_else(|| key_not_found(&accessor))?);
+            return Ok(());
+        }
+        LeBlancObjectData::Tuple(tuple) => {
+            let index = accessor.lock().data.as_i128() as usize;
+            stack.push(tuple.get(index).ok_or_else(|| LeblancError::new("IndexOutOfBoundsException".to_string(), format!("Cannot access an element at index: {} when object length is: {}", index, tuple.len()), vec![]).create_mutex())?);
+            return Ok(());
+        }
+        _ => return Err(not_indexable(borrowed.typing))
+    };
 
     let accessor_type = accessor.lock().typing;
     if accessor_type == LeBlancType::Derived(DerivedType::Slice) {
@@ -512,13 +951,22 @@ fn _INSTRUCT_ELEMENT_ACCESS_(_handle: &mut LeblancHandle, _arg: &Instruction, st
 
 }
 
-fn _INSTRUCT_ELEMENT_STORE_(_handle: &mut LeblancHandle, _arg: &Instruction, stack: &mut ArrayVec<Arc<Strawberry<LeBlancObject>>, 80>) -> Result<(), Arc<Strawberry<LeBlancObject>>> {
-    let list_like = match safe_stack_pop(stack) { Ok(res) => res, Err(err) => return Err(err) };
-    let accessor = match safe_stack_pop(stack) { Ok(res) => res, Err(err) => return Err(err) };
-    let value = match safe_stack_pop(stack) { Ok(res) => res, Err(err) => return Err(err) };
+/// Storing into a dict adds the key if it isn't there.
+fn _INSTRUCT_ELEMENT_STORE_(_handle: &mut LeblancHandle, _arg: &Instruction, stack: &mut Stack) -> Result<(), Arc<Strawberry<LeBlancObject>>> {
+    let accessor = safe_stack_pop(stack)?;
+    let list_like = safe_stack_pop(stack)?;
+    let value = safe_stack_pop(stack)?;
 
     let mut borrowed = list_like.lock();
-    let list: &mut LeblancList = borrowed.data.mut_data().unwrap();
+    let list: &mut LeblancList = match &mut borrowed.data {
+        LeBlancObjectData::List(list) => list,
+        LeBlancObjectData::Dict(dict) => {
+            dict.insert(accessor, value);
+            return Ok(());
+        }
+        LeBlancObjectData::Tuple(_) => return Err(LeblancError::new("TypeException".to_string(), "Cannot assign to an element of a tuple".to_string(), vec![]).create_mutex()),
+        _ => return Err(not_indexable(borrowed.typing))
+    };
 
     let accessor_type = accessor.lock().typing;
     if accessor_type == LeBlancType::Derived(DerivedType::Slice) {
@@ -530,9 +978,24 @@ fn _INSTRUCT_ELEMENT_STORE_(_handle: &mut LeblancHandle, _arg: &Instruction, sta
     Ok(())
 }
 
-fn _INSTRUCT_GROUP_(_handle: &mut LeblancHandle, _arg: &Instruction, stack: &mut ArrayVec<Arc<Strawberry<LeBlancObject>>, 80>) -> Result<(), Arc<Strawberry<LeBlancObject>>> {
-    let target =  match safe_stack_pop(stack) { Ok(res) => res, Err(err) => return Err(err) };
-    let group = match safe_stack_pop(stack) { Ok(res) => res, Err(err) => return Err(err) };
+fn key_not_found(key: &Arc<Strawberry<LeBlancObject>>) -> Arc<Strawberry<LeBlancObject>> {
+    LeblancError::new("KeyException".to_string(), format!("Key not found: {}", key.lock().data), vec![]).create_mutex()
+}
+
+fn not_indexable(typing: LeBlancType) -> Arc<Strawberry<LeBlancObject>> {
+    LeblancError::new("TypeException".to_string(), format!("Cannot index into {}", typing), vec![]).create_mutex()
+}
+
+fn not_callable(typing: LeBlancType) -> Arc<Strawberry<LeBlancObject>> {
+    match typing {
+        LeBlancType::Null => LeblancError::new("OperationOnNullException".to_string(), "Cannot call null".to_string(), vec![]).create_mutex(),
+        _ => LeblancError::new("TypeException".to_string(), format!("{} is not callable", typing), vec![]).create_mutex()
+    }
+}
+
+fn _INSTRUCT_GROUP_(_handle: &mut LeblancHandle, _arg: &Instruction, stack: &mut Stack) -> Result<(), Arc<Strawberry<LeBlancObject>>> {
+    let group = safe_stack_pop(stack)?;
+    let target =  safe_stack_pop(stack)?;
     let mut group_borrow = group.lock();
 
     if group_borrow.typing == LeBlancType::Null {
@@ -545,4 +1008,133 @@ fn _INSTRUCT_GROUP_(_handle: &mut LeblancHandle, _arg: &Instruction, stack: &mut
     stack.push(leblanc_group.promise(target).create_mutex());
 
     Ok(())
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+    use crate::leblanc::compiler::compile::compile_source;
+    use crate::leblanc::core::bytecode::LeblancBytecode;
+    use crate::leblanc::core::interpreter;
+
+    /// Programs install their globals process wide, so only one runs at a time.
+    static RUNNING: Mutex<()> = Mutex::new(());
+
+    /// Compiles and runs `source`, returning what its `main` returned as a string.
+    fn run(source: &str) -> String {
+        let _running = RUNNING.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let (bytecode, diagnostics) = compile_source(source, "<test>");
+        let bytecode = bytecode.unwrap_or_else(|| panic!("does not compile: {:?}", diagnostics.iter().map(|diagnostic| &diagnostic.message).collect::<Vec<_>>()));
+        let result = interpreter::run(LeblancBytecode::from(bytecode), vec![]).unwrap();
+        let result = result.lock().data.to_string();
+        result
+    }
+
+    #[test]
+    fn calling_null_raises_operation_on_null() {
+        let source = "func main() returns string {\n    try {\n        flex f = null;\n        f();\n    } except (OperationOnNullException e) {\n        return e.message();\n    }\n    return \"not raised\";\n}\n";
+        assert_eq!(run(source), "Cannot call null");
+    }
+
+    #[test]
+    fn calling_a_value_raises_type_exception() {
+        let source = "func main() returns string {\n    try {\n        int x = 5;\n        x();\n    } except (TypeException e) {\n        return e.message();\n    }\n    return \"not raised\";\n}\n";
+        assert_eq!(run(source), "int is not callable");
+    }
+
+    #[test]
+    fn finally_runs_on_every_way_out_of_a_try() {
+        let source = "func risky(int n) returns int {\n    if (n > 1) {\n        return n / 0;\n    }\n    return n;\n}\n\nfunc attempt(int n, List<string> log) returns int {\n    try {\n        return risky(n);\n    } finally {\n        log.append(\"finally \" + n);\n    }\n}\n\nfunc main() returns string {\n    List<string> log = [];\n    int total = attempt(1, log);\n    try {\n        total += attempt(2, log);\n    } except (TypeException _e) {\n        log.append(\"wrong\");\n    } except (ArithmeticException! e) {\n        log.append(e.name());\n    } finally {\n        log.append(\"outer\");\n    }\n    return total + \" \" + log;\n}\n";
+        assert_eq!(run(source), "1 [finally 1, finally 2, DivideByZeroException, outer]");
+    }
+
+    #[test]
+    fn the_stack_grows_and_overflows_into_an_exception() {
+        let values = (1..=200).map(|n| n.to_string()).collect::<Vec<_>>().join(", ");
+        let source = "func deep(int n) returns int {\n    return deep(n + 1) + 1;\n}\n\nfunc main() returns string {\n    List<int> wide = [VALUES];\n    string result = \"\" + wide.length();\n    try {\n        deep(0);\n    } except (StackException! e) {\n        result += \" \" + e.name();\n    }\n    return result + \" \" + deep2(100);\n}\n\nfunc deep2(int n) returns int {\n    if (n == 0) {\n        return 0;\n    }\n    return deep2(n - 1) + 1;\n}\n".replace("VALUES", &values);
+        assert_eq!(run(&source), "200 StackOverflowException 100");
+    }
+
+    #[test]
+    fn exception_classes_have_fields_and_methods() {
+        let source = "Class HttpError(Exception) {\n    int code;\n    string hint = \"retry\";\n\n    func HttpError(int code) {\n        self.code = code;\n        self.message = \"status \" + code;\n    }\n\n    func describe() returns string {\n        return self.name() + \" \" + self.code + \" \" + self.hint;\n    }\n}\n\nClass NotFound(HttpError) {}\n\nfunc main() returns string {\n    try {\n        throw NotFound(404);\n    } except (HttpError! e) {\n        return e.describe() + \": \" + e.message;\n    }\n    return \"not raised\";\n}\n";
+        assert_eq!(run(source), "NotFound 404 retry: status 404");
+    }
+
+    #[test]
+    fn methods_can_name_their_receiver_self() {
+        let source = "Trait Named {\n    func name(Self) returns string;\n    auto func greet(Self, string greeting) returns string {\n        return greeting + \" \" + Self.name();\n    }\n}\n\nClass Dog(Named) {\n    string n;\n\n    func Dog(string n) {\n        self.n = n;\n    }\n\n    func name(Self) returns string {\n        return Self.n;\n    }\n}\n\nfunc main() returns string {\n    return Dog(\"Rex\").greet(\"hi\");\n}\n";
+        assert_eq!(run(source), "hi Rex");
+    }
+
+    #[test]
+    fn trait_objects_are_checked_when_they_run() {
+        let source = "Trait Fruit {\n    func color() returns string;\n}\n\nClass Apple(Fruit) {\n    func color() returns string {\n        return \"red\";\n    }\n}\n\nClass Rock {}\n\nfunc pick(int n) returns flex {\n    if (n == 0) {\n        return Apple();\n    }\n    return Rock();\n}\n\nfunc main() returns string {\n    Fruit! apple = pick(0);\n    List<Fruit!> fruits = [apple, pick(0)];\n    string colors = \"\";\n    for Fruit! fruit in fruits {\n        colors += fruit.color();\n    }\n    try {\n        List<Fruit!> rocks = [pick(1)];\n        return \"not raised \" + rocks;\n    } except (TypeException e) {\n        return colors + \" \" + e.message();\n    }\n}\n";
+        assert_eq!(run(source), "redred class Rock does not implement trait Fruit");
+    }
+
+    #[test]
+    fn empty_iterators_have_no_min_max_or_reduce() {
+        let source = "func main() returns string {\n    List<int> none = [];\n    string raised = \"\";\n    try {\n        none.iterate().min();\n    } except (IndexException e) {\n        raised += e.message() + \"; \";\n    }\n    try {\n        none.iterate().max();\n    } except (IndexException e) {\n        raised += e.message() + \"; \";\n    }\n    try {\n        none.iterate().reduce(|a, b| a + b);\n    } except (IndexException e) {\n        raised += e.message() + \"; \";\n    }\n    return raised + none.iterate().fold(0, |a, b| a + b) + \" \" + [3, 1, 2].iterate().min();\n}\n";
+        assert_eq!(run(source), "Cannot take the min of an empty iterator; Cannot take the max of an empty iterator; Cannot reduce an empty iterator; 0 1");
+    }
+
+    #[test]
+    fn blocks_run_in_the_call_that_created_them() {
+        let source = "func run(block b) returns string {\n    try {\n        execute(b);\n    } except (BlockException e) {\n        return e.message();\n    }\n    return \"ran\";\n}\n\nfunc main() returns string {\n    int a = 1;\n    block b = {\n        a = a + 1;\n    }\n    execute(b);\n    execute(b);\n    return a + \" \" + run(b);\n}\n";
+        assert_eq!(run(source), "3 Cannot execute a block outside the call that created it");
+    }
+
+    #[test]
+    fn typed_empty_braces_are_an_empty_dict() {
+        let source = "func main() returns string {\n    Dict<string, int> d = {};\n    d[\"a\"] = 1;\n    Dict<string, int> e = {\"b\": 2};\n    e = {};\n    return d + \" \" + e;\n}\n";
+        assert_eq!(run(source), "{a: 1} {}");
+    }
+
+    #[test]
+    fn typed_empty_braces_are_an_empty_set() {
+        let source = "func main() returns string {\n    Set<int> s = {};\n    Set<int> t = {1, 2};\n    t = {};\n    return s + \" \" + t.union({3}) + \" \" + s.length();\n}\n";
+        assert_eq!(run(source), "{} {3} 0");
+    }
+
+    #[test]
+    fn numeric_keys_match_across_widths() {
+        let source = "func main() returns string {\n    Dict<flex, string> d = {1: \"int\"};\n    d[1l] = \"long\";\n    d[1.0] = \"double\";\n    d[1n] = \"bigint\";\n    d[2.5f] = \"float\";\n    d[2.5] = \"wide\";\n    return d + \" \" + d[1f] + \" \" + d.length();\n}\n";
+        assert_eq!(run(source), "{1: bigint, 2.5: wide} bigint 2");
+    }
+
+    #[test]
+    fn extensions_add_methods_and_properties_to_builtin_types() {
+        let source = "Extension Shout of string {\n    property int count = 0;\n\n    func shout(Self) returns string {\n        Self.count = Self.count + 1;\n        return Self + \"!\";\n    }\n}\n\nfunc main() returns string {\n    string s = \"hi\";\n    string first = s.shout();\n    return first + s.shout() + \" \" + s.count + \" \" + \"yo\".count;\n}\n";
+        assert_eq!(run(source), "hi!hi! 2 0");
+    }
+
+    #[test]
+    fn match_destructures_enum_variants() {
+        let source = "Enum Shape { Circle(int), Rect(int, int), Empty }\n\nfunc describe(Shape shape) returns string {\n    return match shape {\n        Shape::Circle(r) if r > 10 => \"big circle \" + r,\n        Circle(r) => \"circle \" + r,\n        Rect(w, 1) => \"line \" + w,\n        Rect(w, h) => \"rect \" + w * h,\n        Empty => \"empty\",\n    };\n}\n\nfunc size(flex value) returns string {\n    return match value { 0 => \"zero\", \"one\" => \"word\", _ => \"other\" };\n}\n\nfunc main() returns string {\n    List<Shape> shapes = [Shape::Circle(20), Shape::Circle(2), Shape::Rect(4, 1), Shape::Rect(2, 3), Shape::Empty];\n    string result = \"\";\n    for Shape shape in shapes {\n        result += describe(shape) + \"; \";\n    }\n    return result + size(0) + \" \" + size(\"one\") + \" \" + size(2.5);\n}\n";
+        assert_eq!(run(source), "big circle 20; circle 2; line 4; rect 6; empty; zero word other");
+    }
+
+    #[test]
+    fn match_tables_fall_back_to_tests_and_when_dispatches_blocks() {
+        let source = "func name(flex value) returns string {\n    return match value { 1 => \"one\", \"a\" => \"letter\", 'c' => \"char\", 2.5 => \"half\", n if n == 7 => \"seven\", default => \"other\" };\n}\n\nfunc main() returns string {\n    string result = name(1) + \" \" + name(1l) + \" \" + name(1.0) + \" \" + name(2.5) + \" \" + name(2.5f)\n        + \" \" + name(\"a\") + \" \" + name(7) + \" \" + name('c') + \" \" + name(true) + \";\";\n    for int n in [1, 2, 3] {\n        when n {\n            1 => {\n                result += \" first\";\n            }\n            2 => {\n                result += \" second\";\n            }\n            default => {\n                result += \" rest\";\n            }\n        }\n    }\n    return result;\n}\n";
+        assert_eq!(run(source), "one one one half half letter seven char other; first second rest");
+    }
+
+    #[test]
+    fn guards_leave_early_or_yield_their_else_value() {
+        let source = "func check(int n) returns string {\n    guard {\n        n < 0 => return \"negative\",\n        n > 100 => throw IndexException(\"too big\"),\n    }\n    int doubled = guard { n == 0 => return \"zero\", else => n * 2 };\n    return \"\" + doubled;\n}\n\nfunc main() returns string {\n    string result = check(-1) + \" \" + check(0) + \" \" + check(21);\n    try {\n        check(101);\n    } except (IndexException e) {\n        result += \" \" + e.message();\n    }\n    return result;\n}\n";
+        assert_eq!(run(source), "negative zero 42 too big");
+    }
+
+    #[test]
+    fn closures_share_captured_variables_after_returning() {
+        let source = "func counter() returns function {\n    int count = 0;\n    return || {\n        count = count + 1;\n        return count;\n    };\n}\n\nfunc main() returns string {\n    int offset = 10;\n    function add = |n| n + offset;\n    offset = 20;\n    function next = counter();\n    next();\n    List<function> stored = [add, counter()];\n    string result = add(1) + \" \" + next() + \" \" + stored[1]() + \" \";\n    return result + [1, 2, 3].iterate().map(|n| n * offset).list();\n}\n";
+        assert_eq!(run(source), "21 2 1 [20, 40, 60]");
+    }
+
+    #[test]
+    fn generators_keep_their_frame_between_values() {
+        let source = "func numbers(int limit) {\n    int n = 0;\n    while (true) {\n        if (n == limit) {\n            return;\n        }\n        yield n;\n        n = n + 1;\n    }\n}\n\nfunc main() returns string {\n    string result = \"\";\n    for int v in numbers(3) {\n        result += v + \" \";\n    }\n    iterator evens = numbers(10).filter(|n| n % 2 == 0).map(|n| n * 10);\n    iterator manual = numbers(5);\n    result += manual.next() + manual.next() + \" \";\n    return result + evens.list() + \" \" + numbers(4).list() + \" \" + manual.list();\n}\n";
+        assert_eq!(run(source), "0 1 2 1 [0, 20, 40, 60, 80] [0, 1, 2, 3] [2, 3, 4]");
+    }
+}
@@ -52,6 +52,12 @@ pub enum InstructionBase {
     CallClassMethod,
     CallFunction,
     Return,
+    PopTop,
+    Raise,
+    MakeException,
+    MakeInstance,
+    ExtendException,
+    MakeVariant,
     Cast,
     AttributeAccess,
     AttributeStore,
@@ -66,7 +72,32 @@ pub enum InstructionBase {
 
     UseModule,
     MapMatch,
-
+    JumpTable,
+    MakeBlock,
+    MakeUpvalue,
+    LoadUpvalue,
+    StoreUpvalue,
+    MakeClosure,
+    Yield,
+    MakeGenerator,
+    DictSetup,
+    TupleSetup,
+    SetSetup,
+    Unpack,
+    /// `-value`, the unary minus.
+    BinaryNegate,
+    /// Raises unless the value on top of the stack is null or implements the trait at constant `arg`.
+    CheckTrait,
+
+    // Superinstructions, which the peephole pass fuses out of the instructions following them.
+    // Those stay in place as operands and are skipped over, so no jump has to move.
+    /// `LoadLocal a; LoadLocal b`, with `a` in the argument.
+    LoadLocal2,
+    /// `LoadLocal x; LoadConstant c; BinaryAdd; StoreLocal x`, with `x` in the argument.
+    IncrementLocal,
+    /// `LoadLocal x; LoadConstant c; Equality; <jump>`, with `x` in the argument. The jump is a
+    /// `Comparator_If`, `Comparator_ElseIf` or `WhileLoop`.
+    CompareLocalConstJump,
 }
 
 impl Hexable for InstructionBase {
@@ -115,6 +146,7 @@ impl InstructionBase {
                     LBOperator::Modulo => BinaryModulo,
                     LBOperator::Or => BinaryOr,
                     LBOperator::And => BinaryAnd,
+                    LBOperator::Xor => BinaryXor,
                     LBOperator::Not => BinaryNot,
                     LBOperator::Assign => StoreUndefined,
                     LBOperator::Inverse => BinaryInverse,
@@ -150,12 +182,8 @@ impl InstructionBase {
                     _ => Zero
                 }
             }
-            CompileVocab::SPECIAL(special, val) => {
-                match special {
-                    Specials::RangeMarker => IteratorSetup(val),
-                    _ => Zero
-                }
-            },
+            CompileVocab::SPECIAL(Specials::RangeMarker, val) => IteratorSetup(val),
+            CompileVocab::SPECIAL(_, _) => Zero,
             CompileVocab::CONSTRUCTOR(_) => NotImplemented,
             CompileVocab::CLASS(_) => NotImplemented,
             CompileVocab::BOUNDARY(bound) => {
@@ -169,8 +197,8 @@ impl InstructionBase {
         }
     }
 
-    pub fn to_instruction(self, arg: u16, line_number: u32) -> Instruction {
-        Instruction::new(self, arg, line_number)
+    pub fn to_instruction(self, arg: u16, line_number: u32, column: u16) -> Instruction {
+        Instruction::new(self, arg, line_number, column)
     }
 }
 
@@ -178,15 +206,17 @@ impl InstructionBase {
 pub struct Instruction {
     pub instruct: InstructionBase,
     pub arg: u16,
-    pub line_number: u32
+    pub line_number: u32,
+    pub column: u16
 }
 
 impl Instruction {
-    pub fn new(instruct: InstructionBase, arg: u16, line_number: u32) -> Instruction {
+    pub fn new(instruct: InstructionBase, arg: u16, line_number: u32, column: u16) -> Instruction {
         Instruction {
             instruct,
             arg,
-            line_number
+            line_number,
+            column
         }
     }
 
@@ -194,7 +224,8 @@ impl Instruction {
         Instruction {
             instruct: Zero,
             arg: 0,
-            line_number: 0
+            line_number: 0,
+            column: 0
         }
     }
 
@@ -1,37 +1,207 @@
+use std::fs;
 use std::io::Write;
-use crate::{CompilationMode, compile};
+use std::sync::Arc;
+use crate::leblanc::compiler::compile::compile_source;
+use crate::leblanc::compiler::compile_error_reporter::report_diagnostics;
+use crate::leblanc::compiler::compiler_rewrite::ast::{Item, Located, Location, Stmt};
+use crate::leblanc::compiler::compiler_rewrite::parser::parse;
+use crate::leblanc::compiler::diagnostic::{Diagnostic, Label, MessageFormat};
 use crate::leblanc::core::bytecode::LeblancBytecode;
-use crate::leblanc::core::interpreter::instructions::Instruction;
+use crate::leblanc::core::interpreter::run_function;
+use crate::leblanc::core::leblanc_object::{LeBlancObject, LeBlancObjectData, RustDataCast};
+use crate::leblanc::core::native_types::error_type::LeblancError;
+use crate::leblanc::rustblanc::hex::Hexadecimal;
+use crate::leblanc::rustblanc::strawberry::Strawberry;
+use crate::LeBlancType;
 
-static MAIN_MODE: bool = true;
+/// What the REPL's input is called in diagnostics and tracebacks, and what the functions and
+/// classes defined by earlier inputs are called in tracebacks.
+const INPUT: &str = "<input>";
+const SESSION: &str = "<session>";
 
 pub fn start() {
-    loop {
-        let mut s = retrieve_input();
-        if MAIN_MODE {
-            s = wrap_in_main(s);
+    let mut session = Session::default();
+    while let Some(input) = retrieve_input() {
+        session.evaluate(input);
+    }
+    println!();
+}
+
+/// What earlier inputs left behind: the functions, classes and imports they defined, and the
+/// variables they declared at the top level.
+#[derive(Default)]
+struct Session {
+    items: String,
+    variables: Vec<Variable>,
+}
+
+struct Variable {
+    name: String,
+    type_name: String,
+    value: Arc<Strawberry<LeBlancObject>>,
+}
+
+impl Session {
+    fn evaluate(&mut self, input: String) {
+        if parse(&input).is_ok_and(|file| !file.items.is_empty()) {
+            self.define(input);
+        } else {
+            self.execute(input.replace(";;", ";\n"));
         }
-        println!("compiled:\n{}", s);
-
-        let fabric = compile(s, CompilationMode::Realtime);
-        let mut bytecode = LeblancBytecode::from(fabric.bytecode);
-        for mut function in bytecode.body().functions() {
-            let mut instructs: Vec<Instruction> = vec![];
-            function.instruction_lines().into_iter().map(|line| line.to_instructions()).for_each(|mut l| instructs.append(&mut l));
-            println!("instructs: {:#?}", instructs);
+    }
+
+    /// Keeps the functions, classes and imports in `input` for every later input, if they compile.
+    fn define(&mut self, input: String) {
+        let program = Program::new(self.items.clone(), &input, "\nfunc main() {}\n");
+        if compile(&program).is_some() {
+            self.items = format!("{}{}\n", self.items, input);
         }
     }
 
+    /// Runs `input` as the body of `main`. The variables declared so far come in as parameters,
+    /// and every variable is returned in a list so the next input can pick them up again.
+    fn execute(&mut self, input: String) {
+        let unchecked = self.main(&[], &input, "");
+        let declared = match parse(&unchecked.source) {
+            Ok(file) => declarations(&file.items),
+            Err(error) => {
+                report(&unchecked, &[error.into()]);
+                return;
+            }
+        };
+        let kept: Vec<&Variable> = self.variables.iter()
+            .filter(|variable| !declared.iter().any(|(name, _)| *name == variable.name)).collect();
+        let names: Vec<&str> = kept.iter().map(|variable| variable.name.as_str())
+            .chain(declared.iter().map(|(name, _)| name.as_str())).collect();
+        let program = self.main(&kept, &input, &format!("\treturn [{}];\n", names.join(", ")));
+        let bytecode = match compile(&program) {
+            Some(bytecode) => LeblancBytecode::from(bytecode),
+            None => return
+        };
 
+        let args = kept.iter().map(|variable| variable.value.clone()).collect();
+        let result = match run_function(bytecode, "main", args) {
+            Ok(result) => result,
+            Err(message) => { eprintln!("error: {}", message); return; }
+        };
+        if result.lock().typing == LeBlancType::Exception {
+            let error: Option<LeblancError> = result.lock().data.clone_data();
+            if let Some(error) = error {
+                self.print_stack_trace(&program, error);
+            }
+            return;
+        }
+        let values = match &result.lock().data {
+            LeBlancObjectData::List(list) => list.internal_vec.clone(),
+            _ => return
+        };
+        let types: Vec<String> = kept.iter().map(|variable| variable.type_name.clone())
+            .chain(declared.iter().map(|(_, type_name)| type_name.clone())).collect();
+        self.variables = names.into_iter().zip(types).zip(values)
+            .map(|((name, type_name), value)| Variable { name: name.to_string(), type_name, value }).collect();
+    }
 
+    fn main(&self, parameters: &[&Variable], body: &str, tail: &str) -> Program {
+        let parameters: Vec<String> = parameters.iter().map(|variable| format!("{} {}", variable.type_name, variable.name)).collect();
+        let returns = if tail.is_empty() { "" } else { " returns list" };
+        Program::new(format!("{}func main({}){} {{\n", self.items, parameters.join(", "), returns), body, &format!("\n{}}}\n", tail))
+    }
 
+    /// Prints the traceback of an error `program` raised. Frames in the input point at its lines,
+    /// and frames in the functions defined earlier point at the lines of the `<session>` they make up.
+    fn print_stack_trace(&self, program: &Program, mut error: LeblancError) {
+        let definitions = self.items.matches('\n').count();
+        for frame in error.stack_trace_mut().iter_mut().filter(|frame| frame.file == INPUT) {
+            match program.input_line(frame.line as usize) {
+                Some(line) => frame.line = line as u32,
+                None if frame.line as usize <= definitions => frame.file = SESSION.to_string(),
+                None => {}
+            }
+        }
+        error.print_stack_trace_from(|file| match file {
+            INPUT => Some(program.input.clone()),
+            SESSION => Some(self.items.clone()),
+            file => fs::read_to_string(file).ok()
+        });
+    }
+}
 
+/// The whole program compiled for one input: the input with the code the REPL puts around it.
+struct Program {
+    source: String,
+    input: String,
+    /// Where the input starts in `source`.
+    start: usize,
+    /// How many lines of `source` come before the input.
+    lines: usize
+}
 
+impl Program {
+    fn new(before: String, input: &str, after: &str) -> Program {
+        Program { start: before.len(), lines: before.matches('\n').count(), source: before + input + after, input: input.to_string() }
+    }
 
+    /// `line` of the program as a line of the input, if it is one.
+    fn input_line(&self, line: usize) -> Option<usize> {
+        line.checked_sub(self.lines).filter(|line| (1..=self.input.lines().count().max(1)).contains(line))
+    }
 
+    /// `location` counted from the start of the input, if it starts in the input.
+    fn relocate(&self, location: Location) -> Option<Location> {
+        let end = self.start + self.input.len();
+        (self.start..=end).contains(&location.start).then(|| Location::new(location.start - self.start,
+            location.end.min(end) - self.start, location.line - self.lines, location.column))
+    }
+
+    /// `diagnostics` pointing into the input instead of the program. Labels pointing at code the
+    /// REPL added, e.g. a parameter holding a session variable, are left out. `None` when a
+    /// diagnostic itself isn't in the input.
+    fn relocate_all(&self, diagnostics: &[Diagnostic]) -> Option<Vec<Diagnostic>> {
+        diagnostics.iter().map(|diagnostic| {
+            let labels = diagnostic.labels.iter().filter_map(|label| Some(Label { location: self.relocate(label.location)?, message: label.message.clone() }));
+            Some(Diagnostic { location: self.relocate(diagnostic.location)?, labels: labels.collect(), ..diagnostic.clone() })
+        }).collect()
+    }
+}
+
+/// The variables declared directly in the body of the last `main` in `items`, with their types.
+fn declarations(items: &[Located<Item>]) -> Vec<(String, String)> {
+    let body = items.iter().rev().find_map(|item| match &item.data {
+        Item::Function(function) if function.name.data == "main" => function.body.as_ref(),
+        _ => None
+    });
+    let mut declared = vec![];
+    for statement in body.map(|body| body.data.iter()).into_iter().flatten() {
+        match &statement.data {
+            Stmt::Declaration { type_name, name, .. } => declared.push((name.data.clone(), type_name.data.to_string())),
+            Stmt::Destructuring { targets, .. } => declared.extend(targets.iter()
+                .map(|(type_name, name)| (name.data.clone(), type_name.data.to_string()))),
+            _ => {}
+        }
+    }
+    declared
+}
+
+/// Compiles a whole REPL program, reporting its errors. Warnings are left out, since the session's
+/// variables would otherwise keep showing up as unused.
+fn compile(program: &Program) -> Option<Hexadecimal> {
+    let (bytecode, diagnostics) = compile_source(&program.source, INPUT);
+    report(program, &diagnostics);
+    bytecode
+}
+
+/// Reports the errors against the input they were found in, or against the whole program when
+/// one of them is in the code around it.
+fn report(program: &Program, diagnostics: &[Diagnostic]) {
+    let errors: Vec<Diagnostic> = diagnostics.iter().filter(|diagnostic| diagnostic.is_error()).cloned().collect();
+    match program.relocate_all(&errors) {
+        Some(errors) => report_diagnostics(INPUT, &program.input, &errors, MessageFormat::Human),
+        None => report_diagnostics(INPUT, &program.source, &errors, MessageFormat::Human)
+    }
 }
 
-fn retrieve_input() -> String {
+/// Reads one balanced statement from stdin, returning `None` once stdin is closed or cannot be read.
+fn retrieve_input() -> Option<String> {
     let mut dc = DelimiterCount::new();
     let mut line = String::new();
 
@@ -40,18 +210,24 @@ fn retrieve_input() -> String {
     while !dc.balanced() || final_line.is_empty() || final_line.ends_with(";;") {
         final_line = final_line.replace("...", "");
         print!("{}", console);
-        std::io::stdout().flush().unwrap();
-        std::io::stdin().read_line(&mut line).unwrap();
-        line.pop().unwrap();
-        line.lines().map(|l| l.chars()).for_each(|c| c.into_iter().for_each(|ch| dc.check(ch)));
-        final_line += &line;
-        std::io::stdout().flush().unwrap();
+        let _ = std::io::stdout().flush();
+        match std::io::stdin().read_line(&mut line) {
+            Ok(0) => return None,
+            Ok(_) => {}
+            Err(err) => {
+                eprintln!("error: cannot read input: {}", err);
+                return None;
+            }
+        }
+        let trimmed = line.trim_end_matches(['\n', '\r']);
+        trimmed.chars().for_each(|ch| dc.check(ch));
+        final_line += trimmed;
         line = String::new();
         console = "... ";
     }
 
 
-    final_line
+    Some(final_line)
 }
 
 #[derive(Debug)]
@@ -87,6 +263,33 @@ impl DelimiterCount {
     }
 }
 
-pub fn wrap_in_main(string: String) -> String {
-    "func main() {\n".to_owned() + "\t" + &string.replace(";;", ";\n\t") + "\n}"
-}
\ No newline at end of file
+#[cfg(test)]
+mod tests {
+    use crate::leblanc::compiler::compile::compile_source;
+    use super::{Program, INPUT};
+
+    fn diagnostics(program: &Program) -> Option<Vec<(usize, usize, String)>> {
+        let (_, diagnostics) = compile_source(&program.source, INPUT);
+        program.relocate_all(&diagnostics).map(|diagnostics| diagnostics.into_iter()
+            .map(|diagnostic| (diagnostic.location.line, diagnostic.location.column, program.input[diagnostic.location.start..diagnostic.location.end].to_string()))
+            .collect())
+    }
+
+    #[test]
+    fn input_lines_are_counted_from_the_input() {
+        let program = Program::new("func f() {}\nfunc main(int x) returns list {\n".to_string(), "print(x);\nint y = 1;", "\n\treturn [x, y];\n}\n");
+        assert_eq!((1..=6).map(|line| program.input_line(line)).collect::<Vec<_>>(), vec![None, None, Some(1), Some(2), None, None]);
+    }
+
+    #[test]
+    fn diagnostics_point_into_the_input() {
+        let program = Program::new("func f() {}\nfunc main(int x) {\n".to_string(), "print(x);\nint y = \"a\";\nprint(y);", "\n}\n");
+        assert_eq!(diagnostics(&program), Some(vec![(2, 9, "\"a\"".to_string())]));
+    }
+
+    #[test]
+    fn diagnostics_outside_the_input_keep_the_program() {
+        let program = Program::new("func f() {\n    int q = \"a\";\n}\nfunc main() {\n".to_string(), "print(1);", "\n}\n");
+        assert_eq!(diagnostics(&program), None);
+    }
+}
@@ -0,0 +1,153 @@
+use std::fmt::{Display, Formatter};
+
+use fxhash::FxHashMap;
+use num::ToPrimitive;
+
+use crate::leblanc::core::leblanc_object::LeBlancObjectData;
+use crate::leblanc::rustblanc::hex::Hexadecimal;
+use crate::leblanc::rustblanc::Hexable;
+
+/// The value of a constant arm. Every integer type shares one key, so an `int64` finds the arm
+/// written as `2` just like an `int` does. A whole float, double or decimal finds it too, as it is
+/// equal to the integer.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum TableKey {
+    Integer(i128),
+    Char(char),
+    Boolean(bool),
+    String(String)
+}
+
+impl TableKey {
+    pub fn of(data: &LeBlancObjectData) -> Option<TableKey> {
+        match data {
+            LeBlancObjectData::Short(value) => Some(TableKey::Integer(*value as i128)),
+            LeBlancObjectData::Int(value) => Some(TableKey::Integer(*value as i128)),
+            LeBlancObjectData::Int64(value) => Some(TableKey::Integer(*value as i128)),
+            LeBlancObjectData::Int128(value) => Some(TableKey::Integer(*value)),
+            LeBlancObjectData::Arch(value) => Some(TableKey::Integer(*value as i128)),
+            LeBlancObjectData::BigInt(value) => value.to_i128().map(TableKey::Integer),
+            LeBlancObjectData::Float(value) => integral(*value as f64),
+            LeBlancObjectData::Double(value) => integral(*value),
+            LeBlancObjectData::Decimal(value) if value.is_integer() => value.trunc().to_i128().map(TableKey::Integer),
+            LeBlancObjectData::Char(value) => Some(TableKey::Char(*value)),
+            LeBlancObjectData::Boolean(value) => Some(TableKey::Boolean(*value)),
+            LeBlancObjectData::String(value) => Some(TableKey::String(value.to_string())),
+            _ => None
+        }
+    }
+
+    fn tag(&self) -> u16 {
+        match self {
+            TableKey::Integer(_) => 0,
+            TableKey::Char(_) => 1,
+            TableKey::Boolean(_) => 2,
+            TableKey::String(_) => 3
+        }
+    }
+
+    fn text(&self) -> String {
+        match self {
+            TableKey::Integer(value) => value.to_string(),
+            TableKey::Char(value) => value.to_string(),
+            TableKey::Boolean(value) => value.to_string(),
+            TableKey::String(value) => value.clone()
+        }
+    }
+
+    fn from_text(tag: u16, text: String) -> TableKey {
+        match tag {
+            0 => TableKey::Integer(text.parse().unwrap()),
+            1 => TableKey::Char(text.chars().next().unwrap()),
+            2 => TableKey::Boolean(text == "true"),
+            _ => TableKey::String(text)
+        }
+    }
+}
+
+fn integral(value: f64) -> Option<TableKey> {
+    match value.fract() == 0.0 {
+        true => value.to_i128().map(TableKey::Integer),
+        false => None
+    }
+}
+
+impl Display for TableKey {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TableKey::Char(value) => write!(f, "'{}'", value),
+            TableKey::String(value) => write!(f, "\"{}\"", value),
+            other => write!(f, "{}", other.text())
+        }
+    }
+}
+
+/// Where a `match` or `when` whose arms are constants jumps to for each value: the instruction
+/// of the arm matching the value, or `default` for any other value. Stored in the constants of
+/// the function as a `marker` and indexed into a hash map when the function is loaded.
+///
+///     default | entry_count | key_tag | target | key_length | key | ....... |
+///     2 bytes |   4 bytes   | 2 bytes | 2 bytes |  4 bytes  |  X  | ....... |
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct JumpTable {
+    pub entries: Vec<(TableKey, u16)>,
+    pub default: u16,
+    index: FxHashMap<TableKey, u16>
+}
+
+impl JumpTable {
+    /// A table with no entries yet, whose targets are filled in as the arms are compiled.
+    pub fn new() -> JumpTable {
+        JumpTable::default()
+    }
+
+    /// Adds an arm, unless an earlier one already matches `key`.
+    pub fn insert(&mut self, key: TableKey, target: u16) {
+        if self.index.contains_key(&key) { return; }
+        self.index.insert(key.clone(), target);
+        self.entries.push((key, target));
+    }
+
+    /// Moves every target, including the default, to `target(old)`.
+    pub fn retarget(&mut self, target: impl Fn(u16) -> u16) {
+        self.default = target(self.default);
+        self.entries.iter_mut().for_each(|(_, old)| *old = target(*old));
+        self.index.values_mut().for_each(|old| *old = target(*old));
+    }
+
+    pub fn target(&self, value: &LeBlancObjectData) -> u16 {
+        TableKey::of(value).and_then(|key| self.index.get(&key).copied()).unwrap_or(self.default)
+    }
+
+    pub fn to_hex(&self) -> Hexadecimal {
+        let mut hex = self.default.to_hex(2) + (self.entries.len() as u32).to_hex(4);
+        for (key, target) in &self.entries {
+            let text = key.text().to_hex(0);
+            hex += key.tag().to_hex(2) + target.to_hex(2) + (text.len() as u32).to_hex(4) + text;
+        }
+        hex
+    }
+
+    pub fn from_hex(hex: &Hexadecimal) -> JumpTable {
+        let mut hex = hex.clone();
+        let mut table = JumpTable::new();
+        table.default = hex.scrape(2).to_hexable::<u16>();
+        let count = hex.scrape(4).to_hexable::<u32>();
+        for _ in 0..count {
+            let tag = hex.scrape(2).to_hexable::<u16>();
+            let target = hex.scrape(2).to_hexable::<u16>();
+            let length = hex.scrape(4).to_hexable::<u32>();
+            let text = hex.scrape(length as usize).to_hexable::<String>();
+            table.insert(TableKey::from_text(tag, text), target);
+        }
+        table
+    }
+}
+
+/// Targets are shown the way the disassembler numbers instructions.
+impl Display for JumpTable {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let entries = self.entries.iter().map(|(key, target)| format!("{} => {}", key, *target as u32 * 2)).collect::<Vec<String>>();
+        write!(f, "{{{}, _ => {}}}", entries.join(", "), self.default as u32 * 2)
+    }
+}
@@ -1,13 +1,12 @@
 
 
-use alloc::rc::Rc;
-use std::cell::RefCell;
 use crate::leblanc::rustblanc::strawberry::Strawberry;
-use std::sync::{Arc, Mutex};
-use std::time::Instant;
+use std::sync::Arc;
 
 use crate::leblanc::core::leblanc_object::{Callable, LeBlancObject, Reflect, RustDataCast};
 use crate::leblanc::core::method::Method;
+use crate::leblanc::core::native_types::base_type::ToLeblanc;
+use crate::leblanc::core::native_types::derived::list_type::{leblanc_object_list, LeblancList};
 use crate::leblanc::core::native_types::error_type::LeblancError;
 use crate::LeBlancType;
 
@@ -25,23 +24,55 @@ impl LeBlancRunner {
         }
     }
 
-    pub fn run_main(&mut self) {
-        unsafe { GLOBALS = self.globals.to_vec(); }
-        let main_object = self.globals.iter_mut().filter(|g| g.lock().typing == LeBlancType::Function).find(|g| g.reflect().downcast_ref::<Box<Method>>().unwrap().context.name == "main");
-
-        let main_elapsed = Instant::now();
-        let f = main_object.unwrap().call("main", &mut []).unwrap();
-        if f.lock().typing == LeBlancType::Exception {
-            let borrowed = f.lock();
+    pub fn run_main(&mut self, args: Vec<String>) -> Arc<Strawberry<LeBlancObject>> {
+        let takes_args = self.function("main").is_some_and(|main| !main.lock().data.get_inner_method().unwrap().context.arguments.is_empty());
+        let mut main_args = if takes_args {
+            vec![leblanc_object_list(LeblancList::new(args.iter().map(|arg| arg.create_mutex()).collect())).to_mutex()]
+        } else { vec![] };
+        let result = self.run_function("main", &mut main_args);
+        if result.lock().typing == LeBlancType::Exception {
+            let borrowed = result.lock();
             let error: &LeblancError = borrowed.data.ref_data().unwrap();
             error.print_stack_trace();
         }
-        //println!("Final: {:#?}", f.lock().data);
-        println!("Execution Elapsed: {}", main_elapsed.elapsed().as_secs_f64());
+        result
+    }
+
+    /// Installs the globals and calls the function `name` with `args`. Returns whatever the function
+    /// returned, or the error it raised, whose stack trace is left to the caller to print.
+    pub fn run_function(&mut self, name: &str, args: &mut [Arc<Strawberry<LeBlancObject>>]) -> Arc<Strawberry<LeBlancObject>> {
+        self.install_globals();
+        let mut function = match self.function(name) {
+            Some(function) => function,
+            None => {
+                let message = format!("No {} function defined", name);
+                eprintln!("FunctionNotFoundException: {}", message);
+                return LeblancError::new("FunctionNotFoundException".to_string(), message, vec![]).create_mutex();
+            }
+        };
+
+        match function.call(name, args) {
+            Ok(f) => f,
+            Err(err) => err
+        }
+    }
+
+    fn function(&self, name: &str) -> Option<Arc<Strawberry<LeBlancObject>>> {
+        self.globals.iter().filter(|g| g.lock().typing == LeBlancType::Function)
+            .find(|g| g.reflect().downcast_ref::<Box<Method>>().unwrap().context.name == name).cloned()
+    }
+
+    pub fn globals(&self) -> &Vec<Arc<Strawberry<LeBlancObject>>> { &self.globals }
+
+    /// Makes this runner's globals visible to `LoadFunction` without running anything.
+    pub fn install_globals(&self) {
+        unsafe { GLOBALS = self.globals.to_vec(); }
     }
 }
 
+/// # Safety
+/// The globals must not be installed again while the returned reference is in use.
 pub unsafe fn get_globals() -> &'static Vec<Arc<Strawberry<LeBlancObject>>> {
-    &GLOBALS
+    (&raw const GLOBALS).as_ref().unwrap()
 }
 
@@ -0,0 +1,122 @@
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::sync::Arc;
+
+use crate::leblanc::core::extension::has_properties;
+use crate::leblanc::core::leblanc_object::{LeBlancObject, LeBlancObjectData, QuickUnwrap};
+use crate::leblanc::core::native_types::base_type::ToLeblanc;
+use crate::leblanc::core::native_types::char_type::leblanc_object_char;
+use crate::leblanc::core::native_types::LeBlancType;
+use crate::leblanc::rustblanc::strawberry::Strawberry;
+
+/// A value as the VM keeps it on its stack and in locals. Primitives are held inline, so pushing,
+/// storing and adding them doesn't allocate or lock; anything else is a shared object. A primitive
+/// is boxed into an object only once it leaves the VM, e.g. as an argument or a list element.
+#[derive(Clone, Debug, Default)]
+pub enum Value {
+    Int(i32),
+    Int64(i64),
+    Double(f64),
+    Boolean(bool),
+    Char(char),
+    #[default]
+    Null,
+    Object(Arc<Strawberry<LeBlancObject>>)
+}
+
+impl Value {
+    /// Unboxes `object` when it holds a primitive other than null, which stays boxed so a group
+    /// can fill it in place. An object that is locked, e.g. the one whose method is running, or
+    /// that can have extension properties stays boxed as well.
+    pub fn from_object(object: Arc<Strawberry<LeBlancObject>>) -> Value {
+        let primitive = match object.try_lock() {
+            None => None,
+            Some(borrowed) if has_properties(borrowed.typing) => None,
+            Some(borrowed) => match (borrowed.typing, &borrowed.data) {
+                (LeBlancType::Int, LeBlancObjectData::Int(int)) => Some(Value::Int(*int)),
+                (LeBlancType::Int64, LeBlancObjectData::Int64(int)) => Some(Value::Int64(*int)),
+                (LeBlancType::Double, LeBlancObjectData::Double(double)) => Some(Value::Double(*double)),
+                (LeBlancType::Boolean, LeBlancObjectData::Boolean(boolean)) => Some(Value::Boolean(*boolean)),
+                (LeBlancType::Char, LeBlancObjectData::Char(ch)) => Some(Value::Char(*ch)),
+                _ => None
+            }
+        };
+        primitive.unwrap_or(Value::Object(object))
+    }
+
+    /// The value as an object, boxing a primitive into a new one.
+    pub fn into_object(self) -> Arc<Strawberry<LeBlancObject>> {
+        match self {
+            Value::Int(int) => int.create_mutex(),
+            Value::Int64(int) => int.create_mutex(),
+            Value::Double(double) => double.create_mutex(),
+            Value::Boolean(boolean) => boolean.create_mutex(),
+            Value::Char(ch) => leblanc_object_char(ch).to_mutex(),
+            // Never the shared null, as a group fills the null it is given in place.
+            Value::Null => LeBlancObject::null().to_mutex(),
+            Value::Object(object) => object
+        }
+    }
+
+    /// The data of a primitive, or `None` for an object.
+    pub fn primitive_data(&self) -> Option<LeBlancObjectData> {
+        match self {
+            Value::Int(int) => Some(LeBlancObjectData::Int(*int)),
+            Value::Int64(int) => Some(LeBlancObjectData::Int64(*int)),
+            Value::Double(double) => Some(LeBlancObjectData::Double(*double)),
+            Value::Boolean(boolean) => Some(LeBlancObjectData::Boolean(*boolean)),
+            Value::Char(ch) => Some(LeBlancObjectData::Char(*ch)),
+            Value::Null => Some(LeBlancObjectData::Null),
+            Value::Object(_) => None
+        }
+    }
+
+    /// The value with an object holding a primitive unboxed, see `from_object`.
+    #[inline(always)]
+    pub fn unboxed(self) -> Value {
+        match self {
+            Value::Object(object) => Value::from_object(object),
+            primitive => primitive
+        }
+    }
+
+    /// The value as a variable holds it: unboxed, except for null. A group fills the null it is
+    /// given in place, so each variable needs a null of its own.
+    pub fn into_variable(self) -> Value {
+        match self {
+            Value::Null => Value::Object(LeBlancObject::null().to_mutex()),
+            Value::Object(object) => Value::from_object(object),
+            primitive if has_properties(primitive.typing()) => Value::Object(primitive.into_object()),
+            primitive => primitive
+        }
+    }
+
+    pub fn typing(&self) -> LeBlancType {
+        match self {
+            Value::Int(_) => LeBlancType::Int,
+            Value::Int64(_) => LeBlancType::Int64,
+            Value::Double(_) => LeBlancType::Double,
+            Value::Boolean(_) => LeBlancType::Boolean,
+            Value::Char(_) => LeBlancType::Char,
+            Value::Null => LeBlancType::Null,
+            Value::Object(object) => object.lock().typing
+        }
+    }
+
+    /// A copy that shares nothing with the value, not even its object.
+    pub fn full_clone(&self) -> Value {
+        match self {
+            Value::Object(object) => Value::Object(object.clone().arc_unwrap().to_mutex()),
+            primitive => primitive.clone()
+        }
+    }
+}
+
+impl Display for Value {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self.primitive_data() {
+            Some(data) => write!(f, "{}", data),
+            None => write!(f, "{}", self.clone().into_object().lock().data)
+        }
+    }
+}
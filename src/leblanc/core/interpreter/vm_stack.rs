@@ -0,0 +1,212 @@
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use crate::leblanc::core::interpreter::value::Value;
+use crate::leblanc::core::leblanc_object::LeBlancObject;
+use crate::leblanc::core::native_types::base_type::ToLeblanc;
+use crate::leblanc::core::native_types::error_type::LeblancError;
+use crate::leblanc::rustblanc::strawberry::Strawberry;
+
+pub const DEFAULT_MAX_VALUES: usize = 1 << 20;
+pub const DEFAULT_MAX_CALLS: usize = 10_000;
+/// The deepest calls can be allowed to nest, which takes a native stack of about 1.6 GiB.
+pub const MAX_CALLS_LIMIT: usize = 100_000;
+
+/// Native stack each nested call can take, with room to spare: a call goes through the
+/// instruction loop, the call instruction and the method running it.
+const NATIVE_BYTES_PER_CALL: usize = 16 * 1024;
+/// Native stack for everything besides the calls, e.g. compiling and loading the file.
+const NATIVE_BYTES_BASE: usize = 8 * 1024 * 1024;
+
+static MAX_VALUES: AtomicUsize = AtomicUsize::new(DEFAULT_MAX_VALUES);
+static MAX_CALLS: AtomicUsize = AtomicUsize::new(DEFAULT_MAX_CALLS);
+
+thread_local! {
+    /// The operand stacks of every call running on this thread, each on top of its caller's.
+    static VALUES: RefCell<Vec<Value>> = RefCell::new(Vec::with_capacity(256));
+}
+
+/// How many values the operand stacks of a thread can hold in total, and how deep calls can nest,
+/// before a `StackOverflowException` is raised.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StackLimits {
+    pub values: usize,
+    pub calls: usize
+}
+
+impl Default for StackLimits {
+    fn default() -> Self {
+        StackLimits { values: DEFAULT_MAX_VALUES, calls: DEFAULT_MAX_CALLS }
+    }
+}
+
+pub fn set_limits(limits: StackLimits) {
+    MAX_VALUES.store(limits.values, Ordering::Relaxed);
+    MAX_CALLS.store(limits.calls, Ordering::Relaxed);
+}
+
+pub fn max_values() -> usize {
+    MAX_VALUES.load(Ordering::Relaxed)
+}
+
+pub fn max_calls() -> usize {
+    MAX_CALLS.load(Ordering::Relaxed)
+}
+
+/// The native stack a thread needs to run calls nested as deep as allowed, so that the limit is
+/// reached before the thread runs out.
+pub fn native_stack_size() -> usize {
+    max_calls().saturating_mul(NATIVE_BYTES_PER_CALL).saturating_add(NATIVE_BYTES_BASE)
+}
+
+/// Whether the thread's stack holds more values than it is allowed to.
+pub fn overflowed() -> bool {
+    VALUES.with(|values| values.borrow().len()) > max_values()
+}
+
+pub fn stack_overflow(message: String) -> Arc<Strawberry<LeBlancObject>> {
+    LeblancError::new("StackOverflowException".to_string(), message, vec![]).create_mutex()
+}
+
+/// The operand stack of one call: the part of the thread's stack above the values of the call
+/// it was made from. Values left on it are dropped when the call ends.
+#[derive(Debug)]
+pub struct Stack {
+    base: usize
+}
+
+impl Stack {
+    /// Starts an empty stack on top of the thread's stack.
+    pub fn enter() -> Stack {
+        Stack { base: VALUES.with(|values| values.borrow().len()) }
+    }
+
+    /// Starts a stack holding `values`, e.g. the values a generator had when it last stopped.
+    pub fn restore(values: Vec<Value>) -> Stack {
+        let stack = Stack::enter();
+        VALUES.with(|stack_values| stack_values.borrow_mut().extend(values));
+        stack
+    }
+
+    /// Takes every value off the stack, in order.
+    pub fn save(&mut self) -> Vec<Value> {
+        VALUES.with(|values| {
+            let mut values = values.borrow_mut();
+            if values.len() > self.base { values.split_off(self.base) } else { vec![] }
+        })
+    }
+
+    #[inline(always)]
+    pub fn push(&mut self, object: Arc<Strawberry<LeBlancObject>>) {
+        self.push_value(Value::Object(object))
+    }
+
+    #[inline(always)]
+    pub fn push_value(&mut self, value: Value) {
+        VALUES.with(|values| values.borrow_mut().push(value))
+    }
+
+    /// The top value as an object, or `None` when this call's part of the stack is empty.
+    #[inline(always)]
+    pub fn pop(&mut self) -> Option<Arc<Strawberry<LeBlancObject>>> {
+        self.pop_value().map(Value::into_object)
+    }
+
+    /// The top value, or `None` when this call's part of the stack is empty.
+    #[inline(always)]
+    pub fn pop_value(&mut self) -> Option<Value> {
+        VALUES.with(|values| {
+            let mut values = values.borrow_mut();
+            if values.len() > self.base { values.pop() } else { None }
+        })
+    }
+
+    pub fn last(&self) -> Option<Value> {
+        VALUES.with(|values| {
+            let values = values.borrow();
+            if values.len() > self.base { values.last().cloned() } else { None }
+        })
+    }
+
+    pub fn get(&self, index: usize) -> Option<Value> {
+        VALUES.with(|values| values.borrow().get(self.base + index).cloned())
+    }
+
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        VALUES.with(|values| values.borrow().len()) - self.base
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Drops the values above the first `len`.
+    pub fn truncate(&mut self, len: usize) {
+        // The values are dropped once the stack is no longer borrowed.
+        let dropped = VALUES.with(|values| {
+            let mut values = values.borrow_mut();
+            if values.len() > self.base + len { values.split_off(self.base + len) } else { vec![] }
+        });
+        drop(dropped);
+    }
+
+    pub fn clear(&mut self) {
+        self.truncate(0)
+    }
+}
+
+impl Drop for Stack {
+    fn drop(&mut self) {
+        self.clear()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::leblanc::core::interpreter::value::Value;
+    use super::Stack;
+
+    fn contents(stack: &Stack) -> Vec<String> {
+        (0..stack.len()).map(|index| stack.get(index).unwrap().to_string()).collect()
+    }
+
+    #[test]
+    fn calls_only_see_their_own_values() {
+        let mut outer = Stack::enter();
+        outer.push_value(Value::Int(1));
+        outer.push_value(Value::Int(2));
+        {
+            let mut inner = Stack::enter();
+            assert!(inner.is_empty() && inner.last().is_none() && inner.pop_value().is_none());
+            inner.push_value(Value::Int(3));
+            assert_eq!(contents(&inner), ["3"]);
+        }
+        assert_eq!(contents(&outer), ["1", "2"]);
+        assert_eq!(outer.pop_value().unwrap().to_string(), "2");
+    }
+
+    #[test]
+    fn saved_values_are_restored_in_order() {
+        let mut first = Stack::enter();
+        (1..=3).for_each(|n| first.push_value(Value::Int(n)));
+        let saved = first.save();
+        assert!(first.is_empty());
+        drop(first);
+        let restored = Stack::restore(saved);
+        assert_eq!(contents(&restored), ["1", "2", "3"]);
+    }
+
+    #[test]
+    fn truncating_keeps_the_values_below() {
+        let mut stack = Stack::enter();
+        (1..=4).for_each(|n| stack.push_value(Value::Int(n)));
+        stack.truncate(2);
+        assert_eq!(contents(&stack), ["1", "2"]);
+        stack.truncate(5);
+        assert_eq!(stack.len(), 2);
+        stack.clear();
+        assert!(stack.is_empty());
+    }
+}
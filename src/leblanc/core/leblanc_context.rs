@@ -35,13 +35,7 @@ impl VariableContext {
 
 impl Clone for VariableContext {
     fn clone(&self) -> Self {
-        VariableContext {
-            name: self.name,
-            line_number: self.line_number,
-            file: self.file,
-            state: self.state,
-            relationship: self.relationship
-        }
+        *self
     }
 }
 
@@ -1,18 +1,16 @@
+use lazy_static::lazy_static;
 use crate::leblanc::rustblanc::strawberry::Strawberry;
-use std::sync::{Arc, Mutex};
+use std::sync::Arc;
 use crate::leblanc::core::leblanc_object::LeBlancObject;
 use fxhash::FxHashMap;
 
-static mut EMPTY_MEMBERS: Option<Arc<Strawberry<FxHashMap<String, LeBlancObject>>>> = None;
+/// The fields of an object, by name.
+type Members = Arc<Strawberry<FxHashMap<String, Arc<Strawberry<LeBlancObject>>>>>;
 
-pub fn unsafe_empty_members() -> Arc<Strawberry<FxHashMap<String, LeBlancObject>>>{
-    unsafe {
-        match &EMPTY_MEMBERS {
-            Some(members) => members.clone(),
-            None => {
-                EMPTY_MEMBERS = Some(Arc::new(Strawberry::new(FxHashMap::default())));
-                EMPTY_MEMBERS.as_ref().unwrap().clone()
-            }
-        }
-    }
-}
\ No newline at end of file
+lazy_static! {
+    static ref EMPTY_MEMBERS: Members = Arc::new(Strawberry::new(FxHashMap::default()));
+}
+
+pub fn unsafe_empty_members() -> Members {
+    EMPTY_MEMBERS.clone()
+}
@@ -1,52 +1,97 @@
 use fxhash::{FxHashMap};
+use std::cell::Cell;
+use std::sync::atomic::{AtomicU64, Ordering};
 
 
-use alloc::rc::Rc;
-use std::cell::RefCell;
-use std::future::Future;
 use crate::leblanc::rustblanc::strawberry::Strawberry;
-use std::sync::{Arc, Mutex};
+use std::sync::Arc;
 
 
-use arrayvec::ArrayVec;
+use crate::leblanc::core::interpreter::value::Value;
+use crate::leblanc::core::interpreter::vm_stack;
+use crate::leblanc::core::interpreter::vm_stack::{max_calls, max_values, stack_overflow, Stack};
 use smol_str::SmolStr;
+use crate::leblanc::core::bytecode::exception_table::ExceptionHandler;
 use crate::leblanc::core::bytecode::function_bytes::FunctionBytecode;
 
 use crate::leblanc::core::interpreter::instruction_execution::execute_instruction;
 use crate::leblanc::core::interpreter::instructions::{Instruction, InstructionBase};
+use crate::leblanc::core::interpreter::jump_table::JumpTable;
 use crate::leblanc::core::leblanc_context::VariableContext;
-use crate::leblanc::core::leblanc_object::{ArcToRc, LeBlancObject, QuickUnwrap, RustDataCast, Stringify};
-use crate::leblanc::core::native_types::error_type::LeblancError;
-use crate::leblanc::include::lib::leblanc_colored::{Color, colorize};
+use crate::leblanc::core::leblanc_object::{LeBlancObject, QuickUnwrap, RustDataCast};
+use crate::leblanc::core::native_types::base_type::ToLeblanc;
+use crate::leblanc::core::native_types::block_type::NativeBlock;
+use crate::leblanc::core::internal::methods::builtins::builtin_execute::outside_block;
+use crate::leblanc::core::native_types::error_type::{LeblancError, TraceFrame};
+use crate::leblanc::rustblanc::copystring::CopyString;
 
-use crate::leblanc::rustblanc::utils::{Timings};
 
-static DEBUG: bool = true;
-static TIME_DEBUG: bool = false;
-static STACK_DEBUG: bool = true;
-static mut GLOBAL_SIGNAL: ExecutionSignal = ExecutionSignal::Normal;
+static NEXT_FRAME: AtomicU64 = AtomicU64::new(1);
 
-static mut TIMINGS: Timings = Timings { map: None};
+thread_local! {
+    /// How many calls are running on this thread.
+    static CALLS: Cell<usize> = const { Cell::new(0) };
+}
 
-static mut LAMBDA_HANDLE: Option<LeblancHandle> = None;
+/// Counts a call in `CALLS` while it runs, including when it leaves early with an error.
+struct FrameGuard;
 
-static mut COUNT: u32 = 0;
+impl FrameGuard {
+    /// Fails with a `StackOverflowException` when calls are already nested as deep as allowed, or
+    /// the calls they were made from left more values on the stack than allowed. The values a
+    /// single call can push are bounded by its code, so the stack is only checked here.
+    fn enter(handle: &mut LeblancHandle) -> Result<FrameGuard, Arc<Strawberry<LeBlancObject>>> {
+        if CALLS.with(Cell::get) >= max_calls() {
+            return Err(stack_overflow(format!("Calls are nested deeper than the limit of {}", max_calls())));
+        }
+        if vm_stack::overflowed() {
+            return Err(stack_overflow(format!("The operand stack grew past its limit of {} values", max_values())));
+        }
+        handle.frame = NEXT_FRAME.fetch_add(1, Ordering::Relaxed);
+        CALLS.with(|calls| calls.set(calls.get() + 1));
+        Ok(FrameGuard)
+    }
+}
 
-#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
-pub enum ExecutionSignal {
-    Normal,
-    Exception,
-    Terminated
+impl Drop for FrameGuard {
+    fn drop(&mut self) {
+        CALLS.with(|calls| calls.set(calls.get() - 1));
+    }
+}
+
+/// A variable shared between a call and the closures it creates. Assigning it replaces the object
+/// inside, so every closure sees the new value.
+pub type Upvalue = Arc<Strawberry<Arc<Strawberry<LeBlancObject>>>>;
+
+/// Why `LeblancHandle::step` stopped running instructions.
+enum Exit {
+    /// It reached a `Return`, which leaves the value to return on the stack.
+    Returned,
+    /// It reached a `Yield`, which leaves the value to yield on the stack.
+    Yielded,
+    /// It ran out of instructions.
+    Finished
 }
 
 #[derive(Debug, Default)]
 pub struct LeblancHandle {
     pub name: SmolStr,
-    pub constants: Arc<Vec<Arc<Strawberry<LeBlancObject>>>>,
+    /// The source file the function was compiled from, for tracebacks.
+    pub file: CopyString,
+    pub constants: Arc<Vec<Value>>,
+    /// The jump tables among the constants, by constant index. The constant itself only holds
+    /// a description of the table, for the disassembler.
+    pub jump_tables: Arc<FxHashMap<u16, JumpTable>>,
     pub variable_context: Arc<FxHashMap<String, VariableContext>>,
-    pub variables: Vec<Arc<Strawberry<LeBlancObject>>>,
+    pub variables: Vec<Value>,
+    /// The upvalues of the enclosing calls this closure was created in, followed by the ones of the
+    /// running call.
+    pub upvalues: Vec<Upvalue>,
     pub instructions: Arc<Vec<Instruction>>,
+    pub handlers: Arc<Vec<ExceptionHandler>>,
     pub current_instruct: u64,
+    /// Identifies the running call, for the blocks it creates. Zero when not running.
+    pub frame: u64,
     pub null: bool,
     pub is_async: bool,
 }
@@ -63,11 +108,16 @@ impl LeblancHandle {
     pub fn null() -> LeblancHandle {
         LeblancHandle {
             name: SmolStr::default(),
+            file: CopyString::default(),
             constants: Arc::new(vec![]),
+            jump_tables: Arc::new(FxHashMap::default()),
             variable_context: Arc::new(FxHashMap::default()),
             variables: vec![],
+            upvalues: vec![],
             instructions: Arc::new(vec![]),
+            handlers: Arc::new(vec![]),
             current_instruct: 0,
+            frame: 0,
             null: true,
             is_async: false
         }
@@ -77,17 +127,33 @@ impl LeblancHandle {
         let mut instructs: Vec<Instruction> = vec![];
         bytecode.instruction_lines().into_iter().map(|line| line.to_instructions()).for_each(|mut l| instructs.append(&mut l));
         let instructs = Arc::new(instructs);
-        let constants: Vec<Arc<Strawberry<LeBlancObject>>> = bytecode.constants().into_iter().map(|constant| Arc::new(Strawberry::new(constant.to_leblanc_object()))).collect::<Vec<Arc<Strawberry<LeBlancObject>>>>();
+        let mut jump_tables = FxHashMap::default();
+        let constants: Vec<Value> = bytecode.constants().into_iter().enumerate().map(|(index, constant)| {
+            match constant.jump_table() {
+                Some(table) => {
+                    let description = table.to_string().create_mutex();
+                    jump_tables.insert(index as u16, table);
+                    Value::Object(description)
+                }
+                None => Value::from_object(Arc::new(Strawberry::new(constant.to_leblanc_object())))
+            }
+        }).collect::<Vec<Value>>();
         let variable_context = bytecode.variables();
+        let handlers = bytecode.handlers();
         let name = SmolStr::new(bytecode.name());
         let context_length = variable_context.len();
         LeblancHandle {
             name,
+            file: CopyString::default(),
             constants: Arc::new(constants),
+            jump_tables: Arc::new(jump_tables),
             variable_context: Arc::new(variable_context),
             variables: Vec::with_capacity(context_length),
+            upvalues: vec![],
             instructions: instructs,
+            handlers: Arc::new(handlers),
             current_instruct: 0,
+            frame: 0,
             null: false,
             is_async: false
         }
@@ -95,194 +161,144 @@ impl LeblancHandle {
 
     #[inline(always)]
     pub fn execute(&mut self, inputs: &mut [Arc<Strawberry<LeBlancObject>>]) -> Arc<Strawberry<LeBlancObject>> {
-        inputs.clone_into(&mut self.variables);
+        self.variables = inputs.iter().map(|input| Value::Object(input.clone()).into_variable()).collect();
+        self.run().into_object()
+    }
+
+    /// Runs the call on values as the VM holds them, so primitives going in and out of a call
+    /// made from another one aren't boxed.
+    pub fn execute_values(&mut self, inputs: Vec<Value>) -> Value {
+        self.variables = inputs.into_iter().map(Value::into_variable).collect();
+        self.run()
+    }
+
+    /// Runs the function from its first instruction on the variables already set. An error leaving
+    /// the call is its value.
+    fn run(&mut self) -> Value {
         self.current_instruct = 0;
-        let mut instruction = Instruction::empty();
-        let stack_trace = ArrayVec::<_, 50>::new();
-        let mut stack = ArrayVec::<_, 80>::new();
-        while self.current_instruct < self.instructions.len() as u64 {
-            let _last_instruct = instruction;
-            instruction = self.instructions[self.current_instruct as usize];
-            if DEBUG {println!("{} Normal Instruction: {:?}", colorize(self.name.to_string(), Color::Blue), instruction);}
-            match instruction.instruct {
-                InstructionBase::Return => return stack.pop().unwrap(),
-                /*InstructionBase::CallFunction => {
-                    if last_instruct.instruct == InstructionBase::LoadFunction {
-                        stack_trace.push(last_instruct)
-                    }
-                }*/
-                _ => {}
-            }
-            unsafe {if GLOBAL_SIGNAL == ExecutionSignal::Exception { return dump_stack_trace(stack.pop().unwrap(), stack_trace.to_vec()); }}
-            //if TIME_DEBUG { unsafe {TIMINGS.lock(instruction.instruct.to_string())} }
-            let internal_handle = execute_instruction(instruction.instruct);
-            //let now = Instant::now();
-            match internal_handle(self, &instruction, &mut stack) {
-                Ok(_) => {},
-                Err(err) => {
-                    println!("Errored");
-                    let mut borrowed_error = err.lock();
-                    let error: &mut LeblancError = borrowed_error.data.mut_data().unwrap();
-                    error.add_prior_trace(stack_trace.to_vec());
-                    drop(borrowed_error);
-                    unsafe { GLOBAL_SIGNAL = ExecutionSignal::Exception }
-                    return err
-                }
-            };
-            if STACK_DEBUG { println!("{} Stack: {}", colorize(self.name.to_string(), Color::Blue), if stack.len() > 0 {stack.get(stack.len()-1).unwrap_or(&LeBlancObject::unsafe_null()).to_string()} else { LeBlancObject::unsafe_null().to_string()});}
-            /*if TIME_DEBUG {
-                let duration = now.elapsed().as_secs_f64();
-                unsafe { TIMINGS.add_timing(instruction.instruct.to_string(), duration); }
-            }*/
-            self.current_instruct += 1;
+        let _frame = match FrameGuard::enter(self) {
+            Ok(frame) => frame,
+            Err(error) => return Value::Object(error)
+        };
+        let call = self.frame;
+        let mut stack = Stack::enter();
+        match self.step(self.instructions.len() as u64, call, &mut stack, false) {
+            Ok(Exit::Returned) => stack.pop_value().unwrap(),
+            Ok(_) => stack.pop_value().unwrap_or_else(|| Value::Object(LeBlancObject::unsafe_null())),
+            Err(error) => Value::Object(error)
         }
-        /*if self.name == "main" && TIME_DEBUG {
-            unsafe { TIMINGS.print_timing(); }
-        }*/
-        unsafe {if GLOBAL_SIGNAL == ExecutionSignal::Exception { return dump_stack_trace(stack.pop().unwrap(), stack_trace.to_vec()); }}
-        stack.pop().unwrap_or_else(LeBlancObject::unsafe_null)
-
     }
 
-    pub fn execute_range(&mut self, left_bound: u64, right_bound: u64) -> Arc<Strawberry<LeBlancObject>> {
-        self.current_instruct = left_bound;
-        let mut last_instruct = Instruction::empty();
-        let mut instruction = Instruction::empty();
-        let mut stack_trace = ArrayVec::<_, 50>::new();
-        let mut stack = ArrayVec::<_, 80>::new();
-        while self.current_instruct < right_bound {
-            last_instruct = instruction;
-            instruction = self.instructions[self.current_instruct as usize];
-            if DEBUG {println!("{} Range Instruction: {:?}", colorize(self.name.to_string(), Color::Blue), instruction);}
+    /// Runs instructions from the current one until one before `end`, a `Return` or, for a
+    /// generator, a `Yield`. An error raised by an instruction goes to the innermost handler
+    /// covering it, and leaves the loop once none does.
+    fn step(&mut self, end: u64, call: u64, stack: &mut Stack, generator: bool) -> Result<Exit, Arc<Strawberry<LeBlancObject>>> {
+        while self.current_instruct < end {
+            let instruction = self.instructions[self.current_instruct as usize];
             match instruction.instruct {
-                InstructionBase::Return => return stack.pop().unwrap(),
-                InstructionBase::CallFunction => {
-                    if last_instruct.instruct == InstructionBase::LoadFunction {
-                        stack_trace.push(last_instruct)
-                    }
-                }
+                InstructionBase::Return => return Ok(Exit::Returned),
+                InstructionBase::Yield if generator => return Ok(Exit::Yielded),
                 _ => {}
             }
-            unsafe {if GLOBAL_SIGNAL == ExecutionSignal::Exception { return dump_stack_trace(stack.pop().unwrap(), stack_trace.to_vec()); }}
-            //if TIME_DEBUG { unsafe {TIMINGS.lock(instruction.instruct.to_string())} }
             let internal_handle = execute_instruction(instruction.base());
-            //let now = Instant::now();
-            match internal_handle(self, &instruction, &mut stack) {
-                Ok(_) => {},
-                Err(err) => {
-                    println!("Exception");
-                    let mut borrowed_error = err.lock();
-                    let error: &mut LeblancError = borrowed_error.data.mut_data().unwrap();
-                    error.add_prior_trace(stack_trace.to_vec());
-                    drop(borrowed_error);
-                    unsafe { GLOBAL_SIGNAL = ExecutionSignal::Exception }
-                    return err
+            if let Err(err) = internal_handle(self, &instruction, stack) {
+                let err = dump_stack_trace(err, self.frame(&instruction), call);
+                match self.find_handler(&err) {
+                    Some(handler) => {
+                        stack.truncate(handler.depth as usize);
+                        stack.push(err);
+                        self.current_instruct = handler.target as u64;
+                        continue;
+                    }
+                    None => return Err(err)
                 }
-            };
-            if STACK_DEBUG { println!("{} Range Stack: {}", colorize(self.name.to_string(), Color::Blue), if stack.len() > 0 {stack.get(stack.len()-1).unwrap_or(&LeBlancObject::unsafe_null()).to_string()} else { LeBlancObject::unsafe_null().to_string()});}
-            /*if TIME_DEBUG {
-                let duration = now.elapsed().as_secs_f64();
-                unsafe { TIMINGS.add_timing(instruction.instruct.to_string(), duration); }
-            }*/
+            }
             self.current_instruct += 1;
         }
-        stack.pop().unwrap_or_else(LeBlancObject::unsafe_null)
-
+        Ok(Exit::Finished)
     }
 
-    pub fn execute_instructions(&mut self, instructs: &Vec<Instruction>, stack: &mut ArrayVec<Arc<Strawberry<LeBlancObject>>, 80>) -> Arc<Strawberry<LeBlancObject>> {
-        for instruct in instructs {
-            if instruct.instruct == InstructionBase::Return { return stack.pop().unwrap() };
-            let internal_handle = execute_instruction(instruct.base());
-            match internal_handle(self, instruct, stack) {
-                Ok(_) => {},
-                Err(_err) => {
-                }
-            };
-        }
-        stack.pop().unwrap_or_else(LeBlancObject::unsafe_null)
+    /// The traceback frame for `instruction` running in this function.
+    fn frame(&self, instruction: &Instruction) -> TraceFrame {
+        TraceFrame::new(self.name.to_string(), self.file.to_string(), instruction.line_number, instruction.column as u32)
     }
 
-    pub fn execute_from_last_point(&mut self) -> LeBlancObject {
-        self.execute_range(self.current_instruct, self.instructions.len() as u64).arc_unwrap()
+    /// The innermost handler covering the current instruction that catches `error`.
+    fn find_handler(&self, error: &Arc<Strawberry<LeBlancObject>>) -> Option<ExceptionHandler> {
+        if self.handlers.is_empty() { return None; }
+        let borrowed = error.lock();
+        let error: &LeblancError = borrowed.data.ref_data()?;
+        self.handlers.iter().find(|handler| handler.covers(self.current_instruct) && handler.catches(error)).cloned()
     }
 
-    pub fn execute_lambda(&mut self, inputs: &mut [Arc<Strawberry<LeBlancObject>>]) -> Arc<Strawberry<LeBlancObject>> {
-        inputs.clone_into(&mut self.variables);
-        let mut stack = ArrayVec::<_, 80>::new();
-        let length = self.instructions.len();
-        for i in 0..length {
-            let instruct = self.instructions[i];
-            if instruct.instruct == InstructionBase::Return { return stack.pop().unwrap() };
-            execute_instruction(instruct.instruct)(self, &instruct, &mut stack);
+    pub fn execute_range(&mut self, left_bound: u64, right_bound: u64) -> Arc<Strawberry<LeBlancObject>> {
+        self.current_instruct = left_bound;
+        // A block runs in the call that created it, but is a call of its own in tracebacks.
+        let call = NEXT_FRAME.fetch_add(1, Ordering::Relaxed);
+        let mut stack = Stack::enter();
+        match self.step(right_bound, call, &mut stack, false) {
+            Ok(Exit::Returned) => stack.pop().unwrap(),
+            Ok(_) => stack.pop().unwrap_or_else(LeBlancObject::unsafe_null),
+            Err(error) => error
         }
-        stack.pop().unwrap_or_else(LeBlancObject::unsafe_null)
+    }
 
+    /// Runs `block`, which must have been created by this call, and evaluates to its value. The
+    /// call carries on from where it was afterwards.
+    pub fn execute_block(&mut self, block: NativeBlock) -> Arc<Strawberry<LeBlancObject>> {
+        if block.frame != self.frame {
+            return outside_block();
+        }
+        let resume = self.current_instruct;
+        let value = self.execute_range(block.start, block.end);
+        self.current_instruct = resume;
+        value
     }
 
-    #[inline(always)]
-    pub async fn execute_async(&mut self, inputs: Vec<Arc<Strawberry<LeBlancObject>>>) -> Arc<Strawberry<LeBlancObject>> {
-        self.is_async = true;
-        inputs.clone_into(&mut self.variables);
-        self.current_instruct = 0;
-        let mut instruction = Instruction::empty();
-        let stack_trace = ArrayVec::<_, 50>::new();
-        let mut stack: ArrayVec<Arc<Strawberry<LeBlancObject>>, 80> = ArrayVec::<_, 80>::new();
-        while self.current_instruct < self.instructions.len() as u64 {
-            let _last_instruct = instruction;
-            instruction = self.instructions[self.current_instruct as usize];
-            //if DEBUG {println!("{} Async Instruction: {:?}", colorize(self.name.to_string(), Color::Blue), instruction);}
-            match instruction.instruct {
-                InstructionBase::Return => {
-                    //println!("Exiting Async: {:?}", stack.last().unwrap());
-                    return stack.pop().unwrap()
-                },
-                /*InstructionBase::CallFunction => {
-                    if last_instruct.instruct == InstructionBase::LoadFunction {
-                        stack_trace.push(last_instruct)
-                    }
-                }*/
-                _ => {}
-            }
-            unsafe {if GLOBAL_SIGNAL == ExecutionSignal::Exception { return dump_stack_trace(stack.pop().unwrap(), stack_trace.to_vec()); }}
-            //if TIME_DEBUG { unsafe {TIMINGS.lock(instruction.instruct.to_string())} }
-            let internal_handle = execute_instruction(instruction.instruct);
-            //let now = Instant::now();
-            match internal_handle(self, &instruction, &mut stack) {
-                Ok(_) => {},
-                Err(err) => {
-                    println!("Errored: {:#?}", err);
-                    let mut borrowed_error = err.lock();
-                    let error: &mut LeblancError = borrowed_error.data.mut_data().unwrap();
-                    error.add_prior_trace(stack_trace.to_vec());
-                    drop(borrowed_error);
-                    unsafe { GLOBAL_SIGNAL = ExecutionSignal::Exception }
-                    return err
-                }
-            };
-            //if STACK_DEBUG { println!("{} Stack: {}", colorize(self.name.to_string(), Color::Blue), if stack.len() > 0 {stack.get(stack.len()-1).unwrap_or(&LeBlancObject::unsafe_null()).to_string()} else { LeBlancObject::unsafe_null().to_string()});}
-            /*if TIME_DEBUG {
-                let duration = now.elapsed().as_secs_f64();
-                unsafe { TIMINGS.add_timing(instruction.instruct.to_string(), duration); }
-            }*/
+    /// Runs a generator's call on from where it last stopped, with the stack it had then, up to its
+    /// next `yield`. Gives the yielded value, or `None` once the call returns, after which the
+    /// generator is finished. An error leaving the call finishes it too. The values left on the
+    /// stack at a `yield` are moved to `saved` until the next time.
+    pub fn resume(&mut self, saved: &mut Vec<Value>) -> Result<Option<Arc<Strawberry<LeBlancObject>>>, Arc<Strawberry<LeBlancObject>>> {
+        let finished = self.instructions.len() as u64;
+        let _frame = FrameGuard::enter(self)?;
+        let call = self.frame;
+        let mut stack = Stack::restore(std::mem::take(saved));
+        let exit = self.step(finished, call, &mut stack, true);
+        if let Ok(Exit::Yielded) = exit {
             self.current_instruct += 1;
+            let value = stack.pop().unwrap_or_else(LeBlancObject::unsafe_null);
+            *saved = stack.save();
+            return Ok(Some(value));
         }
-        /*if self.name == "main" && TIME_DEBUG {
-            unsafe { TIMINGS.print_timing(); }
-        }*/
-        unsafe {if GLOBAL_SIGNAL == ExecutionSignal::Exception { return dump_stack_trace(stack.pop().unwrap(), stack_trace.to_vec()); }}
-        stack.pop().unwrap_or_else(LeBlancObject::unsafe_null)
+        self.current_instruct = finished;
+        exit.map(|_| None)
+    }
+
+    pub fn execute_lambda(&mut self, inputs: &mut [Arc<Strawberry<LeBlancObject>>]) -> Arc<Strawberry<LeBlancObject>> {
+        // Lambda bodies can contain jumps, so they run through the same loop as any other function.
+        self.execute(inputs)
+    }
 
+    /// Runs the call as a task of a group. Everything but the task it runs in is as for `execute`.
+    pub async fn execute_async(&mut self, mut inputs: Vec<Arc<Strawberry<LeBlancObject>>>) -> Arc<Strawberry<LeBlancObject>> {
+        self.is_async = true;
+        self.execute(&mut inputs)
     }
 
     pub fn full_clone(&self) -> LeblancHandle {
         LeblancHandle {
             name: self.name.clone(),
-            constants: Arc::new(self.constants.iter().map(|v| v.clone().arc_unwrap().to_mutex()).collect()),
+            file: self.file,
+            constants: Arc::new(self.constants.iter().map(Value::full_clone).collect()),
+            jump_tables: self.jump_tables.clone(),
             variable_context: self.variable_context.clone(),
-            variables: self.variables.iter().map(|v| v.clone().arc_unwrap().to_mutex()).collect(),
+            variables: self.variables.iter().map(Value::full_clone).collect(),
+            upvalues: self.upvalues.clone(),
             instructions: self.instructions.clone(),
+            handlers: self.handlers.clone(),
             current_instruct: 0,
+            frame: 0,
             null: false,
             is_async: self.is_async
         }
@@ -293,11 +309,16 @@ impl Clone for LeblancHandle {
     fn clone(&self) -> Self {
         LeblancHandle {
             name: self.name.clone(),
+            file: self.file,
             constants: self.constants.clone(),
+            jump_tables: self.jump_tables.clone(),
             variable_context: self.variable_context.clone(),
             variables: Vec::with_capacity(self.variables.capacity()),
+            upvalues: self.upvalues.clone(),
             instructions: self.instructions.clone(),
+            handlers: self.handlers.clone(),
             current_instruct: self.current_instruct,
+            frame: 0,
             null: self.null,
             is_async: self.is_async
         }
@@ -305,10 +326,14 @@ impl Clone for LeblancHandle {
 }
 
 
-pub fn dump_stack_trace(error: Arc<Strawberry<LeBlancObject>>, stack_trace: Vec<Instruction>) -> Arc<Strawberry<LeBlancObject>> {
-    let mut borrowed =  error.lock();
-    let lbe: &mut LeblancError = borrowed.data.mut_data().unwrap();
-    lbe.add_prior_trace(stack_trace);
+/// Records `frame` on an error raised in, or passing through, the call `call`, unless that call
+/// already recorded where the error was raised.
+pub fn dump_stack_trace(error: Arc<Strawberry<LeBlancObject>>, frame: TraceFrame, call: u64) -> Arc<Strawberry<LeBlancObject>> {
+    let mut borrowed = error.lock();
+    let lbe: Option<&mut LeblancError> = borrowed.data.mut_data();
+    if let Some(lbe) = lbe {
+        lbe.add_frame(call, frame);
+    }
     drop(borrowed);
     error
 }
@@ -1,21 +1,22 @@
+use lazy_static::lazy_static;
 use alloc::rc::Rc;
 use std::any::Any;
-use std::cell::{RefCell, RefMut};
+use std::cell::RefCell;
 
 use std::fmt::{Debug, Display, Formatter};
-use std::future::Future;
+use std::hash::{Hash, Hasher};
 use std::mem::{swap};
-use std::pin::Pin;
 
 
-use std::sync::{Arc, LockResult, MutexGuard, TryLockResult};
+use std::sync::{Arc, MutexGuard};
 use fxhash::{FxHashMap, FxHashSet};
 use crate::leblanc::rustblanc::strawberry::Strawberry;
 
-use std::task::{Context, Poll};
 
+use num::BigInt;
 use smol_str::SmolStr;
 
+use crate::leblanc::core::extension::{extension_method, extension_method_no_args};
 use crate::leblanc::core::leblanc_argument::LeBlancArgument;
 use crate::leblanc::core::leblanc_context::VariableContext;
 use crate::leblanc::core::method::Method;
@@ -23,25 +24,28 @@ use crate::leblanc::core::module::Module;
 use crate::leblanc::core::native_types::base_type::ToLeblanc;
 use crate::leblanc::core::native_types::block_type::NativeBlock;
 use crate::leblanc::core::native_types::class_type::ClassMeta;
+use crate::leblanc::core::native_types::decimal_type::LeblancDecimal;
+use crate::leblanc::core::native_types::enum_type::LeblancEnum;
 
 use crate::leblanc::core::native_types::derived::iterator_type::{LeblancIterator};
+use crate::leblanc::core::native_types::derived::dict_type::LeblancDict;
+use crate::leblanc::core::native_types::derived::set_type::LeblancSet;
+use crate::leblanc::core::native_types::derived::tuple_type::LeblancTuple;
 use crate::leblanc::core::native_types::derived::list_type::LeblancList;
 use crate::leblanc::core::native_types::error_type::{leblanc_object_error, LeblancError};
 use crate::leblanc::core::native_types::group_type::LeblancGroup;
 
 use crate::leblanc::core::native_types::LeBlancType;
-use crate::leblanc::core::native_types::promise_type::{ArcLeblancPromise, LeblancPromise};
+use crate::leblanc::core::native_types::promise_type::ArcLeblancPromise;
 use crate::leblanc::core::native_types::rust_type::RustObject;
 use crate::leblanc::rustblanc::Appendable;
 
 
-static mut NULL: Option<Arc<Strawberry<LeBlancObject>>> = None;
-
-static mut ERROR: Option<Arc<Strawberry<LeBlancObject>>> = None;
-
-static mut MARKER: Option<Arc<Strawberry<LeBlancObject>>> = None;
-
-static mut NO_ARGS: [Arc<Strawberry<LeBlancObject>>; 0] = [];
+lazy_static! {
+    static ref NULL: Arc<Strawberry<LeBlancObject>> = LeBlancObject::null().to_mutex();
+    static ref ERROR: Arc<Strawberry<LeBlancObject>> = LeBlancObject::error().to_mutex();
+    static ref MARKER: Arc<Strawberry<LeBlancObject>> = LeBlancObject::marker().to_mutex();
+}
 
 pub trait Callable {
     fn call(&mut self, method_name: &str, arguments: &mut [Arc<Strawberry<LeBlancObject>>]) -> Result<Arc<Strawberry<LeBlancObject>>, Arc<Strawberry<LeBlancObject>>>;
@@ -63,12 +67,12 @@ pub struct LeBlancObject {
     pub data: LeBlancObjectData,
     pub(crate) typing: LeBlancType,
     pub methods: Arc<FxHashSet<Method>>,
-    pub members: Arc<Strawberry<FxHashMap<String, LeBlancObject>>>,
+    pub members: Arc<Strawberry<FxHashMap<String, Arc<Strawberry<LeBlancObject>>>>>,
     pub context: VariableContext
 }
 
 impl LeBlancObject {
-    pub fn new(data: LeBlancObjectData, typing: LeBlancType, methods: Arc<FxHashSet<Method>>, members: Arc<Strawberry<FxHashMap<String, LeBlancObject>>>, context: VariableContext) -> LeBlancObject {
+    pub fn new(data: LeBlancObjectData, typing: LeBlancType, methods: Arc<FxHashSet<Method>>, members: Arc<Strawberry<FxHashMap<String, Arc<Strawberry<LeBlancObject>>>>>, context: VariableContext) -> LeBlancObject {
         LeBlancObject {data, typing, methods, members, context}
     }
 
@@ -85,17 +89,7 @@ impl LeBlancObject {
     }
 
     pub fn unsafe_null() -> Arc<Strawberry<LeBlancObject>> {
-        return unsafe {
-            match NULL.as_ref() {
-                None => {
-                    NULL = Some(LeBlancObject::null().to_mutex());
-                    NULL.as_ref().unwrap().clone()
-                }
-                Some(null) => {
-                    null.clone()
-                }
-            }
-        }
+        NULL.clone()
     }
 
     pub fn marker() -> LeBlancObject {
@@ -109,17 +103,7 @@ impl LeBlancObject {
     }
 
     pub fn unsafe_marker() -> Arc<Strawberry<LeBlancObject>> {
-        return unsafe {
-            match MARKER.as_ref() {
-                None => {
-                    MARKER = Some(LeBlancObject::marker().to_mutex());
-                    MARKER.as_ref().unwrap().clone()
-                }
-                Some(marker) => {
-                    marker.clone()
-                }
-            }
-        }
+        MARKER.clone()
     }
 
     pub fn error() -> LeBlancObject {
@@ -133,17 +117,7 @@ impl LeBlancObject {
     }
 
     pub fn unsafe_error() -> Arc<Strawberry<LeBlancObject>> {
-        return unsafe {
-            match ERROR.as_ref() {
-                None => {
-                    ERROR = Some(LeBlancObject::error().to_mutex());
-                    ERROR.as_ref().unwrap().clone()
-                }
-                Some(error) => {
-                    error.clone()
-                }
-            }
-        }
+        ERROR.clone()
     }
 
     pub fn error2() -> LeBlancObject {
@@ -162,6 +136,7 @@ impl LeBlancObject {
                     "NOT_IMPLEMENTED".to_string()
                 }
             }
+            LeBlancType::Enum(name) => name.to_string(),
             _ => self.typing.as_str().to_string()
         }
     }
@@ -204,16 +179,16 @@ impl LeBlancObject {
 
     pub fn cast(&self, cast: LeBlancType) -> LeBlancObject {
         let object_data = match cast {
-            LeBlancType::Char => LeBlancObjectData::Char(unsafe {*self.reflect().downcast_ref_unchecked()}),
-            LeBlancType::Short => LeBlancObjectData::Short(unsafe {*self.reflect().downcast_ref_unchecked()}),
-            LeBlancType::Int => LeBlancObjectData::Int(unsafe {*self.reflect().downcast_ref_unchecked()}),
-            LeBlancType::Int64 => LeBlancObjectData::Int64(unsafe {*self.reflect().downcast_ref_unchecked()}),
-            LeBlancType::Int128 => LeBlancObjectData::Int128(unsafe {*self.reflect().downcast_ref_unchecked()}),
-            LeBlancType::Arch => LeBlancObjectData::Arch(unsafe {*self.reflect().downcast_ref_unchecked()}),
-            LeBlancType::Float => LeBlancObjectData::Float(unsafe {*self.reflect().downcast_ref_unchecked()}),
-            LeBlancType::Double => LeBlancObjectData::Double(unsafe {*self.reflect().downcast_ref_unchecked()}),
-            LeBlancType::Boolean => LeBlancObjectData::Boolean(unsafe {*self.reflect().downcast_ref_unchecked()}),
-            LeBlancType::String => LeBlancObjectData::String((unsafe {self.reflect().downcast_ref_unchecked::<SmolStr>()}).clone()),
+            LeBlancType::Char => LeBlancObjectData::Char(unsafe {*self.reflect().downcast_unchecked_ref()}),
+            LeBlancType::Short => LeBlancObjectData::Short(unsafe {*self.reflect().downcast_unchecked_ref()}),
+            LeBlancType::Int => LeBlancObjectData::Int(unsafe {*self.reflect().downcast_unchecked_ref()}),
+            LeBlancType::Int64 => LeBlancObjectData::Int64(unsafe {*self.reflect().downcast_unchecked_ref()}),
+            LeBlancType::Int128 => LeBlancObjectData::Int128(unsafe {*self.reflect().downcast_unchecked_ref()}),
+            LeBlancType::Arch => LeBlancObjectData::Arch(unsafe {*self.reflect().downcast_unchecked_ref()}),
+            LeBlancType::Float => LeBlancObjectData::Float(unsafe {*self.reflect().downcast_unchecked_ref()}),
+            LeBlancType::Double => LeBlancObjectData::Double(unsafe {*self.reflect().downcast_unchecked_ref()}),
+            LeBlancType::Boolean => LeBlancObjectData::Boolean(unsafe {*self.reflect().downcast_unchecked_ref()}),
+            LeBlancType::String => LeBlancObjectData::String((unsafe {self.reflect().downcast_unchecked_ref::<SmolStr>()}).clone()),
             _ => LeBlancObjectData::Null
         };
         LeBlancObject::new(
@@ -247,12 +222,47 @@ impl LeBlancObject {
 impl PartialEq for LeBlancObject {
     fn eq(&self, other: &Self) -> bool {
         if self.data != other.data { return false }
-        if !self.members.lock().eq(&other.members.lock()) { return false }
+        if !Arc::ptr_eq(&self.members, &other.members) {
+            let members = self.members.lock();
+            let other_members = other.members.lock();
+            if members.len() != other_members.len() { return false }
+            let same = members.iter().all(|(name, member)| match other_members.get(name) {
+                Some(other_member) => Arc::ptr_eq(member, other_member) || *member.lock() == *other_member.lock(),
+                None => false
+            });
+            if !same { return false }
+        }
         self.typing == other.typing
     }
 }
 
 
+/// Consistent with equality: objects that are equal hash the same. Only the values of primitives,
+/// strings, lists and tuples are hashed, other objects only by their kind of data.
+impl Hash for LeBlancObject {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(&self.data).hash(state);
+        match &self.data {
+            LeBlancObjectData::Char(value) => value.hash(state),
+            LeBlancObjectData::Short(value) => value.hash(state),
+            LeBlancObjectData::Int(value) => value.hash(state),
+            LeBlancObjectData::Int64(value) => value.hash(state),
+            LeBlancObjectData::Int128(value) => value.hash(state),
+            LeBlancObjectData::Arch(value) => value.hash(state),
+            LeBlancObjectData::BigInt(value) => value.hash(state),
+            LeBlancObjectData::Decimal(value) => value.hash(state),
+            // Zero and negative zero are equal but differ in their bits.
+            LeBlancObjectData::Float(value) => (if *value == 0.0 { 0.0 } else { *value }).to_bits().hash(state),
+            LeBlancObjectData::Double(value) => (if *value == 0.0 { 0.0 } else { *value }).to_bits().hash(state),
+            LeBlancObjectData::Boolean(value) => value.hash(state),
+            LeBlancObjectData::String(value) => value.hash(state),
+            LeBlancObjectData::List(list) => list.internal_vec.iter().for_each(|item| item.lock().hash(state)),
+            LeBlancObjectData::Tuple(tuple) => tuple.values.iter().for_each(|item| item.lock().hash(state)),
+            _ => {}
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, PartialOrd, Default)]
 pub enum LeBlancObjectData {
     Flex(&'static LeBlancObjectData),
@@ -262,14 +272,17 @@ pub enum LeBlancObjectData {
     Int64(i64),
     Int128(i128),
     Arch(isize),
+    BigInt(BigInt),
     Float(f32), //"double32" -- internally f32
     Double(f64), // internally f64
+    Decimal(LeblancDecimal),
     Boolean(bool),
     String(SmolStr),
     Block(NativeBlock),
     Function(Box<Method>),
     Module(Module),
     Class(Box<ClassMeta>), // User defined class with ID
+    Enum(Box<LeblancEnum>),
     Dynamic(&'static LeBlancObjectData),
     Rust(RustObject),
 
@@ -277,6 +290,9 @@ pub enum LeBlancObjectData {
     Group(LeblancGroup),
 
     List(LeblancList),
+    Dict(LeblancDict),
+    Tuple(LeblancTuple),
+    Set(LeblancSet),
     Iterator(LeblancIterator),
     Error(Box<LeblancError>),
     #[default]
@@ -292,12 +308,17 @@ impl Reflect for LeBlancObject {
             LeBlancObjectData::Int64(item) => Box::new(*item),
             LeBlancObjectData::Int128(item) => Box::new(*item),
             LeBlancObjectData::Arch(item) => Box::new(*item),
+            LeBlancObjectData::BigInt(item) => Box::new(item.clone()),
             LeBlancObjectData::Float(item) => Box::new(*item),
             LeBlancObjectData::Double(item) => Box::new(*item),
+            LeBlancObjectData::Decimal(item) => Box::new(item.clone()),
             LeBlancObjectData::Boolean(item) => Box::new(*item),
             LeBlancObjectData::String(item) => Box::new(item.clone()),
             LeBlancObjectData::Function(item) => Box::new(item.clone()),
             LeBlancObjectData::List(item) => Box::new(item.clone()),
+            LeBlancObjectData::Dict(item) => Box::new(item.clone()),
+            LeBlancObjectData::Tuple(item) => Box::new(item.clone()),
+            LeBlancObjectData::Set(item) => Box::new(item.clone()),
             LeBlancObjectData::Iterator(item) => Box::new(item.clone()),
             _ => Box::new(0),
         };
@@ -311,10 +332,10 @@ impl Reflect for Arc<Strawberry<LeBlancObject>> {
     }
 }
 
-pub fn passed_args_to_types(args: &Vec<Arc<Strawberry<LeBlancObject>>>) -> Vec<LeBlancArgument> {
+pub fn passed_args_to_types(args: &[Arc<Strawberry<LeBlancObject>>]) -> Vec<LeBlancArgument> {
     let mut arg_types = Vec::new();
-    for i in 0..args.len() {
-        arg_types.append_item( args[i].lock().to_leblanc_arg(i as u32));
+    for (i, arg) in args.iter().enumerate() {
+        arg_types.append_item(arg.lock().to_leblanc_arg(i as u32));
     }
     arg_types
 
@@ -330,16 +351,22 @@ impl Display for LeBlancObjectData {
             LeBlancObjectData::Int64(data) => data.to_string(),
             LeBlancObjectData::Int128(data) => data.to_string(),
             LeBlancObjectData::Arch(data) => data.to_string(),
+            LeBlancObjectData::BigInt(data) => data.to_string(),
             LeBlancObjectData::Float(data) => data.to_string(),
             LeBlancObjectData::Double(data) => data.to_string(),
+            LeBlancObjectData::Decimal(data) => data.to_string(),
             LeBlancObjectData::Boolean(data) => data.to_string(),
             LeBlancObjectData::String(data) => data.to_string(),
             LeBlancObjectData::Block(data) => data.to_string(),
             LeBlancObjectData::Function(data) => data.to_string(),
             LeBlancObjectData::Module(data) => data.to_string(),
             LeBlancObjectData::Class(data) => data.to_string(),
+            LeBlancObjectData::Enum(data) => data.to_string(),
             LeBlancObjectData::Dynamic(data) => data.to_string(),
             LeBlancObjectData::List(data) => data.to_string(),
+            LeBlancObjectData::Dict(data) => data.to_string(),
+            LeBlancObjectData::Tuple(data) => data.to_string(),
+            LeBlancObjectData::Set(data) => data.to_string(),
             LeBlancObjectData::Promise(data) => data.to_string(),
             LeBlancObjectData::Group(data) => data.to_string(),
             LeBlancObjectData::Iterator(data) => data.to_string(),
@@ -353,14 +380,18 @@ impl Display for LeBlancObjectData {
 
 impl Callable for Arc<Strawberry<LeBlancObject>> {
     fn call(&mut self, method_name: &str, arguments: &mut [Arc<Strawberry<LeBlancObject>>]) -> Result<Arc<Strawberry<LeBlancObject>>, Arc<Strawberry<LeBlancObject>>> {
+        if self.lock().typing == LeBlancType::Null {
+            return Err(LeblancError::new("OperationOnNullException".to_string(), format!("Cannot call {} on null", method_name), vec![]).create_mutex());
+        }
         let argument_vec = arguments.to_vec();
         let args = passed_args_to_types(&argument_vec);
 
 
         //let self_clone = Arc::clone(self);
-        let method = self.lock().methods.iter().filter(|m| {
+        let method = self.lock().methods.iter().find(|m| {
             m.matches(method_name.to_string(), &args)
-        }).next().cloned();
+        }).cloned();
+        let method = method.or_else(|| extension_method(self.lock().typing, method_name, &args));
         if method.is_none() {
             return Err(LeblancError::new("ClassMethodNotFoundException".to_string(), format!("Method {} not found in {}", method_name, self.lock().typing),vec![]).create_mutex());
         }
@@ -368,12 +399,13 @@ impl Callable for Arc<Strawberry<LeBlancObject>> {
     }
 
     fn call_name(&mut self, method_name: &str) -> Result<Arc<Strawberry<LeBlancObject>>, Arc<Strawberry<LeBlancObject>>> {
-        if self.lock().typing == LeBlancType::Null { return Err(LeblancError::new("OperationOnNullException".to_string(), "".to_string(), vec![]).create_mutex())}
-        let handle = match self.lock().methods.iter().find(|m| m.context.name == method_name) {
-            None => return Err(LeblancError::new("ClassMethodNotFoundException".to_string(), format!("Method {} not found in {}", method_name, self.lock().typing),vec![]).create_mutex()),
-            Some(some) => some.handle
-        };
-        Ok(unsafe { handle(self.clone(), &mut NO_ARGS) })
+        if self.lock().typing == LeBlancType::Null { return Err(LeblancError::new("OperationOnNullException".to_string(), format!("Cannot call {} on null", method_name), vec![]).create_mutex())}
+        let method = self.lock().methods.iter().find(|m| m.context.name == method_name && (m.is_internal_method() || m.context.arguments.is_empty())).cloned();
+        let method = method.or_else(|| extension_method_no_args(self.lock().typing, method_name));
+        match method {
+            None => Err(LeblancError::new("ClassMethodNotFoundException".to_string(), format!("Method {} not found in {}", method_name, self.lock().typing),vec![]).create_mutex()),
+            Some(mut method) => Ok(method.run(self.clone(), &mut []))
+        }
     }
 }
 
@@ -413,7 +445,7 @@ impl LeBlancObjectData {
             LeBlancObjectData::Short(item) => *item as i128,
             LeBlancObjectData::Int(item) => *item as i128,
             LeBlancObjectData::Int64(item) => *item as i128,
-            LeBlancObjectData::Int128(item) => *item as i128,
+            LeBlancObjectData::Int128(item) => *item,
             LeBlancObjectData::Arch(item) => *item as i128,
             LeBlancObjectData::Float(item) => *item as i128,
             LeBlancObjectData::Double(item) => *item as i128,
@@ -6,22 +6,23 @@ use std::hash::{Hash, Hasher};
 
 
 use crate::leblanc::core::leblanc_argument::LeBlancArgument;
-use crate::leblanc::core::leblanc_object::{ArcToRc, LeBlancObject, QuickUnwrap};
+use crate::leblanc::core::leblanc_object::{LeBlancObject, QuickUnwrap};
 use crate::leblanc::core::leblanc_handle::LeblancHandle;
 use crate::leblanc::core::method_store::MethodStore;
 use crate::leblanc::core::method_tag::MethodTag;
 
-use alloc::rc::Rc;
 
-use std::cell::{RefCell};
 use crate::leblanc::rustblanc::strawberry::Strawberry;
-use std::sync::{Arc, Mutex};
+use std::sync::Arc;
+
+/// The native function behind a method, given the receiver and the arguments.
+pub type MethodHandle = fn(Arc<Strawberry<LeBlancObject>>, &mut [Arc<Strawberry<LeBlancObject>>]) -> Arc<Strawberry<LeBlancObject>>;
 
 pub struct Method {
     pub context: MethodStore,
     pub leblanc_handle: Arc<Strawberry<LeblancHandle>>,
     pub arc_handle: Option<LeblancHandle>,
-    pub handle: fn(Arc<Strawberry<LeBlancObject>>, &mut [Arc<Strawberry<LeBlancObject>>]) -> Arc<Strawberry<LeBlancObject>>,
+    pub handle: MethodHandle,
     pub tags: BTreeSet<MethodTag>,
     pub method_type: MethodType,
 }
@@ -29,7 +30,7 @@ pub struct Method {
 
 
 impl Method {
-    pub fn new(context: MethodStore, handle: fn(Arc<Strawberry<LeBlancObject>>, &mut [Arc<Strawberry<LeBlancObject>>]) -> Arc<Strawberry<LeBlancObject>>, tags: BTreeSet<MethodTag>) -> Method {
+    pub fn new(context: MethodStore, handle: MethodHandle, tags: BTreeSet<MethodTag>) -> Method {
         Method {
             context,
             leblanc_handle: Arc::new(Strawberry::new(LeblancHandle::null())),
@@ -81,17 +82,35 @@ impl Method {
         }
     }
 
-    pub fn default(context: MethodStore, handle: fn(Arc<Strawberry<LeBlancObject>>, &mut [Arc<Strawberry<LeBlancObject>>]) -> Arc<Strawberry<LeBlancObject>>) -> Method {
+    /// A method defined by a class. The object it is called on is passed to `leblanc_handle` in
+    /// front of the other arguments, where the method's code expects `self`.
+    pub fn of_class(context: MethodStore, leblanc_handle: Arc<Strawberry<LeblancHandle>>) -> Method {
+        Method {
+            context,
+            leblanc_handle,
+            arc_handle: None,
+            handle: null_func,
+            tags: BTreeSet::new(),
+            method_type: MethodType::ClassMethod
+        }
+    }
+
+    pub fn default(context: MethodStore, handle: MethodHandle) -> Method {
         Method::new(context, handle, BTreeSet::new())
     }
 
     #[inline(always)]
+    #[allow(clippy::duplicate_underscore_argument)]
     pub fn run(&mut self, _self: Arc<Strawberry<LeBlancObject>>, args: &mut [Arc<Strawberry<LeBlancObject>>]) -> Arc<Strawberry<LeBlancObject>> {
-        unsafe {
-            return match self.is_internal_method() {
-                false => self.leblanc_handle.clone_if_locked().lock().execute(args),
-                true => (self.handle)(_self, args)
+        match self.method_type {
+            MethodType::DefinedMethod => self.leblanc_handle.clone_if_locked().lock().execute(args),
+            MethodType::ClassMethod => {
+                let mut arguments = Vec::with_capacity(args.len() + 1);
+                arguments.push(_self);
+                arguments.extend_from_slice(args);
+                self.leblanc_handle.clone_if_locked().lock().execute(&mut arguments)
             }
+            MethodType::InternalMethod => (self.handle)(_self, args)
         }
     }
 
@@ -125,6 +144,7 @@ impl Method {
     }*/
 
     #[inline(always)]
+    #[allow(clippy::duplicate_underscore_argument)]
     pub fn run_uncloned(&self, _self: Arc<Strawberry<LeBlancObject>>, args: &mut [Arc<Strawberry<LeBlancObject>>]) -> Arc<Strawberry<LeBlancObject>> {
         (self.handle)(_self, args)
     }
@@ -148,7 +168,7 @@ impl Method {
     pub fn has_tag(&self, tag: MethodTag) -> bool { self.tags.contains(&tag) }
 
     pub fn has_tags(&self, tags: Vec<MethodTag>) -> bool {
-        return tags.iter().all(|tag| self.tags.contains(tag));
+        tags.iter().all(|tag| self.tags.contains(tag))
     }
 }
 
@@ -231,5 +251,6 @@ fn tags_to_string(tags: &BTreeSet<MethodTag>) -> String {
 #[derive(Copy, Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
 pub enum MethodType {
     InternalMethod,
-    DefinedMethod
+    DefinedMethod,
+    ClassMethod
 }
\ No newline at end of file
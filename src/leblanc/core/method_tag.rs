@@ -1,12 +1,15 @@
 use std::collections::BTreeSet;
 use std::fmt::{Display, Formatter};
-use std::hash::{Hash, Hasher};
+use std::hash::Hash;
 
 #[derive(Eq, PartialEq, Ord, Hash, PartialOrd, Debug, Copy, Clone)]
 pub enum MethodTag {
     Addition,
     Subtraction,
-    InPlaceAddition
+    InPlaceAddition,
+    /// Runs a block in the running call, which the call instruction does itself since the method
+    /// has no access to it.
+    ExecuteBlock
 }
 
 impl MethodTag {
@@ -2,12 +2,13 @@ use core::str::FromStr;
 
 use std::fmt::{Display, Formatter};
 
+use crate::leblanc::core::native_types::decimal_type::LeblancDecimal;
 use crate::leblanc::core::native_types::derived::DerivedType;
 use crate::leblanc::rustblanc::copystring::{CopyString, CopyStringable};
 
 use crate::leblanc::rustblanc::hex::Hexadecimal;
 use crate::leblanc::rustblanc::Hexable;
-use crate::LeBlancType::{Arch, Group, Boolean, Char, Class, Derived, Double, Dynamic, Exception, Flex, Float, Function, Int, Int128, Int64, Module, Null, SelfType, Short, Marker, Promise};
+use crate::LeBlancType::{Arch, BigInt, Block, Group, Boolean, Char, Class, Decimal, Derived, Double, Dynamic, Enum, Exception, Flex, Float, Function, Int, Int128, Int64, Module, Null, SelfType, Short, Marker, Promise};
 
 pub mod NULL;
 pub mod string_type;
@@ -17,24 +18,28 @@ pub mod int_type;
 pub mod int64_type;
 pub mod arch_type;
 pub mod int128_type;
+pub mod bigint_type;
+pub mod decimal_type;
 pub mod boolean_type;
 pub mod double_type;
 pub mod float_type;
 pub mod short_type;
 pub mod class_type;
+pub mod enum_type;
 pub mod char_type;
 pub mod error_type;
-pub mod attributes;
 pub mod derived;
 pub mod group_type;
 pub mod promise_type;
 pub mod rust_type;
 
-static VARIANTS: [&str; 24] = ["flex", "Self", "char", "short", "int", "int64", "int128", "arch", "float", "double", "boolean", "string", "group", "function", "module", "promise", "class", "dynamic", "exception", "marker", "null", "list", "iterator", "class.0"];
+static VARIANTS: [&str; 31] = ["flex", "Self", "char", "short", "int", "int64", "int128", "arch", "float", "double", "boolean", "string", "group", "function", "module", "promise", "class", "dynamic", "exception", "marker", "null", "list", "iterator", "class.0", "enum", "block", "dict", "tuple", "set", "bigint", "decimal"];
 
+#[allow(clippy::derived_hash_with_manual_eq)]
 #[derive(Eq, Clone, Copy, Debug, Ord, PartialOrd, Hash, Default)]
 pub enum LeBlancType {
     Class(CopyString), // User defined class with ID
+    Enum(CopyString), // User defined enum, by name
     Flex,
     SelfType, // internal implementation of "flex"
     Char,
@@ -43,11 +48,14 @@ pub enum LeBlancType {
     Int64,
     Int128,
     Arch,
+    BigInt, // arbitrary precision integer
     Float, //"double32" -- internally f32
     Double, // internally f64
+    Decimal, // arbitrary precision base 10 number
     Boolean,
     String,
     Group,
+    Block,
     Function,
     Module,
     Dynamic,
@@ -70,12 +78,15 @@ pub fn type_value(string: &str) -> LeBlancType {
         "int64" => Int64,
         "int128" => Int128,
         "arch" => Arch,
+        "bigint" => BigInt,
         "float" => Float,
         "double32" => Float,
         "double" => Double,
+        "decimal" => Decimal,
         "boolean" => Boolean,
         "string" => LeBlancType::String,
         "group" => Group,
+        "block" => Block,
         "function" => Function,
         "module" => Module,
         "dynamic" => Dynamic,
@@ -84,11 +95,16 @@ pub fn type_value(string: &str) -> LeBlancType {
         "null" => Null,
         "marker" => Marker,
         "promise" => Promise,
-        "List" => Derived(DerivedType::List),
+        "List" | "list" => Derived(DerivedType::List),
+        "iterator" => Derived(DerivedType::Iterator),
+        "Dict" | "dict" => Derived(DerivedType::Dict),
+        "Tuple" | "tuple" => Derived(DerivedType::Tuple),
+        "Set" | "set" => Derived(DerivedType::Set),
         Other => {
-            if Other.starts_with("class.") {
-                let class_value = Other[6..].parse::<String>().unwrap();
+            if let Some(class_value) = Other.strip_prefix("class.") {
                 Class(class_value.to_cstring())
+            } else if let Some(enum_name) = Other.strip_prefix("enum.") {
+                Enum(enum_name.to_cstring())
             } else {
                 Class("DNE".to_cstring())
             }
@@ -98,7 +114,7 @@ pub fn type_value(string: &str) -> LeBlancType {
 
 impl LeBlancType {
     pub fn is_numeric(&self) -> bool {
-        matches!(self, Short | Int | Int64 | Int128 | Arch | Float | Double | Boolean)
+        matches!(self, Short | Int | Int64 | Int128 | Arch | BigInt | Float | Double | Decimal | Boolean)
     }
 
     pub fn is_native(&self) -> bool {
@@ -110,8 +126,9 @@ impl LeBlancType {
     }
 
     pub fn as_str_real(&self) -> String {
-        return match self {
+        match self {
             Class(v) => "class.".to_string() + &v.to_string(),
+            Enum(v) => "enum.".to_string() + &v.to_string(),
             _ => self.as_str().to_string()
         }
     }
@@ -126,11 +143,14 @@ impl LeBlancType {
             Int64 => "int64",
             Int128 => "int128",
             Arch => "arch",
+            BigInt => "bigint",
             Float => "float",
             Double => "double",
+            Decimal => "decimal",
             Boolean => "boolean",
             LeBlancType::String => "string",
             Group => "group",
+            Block => "block",
             Function => "function",
             Module => "module",
             Promise => "promise",
@@ -141,13 +161,17 @@ impl LeBlancType {
                     "class"
                 }
             }
+            Enum(_) => "enum",
             Dynamic => "dynamic",
             Exception => "exception",
             Derived(Derive) => {
                 match Derive {
                     DerivedType::List => "list",
                     DerivedType::Iterator => "iterator",
-                    DerivedType::Slice => "slice"
+                    DerivedType::Slice => "slice",
+                    DerivedType::Dict => "dict",
+                    DerivedType::Tuple => "tuple",
+                    DerivedType::Set => "set"
                 }
             }
             Marker => "marker",
@@ -156,7 +180,6 @@ impl LeBlancType {
     }
 
     pub fn enum_id(&self) -> u32 {
-        println!("{}", self);
         VARIANTS.iter().position(|&s| s == self.as_str()).unwrap() as u32
     }
 
@@ -165,18 +188,19 @@ impl LeBlancType {
     }
 
     pub fn transform(&self, string: std::string::String) -> Hexadecimal {
-        return match self {
+        match self {
             Char => string.chars().next().unwrap().to_hex(128),
             Short => i16::from_str(string.as_str()).unwrap().to_hex(128),
             Int => i32::from_str(string.as_str()).unwrap().to_hex(128),
             Int64 => i64::from_str(string.as_str()).unwrap().to_hex(128),
             Int128 => i128::from_str(string.as_str()).unwrap().to_hex(128),
             Arch => usize::from_str(string.as_str()).unwrap().to_hex(128),
+            BigInt => num::BigInt::from_str(string.as_str()).unwrap().to_hex(0),
             Float => f32::from_str(string.as_str()).unwrap().to_hex(128),
             Double => f64::from_str(string.as_str()).unwrap().to_hex(128),
+            Decimal => LeblancDecimal::parse(string.as_str()).unwrap().to_hex(0),
             Boolean => bool::from_str(string.as_str()).unwrap().to_hex(128),
             _String => string[1..string.len()-1].to_string().to_hex(128),
-            _ => string.to_hex(128)
         }
     }
 }
@@ -1,5 +0,0 @@
-use crate::LeBlancType;
-
-pub fn can_add_self(lbt: &LeBlancType) -> bool {
-    matches!(lbt, LeBlancType::Short | LeBlancType::Int | LeBlancType::Int64 | LeBlancType::Int128 | LeBlancType::Arch | LeBlancType::String)
-}
\ No newline at end of file
@@ -1,22 +1,27 @@
+use lazy_static::lazy_static;
 use fxhash::{FxBuildHasher, FxHashMap, FxHashSet};
 use crate::leblanc::rustblanc::strawberry::Strawberry;
-use std::sync::{Arc, Mutex};
+use std::sync::Arc;
 
-use alloc::rc::Rc;
-use std::cell::RefCell;
 
 use crate::leblanc::core::internal::methods::internal_class::{_internal_expose_, _internal_field_, _internal_to_string_};
-use crate::leblanc::core::internal::methods::internal_math::_internal_add_number_;
-use crate::leblanc::core::leblanc_argument::{LeBlancArgument, number_argset};
+use crate::leblanc::core::leblanc_argument::LeBlancArgument;
 use crate::leblanc::core::leblanc_context::VariableContext;
 use crate::leblanc::core::leblanc_object::{LeBlancObject, LeBlancObjectData};
 use crate::leblanc::core::method::Method;
 use crate::leblanc::core::method_store::MethodStore;
-use crate::leblanc::core::method_tag::MethodTag;
+use crate::leblanc::core::native_types::derived::DerivedType;
+use crate::leblanc::core::native_types::derived::dict_type::dict_methods;
+use crate::leblanc::core::native_types::derived::iterator_type::iterator_methods;
+use crate::leblanc::core::native_types::derived::list_type::list_methods;
+use crate::leblanc::core::native_types::derived::set_type::set_methods;
+use crate::leblanc::core::native_types::derived::tuple_type::tuple_methods;
+use crate::leblanc::core::native_types::group_type::group_methods;
+use crate::leblanc::core::native_types::promise_type::promise_methods;
+use crate::leblanc::core::native_types::string_type::string_methods;
 use crate::leblanc::core::native_types::LeBlancType;
 use crate::leblanc::core::native_types::LeBlancType::*;
 
-static mut BASE_METHODS: Option<Arc<FxHashSet<Method>>> = None;
 
 pub trait ToLeblanc {
     fn create(&self) -> LeBlancObject;
@@ -25,19 +30,34 @@ pub trait ToLeblanc {
 
 
 
+lazy_static! {
+    static ref BASE_METHODS: Arc<FxHashSet<Method>> = {
+        let mut hash_set = FxHashSet::with_capacity_and_hasher(5, FxBuildHasher::default());
+        hash_set.insert(Method::default(base_to_string_method(), _internal_to_string_));
+        hash_set.insert(Method::default(base_expose_method(), _internal_expose_));
+        hash_set.insert(Method::default(base_equals_method(), _internal_to_string_));
+        hash_set.insert(Method::default(base_clone_method(), _internal_to_string_));
+        hash_set.insert(Method::default(base_field_method(), _internal_field_));
+        Arc::new(hash_set)
+    };
+}
+
 pub fn base_methods() -> Arc<FxHashSet<Method>> {
-    unsafe {
-        if BASE_METHODS.is_none() {
-            let mut hash_set = FxHashSet::with_capacity_and_hasher(6, FxBuildHasher::default());
-            hash_set.insert(Method::default(base_to_string_method(), _internal_to_string_));
-            hash_set.insert(Method::default(base_expose_method(), _internal_expose_));
-            hash_set.insert(Method::default(base_equals_method(), _internal_to_string_));
-            hash_set.insert(Method::default(base_clone_method(), _internal_to_string_));
-            hash_set.insert(Method::default(base_field_method(), _internal_field_));
-            hash_set.insert( base_addition_method());
-            BASE_METHODS = Some(Arc::new(hash_set));
-        }
-        return BASE_METHODS.as_ref().unwrap().clone();
+    BASE_METHODS.clone()
+}
+
+/// The methods every object of `typing` is created with, which extensions cannot redefine.
+pub fn native_methods(typing: LeBlancType) -> Arc<FxHashSet<Method>> {
+    match typing {
+        String => string_methods(),
+        Derived(DerivedType::List) => list_methods(),
+        Derived(DerivedType::Iterator) => iterator_methods(),
+        Derived(DerivedType::Dict) => dict_methods(),
+        Derived(DerivedType::Tuple) => tuple_methods(),
+        Derived(DerivedType::Set) => set_methods(),
+        Group => group_methods(),
+        Promise => promise_methods(),
+        _ => base_methods()
     }
 }
 
@@ -74,13 +94,3 @@ pub fn base_clone_method() -> MethodStore {
 
 pub fn base_field_method() -> MethodStore { MethodStore::new("field".to_string(),
                                                                 vec![LeBlancArgument::default(LeBlancType::String, 0)])}
-
-
-pub fn base_addition_method() -> Method {
-    let method_store = MethodStore::new("_ADD_".to_string(), number_argset(0));
-    Method::new(
-        method_store,
-        _internal_add_number_,
-        MethodTag::Addition.singleton()
-    )
-}
\ No newline at end of file
@@ -0,0 +1,50 @@
+use std::sync::Arc;
+
+use num::BigInt;
+
+use crate::leblanc::core::leblanc_context::VariableContext;
+use crate::leblanc::core::leblanc_default_data::unsafe_empty_members;
+use crate::leblanc::core::leblanc_object::{LeBlancObject, LeBlancObjectData, RustDataCast};
+use crate::leblanc::core::native_types::base_type::{base_methods, ToLeblanc};
+use crate::leblanc::core::native_types::LeBlancType;
+use crate::leblanc::rustblanc::strawberry::Strawberry;
+
+pub fn leblanc_object_bigint(integer: BigInt) -> LeBlancObject {
+    LeBlancObject::new(
+        LeBlancObjectData::BigInt(integer),
+        LeBlancType::BigInt,
+        base_methods(),
+        unsafe_empty_members(),
+        VariableContext::empty(),
+    )
+}
+
+impl ToLeblanc for BigInt {
+    fn create(&self) -> LeBlancObject {
+        leblanc_object_bigint(self.clone())
+    }
+    fn create_mutex(&self) -> Arc<Strawberry<LeBlancObject>> { Arc::new(Strawberry::new(self.create())) }
+}
+
+impl RustDataCast<BigInt> for LeBlancObjectData {
+    fn clone_data(&self) -> Option<BigInt> {
+        match self {
+            LeBlancObjectData::BigInt(integer) => Some(integer.clone()),
+            _ => None,
+        }
+    }
+
+    fn ref_data(&self) -> Option<&BigInt> {
+        match self {
+            LeBlancObjectData::BigInt(integer) => Some(integer),
+            _ => None,
+        }
+    }
+
+    fn mut_data(&mut self) -> Option<&mut BigInt> {
+        match self {
+            LeBlancObjectData::BigInt(integer) => Some(integer),
+            _ => None,
+        }
+    }
+}
@@ -2,20 +2,21 @@ use fxhash::{FxHashMap};
 use std::fmt::{Display, Formatter};
 
 
-use alloc::rc::Rc;
-use std::cell::RefCell;
 use std::sync::Arc;
 use crate::leblanc::rustblanc::strawberry::Strawberry;
-use std::sync::Mutex;
 
 use crate::leblanc::core::leblanc_context::VariableContext;
 use crate::leblanc::core::leblanc_object::{LeBlancObject, LeBlancObjectData};
 use crate::leblanc::core::native_types::base_type::{base_methods, ToLeblanc};
 use crate::leblanc::core::native_types::LeBlancType;
 
+/// The instructions `start..end` of a function, run in the frame of the call that created the
+/// block so they read and write that call's variables. See `LeblancHandle::execute_block`.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd)]
 pub struct NativeBlock {
-
+    pub frame: u64,
+    pub start: u64,
+    pub end: u64
 }
 
 pub fn leblanc_object_block(block: NativeBlock) -> LeBlancObject {
@@ -23,7 +24,7 @@ pub fn leblanc_object_block(block: NativeBlock) -> LeBlancObject {
 
     LeBlancObject::new(
         LeBlancObjectData::Block(block),
-        LeBlancType::Group,
+        LeBlancType::Block,
         base_methods,
         Arc::new(Strawberry::new(FxHashMap::default())),
         VariableContext::empty(),
@@ -40,6 +41,6 @@ impl ToLeblanc for NativeBlock {
 
 impl Display for NativeBlock {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{:?}", self)
+        write!(f, "<block>")
     }
 }
\ No newline at end of file
@@ -1,21 +1,15 @@
-use fxhash::{FxHashMap, FxHashSet};
-use std::sync::Arc;
-use crate::leblanc::rustblanc::strawberry::Strawberry;
-use std::sync::Mutex;
-
 use crate::leblanc::core::leblanc_context::VariableContext;
+use crate::leblanc::core::leblanc_default_data::unsafe_empty_members;
 use crate::leblanc::core::leblanc_object::{LeBlancObject, LeBlancObjectData};
+use crate::leblanc::core::native_types::base_type::base_methods;
 use crate::leblanc::core::native_types::LeBlancType;
 
 pub fn leblanc_object_char(ch: char) -> LeBlancObject {
-    let base_methods = Arc::new(FxHashSet::default());
-
-
     LeBlancObject::new(
         LeBlancObjectData::Char(ch),
         LeBlancType::Char,
-        base_methods,
-        Arc::new(Strawberry::new(FxHashMap::default())),
+        base_methods(),
+        unsafe_empty_members(),
         VariableContext::empty(),
     )
-}
\ No newline at end of file
+}
@@ -1,6 +1,5 @@
 use std::fmt::{Display, Formatter};
 use std::sync::Arc;
-use parking_lot::Mutex;
 use crate::leblanc::core::leblanc_context::VariableContext;
 use crate::leblanc::core::leblanc_default_data::unsafe_empty_members;
 use crate::leblanc::core::leblanc_object::{LeBlancObject, LeBlancObjectData};
@@ -30,7 +29,7 @@ pub fn leblanc_object_custom(meta: ClassMeta) -> LeBlancObject {
 }
 
 impl ClassMeta {
-    pub fn new<'a>(name: CopyString, supertypes: Vec<ClassMeta>, parse_id: u32) -> ClassMeta {
+    pub fn new(name: CopyString, supertypes: Vec<ClassMeta>, parse_id: u32) -> ClassMeta {
         ClassMeta {
             name,
             supertypes,
@@ -38,7 +37,7 @@ impl ClassMeta {
         }
     }
 
-    pub fn default<'a>(name: String, parse_id: u32) -> ClassMeta {
+    pub fn default(name: String, parse_id: u32) -> ClassMeta {
         ClassMeta {
             name: CopyString::new(name),
             supertypes: vec![],
@@ -56,13 +55,13 @@ impl ClassMeta {
     }
 
     pub fn builder() -> ClassMetaBuilder {
-        return ClassMetaBuilder::default()
+        ClassMetaBuilder::default()
     }
 }
 
 impl Display for ClassMeta {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{:#?}", self)
+        write!(f, "<{} object>", self.name)
     }
 }
 
@@ -72,6 +71,7 @@ pub struct ClassMetaBuilder {
     supertypes: Vec<ClassMeta>,
     parse_id: u32,
     methods: Vec<Method>,
+    #[allow(dead_code)]
     members: Vec<Arc<Strawberry<LeBlancObject>>>
 }
 
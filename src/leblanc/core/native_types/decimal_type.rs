@@ -0,0 +1,312 @@
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
+use std::ops::Neg;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use num::{BigInt, Integer, Signed, Zero};
+use num::traits::Pow;
+
+use crate::leblanc::core::leblanc_context::VariableContext;
+use crate::leblanc::core::leblanc_default_data::unsafe_empty_members;
+use crate::leblanc::core::leblanc_object::{LeBlancObject, LeBlancObjectData, RustDataCast};
+use crate::leblanc::core::native_types::base_type::{base_methods, ToLeblanc};
+use crate::leblanc::core::native_types::LeBlancType;
+use crate::leblanc::rustblanc::hex::Hexadecimal;
+use crate::leblanc::rustblanc::strawberry::Strawberry;
+use crate::leblanc::rustblanc::Hexable;
+
+/// How many fractional digits a quotient is worked out to, unless its operands have more.
+const DIVISION_DIGITS: u32 = 16;
+
+/// An exact decimal number, `mantissa / 10^scale`. The scale is kept as written and as arithmetic
+/// produces it, so `1.10d` prints as `1.10` and `1.10d * 2` as `2.20`. Equality and hashing are
+/// by digits like any other data, while `==` and the other comparisons go by value.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct LeblancDecimal {
+    mantissa: BigInt,
+    scale: u32
+}
+
+impl LeblancDecimal {
+    pub fn new(mantissa: BigInt, scale: u32) -> LeblancDecimal {
+        LeblancDecimal { mantissa, scale }
+    }
+
+    pub fn from_integer(integer: BigInt) -> LeblancDecimal {
+        LeblancDecimal::new(integer, 0)
+    }
+
+    /// Parses a number like `-12.50`, keeping every digit after the point.
+    pub fn parse(text: &str) -> Option<LeblancDecimal> {
+        let (whole, fraction) = text.split_once('.').unwrap_or((text, ""));
+        let digits = whole.trim_start_matches(['-', '+']);
+        if digits.is_empty() || !digits.chars().chain(fraction.chars()).all(|ch| ch.is_ascii_digit()) {
+            return None;
+        }
+        let mantissa = BigInt::from_str(&(whole.to_string() + fraction)).ok()?;
+        Some(LeblancDecimal::new(mantissa, fraction.len() as u32))
+    }
+
+    /// The integer part, dropping the fraction.
+    pub fn trunc(&self) -> BigInt {
+        &self.mantissa / power_of_ten(self.scale)
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        self.to_string().parse().unwrap()
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.mantissa.is_zero()
+    }
+
+    /// Whether the value has no fraction, however many zeros it is written with.
+    pub fn is_integer(&self) -> bool {
+        (&self.mantissa % power_of_ten(self.scale)).is_zero()
+    }
+
+    /// The mantissa at a scale at least as large as the current one.
+    fn mantissa_at(&self, scale: u32) -> BigInt {
+        &self.mantissa * power_of_ten(scale - self.scale)
+    }
+
+    /// Both mantissas at the larger of the two scales, along with that scale.
+    fn aligned(&self, other: &LeblancDecimal) -> (BigInt, BigInt, u32) {
+        let scale = self.scale.max(other.scale);
+        (self.mantissa_at(scale), other.mantissa_at(scale), scale)
+    }
+
+    /// The value without trailing zeros in its fraction beyond `scale` digits.
+    fn trimmed(mut self, scale: u32) -> LeblancDecimal {
+        let ten = BigInt::from(10);
+        while self.scale > scale && (&self.mantissa % &ten).is_zero() {
+            self.mantissa /= &ten;
+            self.scale -= 1;
+        }
+        self
+    }
+
+    /// How the values compare, regardless of how many digits they are written with.
+    pub fn compare(&self, other: &LeblancDecimal) -> Ordering {
+        let (left, right, _) = self.aligned(other);
+        left.cmp(&right)
+    }
+
+    pub fn add(&self, other: &LeblancDecimal) -> LeblancDecimal {
+        let (left, right, scale) = self.aligned(other);
+        LeblancDecimal::new(left + right, scale)
+    }
+
+    pub fn sub(&self, other: &LeblancDecimal) -> LeblancDecimal {
+        let (left, right, scale) = self.aligned(other);
+        LeblancDecimal::new(left - right, scale)
+    }
+
+    pub fn mul(&self, other: &LeblancDecimal) -> LeblancDecimal {
+        LeblancDecimal::new(&self.mantissa * &other.mantissa, self.scale + other.scale)
+    }
+
+    /// The quotient worked out to `DIVISION_DIGITS` fractional digits, or more if either operand
+    /// has more, rounding half to even. Trailing zeros are then dropped down to the scale of the
+    /// operands, so `10.00d / 4` is `2.50`. `None` when dividing by zero.
+    pub fn div(&self, other: &LeblancDecimal) -> Option<LeblancDecimal> {
+        if other.is_zero() {
+            return None;
+        }
+        let operand_scale = self.scale.max(other.scale);
+        let scale = operand_scale.max(DIVISION_DIGITS);
+        let numerator = &self.mantissa * power_of_ten(scale + other.scale - self.scale);
+        let quotient = rounded_half_even(&numerator, &other.mantissa);
+        Some(LeblancDecimal::new(quotient, scale).trimmed(operand_scale))
+    }
+
+    /// The remainder of dividing towards zero, with the sign of `self`. `None` when dividing by
+    /// zero.
+    pub fn rem(&self, other: &LeblancDecimal) -> Option<LeblancDecimal> {
+        if other.is_zero() {
+            return None;
+        }
+        let (left, right, scale) = self.aligned(other);
+        Some(LeblancDecimal::new(left % right, scale))
+    }
+
+    pub fn pow(&self, exponent: u32) -> LeblancDecimal {
+        LeblancDecimal::new(Pow::pow(&self.mantissa, exponent), self.scale * exponent)
+    }
+}
+
+/// `numerator / denominator` rounded to the nearest integer, and to the even one on a tie.
+fn rounded_half_even(numerator: &BigInt, denominator: &BigInt) -> BigInt {
+    let (quotient, remainder) = numerator.div_rem(denominator);
+    let twice: BigInt = remainder.abs() * 2;
+    let away = match twice.cmp(&denominator.abs()) {
+        Ordering::Greater => true,
+        Ordering::Equal => quotient.is_odd(),
+        Ordering::Less => false
+    };
+    match away {
+        true if numerator.is_negative() != denominator.is_negative() => quotient - 1,
+        true => quotient + 1,
+        false => quotient
+    }
+}
+
+fn power_of_ten(exponent: u32) -> BigInt {
+    Pow::pow(BigInt::from(10), exponent)
+}
+
+/// Ordered by value, and by scale between equal values written differently, so the order agrees
+/// with equality.
+impl PartialOrd for LeblancDecimal {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.compare(other).then(self.scale.cmp(&other.scale)))
+    }
+}
+
+impl Neg for LeblancDecimal {
+    type Output = LeblancDecimal;
+
+    fn neg(self) -> LeblancDecimal {
+        LeblancDecimal::new(-self.mantissa, self.scale)
+    }
+}
+
+impl Display for LeblancDecimal {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let digits = self.mantissa.abs().to_string();
+        let sign = if self.mantissa.is_negative() { "-" } else { "" };
+        let scale = self.scale as usize;
+        if scale == 0 {
+            return write!(f, "{}{}", sign, digits);
+        }
+        let digits = format!("{:0>width$}", digits, width = scale + 1);
+        let (whole, fraction) = digits.split_at(digits.len() - scale);
+        write!(f, "{}{}.{}", sign, whole, fraction)
+    }
+}
+
+/// Stored as the scale followed by the mantissa.
+impl Hexable for LeblancDecimal {
+    fn to_hex(&self, _bytes: usize) -> Hexadecimal {
+        self.scale.to_hex(4) + self.mantissa.to_hex(0)
+    }
+
+    fn from_hex(hex: &Hexadecimal) -> Self {
+        let mut hex = hex.clone();
+        let scale = hex.scrape(4).to_hexable::<u32>();
+        LeblancDecimal::new(BigInt::from_hex(&hex), scale)
+    }
+}
+
+pub fn leblanc_object_decimal(decimal: LeblancDecimal) -> LeBlancObject {
+    LeBlancObject::new(
+        LeBlancObjectData::Decimal(decimal),
+        LeBlancType::Decimal,
+        base_methods(),
+        unsafe_empty_members(),
+        VariableContext::empty(),
+    )
+}
+
+impl ToLeblanc for LeblancDecimal {
+    fn create(&self) -> LeBlancObject {
+        leblanc_object_decimal(self.clone())
+    }
+    fn create_mutex(&self) -> Arc<Strawberry<LeBlancObject>> { Arc::new(Strawberry::new(self.create())) }
+}
+
+impl RustDataCast<LeblancDecimal> for LeBlancObjectData {
+    fn clone_data(&self) -> Option<LeblancDecimal> {
+        match self {
+            LeBlancObjectData::Decimal(decimal) => Some(decimal.clone()),
+            _ => None,
+        }
+    }
+
+    fn ref_data(&self) -> Option<&LeblancDecimal> {
+        match self {
+            LeBlancObjectData::Decimal(decimal) => Some(decimal),
+            _ => None,
+        }
+    }
+
+    fn mut_data(&mut self) -> Option<&mut LeblancDecimal> {
+        match self {
+            LeBlancObjectData::Decimal(decimal) => Some(decimal),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cmp::Ordering;
+    use num::BigInt;
+    use crate::leblanc::rustblanc::Hexable;
+    use super::LeblancDecimal;
+
+    fn decimal(text: &str) -> LeblancDecimal {
+        LeblancDecimal::parse(text).unwrap()
+    }
+
+    fn quotient(left: &str, right: &str) -> String {
+        decimal(left).div(&decimal(right)).unwrap().to_string()
+    }
+
+    #[test]
+    fn the_scale_is_kept_as_written() {
+        assert_eq!(decimal("1.10").to_string(), "1.10");
+        assert_eq!(decimal("-0.05").to_string(), "-0.05");
+        assert_eq!(decimal("+7").to_string(), "7");
+        assert_eq!(decimal("1.10").add(&decimal("2.5")).to_string(), "3.60");
+        assert_eq!(decimal("1.10").sub(&decimal("3")).to_string(), "-1.90");
+        assert_eq!(decimal("1.10").mul(&decimal("2")).to_string(), "2.20");
+        assert_eq!(decimal("1.1").pow(2).to_string(), "1.21");
+        assert!(decimal("2.00").is_integer() && !decimal("2.01").is_integer());
+        assert_eq!(decimal("-12.75").trunc(), BigInt::from(-12));
+        assert_eq!(["", ".5", "1.2.3", "1e5", "--1"].map(LeblancDecimal::parse), [None, None, None, None, None]);
+    }
+
+    #[test]
+    fn values_compare_regardless_of_scale() {
+        assert_eq!(decimal("1.10").compare(&decimal("1.1")), Ordering::Equal);
+        assert_ne!(decimal("1.10"), decimal("1.1"));
+        assert_eq!(decimal("1.1").partial_cmp(&decimal("1.10")), Some(Ordering::Less));
+        assert_eq!(decimal("-2").compare(&decimal("1.5")), Ordering::Less);
+    }
+
+    #[test]
+    fn quotients_round_half_to_even() {
+        assert_eq!(quotient("1", "3"), "0.3333333333333333");
+        assert_eq!(quotient("2", "3"), "0.6666666666666667");
+        assert_eq!(quotient("-2", "3"), "-0.6666666666666667");
+        assert_eq!(quotient("1", "131072"), "0.0000076293945312");
+        assert_eq!(quotient("3", "131072"), "0.0000228881835938");
+        assert_eq!(quotient("-3", "131072"), "-0.0000228881835938");
+        assert_eq!(quotient("3", "-131072"), "-0.0000228881835938");
+    }
+
+    #[test]
+    fn quotients_keep_the_scale_of_their_operands() {
+        assert_eq!(quotient("10.00", "4"), "2.50");
+        assert_eq!(quotient("10", "4"), "2.5");
+        assert_eq!(quotient("1", "8"), "0.125");
+        assert_eq!(quotient("1.000000000000000000", "3"), "0.333333333333333333");
+    }
+
+    #[test]
+    fn dividing_by_zero_gives_nothing() {
+        assert_eq!(decimal("1.5").div(&decimal("0.00")), None);
+        assert_eq!(decimal("1.5").rem(&decimal("0")), None);
+        assert_eq!(decimal("-7.5").rem(&decimal("2")).unwrap().to_string(), "-1.5");
+    }
+
+    #[test]
+    fn bytecode_round_trips() {
+        for text in ["0", "1.10", "-123456789012345678901234567890.123"] {
+            let value = decimal(text);
+            assert_eq!(LeblancDecimal::from_hex(&value.to_hex(0)), value);
+        }
+    }
+}
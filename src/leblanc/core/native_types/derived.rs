@@ -1,6 +1,9 @@
 use core::fmt::{Display, Formatter};
 
 pub mod list_type;
+pub mod dict_type;
+pub mod set_type;
+pub mod tuple_type;
 pub mod iterator_type;
 pub mod generator_type;
 pub mod slice_type;
@@ -9,7 +12,10 @@ pub mod slice_type;
 pub enum DerivedType {
     List,
     Iterator,
-    Slice
+    Slice,
+    Dict,
+    Tuple,
+    Set
 }
 
 impl Display for DerivedType {
@@ -17,7 +23,10 @@ impl Display for DerivedType {
         let s = match self {
             DerivedType::List => "list",
             DerivedType::Iterator => "iterator",
-            DerivedType::Slice => "slice"
+            DerivedType::Slice => "slice",
+            DerivedType::Dict => "dict",
+            DerivedType::Tuple => "tuple",
+            DerivedType::Set => "set"
         };
         write!(f, "{}", s)
     }
@@ -0,0 +1,248 @@
+use core::fmt::{Display, Formatter};
+use std::cmp::Ordering;
+use std::hash::{Hash, Hasher};
+use fxhash::{FxHashMap, FxHashSet};
+
+
+use std::collections::BTreeSet;
+use std::sync::Arc;
+use lazy_static::lazy_static;
+use num::{BigInt, FromPrimitive};
+use crate::leblanc::rustblanc::strawberry::Strawberry;
+use crate::leblanc::core::internal::methods::internal_class::{_internal_expose_, _internal_field_, _internal_to_string_};
+use crate::leblanc::core::internal::methods::internal_dict::{_internal_dict_contains_, _internal_dict_get_, _internal_dict_items_, _internal_dict_keys_, _internal_dict_length_, _internal_dict_remove_, _internal_dict_values_};
+use crate::leblanc::core::leblanc_argument::LeBlancArgument;
+
+use crate::leblanc::core::leblanc_context::VariableContext;
+use crate::leblanc::core::leblanc_object::{Callable, LeBlancObject, LeBlancObjectData, RustDataCast};
+use crate::leblanc::core::method::{Method, MethodHandle};
+use crate::leblanc::core::method_store::MethodStore;
+use crate::leblanc::core::native_types::base_type::{base_clone_method, base_equals_method, base_expose_method, base_field_method, base_to_string_method, ToLeblanc};
+use crate::leblanc::core::native_types::derived::DerivedType;
+use crate::LeBlancType;
+
+/// A key of a dictionary. Numbers are keyed by their value whatever their type, so `1`, `1l` and
+/// `1.0` are the same key; other keys are equal when the objects are, and hash the way
+/// `LeBlancObject` does.
+#[derive(Clone, Debug)]
+pub struct DictKey(pub Arc<Strawberry<LeBlancObject>>);
+
+/// The value of a numeric key: integers of any width and integral floats as a `BigInt`, and any
+/// other float widened to a double.
+#[derive(PartialEq, Eq, Hash)]
+enum NumericKey {
+    Integral(BigInt),
+    Fractional(u64)
+}
+
+impl NumericKey {
+    fn of(data: &LeBlancObjectData) -> Option<NumericKey> {
+        Some(match data {
+            LeBlancObjectData::Short(value) => NumericKey::Integral(BigInt::from(*value)),
+            LeBlancObjectData::Int(value) => NumericKey::Integral(BigInt::from(*value)),
+            LeBlancObjectData::Int64(value) => NumericKey::Integral(BigInt::from(*value)),
+            LeBlancObjectData::Int128(value) => NumericKey::Integral(BigInt::from(*value)),
+            LeBlancObjectData::Arch(value) => NumericKey::Integral(BigInt::from(*value)),
+            LeBlancObjectData::BigInt(value) => NumericKey::Integral(value.clone()),
+            LeBlancObjectData::Float(value) => NumericKey::of_double(*value as f64),
+            LeBlancObjectData::Double(value) => NumericKey::of_double(*value),
+            _ => return None
+        })
+    }
+
+    fn of_double(value: f64) -> NumericKey {
+        match BigInt::from_f64(value) {
+            Some(integral) if value.fract() == 0.0 => NumericKey::Integral(integral),
+            _ => NumericKey::Fractional(value.to_bits())
+        }
+    }
+}
+
+impl PartialEq for DictKey {
+    fn eq(&self, other: &Self) -> bool {
+        if Arc::ptr_eq(&self.0, &other.0) { return true }
+        let (key, other_key) = (self.0.lock(), other.0.lock());
+        match (NumericKey::of(&key.data), NumericKey::of(&other_key.data)) {
+            (Some(value), Some(other_value)) => value == other_value,
+            (None, None) => *key == *other_key,
+            _ => false
+        }
+    }
+}
+
+impl Eq for DictKey {}
+
+impl Hash for DictKey {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let key = self.0.lock();
+        match NumericKey::of(&key.data) {
+            Some(value) => value.hash(state),
+            None => key.hash(state)
+        }
+    }
+}
+
+/// A key and the value it maps to.
+pub type DictEntry = (Arc<Strawberry<LeBlancObject>>, Arc<Strawberry<LeBlancObject>>);
+
+/// Keys mapped to values, kept in the order they were first inserted in.
+#[derive(Clone, Debug, Default)]
+pub struct LeblancDict {
+    pub entries: Vec<DictEntry>,
+    index: FxHashMap<DictKey, usize>
+}
+
+impl LeblancDict {
+    pub fn empty() -> LeblancDict {
+        LeblancDict::default()
+    }
+
+    pub fn new(entries: Vec<DictEntry>) -> LeblancDict {
+        let mut dict = LeblancDict::empty();
+        for (key, value) in entries {
+            dict.insert(key, value);
+        }
+        dict
+    }
+
+    pub fn get(&self, key: &Arc<Strawberry<LeBlancObject>>) -> Option<Arc<Strawberry<LeBlancObject>>> {
+        self.index.get(&DictKey(key.clone())).map(|position| self.entries[*position].1.clone())
+    }
+
+    /// Sets the value of `key`, which keeps its place if it is there already.
+    pub fn insert(&mut self, key: Arc<Strawberry<LeBlancObject>>, value: Arc<Strawberry<LeBlancObject>>) {
+        match self.index.get(&DictKey(key.clone())) {
+            Some(position) => self.entries[*position].1 = value,
+            None => {
+                self.index.insert(DictKey(key.clone()), self.entries.len());
+                self.entries.push((key, value));
+            }
+        }
+    }
+
+    pub fn remove(&mut self, key: &Arc<Strawberry<LeBlancObject>>) -> Option<Arc<Strawberry<LeBlancObject>>> {
+        let position = self.index.remove(&DictKey(key.clone()))?;
+        let (_, value) = self.entries.remove(position);
+        self.index.values_mut().filter(|later| **later > position).for_each(|later| *later -= 1);
+        Some(value)
+    }
+
+    pub fn contains(&self, key: &Arc<Strawberry<LeBlancObject>>) -> bool {
+        self.index.contains_key(&DictKey(key.clone()))
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn keys(&self) -> Vec<Arc<Strawberry<LeBlancObject>>> {
+        self.entries.iter().map(|(key, _)| key.clone()).collect()
+    }
+
+    pub fn values(&self) -> Vec<Arc<Strawberry<LeBlancObject>>> {
+        self.entries.iter().map(|(_, value)| value.clone()).collect()
+    }
+}
+
+pub fn leblanc_object_dict_empty() -> LeBlancObject {
+    leblanc_object_dict(LeblancDict::empty())
+}
+
+lazy_static! {
+    static ref DICT_METHODS: Arc<FxHashSet<Method>> = {
+        let mut hash_set = FxHashSet::default();
+        hash_set.insert(Method::default(base_to_string_method(), _internal_to_string_));
+        hash_set.insert(Method::default(base_expose_method(), _internal_expose_));
+        hash_set.insert(Method::default(base_equals_method(), _internal_to_string_));
+        hash_set.insert(Method::default(base_clone_method(), _internal_to_string_));
+        hash_set.insert(Method::default(base_field_method(), _internal_field_));
+        hash_set.insert(dict_method("iterate", vec![], _internal_dict_keys_));
+        hash_set.insert(dict_method("keys", vec![], _internal_dict_keys_));
+        hash_set.insert(dict_method("values", vec![], _internal_dict_values_));
+        hash_set.insert(dict_method("items", vec![], _internal_dict_items_));
+        hash_set.insert(dict_method("get", vec![LeBlancArgument::default(LeBlancType::Flex, 0), LeBlancArgument::optional(LeBlancType::Flex, 1)], _internal_dict_get_));
+        hash_set.insert(dict_method("remove", vec![LeBlancArgument::default(LeBlancType::Flex, 0)], _internal_dict_remove_));
+        hash_set.insert(dict_method("contains", vec![LeBlancArgument::default(LeBlancType::Flex, 0)], _internal_dict_contains_));
+        hash_set.insert(dict_method("len", vec![], _internal_dict_length_));
+        hash_set.insert(dict_method("length", vec![], _internal_dict_length_));
+        Arc::new(hash_set)
+    };
+}
+
+pub fn dict_methods() -> Arc<FxHashSet<Method>> {
+    DICT_METHODS.clone()
+}
+
+fn dict_method(name: &str, arguments: Vec<LeBlancArgument>, handle: MethodHandle) -> Method {
+    Method::new(MethodStore::new(name.to_string(), arguments), handle, BTreeSet::new())
+}
+
+pub fn leblanc_object_dict(dict: LeblancDict) -> LeBlancObject {
+    let base_methods = dict_methods();
+
+    LeBlancObject::new(
+        LeBlancObjectData::Dict(dict),
+        LeBlancType::Derived(DerivedType::Dict),
+        base_methods,
+        Arc::new(Strawberry::new(FxHashMap::default())),
+        VariableContext::empty(),
+    )
+}
+
+impl ToLeblanc for LeblancDict {
+    fn create(&self) -> LeBlancObject {
+        leblanc_object_dict(self.clone())
+    }
+    fn create_mutex(&self) -> Arc<Strawberry<LeBlancObject>> { Arc::new(Strawberry::new(self.create())) }
+}
+
+impl Display for LeblancDict {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let text = |item: &Arc<Strawberry<LeBlancObject>>| item.clone().call_name("to_string").unwrap().lock().data.to_string();
+        write!(f, "{{{}}}", self.entries.iter().map(|(key, value)| format!("{}: {}", text(key), text(value))).collect::<Vec<String>>().join(", "))
+    }
+}
+
+/// Dictionaries are equal when they map the same keys to equal values, in any order.
+impl PartialEq for LeblancDict {
+    fn eq(&self, other: &Self) -> bool {
+        if self.len() != other.len() { return false }
+        self.entries.iter().all(|(key, value)| match other.get(key) {
+            Some(other_value) => Arc::ptr_eq(value, &other_value) || value.lock().data == other_value.lock().data,
+            None => false
+        })
+    }
+}
+
+impl PartialOrd for LeblancDict {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.len().partial_cmp(&other.len())
+    }
+}
+
+impl RustDataCast<LeblancDict> for LeBlancObjectData {
+    fn clone_data(&self) -> Option<LeblancDict> {
+        match self {
+            LeBlancObjectData::Dict(dict) => Some(dict.clone()),
+            _ => None
+        }
+    }
+
+    fn ref_data(&self) -> Option<&LeblancDict> {
+        match self {
+            LeBlancObjectData::Dict(dict) => Some(dict),
+            _ => None
+        }
+    }
+
+    fn mut_data(&mut self) -> Option<&mut LeblancDict> {
+        match self {
+            LeBlancObjectData::Dict(dict) => Some(dict),
+            _ => None
+        }
+    }
+}
@@ -1,13 +1,10 @@
-use alloc::rc::Rc;
-
 use core::fmt::{Display, Formatter};
 
-use std::cell::RefCell;
 use std::sync::Arc;
 use fxhash::{FxHashMap};
 use crate::leblanc::rustblanc::strawberry::Strawberry;
-use std::sync::Mutex;
 use crate::leblanc::core::internal::transformed_iterator::TransformedIterator;
+use crate::leblanc::core::interpreter::value::Value;
 use crate::leblanc::core::leblanc_context::VariableContext;
 use crate::leblanc::core::leblanc_handle::LeblancHandle;
 use crate::leblanc::core::leblanc_object::{LeBlancObject, LeBlancObjectData};
@@ -17,15 +14,58 @@ use crate::leblanc::core::native_types::derived::iterator_type::{iterator_method
 use crate::leblanc::core::native_types::derived::list_type::LeblancList;
 use crate::LeBlancType;
 
-#[derive(Clone, Debug, PartialEq)]
+/// The iterator a function containing `yield` returns. It holds on to the call, which runs up to
+/// its next `yield` whenever another value is needed. Copies of a generator share the call.
+#[derive(Clone, Debug)]
 pub struct LeblancGenerator {
-    leblanc_handle: LeblancHandle
+    state: Arc<Strawberry<GeneratorState>>
+}
+
+#[derive(Clone, Debug, Default)]
+struct GeneratorState {
+    leblanc_handle: LeblancHandle,
+    /// The stack of the call, kept between values, e.g. for the iterator of a `for` around a `yield`.
+    stack: Vec<Value>,
+    /// The value the call has yielded but nobody has taken yet.
+    next: Option<Arc<Strawberry<LeBlancObject>>>,
+    done: bool,
+    /// The remaining values, last first, once the generator has been reversed.
+    reversed: Option<Vec<Arc<Strawberry<LeBlancObject>>>>
+}
+
+impl GeneratorState {
+    /// Runs the call up to its next value, unless one is waiting already. An error leaving the call
+    /// becomes its last value.
+    fn fill(&mut self) {
+        if self.next.is_some() || self.done { return; }
+        match self.leblanc_handle.resume(&mut self.stack) {
+            Ok(Some(value)) => self.next = Some(value),
+            Ok(None) => self.done = true,
+            Err(error) => {
+                self.next = Some(error);
+                self.done = true;
+            }
+        }
+    }
+
+    fn has_next(&mut self) -> bool {
+        if let Some(reversed) = &self.reversed { return !reversed.is_empty(); }
+        self.fill();
+        self.next.is_some()
+    }
+
+    fn take(&mut self) -> Arc<Strawberry<LeBlancObject>> {
+        if let Some(reversed) = &mut self.reversed { return reversed.pop().unwrap_or_else(LeBlancObject::unsafe_null); }
+        self.fill();
+        self.next.take().unwrap_or_else(LeBlancObject::unsafe_null)
+    }
 }
 
 pub fn leblanc_object_generator(leblanc_handle: LeblancHandle) -> LeBlancObject {
     let base_methods = iterator_methods();
 
-    let generator = LeblancGenerator {leblanc_handle};
+    let state = GeneratorState { leblanc_handle, ..GeneratorState::default() };
+    let generator = LeblancGenerator { state: Arc::new(Strawberry::new(state)) };
 
 
     LeBlancObject::new(
@@ -37,22 +77,34 @@ pub fn leblanc_object_generator(leblanc_handle: LeblancHandle) -> LeBlancObject
     )
 }
 
+impl PartialEq for LeblancGenerator {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.state, &other.state)
+    }
+}
+
 impl Display for LeblancGenerator {
     fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
-        write!(f, "<Generator of {}>", self.leblanc_handle.name)
+        write!(f, "<Generator of {}>", self.state.lock().leblanc_handle.name)
     }
 }
 
 impl LeblancIterable for LeblancGenerator {
     fn lb_next(&mut self) -> Arc<Strawberry<LeBlancObject>> {
-        self.leblanc_handle.execute_from_last_point().to_mutex()
+        self.state.lock().take()
     }
-    fn has_next(&self) -> bool {
-        self.leblanc_handle.current_instruct < self.leblanc_handle.instructions.len() as u64
+    fn has_next(&mut self) -> bool {
+        self.state.lock().has_next()
     }
 
+    /// Runs the call to the end, since the last value is only known then.
     fn reverse(&mut self) {
-        todo!()
+        let mut state = self.state.lock();
+        let mut values = vec![];
+        while state.has_next() {
+            values.push(state.take());
+        }
+        state.reversed = Some(values);
     }
 
     fn to_list(&mut self) -> LeblancList {
@@ -75,7 +127,7 @@ impl Iterator for LeblancGenerator {
 
     fn next(&mut self) -> Option<Self::Item> {
         match self.has_next() {
-            true => Some(self.leblanc_handle.execute_from_last_point().to_mutex()),
+            true => Some(self.lb_next()),
             false => None
         }
     }
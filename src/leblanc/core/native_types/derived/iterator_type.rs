@@ -1,19 +1,17 @@
-use alloc::rc::Rc;
 use core::fmt::{Display, Formatter};
-use std::cell::RefCell;
 use std::collections::{BTreeSet};
 
 use std::sync::Arc;
+use lazy_static::lazy_static;
 use fxhash::{FxHashMap, FxHashSet};
 use crate::leblanc::rustblanc::strawberry::Strawberry;
-use std::sync::Mutex;
 use crate::leblanc::core::internal::methods::internal_class::{_internal_expose_, _internal_field_, _internal_to_string_};
-use crate::leblanc::core::internal::methods::internal_iterator::{_internal_iterator_filter_, _internal_iterator_map_, _internal_iterator_next, _internal_iterator_to_list_};
+use crate::leblanc::core::internal::methods::internal_iterator::{_internal_iterator_all_, _internal_iterator_any_, _internal_iterator_chain_, _internal_iterator_collect_, _internal_iterator_count_, _internal_iterator_enumerate_, _internal_iterator_filter_, _internal_iterator_flat_map_, _internal_iterator_fold_, _internal_iterator_map_, _internal_iterator_max_, _internal_iterator_min_, _internal_iterator_next, _internal_iterator_reduce_, _internal_iterator_skip_, _internal_iterator_sum_, _internal_iterator_take_, _internal_iterator_take_while_, _internal_iterator_to_list_, _internal_iterator_zip_};
 use crate::leblanc::core::internal::transformed_iterator::TransformedIterator;
 use crate::leblanc::core::leblanc_argument::LeBlancArgument;
 use crate::leblanc::core::leblanc_context::VariableContext;
 use crate::leblanc::core::leblanc_object::{LeBlancObject, LeBlancObjectData, RustDataCast};
-use crate::leblanc::core::method::Method;
+use crate::leblanc::core::method::{Method, MethodHandle};
 use crate::leblanc::core::method_store::MethodStore;
 use crate::leblanc::core::native_types::base_type::{base_clone_method, base_equals_method, base_expose_method, base_field_method, base_to_string_method};
 use crate::leblanc::core::native_types::derived::DerivedType;
@@ -43,7 +41,7 @@ where
 
 pub trait LeblancIterable: IteratorUtils {
     fn lb_next(&mut self) -> Arc<Strawberry<LeBlancObject>>;
-    fn has_next(&self) -> bool;
+    fn has_next(&mut self) -> bool;
     fn reverse(&mut self);
     fn to_list(&mut self) -> LeblancList;
     fn to_rust_iter(&mut self) -> Box<dyn Iterator<Item=Arc<Strawberry<LeBlancObject>>>>;
@@ -75,16 +73,16 @@ impl LeblancIterator {
         }
     }
 
+    #[allow(clippy::should_implement_trait)]
     pub fn next(&mut self) -> Arc<Strawberry<LeBlancObject>> { self.iterator.lb_next() }
 
-    pub fn has_next(&self) -> bool {
+    pub fn has_next(&mut self) -> bool {
         self.iterator.has_next()
     }
 
     pub fn reverse(&mut self) { self.iterator.reverse() }
 
     pub fn to_list(&mut self) -> LeblancList {
-        println!("LeblancIterator to list");
         self.iterator.to_list() }
 
     pub fn transformed(&mut self) -> Option<&mut TransformedIterator> { self.iterator.transformed() }
@@ -98,18 +96,44 @@ impl Display for LeblancIterator {
     }
 }
 
+lazy_static! {
+    static ref ITERATOR_METHODS: Arc<FxHashSet<Method>> = {
+        let mut hash_set = FxHashSet::default();
+        hash_set.insert(Method::default(base_to_string_method(), _internal_to_string_));
+        hash_set.insert(Method::default(base_expose_method(), _internal_expose_));
+        hash_set.insert(Method::default(base_equals_method(), _internal_to_string_));
+        hash_set.insert(Method::default(base_clone_method(), _internal_to_string_));
+        hash_set.insert(Method::default(base_field_method(), _internal_field_));
+        hash_set.insert( iterator_next_method());
+        hash_set.insert(iterator_to_list());
+        hash_set.insert(iterator_filter());
+        hash_set.insert(iterator_map());
+        hash_set.insert(iterator_method("take", vec![LeBlancArgument::default(LeBlancType::Int, 0)], _internal_iterator_take_));
+        hash_set.insert(iterator_method("skip", vec![LeBlancArgument::default(LeBlancType::Int, 0)], _internal_iterator_skip_));
+        hash_set.insert(iterator_method("take_while", vec![LeBlancArgument::default(LeBlancType::Function, 0)], _internal_iterator_take_while_));
+        hash_set.insert(iterator_method("enumerate", vec![], _internal_iterator_enumerate_));
+        hash_set.insert(iterator_method("zip", vec![LeBlancArgument::default(LeBlancType::Flex, 0)], _internal_iterator_zip_));
+        hash_set.insert(iterator_method("chain", vec![LeBlancArgument::default(LeBlancType::Flex, 0)], _internal_iterator_chain_));
+        hash_set.insert(iterator_method("flat_map", vec![LeBlancArgument::default(LeBlancType::Function, 0)], _internal_iterator_flat_map_));
+        hash_set.insert(iterator_method("fold", vec![LeBlancArgument::default(LeBlancType::Flex, 0), LeBlancArgument::default(LeBlancType::Function, 1)], _internal_iterator_fold_));
+        hash_set.insert(iterator_method("reduce", vec![LeBlancArgument::default(LeBlancType::Function, 0)], _internal_iterator_reduce_));
+        hash_set.insert(iterator_method("sum", vec![], _internal_iterator_sum_));
+        hash_set.insert(iterator_method("min", vec![], _internal_iterator_min_));
+        hash_set.insert(iterator_method("max", vec![], _internal_iterator_max_));
+        hash_set.insert(iterator_method("any", vec![LeBlancArgument::default(LeBlancType::Function, 0)], _internal_iterator_any_));
+        hash_set.insert(iterator_method("all", vec![LeBlancArgument::default(LeBlancType::Function, 0)], _internal_iterator_all_));
+        hash_set.insert(iterator_method("count", vec![], _internal_iterator_count_));
+        hash_set.insert(iterator_method("collect", vec![LeBlancArgument::default(LeBlancType::String, 0)], _internal_iterator_collect_));
+        Arc::new(hash_set)
+    };
+}
+
 pub fn iterator_methods() -> Arc<FxHashSet<Method>> {
-    let mut hash_set = FxHashSet::default();
-    hash_set.insert(Method::default(base_to_string_method(), _internal_to_string_));
-    hash_set.insert(Method::default(base_expose_method(), _internal_expose_));
-    hash_set.insert(Method::default(base_equals_method(), _internal_to_string_));
-    hash_set.insert(Method::default(base_clone_method(), _internal_to_string_));
-    hash_set.insert(Method::default(base_field_method(), _internal_field_));
-    hash_set.insert( iterator_next_method());
-    hash_set.insert(iterator_to_list());
-    hash_set.insert(iterator_filter());
-    hash_set.insert(iterator_map());
-    Arc::new(hash_set)
+    ITERATOR_METHODS.clone()
+}
+
+fn iterator_method(name: &str, arguments: Vec<LeBlancArgument>, handle: MethodHandle) -> Method {
+    Method::new(MethodStore::new(name.to_string(), arguments), handle, BTreeSet::new())
 }
 
 pub fn iterator_next_method() -> Method {
@@ -3,12 +3,10 @@ use std::cmp::Ordering;
 use fxhash::{FxHashMap, FxHashSet};
 
 
-use alloc::rc::Rc;
-use std::cell::RefCell;
 use std::collections::BTreeSet;
 use std::sync::Arc;
+use lazy_static::lazy_static;
 use crate::leblanc::rustblanc::strawberry::Strawberry;
-use std::sync::Mutex;
 use crate::leblanc::core::internal::methods::internal_class::{_internal_expose_, _internal_field_, _internal_to_string_};
 use crate::leblanc::core::internal::methods::internal_list::{_internal_list_append_, _internal_list_iterate_, _internal_list_length_};
 use crate::leblanc::core::leblanc_argument::LeBlancArgument;
@@ -52,17 +50,23 @@ pub fn leblanc_object_list_empty() -> LeBlancObject {
     )
 }
 
+lazy_static! {
+    static ref LIST_METHODS: Arc<FxHashSet<Method>> = {
+        let mut hash_set = FxHashSet::default();
+        hash_set.insert(Method::default(base_to_string_method(), _internal_to_string_));
+        hash_set.insert(Method::default(base_expose_method(), _internal_expose_));
+        hash_set.insert(Method::default(base_equals_method(), _internal_to_string_));
+        hash_set.insert(Method::default(base_clone_method(), _internal_to_string_));
+        hash_set.insert(Method::default(base_field_method(), _internal_field_));
+        hash_set.insert(list_iterate_method());
+        hash_set.insert(list_append_method());
+        hash_set.insert(list_length_method());
+        Arc::new(hash_set)
+    };
+}
+
 pub fn list_methods() -> Arc<FxHashSet<Method>> {
-    let mut hash_set = FxHashSet::default();
-    hash_set.insert(Method::default(base_to_string_method(), _internal_to_string_));
-    hash_set.insert(Method::default(base_expose_method(), _internal_expose_));
-    hash_set.insert(Method::default(base_equals_method(), _internal_to_string_));
-    hash_set.insert(Method::default(base_clone_method(), _internal_to_string_));
-    hash_set.insert(Method::default(base_field_method(), _internal_field_));
-    hash_set.insert(list_iterate_method());
-    hash_set.insert(list_append_method());
-    hash_set.insert(list_length_method());
-    Arc::new(hash_set)
+    LIST_METHODS.clone()
 }
 
 pub fn list_iterate_method() -> Method {
@@ -121,7 +125,8 @@ impl PartialEq for LeblancList {
     fn eq(&self, other: &Self) -> bool {
         if self.internal_vec.len() != other.internal_vec.len() { return false }
         for i in 0..self.internal_vec.len() {
-            if self.internal_vec[i].lock().data != other.internal_vec[i].lock().data {
+            let (item, other_item) = (&self.internal_vec[i], &other.internal_vec[i]);
+            if !Arc::ptr_eq(item, other_item) && item.lock().data != other_item.lock().data {
                 return false;
             }
         }
@@ -0,0 +1,172 @@
+use core::fmt::{Display, Formatter};
+use std::cmp::Ordering;
+use fxhash::{FxHashMap, FxHashSet};
+
+
+use std::collections::BTreeSet;
+use std::sync::Arc;
+use lazy_static::lazy_static;
+use crate::leblanc::rustblanc::strawberry::Strawberry;
+use crate::leblanc::core::internal::methods::internal_class::{_internal_expose_, _internal_field_, _internal_to_string_};
+use crate::leblanc::core::internal::methods::internal_set::{_internal_set_contains_, _internal_set_difference_, _internal_set_intersection_, _internal_set_is_subset_, _internal_set_is_superset_, _internal_set_iterate_, _internal_set_length_, _internal_set_union_};
+use crate::leblanc::core::leblanc_argument::LeBlancArgument;
+
+use crate::leblanc::core::leblanc_context::VariableContext;
+use crate::leblanc::core::leblanc_object::{Callable, LeBlancObject, LeBlancObjectData, RustDataCast};
+use crate::leblanc::core::method::{Method, MethodHandle};
+use crate::leblanc::core::method_store::MethodStore;
+use crate::leblanc::core::native_types::base_type::{base_clone_method, base_equals_method, base_expose_method, base_field_method, base_to_string_method, ToLeblanc};
+use crate::leblanc::core::native_types::derived::dict_type::DictKey;
+use crate::leblanc::core::native_types::derived::DerivedType;
+use crate::LeBlancType;
+
+/// Distinct values, kept in the order they were first added in. Sets can't be changed once made:
+/// `union`, `intersection` and `difference` give new ones.
+#[derive(Clone, Debug, Default)]
+pub struct LeblancSet {
+    pub values: Vec<Arc<Strawberry<LeBlancObject>>>,
+    index: FxHashSet<DictKey>
+}
+
+impl LeblancSet {
+    pub fn empty() -> LeblancSet {
+        LeblancSet::default()
+    }
+
+    /// A set of the values, leaving out any equal to one before it.
+    pub fn new(values: Vec<Arc<Strawberry<LeBlancObject>>>) -> LeblancSet {
+        let mut set = LeblancSet::empty();
+        values.into_iter().for_each(|value| set.add(value));
+        set
+    }
+
+    fn add(&mut self, value: Arc<Strawberry<LeBlancObject>>) {
+        if self.index.insert(DictKey(value.clone())) {
+            self.values.push(value);
+        }
+    }
+
+    pub fn contains(&self, value: &Arc<Strawberry<LeBlancObject>>) -> bool {
+        self.index.contains(&DictKey(value.clone()))
+    }
+
+    pub fn union(&self, other: &LeblancSet) -> LeblancSet {
+        LeblancSet::new(self.values.iter().chain(&other.values).cloned().collect())
+    }
+
+    pub fn intersection(&self, other: &LeblancSet) -> LeblancSet {
+        LeblancSet::new(self.values.iter().filter(|value| other.contains(value)).cloned().collect())
+    }
+
+    pub fn difference(&self, other: &LeblancSet) -> LeblancSet {
+        LeblancSet::new(self.values.iter().filter(|value| !other.contains(value)).cloned().collect())
+    }
+
+    pub fn is_subset(&self, other: &LeblancSet) -> bool {
+        self.len() <= other.len() && self.values.iter().all(|value| other.contains(value))
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+}
+
+lazy_static! {
+    static ref SET_METHODS: Arc<FxHashSet<Method>> = {
+        let mut hash_set = FxHashSet::default();
+        hash_set.insert(Method::default(base_to_string_method(), _internal_to_string_));
+        hash_set.insert(Method::default(base_expose_method(), _internal_expose_));
+        hash_set.insert(Method::default(base_equals_method(), _internal_to_string_));
+        hash_set.insert(Method::default(base_clone_method(), _internal_to_string_));
+        hash_set.insert(Method::default(base_field_method(), _internal_field_));
+        hash_set.insert(set_method("iterate", vec![], _internal_set_iterate_));
+        hash_set.insert(set_method("contains", vec![LeBlancArgument::default(LeBlancType::Flex, 0)], _internal_set_contains_));
+        hash_set.insert(set_method("union", vec![LeBlancArgument::default(LeBlancType::Flex, 0)], _internal_set_union_));
+        hash_set.insert(set_method("intersection", vec![LeBlancArgument::default(LeBlancType::Flex, 0)], _internal_set_intersection_));
+        hash_set.insert(set_method("difference", vec![LeBlancArgument::default(LeBlancType::Flex, 0)], _internal_set_difference_));
+        hash_set.insert(set_method("is_subset", vec![LeBlancArgument::default(LeBlancType::Flex, 0)], _internal_set_is_subset_));
+        hash_set.insert(set_method("is_superset", vec![LeBlancArgument::default(LeBlancType::Flex, 0)], _internal_set_is_superset_));
+        hash_set.insert(set_method("len", vec![], _internal_set_length_));
+        hash_set.insert(set_method("length", vec![], _internal_set_length_));
+        Arc::new(hash_set)
+    };
+}
+
+pub fn set_methods() -> Arc<FxHashSet<Method>> {
+    SET_METHODS.clone()
+}
+
+fn set_method(name: &str, arguments: Vec<LeBlancArgument>, handle: MethodHandle) -> Method {
+    Method::new(MethodStore::new(name.to_string(), arguments), handle, BTreeSet::new())
+}
+
+pub fn leblanc_object_set(set: LeblancSet) -> LeBlancObject {
+    let base_methods = set_methods();
+
+    LeBlancObject::new(
+        LeBlancObjectData::Set(set),
+        LeBlancType::Derived(DerivedType::Set),
+        base_methods,
+        Arc::new(Strawberry::new(FxHashMap::default())),
+        VariableContext::empty(),
+    )
+}
+
+impl ToLeblanc for LeblancSet {
+    fn create(&self) -> LeBlancObject {
+        leblanc_object_set(self.clone())
+    }
+    fn create_mutex(&self) -> Arc<Strawberry<LeBlancObject>> { Arc::new(Strawberry::new(self.create())) }
+}
+
+impl Display for LeblancSet {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{{{}}}", self.values.iter().map(|item| item.clone().call_name("to_string").unwrap().lock().data.to_string()).collect::<Vec<String>>().join(", "))
+    }
+}
+
+/// Sets are equal when they have the same values, in any order.
+impl PartialEq for LeblancSet {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.is_subset(other)
+    }
+}
+
+/// Sets are ordered by inclusion, so two sets neither of which contains the other don't compare.
+impl PartialOrd for LeblancSet {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match (self.is_subset(other), other.is_subset(self)) {
+            (true, true) => Some(Ordering::Equal),
+            (true, false) => Some(Ordering::Less),
+            (false, true) => Some(Ordering::Greater),
+            (false, false) => None
+        }
+    }
+}
+
+impl RustDataCast<LeblancSet> for LeBlancObjectData {
+    fn clone_data(&self) -> Option<LeblancSet> {
+        match self {
+            LeBlancObjectData::Set(set) => Some(set.clone()),
+            _ => None
+        }
+    }
+
+    fn ref_data(&self) -> Option<&LeblancSet> {
+        match self {
+            LeBlancObjectData::Set(set) => Some(set),
+            _ => None
+        }
+    }
+
+    fn mut_data(&mut self) -> Option<&mut LeblancSet> {
+        match self {
+            LeBlancObjectData::Set(set) => Some(set),
+            _ => None
+        }
+    }
+}
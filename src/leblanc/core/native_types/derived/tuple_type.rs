@@ -0,0 +1,145 @@
+use core::fmt::{Display, Formatter};
+use std::cmp::Ordering;
+use fxhash::{FxHashMap, FxHashSet};
+
+
+use std::collections::BTreeSet;
+use std::sync::Arc;
+use lazy_static::lazy_static;
+use crate::leblanc::rustblanc::strawberry::Strawberry;
+use crate::leblanc::core::internal::methods::internal_class::{_internal_expose_, _internal_field_, _internal_to_string_};
+use crate::leblanc::core::internal::methods::internal_tuple::{_internal_tuple_iterate_, _internal_tuple_length_};
+use crate::leblanc::core::leblanc_argument::LeBlancArgument;
+
+use crate::leblanc::core::leblanc_context::VariableContext;
+use crate::leblanc::core::leblanc_object::{Callable, LeBlancObject, LeBlancObjectData, RustDataCast};
+use crate::leblanc::core::method::{Method, MethodHandle};
+use crate::leblanc::core::method_store::MethodStore;
+use crate::leblanc::core::native_types::base_type::{base_clone_method, base_equals_method, base_expose_method, base_field_method, base_to_string_method, ToLeblanc};
+use crate::leblanc::core::native_types::derived::DerivedType;
+use crate::LeBlancType;
+
+/// A fixed sequence of values, which is what a function returning several values gives. Tuples
+/// can't be changed once made, so a constant one can be shared by every use of it.
+#[derive(Clone, Debug)]
+pub struct LeblancTuple {
+    pub values: Vec<Arc<Strawberry<LeBlancObject>>>
+}
+
+impl LeblancTuple {
+    pub fn new(values: Vec<Arc<Strawberry<LeBlancObject>>>) -> LeblancTuple {
+        LeblancTuple {
+            values
+        }
+    }
+
+    pub fn get(&self, index: usize) -> Option<Arc<Strawberry<LeBlancObject>>> {
+        self.values.get(index).cloned()
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+}
+
+lazy_static! {
+    static ref TUPLE_METHODS: Arc<FxHashSet<Method>> = {
+        let mut hash_set = FxHashSet::default();
+        hash_set.insert(Method::default(base_to_string_method(), _internal_to_string_));
+        hash_set.insert(Method::default(base_expose_method(), _internal_expose_));
+        hash_set.insert(Method::default(base_equals_method(), _internal_to_string_));
+        hash_set.insert(Method::default(base_clone_method(), _internal_to_string_));
+        hash_set.insert(Method::default(base_field_method(), _internal_field_));
+        hash_set.insert(tuple_method("iterate", vec![], _internal_tuple_iterate_));
+        hash_set.insert(tuple_method("len", vec![], _internal_tuple_length_));
+        hash_set.insert(tuple_method("length", vec![], _internal_tuple_length_));
+        Arc::new(hash_set)
+    };
+}
+
+pub fn tuple_methods() -> Arc<FxHashSet<Method>> {
+    TUPLE_METHODS.clone()
+}
+
+fn tuple_method(name: &str, arguments: Vec<LeBlancArgument>, handle: MethodHandle) -> Method {
+    Method::new(MethodStore::new(name.to_string(), arguments), handle, BTreeSet::new())
+}
+
+pub fn leblanc_object_tuple(tuple: LeblancTuple) -> LeBlancObject {
+    let base_methods = tuple_methods();
+
+    LeBlancObject::new(
+        LeBlancObjectData::Tuple(tuple),
+        LeBlancType::Derived(DerivedType::Tuple),
+        base_methods,
+        Arc::new(Strawberry::new(FxHashMap::default())),
+        VariableContext::empty(),
+    )
+}
+
+impl ToLeblanc for LeblancTuple {
+    fn create(&self) -> LeBlancObject {
+        leblanc_object_tuple(self.clone())
+    }
+    fn create_mutex(&self) -> Arc<Strawberry<LeBlancObject>> { Arc::new(Strawberry::new(self.create())) }
+}
+
+/// A tuple of one keeps its trailing comma, as it is written.
+impl Display for LeblancTuple {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let values = self.values.iter().map(|item| item.clone().call_name("to_string").unwrap().lock().data.to_string()).collect::<Vec<String>>();
+        match values.len() {
+            1 => write!(f, "({},)", values[0]),
+            _ => write!(f, "({})", values.join(", "))
+        }
+    }
+}
+
+impl PartialEq for LeblancTuple {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.values.iter().zip(&other.values).all(|(value, other_value)| {
+            Arc::ptr_eq(value, other_value) || value.lock().data == other_value.lock().data
+        })
+    }
+}
+
+/// Tuples compare by their first differing value, then by length.
+impl PartialOrd for LeblancTuple {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        for (value, other_value) in self.values.iter().zip(&other.values) {
+            if Arc::ptr_eq(value, other_value) { continue; }
+            match value.lock().data.partial_cmp(&other_value.lock().data)? {
+                Ordering::Equal => {}
+                ordering => return Some(ordering)
+            }
+        }
+        self.len().partial_cmp(&other.len())
+    }
+}
+
+impl RustDataCast<LeblancTuple> for LeBlancObjectData {
+    fn clone_data(&self) -> Option<LeblancTuple> {
+        match self {
+            LeBlancObjectData::Tuple(tuple) => Some(tuple.clone()),
+            _ => None
+        }
+    }
+
+    fn ref_data(&self) -> Option<&LeblancTuple> {
+        match self {
+            LeBlancObjectData::Tuple(tuple) => Some(tuple),
+            _ => None
+        }
+    }
+
+    fn mut_data(&mut self) -> Option<&mut LeblancTuple> {
+        match self {
+            LeBlancObjectData::Tuple(tuple) => Some(tuple),
+            _ => None
+        }
+    }
+}
@@ -0,0 +1,104 @@
+use core::fmt::{Display, Formatter};
+use std::cmp::Ordering;
+use std::sync::Arc;
+
+use crate::leblanc::core::leblanc_context::VariableContext;
+use crate::leblanc::core::leblanc_default_data::unsafe_empty_members;
+use crate::leblanc::core::leblanc_object::{Callable, LeBlancObject, LeBlancObjectData, RustDataCast};
+use crate::leblanc::core::native_types::base_type::{base_methods, ToLeblanc};
+use crate::leblanc::rustblanc::copystring::CopyString;
+use crate::leblanc::rustblanc::strawberry::Strawberry;
+use crate::LeBlancType;
+
+/// A value of a user defined enum: the variant it was constructed as and its payload, if any.
+#[derive(Clone, Debug)]
+pub struct LeblancEnum {
+    pub name: CopyString,
+    pub variant: CopyString,
+    pub payload: Vec<Arc<Strawberry<LeBlancObject>>>
+}
+
+impl LeblancEnum {
+    pub fn new(name: CopyString, variant: CopyString, payload: Vec<Arc<Strawberry<LeBlancObject>>>) -> LeblancEnum {
+        LeblancEnum { name, variant, payload }
+    }
+
+    /// Whether this is the variant `Enum::Variant` names.
+    pub fn is(&self, path: &str) -> bool {
+        path.split_once("::").is_some_and(|(name, variant)| self.name == name && self.variant == variant)
+    }
+}
+
+pub fn leblanc_object_enum(value: LeblancEnum) -> LeBlancObject {
+    let name = value.name;
+    LeBlancObject::new(
+        LeBlancObjectData::Enum(Box::new(value)),
+        LeBlancType::Enum(name),
+        base_methods(),
+        unsafe_empty_members(),
+        VariableContext::empty(),
+    )
+}
+
+impl ToLeblanc for LeblancEnum {
+    fn create(&self) -> LeBlancObject {
+        leblanc_object_enum(self.clone())
+    }
+    fn create_mutex(&self) -> Arc<Strawberry<LeBlancObject>> { Arc::new(Strawberry::new(self.create())) }
+}
+
+impl Display for LeblancEnum {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self.variant)?;
+        if !self.payload.is_empty() {
+            let payload = self.payload.iter().map(|item| item.clone().call_name("to_string").unwrap().lock().data.to_string()).collect::<Vec<String>>();
+            write!(f, "({})", payload.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+impl PartialEq for LeblancEnum {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name && self.variant == other.variant && self.payload.len() == other.payload.len()
+            && self.payload.iter().zip(&other.payload).all(|(left, right)| Arc::ptr_eq(left, right) || left.lock().data == right.lock().data)
+    }
+}
+
+/// Only values of the same variant are ordered, by their payloads.
+impl PartialOrd for LeblancEnum {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        if self.name != other.name || self.variant != other.variant { return None; }
+        for (left, right) in self.payload.iter().zip(&other.payload) {
+            if Arc::ptr_eq(left, right) { continue; }
+            match left.lock().data.partial_cmp(&right.lock().data) {
+                Some(Ordering::Equal) => {}
+                ordering => return ordering
+            }
+        }
+        Some(Ordering::Equal)
+    }
+}
+
+impl RustDataCast<LeblancEnum> for LeBlancObjectData {
+    fn clone_data(&self) -> Option<LeblancEnum> {
+        match self {
+            LeBlancObjectData::Enum(value) => Some(*value.clone()),
+            _ => None
+        }
+    }
+
+    fn ref_data(&self) -> Option<&LeblancEnum> {
+        match self {
+            LeBlancObjectData::Enum(value) => Some(value),
+            _ => None
+        }
+    }
+
+    fn mut_data(&mut self) -> Option<&mut LeblancEnum> {
+        match self {
+            LeBlancObjectData::Enum(value) => Some(value),
+            _ => None
+        }
+    }
+}
@@ -1,28 +1,98 @@
-use alloc::rc::Rc;
 use core::fmt::{Display, Formatter};
-use std::cell::{RefCell};
 use fxhash::{FxHashMap, FxHashSet};
+use std::fs;
 use std::sync::{Arc};
 use crate::leblanc::rustblanc::strawberry::Strawberry;
-use std::sync::Mutex;
 
 
+use std::collections::BTreeSet;
 use crate::leblanc::core::internal::methods::internal_class::{_internal_expose_, _internal_field_, _internal_to_string_};
-use crate::leblanc::core::interpreter::instructions::Instruction;
-use crate::leblanc::core::interpreter::instructions::InstructionBase::{LoadFunction};
-use crate::leblanc::core::interpreter::leblanc_runner::get_globals;
+use crate::leblanc::core::internal::methods::internal_error::{_internal_error_message_, _internal_error_name_, _internal_error_trace_};
+use crate::leblanc::compiler::compile_error_reporter::source_excerpt;
 use crate::leblanc::core::leblanc_context::VariableContext;
 use crate::leblanc::core::leblanc_object::{LeBlancObject, LeBlancObjectData, RustDataCast};
 use crate::leblanc::core::method::Method;
+use crate::leblanc::core::method_store::MethodStore;
 use crate::leblanc::core::native_types::base_type::{base_clone_method, base_equals_method, base_expose_method, base_field_method, base_to_string_method, ToLeblanc};
 use crate::leblanc::core::native_types::LeBlancType;
 use crate::leblanc::include::lib::leblanc_colored::{Color, ColorBright, colorize, ColorString};
 
+/// Every built-in exception with its parent. `Exception` is the root and anything not listed here
+/// is treated as a direct subtype of it.
+static BUILTIN_EXCEPTIONS: [(&str, &str); 22] = [
+    ("NullPointerException", "Exception"),
+    ("TypeException", "Exception"),
+    ("IndexException", "Exception"),
+    ("ArithmeticException", "Exception"),
+    ("StackException", "Exception"),
+    ("PromiseException", "Exception"),
+    ("IOException", "Exception"),
+    ("MatchException", "Exception"),
+    ("BlockException", "Exception"),
+    ("KeyException", "Exception"),
+    ("OperationOnNullException", "NullPointerException"),
+    ("ClassMethodNotFoundException", "TypeException"),
+    ("FieldNotFoundException", "TypeException"),
+    ("FunctionNotFoundException", "TypeException"),
+    ("IndexOutOfBoundsException", "IndexException"),
+    ("DivideByZeroException", "ArithmeticException"),
+    ("OverflowException", "ArithmeticException"),
+    ("UnknownStackException", "StackException"),
+    ("StackOverflowException", "StackException"),
+    ("PromiseNotFulfilledException", "PromiseException"),
+    ("GenericException", "Exception"),
+    ("InstructionNotFoundException", "Exception"),
+];
+
+pub fn is_builtin_exception(name: &str) -> bool {
+    name == "Exception" || BUILTIN_EXCEPTIONS.iter().any(|(exception, _)| *exception == name)
+}
+
+/// The ancestors of a built-in exception, nearest first, ending at `Exception`.
+pub fn builtin_family(name: &str) -> Vec<String> {
+    let mut family = vec![];
+    let mut current = name;
+    while current != "Exception" {
+        current = BUILTIN_EXCEPTIONS.iter().find(|(exception, _)| *exception == current).map_or("Exception", |(_, parent)| parent);
+        family.push(current.to_string());
+    }
+    family
+}
+
 #[derive(Clone, PartialEq, Eq, Debug, PartialOrd, Hash)]
 pub struct LeblancError {
     name: String,
+    /// Ancestors of `name`, nearest first, so `except (Parent! e)` can match any descendant.
+    family: Vec<String>,
     message: String,
-    stack_trace: Vec<Instruction>,
+    stack_trace: Vec<TraceFrame>,
+    /// The calls `stack_trace` has a frame for, so raising the error again in one of them keeps
+    /// the frame of where it was first raised there.
+    calls: Vec<u64>,
+}
+
+/// How many times in a row the same frame is printed in a traceback before the rest are counted.
+const REPEATED_FRAMES_SHOWN: usize = 3;
+
+/// A call frame an error passed through, pointing at the instruction that was running in it.
+#[derive(Clone, PartialEq, Eq, Debug, PartialOrd, Hash)]
+pub struct TraceFrame {
+    pub function: String,
+    pub file: String,
+    pub line: u32,
+    pub column: u32
+}
+
+impl TraceFrame {
+    pub fn new(function: String, file: String, line: u32, column: u32) -> TraceFrame {
+        TraceFrame { function, file, line, column }
+    }
+}
+
+impl Display for TraceFrame {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}:{}:{} in {}", self.file, self.line, self.column, self.function)
+    }
 }
 
 
@@ -33,6 +103,9 @@ pub fn leblanc_object_error(error: LeblancError) -> LeBlancObject {
     hash_set.insert(Method::default(base_equals_method(), _internal_to_string_));
     hash_set.insert(Method::default(base_clone_method(), _internal_to_string_));
     hash_set.insert(Method::default(base_field_method(), _internal_field_));
+    hash_set.insert(error_name_method());
+    hash_set.insert(error_message_method());
+    hash_set.insert(error_trace_method());
 
 
     LeBlancObject::new(
@@ -44,6 +117,18 @@ pub fn leblanc_object_error(error: LeblancError) -> LeBlancObject {
     )
 }
 
+pub fn error_name_method() -> Method {
+    Method::new(MethodStore::new("name".to_string(), vec![]), _internal_error_name_, BTreeSet::new())
+}
+
+pub fn error_message_method() -> Method {
+    Method::new(MethodStore::new("message".to_string(), vec![]), _internal_error_message_, BTreeSet::new())
+}
+
+pub fn error_trace_method() -> Method {
+    Method::new(MethodStore::new("trace".to_string(), vec![]), _internal_error_trace_, BTreeSet::new())
+}
+
 impl Display for LeblancError {
     fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         write!(f, "{}", self.name)
@@ -59,43 +144,102 @@ impl ToLeblanc for LeblancError {
 
 
 impl LeblancError {
-    pub fn new(name: String, message: String, stack_trace: Vec<Instruction>) -> LeblancError {
+    pub fn new(name: String, message: String, stack_trace: Vec<TraceFrame>) -> LeblancError {
         LeblancError {
+            family: builtin_family(&name),
             name,
             message,
-            stack_trace
+            stack_trace,
+            calls: vec![]
         }
     }
 
-    pub fn generic() -> LeblancError {
+    /// An error of a user defined exception class, whose ancestors are given nearest first.
+    pub fn derived(name: String, family: Vec<String>, message: String) -> LeblancError {
         LeblancError {
-            name: "GenericException".to_string(),
-            message: "".to_string(),
-            stack_trace: vec![]
+            name,
+            family,
+            message,
+            stack_trace: vec![],
+            calls: vec![]
         }
     }
 
-    pub fn add_prior_trace(&mut self, mut stack_trace: Vec<Instruction>) {
-        stack_trace.append(&mut self.stack_trace);
-        self.stack_trace = stack_trace;
-        //self.stack_trace.append(&mut stack_trace);
+    pub fn generic() -> LeblancError {
+        LeblancError::new("GenericException".to_string(), "".to_string(), vec![])
     }
 
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    pub fn set_message(&mut self, message: String) {
+        self.message = message;
+    }
+
+    /// The frames the error has unwound through, outermost first.
+    pub fn stack_trace(&self) -> &[TraceFrame] {
+        &self.stack_trace
+    }
+
+    pub fn stack_trace_mut(&mut self) -> &mut [TraceFrame] {
+        &mut self.stack_trace
+    }
+
+    /// Whether this error is an `exception` or one of its descendants.
+    pub fn is_a(&self, exception: &str) -> bool {
+        self.name == exception || self.family.iter().any(|ancestor| ancestor == exception)
+    }
+
+    /// Records the frame where the error was raised in, or passed through, the call `call`.
+    /// Frames are added innermost first, so each goes in front. A call keeps the first frame it
+    /// recorded, so an error raised again after a `finally` still points at its `throw`.
+    pub fn add_frame(&mut self, call: u64, frame: TraceFrame) {
+        if self.calls.contains(&call) { return; }
+        self.calls.push(call);
+        self.stack_trace.insert(0, frame);
+    }
+
+    /// Prints a traceback, outermost call first, quoting the source line of every frame whose file
+    /// can still be read. A frame repeated more than a few times in a row, as deep recursion
+    /// leaves, is printed once with the count of the rest.
     pub fn print_stack_trace(&self) {
-        let func_details = get_func_details(self.stack_trace.get(0).unwrap_or(&Instruction::empty()).arg as u32);
-        eprintln!("{}", colorize(format!("Exception starts at {} on {}", colorize(func_details.name, Color::Bright(ColorBright::BrightYellow)), ColorString::new(&("line ".to_owned() + &self.stack_trace.get(0).unwrap_or(&Instruction::empty()).line_number.to_string())).colorize(Color::Bright(ColorBright::BrightRed)).bold()), Color::Red));
-        eprintln!("   -file:///{}:{}", func_details.file.replace('\\', "/"), self.stack_trace.get(0).unwrap_or(&Instruction::empty()).line_number);
-        for instruct in self.stack_trace[1..self.stack_trace.len()-1].iter() {
-            if instruct.instruct == LoadFunction {
-                let func_details = get_func_details(instruct.arg as u32);
-                eprintln!("{}", colorize(format!("Which calls {} on {}", colorize(func_details.name, Color::Bright(ColorBright::BrightYellow)), ColorString::new(&("line ".to_owned() + &instruct.line_number.to_string())).colorize(Color::Bright(ColorBright::BrightRed)).bold()), Color::Red));
-                eprintln!("   -file:///{}:{}", func_details.file.replace('\\', "/"), instruct.line_number);
+        self.print_stack_trace_from(|file| fs::read_to_string(file).ok())
+    }
+
+    /// Like [`print_stack_trace`](Self::print_stack_trace), but quotes frames from the text `source`
+    /// gives for their file, for sources that aren't files, like the REPL's input.
+    pub fn print_stack_trace_from(&self, source: impl Fn(&str) -> Option<String>) {
+        let mut sources: FxHashMap<String, Option<String>> = FxHashMap::default();
+        eprintln!("{}", ColorString::new("Traceback (most recent call last):").bold().red().string());
+        let mut repeated = 0;
+        for (index, frame) in self.stack_trace.iter().enumerate() {
+            if index > 0 && self.stack_trace[index - 1] == *frame {
+                repeated += 1;
+            } else {
+                repeated = 0;
+            }
+            if repeated >= REPEATED_FRAMES_SHOWN {
+                if self.stack_trace.get(index + 1) != Some(frame) {
+                    eprintln!("  [Previous frame repeated {} more times]", repeated - REPEATED_FRAMES_SHOWN + 1);
+                }
+                continue;
+            }
+            eprintln!("  File \"{}\", line {}, column {}, in {}", frame.file, frame.line, frame.column,
+                colorize(frame.function.clone(), Color::Bright(ColorBright::BrightYellow)));
+            let source = sources.entry(frame.file.clone()).or_insert_with(|| source(&frame.file));
+            if let Some(line) = source.as_ref().and_then(|source| source.lines().nth((frame.line as usize).saturating_sub(1))) {
+                let width = line.chars().skip((frame.column as usize).saturating_sub(1)).take_while(|ch| ch.is_alphanumeric() || *ch == '_').count();
+                for excerpt in source_excerpt(line, frame.column as usize, width.max(1)).lines() {
+                    eprintln!("    {}", excerpt);
+                }
             }
         }
-        let func_details = get_func_details(self.stack_trace[self.stack_trace.len()-1].arg as u32);
-        eprintln!("{}", colorize(format!("And finally errors in {} on {}", colorize(func_details.name, Color::Bright(ColorBright::BrightYellow)),  ColorString::new(&("line ".to_owned() + &self.stack_trace[self.stack_trace.len()-1].line_number.to_string())).colorize(Color::Bright(ColorBright::BrightRed)).bold()), Color::Red));
-        eprintln!("   -file:///{}:{}", func_details.file.replace('\\', "/"), self.stack_trace[self.stack_trace.len()-1].line_number);
-        println!("{}", format!("{}: {}", ColorString::new(self.name.as_str()).colorize(Color::Bright(ColorBright::BrightRed)).bold().string(), colorize(self.message.clone(), Color::Red)))
+        eprintln!("{}: {}", ColorString::new(self.name.as_str()).colorize(Color::Bright(ColorBright::BrightRed)).bold().string(), colorize(self.message.clone(), Color::Red))
     }
 
 }
@@ -128,21 +272,3 @@ impl RustDataCast<LeblancError> for LeBlancObjectData {
         }
     }
 }
-
-struct FuncDetails {
-    name: String,
-    file: String
-}
-
-fn get_func_details(func_number: u32) -> FuncDetails {
-    unsafe {
-        let function: Arc<Strawberry<LeBlancObject>> = get_globals()[func_number as usize].clone();
-        let borrow = function.lock();
-        let inner_method = borrow.data.get_inner_method().unwrap();
-        FuncDetails {
-            name: inner_method.context.name.clone(),
-            file: borrow.context.file.to_string()
-        }
-    }
-
-}
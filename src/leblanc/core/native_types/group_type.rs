@@ -1,14 +1,11 @@
-use alloc::rc::Rc;
 use core::fmt::{Debug, Display, Formatter};
-use std::cell::RefCell;
 use std::cmp::Ordering;
 use std::collections::BTreeSet;
 use std::future::Future;
 use std::mem::take;
-use std::process::Output;
 use crate::leblanc::rustblanc::strawberry::Strawberry;
-use std::sync::{Arc, Mutex};
-use std::thread::spawn;
+use std::sync::Arc;
+use lazy_static::lazy_static;
 use async_std::task::JoinHandle;
 use futures::executor::block_on;
 use futures::future::join_all;
@@ -21,7 +18,7 @@ use crate::leblanc::core::leblanc_handle::LeblancHandle;
 use crate::leblanc::core::leblanc_object::{LeBlancObject, LeBlancObjectData, RustDataCast};
 use crate::leblanc::core::method::Method;
 use crate::leblanc::core::method_store::MethodStore;
-use crate::leblanc::core::native_types::base_type::{base_clone_method, base_equals_method, base_expose_method, base_field_method, base_to_string_method, ToLeblanc};
+use crate::leblanc::core::native_types::base_type::{base_clone_method, base_equals_method, base_expose_method, base_field_method, base_to_string_method};
 use crate::leblanc::core::native_types::promise_type::{LeblancPromise};
 use crate::LeBlancType;
 
@@ -60,13 +57,13 @@ impl PartialEq for LeblancGroup {
         for i in 0..self_length {
             if !self.promises[i].lock().eq(&other.promises[i].lock()) {return false}
         }
-        return true;
+        true
     }
 }
 
 impl PartialOrd for LeblancGroup {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        match self.eq(&other) {
+        match self.eq(other) {
             true => Some(Ordering::Equal),
             false => None
         }
@@ -101,7 +98,7 @@ impl LeblancGroup {
         self.promises.iter_mut().for_each(|prom| {
             let mut mutex = prom.lock();
             let consumed = mutex.promise.lock().consumed;
-            let truth = !consumed && if self.strict_type.is_some() { self.strict_type.unwrap() == mutex.echo.typing } else { true };
+            let truth = !consumed && self.strict_type.is_none_or(|typing| typing == mutex.echo.typing);
             if truth {
                 mutex.promise.lock().result = {
                     let result = mutex.echo.data.get_mut_inner_method().unwrap().run(LeBlancObject::unsafe_null(), args);
@@ -117,7 +114,7 @@ impl LeblancGroup {
         let mut futures_functions = vec![];
         self.promises.iter_mut().for_each(|prom| {
             let consumed = prom.lock().promise.lock().consumed;
-            let truth = !consumed && if self.strict_type.is_some() { self.strict_type.unwrap() == prom.lock().echo.typing } else { true };
+            let truth = !consumed && self.strict_type.is_none_or(|typing| typing == prom.lock().echo.typing);
             if truth {
                 futures_functions.push(prom.lock().echo.data.get_mut_inner_method().unwrap().clone());
                 consumers.push(prom);
@@ -125,7 +122,7 @@ impl LeblancGroup {
         });
         //let args = args.to_vec();
 
-        let mut nargs = args.to_vec();
+        let nargs = args.to_vec();
         let real_futures: Vec<JoinHandle<Arc<Strawberry<LeBlancObject>>>> = futures_functions.into_iter().map(|a| a.leblanc_handle.lock().full_clone()).map(|mut f| {
             let nargs_clone = nargs.clone();
             async_std::task::spawn(async move {
@@ -136,17 +133,17 @@ impl LeblancGroup {
             .await
             .into_iter()
             .collect()});
-        while !consumers.is_empty() {
-            let prom = consumers.pop().unwrap();
+        while let Some(prom) = consumers.pop() {
+            
             let mutex = prom.lock();
             let result = tasks.pop().unwrap();
             mutex.promise.lock().result = Some(result);
             mutex.promise.lock().complete = true;
         }
-        println!("N");
     }
 }
 
+#[allow(dead_code)]
 async fn join_parallel<T: Send + 'static>(
     futs: impl IntoIterator<Item = impl Future<Output = T> + Send + 'static>,
 ) -> Vec<T> {
@@ -191,13 +188,13 @@ impl RustDataCast<LeblancGroup> for LeBlancObjectData {
 
 impl Display for PromiseCell {
     fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
-        write!(f, "PromiseCell(Promise={}, Promised={})", self.promise.lock().to_string(), self.echo.data.to_string())
+        write!(f, "PromiseCell(Promise={}, Promised={})", self.promise.lock(), self.echo.data)
     }
 }
 
 impl Display for LeblancGroup {
     fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
-        write!(f, "Group[{}]", self.promises.iter().cloned().map(|p| p.lock().to_string()).collect::<Vec<String>>().join(",\n"))
+        write!(f, "Group[{}]", self.promises.iter().map(|p| p.lock().to_string()).collect::<Vec<String>>().join(",\n"))
     }
 }
 
@@ -211,17 +208,23 @@ pub fn leblanc_object_group(group: LeblancGroup) -> LeBlancObject {
     )
 }
 
+lazy_static! {
+    static ref GROUP_METHODS: Arc<FxHashSet<Method>> = {
+        let mut hash_set = FxHashSet::default();
+        hash_set.insert(Method::default(base_to_string_method(), _internal_to_string_));
+        hash_set.insert(Method::default(base_expose_method(), _internal_expose_));
+        hash_set.insert(Method::default(base_equals_method(), _internal_to_string_));
+        hash_set.insert(Method::default(base_clone_method(), _internal_to_string_));
+        hash_set.insert(Method::default(base_field_method(), _internal_field_));
+        hash_set.insert(group_apply_method());
+        hash_set.insert(group_pipe_method());
+        hash_set.insert(group_pipe_async_method());
+        Arc::new(hash_set)
+    };
+}
+
 pub fn group_methods() -> Arc<FxHashSet<Method>> {
-    let mut hash_set = FxHashSet::default();
-    hash_set.insert(Method::default(base_to_string_method(), _internal_to_string_));
-    hash_set.insert(Method::default(base_expose_method(), _internal_expose_));
-    hash_set.insert(Method::default(base_equals_method(), _internal_to_string_));
-    hash_set.insert(Method::default(base_clone_method(), _internal_to_string_));
-    hash_set.insert(Method::default(base_field_method(), _internal_field_));
-    hash_set.insert(group_apply_method());
-    hash_set.insert(group_pipe_method());
-    hash_set.insert(group_pipe_async_method());
-    Arc::new(hash_set)
+    GROUP_METHODS.clone()
 }
 
 pub fn group_apply_method() -> Method {
@@ -1,12 +1,11 @@
 use std::collections::{BTreeSet};
 use std::sync::{Arc};
 
-use alloc::rc::Rc;
-use std::cell::RefCell;
-use fxhash::FxHashMap;
+use fxhash::FxHashSet;
+use lazy_static::lazy_static;
 use crate::leblanc::rustblanc::strawberry::Strawberry;
-use std::sync::Mutex;
 
+use crate::leblanc::core::leblanc_default_data::unsafe_empty_members;
 use crate::leblanc::core::internal::methods::internal_math::_internal_inplace_add_;
 use crate::leblanc::core::leblanc_argument::number_argset;
 use crate::leblanc::core::leblanc_context::VariableContext;
@@ -17,15 +16,20 @@ use crate::leblanc::core::method_tag::MethodTag;
 use crate::leblanc::core::native_types::base_type::{base_methods, ToLeblanc};
 use crate::leblanc::core::native_types::LeBlancType;
 
-pub fn leblanc_object_int64(integer: i64) -> LeBlancObject {
-    let mut base_methods = Arc::unwrap_or_clone(base_methods());
-    base_methods.insert(inplace_addition());
+lazy_static! {
+    static ref INT64_METHODS: Arc<FxHashSet<Method>> = {
+        let mut base_methods = Arc::unwrap_or_clone(base_methods());
+        base_methods.insert(inplace_addition());
+        Arc::new(base_methods)
+    };
+}
 
+pub fn leblanc_object_int64(integer: i64) -> LeBlancObject {
     LeBlancObject::new(
         LeBlancObjectData::Int64(integer),
         LeBlancType::Int64,
-        Arc::new(base_methods),
-        Arc::new(Strawberry::new(FxHashMap::default())),
+        INT64_METHODS.clone(),
+        unsafe_empty_members(),
         VariableContext::empty(),
     )
 }
@@ -1,10 +1,8 @@
 
 
 
-use alloc::rc::Rc;
-use std::cell::RefCell;
 use crate::leblanc::rustblanc::strawberry::Strawberry;
-use std::sync::{Arc, Mutex};
+use std::sync::Arc;
 
 
 use crate::leblanc::core::leblanc_context::VariableContext;
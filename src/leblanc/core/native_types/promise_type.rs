@@ -1,10 +1,9 @@
-use alloc::rc::Rc;
 use core::fmt::{Display, Formatter};
-use std::cell::RefCell;
 use std::cmp::Ordering;
 use std::collections::BTreeSet;
 use crate::leblanc::rustblanc::strawberry::Strawberry;
-use std::sync::{Arc, Mutex};
+use std::sync::Arc;
+use lazy_static::lazy_static;
 use fxhash::{FxHashMap, FxHashSet};
 use crate::leblanc::core::internal::methods::internal_class::{_internal_expose_, _internal_field_, _internal_to_string_};
 use crate::leblanc::core::internal::methods::internal_promise::_internal_promise_consume_;
@@ -56,7 +55,7 @@ impl PartialEq for LeblancPromise {
 
 impl PartialOrd for LeblancPromise {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        match self.eq(&other) {
+        match self.eq(other) {
             true => Some(Ordering::Equal),
             false => None
         }
@@ -121,15 +120,21 @@ pub fn leblanc_object_promise(promise: ArcLeblancPromise) -> LeBlancObject {
     )
 }
 
+lazy_static! {
+    static ref PROMISE_METHODS: Arc<FxHashSet<Method>> = {
+        let mut hash_set = FxHashSet::default();
+        hash_set.insert(Method::default(base_to_string_method(), _internal_to_string_));
+        hash_set.insert(Method::default(base_expose_method(), _internal_expose_));
+        hash_set.insert(Method::default(base_equals_method(), _internal_to_string_));
+        hash_set.insert(Method::default(base_clone_method(), _internal_to_string_));
+        hash_set.insert(Method::default(base_field_method(), _internal_field_));
+        hash_set.insert(promise_consume_method());
+        Arc::new(hash_set)
+    };
+}
+
 pub fn promise_methods() -> Arc<FxHashSet<Method>> {
-    let mut hash_set = FxHashSet::default();
-    hash_set.insert(Method::default(base_to_string_method(), _internal_to_string_));
-    hash_set.insert(Method::default(base_expose_method(), _internal_expose_));
-    hash_set.insert(Method::default(base_equals_method(), _internal_to_string_));
-    hash_set.insert(Method::default(base_clone_method(), _internal_to_string_));
-    hash_set.insert(Method::default(base_field_method(), _internal_field_));
-    hash_set.insert(promise_consume_method());
-    Arc::new(hash_set)
+    PROMISE_METHODS.clone()
 }
 
 pub fn promise_consume_method() -> Method {
@@ -36,7 +36,7 @@ pub struct RustObject {
 }
 
 impl PartialEq for RustObject {
-    fn eq(&self, other: &Self) -> bool {
+    fn eq(&self, _other: &Self) -> bool {
         true
     }
 }
@@ -74,7 +74,7 @@ pub struct RustObjectBuilder {
     name: CopyString,
     data: RustObject,
     methods: FxHashSet<Method>,
-    members: FxHashMap<String, LeBlancObject>
+    members: FxHashMap<String, Arc<Strawberry<LeBlancObject>>>
 }
 
 impl RustType for String {}
@@ -1,11 +1,8 @@
 use fxhash::{FxHashMap};
 
 
-use alloc::rc::Rc;
-use std::cell::RefCell;
 use std::sync::Arc;
 use crate::leblanc::rustblanc::strawberry::Strawberry;
-use std::sync::Mutex;
 
 use crate::leblanc::core::leblanc_context::VariableContext;
 use crate::leblanc::core::leblanc_object::{LeBlancObject, LeBlancObjectData};
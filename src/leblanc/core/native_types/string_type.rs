@@ -1,10 +1,8 @@
-use alloc::rc::Rc;
-use std::cell::RefCell;
 use fxhash::{FxHashMap, FxHashSet};
 use std::sync::{Arc};
 use crate::leblanc::rustblanc::strawberry::Strawberry;
-use std::sync::Mutex;
 use smol_str::SmolStr;
+use lazy_static::lazy_static;
 
 
 use crate::leblanc::core::internal::methods::internal_class::{_internal_expose_, _internal_field_, _internal_to_string_};
@@ -20,20 +18,28 @@ use crate::leblanc::core::native_types::LeBlancType;
 
 use crate::LeBlancType::Flex;
 
-pub fn leblanc_object_string(string: String) -> LeBlancObject {
-    let mut hash_set = FxHashSet::default();
-    hash_set.insert(Method::default(base_to_string_method(), _internal_to_string_));
-    hash_set.insert(Method::default(base_expose_method(), _internal_expose_));
-    hash_set.insert(Method::default(base_equals_method(), _internal_to_string_));
-    hash_set.insert(Method::default(base_clone_method(), _internal_to_string_));
-    hash_set.insert(Method::default(base_field_method(), _internal_field_));
-    hash_set.insert( string_addition_method());
+lazy_static! {
+    static ref STRING_METHODS: Arc<FxHashSet<Method>> = {
+        let mut hash_set = FxHashSet::default();
+        hash_set.insert(Method::default(base_to_string_method(), _internal_to_string_));
+        hash_set.insert(Method::default(base_expose_method(), _internal_expose_));
+        hash_set.insert(Method::default(base_equals_method(), _internal_to_string_));
+        hash_set.insert(Method::default(base_clone_method(), _internal_to_string_));
+        hash_set.insert(Method::default(base_field_method(), _internal_field_));
+        hash_set.insert( string_addition_method());
+        Arc::new(hash_set)
+    };
+}
 
+pub fn string_methods() -> Arc<FxHashSet<Method>> {
+    STRING_METHODS.clone()
+}
 
+pub fn leblanc_object_string(string: String) -> LeBlancObject {
     LeBlancObject::new(
         LeBlancObjectData::String(SmolStr::new(string)),
         LeBlancType::String,
-        Arc::new(hash_set),
+        string_methods(),
         Arc::new(Strawberry::new(FxHashMap::default())),
         VariableContext::empty(),
     )
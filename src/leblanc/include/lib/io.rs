@@ -8,6 +8,8 @@ use crate::LeBlancType;
 
 mod _functions_;
 
+pub use _functions_::io_exception;
+
 pub fn io_core_module() -> CoreModule {
     CoreModule::new("io".to_string(), vec![
         ModuleMethod::new(io_stdin_read(), vec![LeBlancType::String]),
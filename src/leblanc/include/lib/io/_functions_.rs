@@ -1,19 +1,35 @@
 use core::str::FromStr;
 use std::io;
-use std::io::{BufRead, Stdin, stdout, Write};
+use std::io::{stdout, Write};
 use std::sync::Arc;
 use smol_str::SmolStr;
 
 use crate::leblanc::core::leblanc_object::{LeBlancObject, RustDataCast};
 use crate::leblanc::core::native_types::base_type::ToLeblanc;
-use crate::leblanc::core::native_types::class_type::{ClassMeta, leblanc_object_custom};
-use crate::leblanc::core::native_types::rust_type::{RustObject, RustObjectBuilder, RustSubTrait, RustType};
+use crate::leblanc::core::native_types::error_type::LeblancError;
 use crate::leblanc::rustblanc::strawberry::Strawberry;
 
-pub fn _stdin_read_(_self: Arc<Strawberry<LeBlancObject>>, _args: &mut [Arc<Strawberry<LeBlancObject>>]) -> Arc<Strawberry<LeBlancObject>> {
+/// An `IOException` saying `message`.
+pub fn io_exception(message: String) -> Arc<Strawberry<LeBlancObject>> {
+    LeblancError::new("IOException".to_string(), message, vec![]).create_mutex()
+}
+
+/// Reads a line from stdin without its line ending. Fails with an `IOException` once stdin is
+/// closed or can't be read.
+fn read_line() -> Result<String, Arc<Strawberry<LeBlancObject>>> {
     let mut line = String::new();
-    io::stdin().read_line(&mut line).unwrap();
-    line[0..line.len()-1].to_string().create_mutex()
+    match io::stdin().read_line(&mut line) {
+        Ok(0) => Err(io_exception("Cannot read past the end of stdin".to_string())),
+        Ok(_) => Ok(line.trim_end_matches(['\n', '\r']).to_string()),
+        Err(err) => Err(io_exception(format!("Cannot read stdin: {}", err)))
+    }
+}
+
+pub fn _stdin_read_(_self: Arc<Strawberry<LeBlancObject>>, _args: &mut [Arc<Strawberry<LeBlancObject>>]) -> Arc<Strawberry<LeBlancObject>> {
+    match read_line() {
+        Ok(line) => line.create_mutex(),
+        Err(err) => err
+    }
 }
 
 
@@ -21,13 +37,19 @@ pub fn _stdin_prompt_(_self: Arc<Strawberry<LeBlancObject>>, _args: &mut [Arc<St
     let locked = _args[0].underlying_pointer();
     let s: &SmolStr = locked.data.ref_data().unwrap();
     print!("{}", s);
-    stdout().flush().unwrap();
+    if let Err(err) = stdout().flush() {
+        return io_exception(format!("Cannot write to stdout: {}", err));
+    }
     _stdin_read_(_self, _args)
 }
 
 pub fn _stdin_read_int_(_self: Arc<Strawberry<LeBlancObject>>, _args: &mut [Arc<Strawberry<LeBlancObject>>]) -> Arc<Strawberry<LeBlancObject>> {
-    let mut line = String::new();
-    io::stdin().read_line(&mut line).unwrap();
-    line = line[0..line.len()-1].to_string();
-    i32::from_str(&line).unwrap().create_mutex()
+    let line = match read_line() {
+        Ok(line) => line,
+        Err(err) => return err
+    };
+    match i32::from_str(line.trim()) {
+        Ok(int) => int.create_mutex(),
+        Err(_) => io_exception(format!("Cannot read '{}' as an int", line))
+    }
 }
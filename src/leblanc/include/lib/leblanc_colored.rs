@@ -57,7 +57,7 @@ impl ColorBright {
 
 impl Color {
     pub fn ansi(&self) -> &'static str {
-        return match self {
+        match self {
             Color::Black => "\x1b[30m",
             Color::Red => "\x1b[31m",
             Color::Green => "\x1b[32m",
@@ -190,13 +190,13 @@ impl Display for ColorString {
 pub fn colorize(string: String, color: Color) -> String {
     let mut color_string = string;
     color_string = color_string.replace(Color::Reset.ansi(), &(Color::Reset.ansi().to_owned() + color.ansi()));
-    let color_string = color.ansi().to_string() + &color_string + Color::Reset.ansi();
-    color_string
+    
+    color.ansi().to_string() + &color_string + Color::Reset.ansi()
 }
 
 pub fn colorize_str(string: &str, color: Color) -> String {
     let cstring = string.replace(Color::Reset.ansi(), &(Color::Reset.ansi().to_owned() + color.ansi()));
-    return color.ansi().to_owned() + &cstring + Color::Reset.ansi();
+    color.ansi().to_owned() + &cstring + Color::Reset.ansi()
 }
 
 pub fn terminal_color_support() -> bool {
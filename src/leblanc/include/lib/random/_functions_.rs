@@ -1,8 +1,7 @@
-use alloc::rc::Rc;
 use std::cell::RefCell;
 use std::ops::{Add, Div, Mul};
 use crate::leblanc::rustblanc::strawberry::Strawberry;
-use std::sync::{Arc, Mutex};
+use std::sync::Arc;
 
 use chrono::Local;
 use rand_chacha::{ChaCha8Rng};
@@ -11,18 +10,13 @@ use crate::leblanc::core::leblanc_object::LeBlancObject;
 use crate::leblanc::core::native_types::base_type::ToLeblanc;
 use crate::LeBlancType;
 
-static mut RNG_GENERATOR: Option<ChaCha8Rng> = None;
-
-fn initialize_generator() -> &'static mut Option<ChaCha8Rng> {
-    unsafe {
-        if RNG_GENERATOR.is_none() { RNG_GENERATOR = Some(ChaCha8Rng::seed_from_u64(Local::now().timestamp() as u64)) }
-        &mut RNG_GENERATOR
-    }
+thread_local! {
+    static RNG_GENERATOR: RefCell<ChaCha8Rng> = RefCell::new(ChaCha8Rng::seed_from_u64(Local::now().timestamp() as u64));
 }
 
 fn random_number() -> f64 {
-    let generator = initialize_generator().as_mut().unwrap();
-    (generator.next_u64() as f64).div(u64::MAX as f64)
+    let number = RNG_GENERATOR.with(|generator| generator.borrow_mut().next_u64());
+    (number as f64).div(u64::MAX as f64)
 }
 
 pub fn _random_no_arg_(_self: Arc<Strawberry<LeBlancObject>>, _args: &mut [Arc<Strawberry<LeBlancObject>>]) -> Arc<Strawberry<LeBlancObject>> {
@@ -1,4 +1,3 @@
-use alloc::rc::Rc;
 use std::ops::Div;
 use crate::leblanc::rustblanc::strawberry::Strawberry;
 use std::sync::{Arc};
@@ -0,0 +1,488 @@
+use std::fmt::Write;
+
+use crate::leblanc::compiler::compile::compile_source;
+use crate::leblanc::compiler::compiler_rewrite::ast::{Block, Expr, Expression, File, Function, Item, LambdaBody, Located, Location, MatchArm, Parameter, Pattern, Stmt, Statement, TypeName};
+use crate::leblanc::compiler::compiler_rewrite::parser::parse;
+use crate::leblanc::compiler::diagnostic::Diagnostic;
+use crate::leblanc::core::internal::methods::builtins::create_partial_functions;
+use crate::leblanc::core::leblanc_argument::LeBlancArgument;
+use crate::leblanc::core::method::Method;
+use crate::leblanc::core::native_types::derived::iterator_type::iterator_methods;
+use crate::leblanc::core::native_types::derived::list_type::list_methods;
+use crate::leblanc::core::native_types::LeBlancType;
+use crate::leblanc::include::lib::get_core_modules;
+
+static KEYWORDS: [&str; 31] = ["func", "returns", "return", "using", "ext", "from", "Class", "Trait", "auto", "Enum", "match", "when", "guard", "yield", "Extension", "of", "property",
+    "if", "elif", "else", "while", "for", "in", "to", "by", "as", "try", "except", "finally", "throw", "raise"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymbolKind {
+    Function,
+    Class,
+    Variable,
+    Parameter,
+    Field
+}
+
+/// A name declared in a document. `scope` is the span the name is visible in; `None` means the
+/// whole file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Symbol {
+    pub name: String,
+    pub kind: SymbolKind,
+    pub signature: String,
+    pub location: Location,
+    pub scope: Option<Location>
+}
+
+/// A function the runtime provides: a builtin, a core module function or a list/iterator method.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct External {
+    pub name: String,
+    pub signature: String,
+    /// The core module that has to be imported for the function, if any.
+    pub module: Option<String>,
+    pub method: bool
+}
+
+/// What the server knows about an open document. The symbols of the last version that parsed are
+/// kept so navigation keeps working while the user is in the middle of typing.
+pub struct Document {
+    pub path: String,
+    pub text: String,
+    pub symbols: Vec<Symbol>
+}
+
+impl Document {
+    pub fn new(path: String, text: String) -> Document {
+        let mut document = Document { path, text: String::new(), symbols: vec![] };
+        document.update(text);
+        document
+    }
+
+    pub fn update(&mut self, text: String) {
+        if let Some(file) = parse_leniently(&text) {
+            self.symbols = symbols(&file);
+        }
+        self.text = text;
+    }
+
+    pub fn diagnostics(&self) -> Vec<Diagnostic> {
+        compile_source(&self.text, &self.path).1
+    }
+
+    /// The file symbol the identifier at `offset` refers to: the innermost visible declaration of
+    /// that name, falling back to any declaration of it.
+    pub fn definition(&self, offset: usize) -> Option<&Symbol> {
+        let (name, _) = identifier_at(&self.text, offset)?;
+        let mut candidates = self.symbols.iter().filter(|symbol| symbol.name == name).collect::<Vec<&Symbol>>();
+        candidates.sort_by_key(|symbol| symbol.scope.map_or(usize::MAX, |scope| scope.end - scope.start));
+        candidates.iter().find(|symbol| symbol.visible_at(offset)).or_else(|| candidates.first()).copied()
+    }
+
+    /// Markdown describing the identifier at `offset`.
+    pub fn hover(&self, offset: usize) -> Option<(String, Location)> {
+        let (name, location) = identifier_at(&self.text, offset)?;
+        let signature = match self.definition(offset) {
+            Some(symbol) => symbol.signature.clone(),
+            None => {
+                let method = self.text[..location.start].trim_end().ends_with('.');
+                let external = externals().into_iter().find(|external| external.name == name && external.method == method)?;
+                match external.module {
+                    Some(module) => format!("{}\n// from module {}", external.signature, module),
+                    None => external.signature
+                }
+            }
+        };
+        Some((format!("```leblanc\n{}\n```", signature), location))
+    }
+
+    /// Completion candidates for the identifier being typed at `offset` as (label, kind, detail).
+    /// After a `.` these are list and iterator methods, otherwise keywords, runtime functions and
+    /// the file's symbols visible at `offset`.
+    pub fn completions(&self, offset: usize) -> Vec<(String, CompletionKind, String)> {
+        let start = self.text[..offset].rfind(|c: char| !is_ident_char(c)).map_or(0, |index| index + 1);
+        let prefix = &self.text[start..offset];
+        let method = self.text[..start].trim_end().ends_with('.');
+
+        let mut items: Vec<(String, CompletionKind, String)> = vec![];
+        for external in externals().into_iter().filter(|external| external.method == method) {
+            let kind = if method { CompletionKind::Method } else { CompletionKind::Function };
+            items.push((external.name, kind, external.signature));
+        }
+        if !method {
+            items.extend(KEYWORDS.iter().map(|keyword| (keyword.to_string(), CompletionKind::Keyword, String::new())));
+            for symbol in self.symbols.iter().filter(|symbol| symbol.visible_at(offset)) {
+                let kind = match symbol.kind {
+                    SymbolKind::Function => CompletionKind::Function,
+                    SymbolKind::Class => CompletionKind::Class,
+                    SymbolKind::Field => CompletionKind::Field,
+                    SymbolKind::Variable | SymbolKind::Parameter => CompletionKind::Variable
+                };
+                items.push((symbol.name.clone(), kind, symbol.signature.clone()));
+            }
+        }
+
+        let mut seen = vec![];
+        items.retain(|(label, _, _)| {
+            let keep = label.starts_with(prefix) && !seen.contains(label);
+            seen.push(label.clone());
+            keep
+        });
+        items
+    }
+}
+
+impl Symbol {
+    fn visible_at(&self, offset: usize) -> bool {
+        match self.scope {
+            None => true,
+            Some(scope) => scope.start <= offset && offset <= scope.end
+                && (self.kind == SymbolKind::Parameter || self.location.start <= offset)
+        }
+    }
+}
+
+/// The completion item kinds of the protocol that the server uses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompletionKind {
+    Method = 2,
+    Function = 3,
+    Field = 5,
+    Variable = 6,
+    Class = 7,
+    Keyword = 14
+}
+
+/// Every builtin, core module function and list/iterator method.
+pub fn externals() -> Vec<External> {
+    let mut externals = create_partial_functions().into_iter()
+        .map(|partial| External { signature: signature(&partial.name, &partial.args, &partial.returns), name: partial.name, module: None, method: false })
+        .collect::<Vec<External>>();
+    for module in get_core_modules() {
+        externals.extend(module.methods_as_partials().into_iter().map(|partial| External {
+            signature: signature(&partial.name, &partial.args, &partial.returns), name: partial.name, module: Some(module.name.clone()), method: false
+        }));
+    }
+    let mut methods = list_methods().iter().chain(iterator_methods().iter()).cloned().collect::<Vec<Method>>();
+    methods.sort_by(|a, b| a.context.name.cmp(&b.context.name));
+    methods.dedup_by(|a, b| a.context.name == b.context.name);
+    externals.extend(methods.into_iter().map(|method| External {
+        signature: signature(&method.context.name, &method.context.arguments, &[]), name: method.context.name, module: None, method: true
+    }));
+    externals
+}
+
+fn signature(name: &str, arguments: &[LeBlancArgument], returns: &[LeBlancType]) -> String {
+    let arguments = arguments.iter().map(|argument| {
+        argument.typing.to_string() + if argument.variable { "..." } else { "" }
+    }).collect::<Vec<String>>();
+    let mut signature = format!("func {}({})", name, arguments.join(", "));
+    if !returns.is_empty() {
+        let _ = write!(signature, " returns {}", returns.iter().map(LeBlancType::to_string).collect::<Vec<String>>().join(", "));
+    }
+    signature
+}
+
+fn function_signature(function: &Function) -> String {
+    let parameters = function.parameters.iter()
+        .map(|parameter| format!("{} {}", parameter.data.type_name.data, parameter.data.name.data))
+        .collect::<Vec<String>>();
+    let mut signature = format!("func {}({})", function.name.data, parameters.join(", "));
+    if !function.returns.is_empty() {
+        let returns = function.returns.iter().map(|returns| returns.data.to_string()).collect::<Vec<String>>();
+        let _ = write!(signature, " returns {}", returns.join(", "));
+    }
+    signature
+}
+
+/// Parses `text`, blanking out the line a syntax error is reported on and trying again a few times
+/// so that the line being typed doesn't hide every other declaration. An error at the very start
+/// of a line usually belongs to the statement before it, so that line is blanked instead. Blanking
+/// keeps every byte offset of the rest of the text intact.
+fn parse_leniently(text: &str) -> Option<File> {
+    let mut text = text.to_string();
+    for _ in 0..8 {
+        let error = match parse(&text) {
+            Ok(file) => return Some(file),
+            Err(error) => error
+        };
+        let offset = error.location.start.min(text.len());
+        let line_start = text[..offset].rfind('\n').map_or(0, |index| index + 1);
+        let (start, end) = if text[line_start..offset].trim().is_empty() && line_start > 0 {
+            (text[..line_start - 1].rfind('\n').map_or(0, |index| index + 1), line_start - 1)
+        } else {
+            (line_start, text[line_start..].find('\n').map_or(text.len(), |index| line_start + index))
+        };
+        let blank = text[start..end].chars().map(|c| if c.is_whitespace() { c.to_string() } else { " ".repeat(c.len_utf8()) }).collect::<String>();
+        text.replace_range(start..end, &blank);
+    }
+    None
+}
+
+/// Collects every declaration in `file` along with the span it is visible in.
+pub fn symbols(file: &File) -> Vec<Symbol> {
+    let variants = file.items.iter().flat_map(|item| match &item.data {
+        Item::Enum(enum_item) => enum_item.variants.iter().map(|variant| variant.data.name.data.clone()).collect(),
+        _ => vec![]
+    }).collect();
+    let mut collector = SymbolCollector { symbols: vec![], variants };
+    for item in &file.items {
+        match &item.data {
+            Item::Function(function) => collector.function(function, None),
+            Item::Class(class) => {
+                let mut signature = format!("Class {}", class.name.data);
+                if !class.supertypes.is_empty() {
+                    let _ = write!(signature, "({})", class.supertypes.iter().map(|supertype| supertype.data.clone()).collect::<Vec<String>>().join(", "));
+                }
+                collector.add(&class.name, SymbolKind::Class, signature, None);
+                for field in &class.fields {
+                    let signature = format!("{}.{}: {}", class.name.data, field.data.name.data, field.data.type_name.data);
+                    collector.add(&field.data.name, SymbolKind::Field, signature, Some(item.location));
+                }
+                for method in &class.methods {
+                    collector.function(&method.data, Some(item.location));
+                }
+            }
+            Item::Trait(trait_item) => {
+                let mut signature = format!("Trait {}", trait_item.name.data);
+                if !trait_item.supertypes.is_empty() {
+                    let _ = write!(signature, "({})", trait_item.supertypes.iter().map(|supertype| supertype.data.clone()).collect::<Vec<String>>().join(", "));
+                }
+                collector.add(&trait_item.name, SymbolKind::Class, signature, None);
+                for method in &trait_item.methods {
+                    collector.function(&method.data.function, Some(item.location));
+                }
+            }
+            Item::Enum(enum_item) => {
+                collector.add(&enum_item.name, SymbolKind::Class, format!("Enum {}", enum_item.name.data), None);
+                for variant in &enum_item.variants {
+                    let mut signature = format!("{}::{}", enum_item.name.data, variant.data.name.data);
+                    if !variant.data.fields.is_empty() {
+                        let _ = write!(signature, "({})", variant.data.fields.iter().map(|field| field.data.to_string()).collect::<Vec<String>>().join(", "));
+                    }
+                    collector.add(&variant.data.name, SymbolKind::Field, signature, None);
+                }
+            }
+            Item::Extension(extension) => {
+                let signature = format!("Extension {} of {}", extension.name.data, extension.target.data);
+                collector.add(&extension.name, SymbolKind::Class, signature, None);
+                for field in &extension.fields {
+                    let signature = format!("{}.{}: {}", extension.target.data, field.data.name.data, field.data.type_name.data);
+                    collector.add(&field.data.name, SymbolKind::Field, signature, Some(item.location));
+                }
+                for method in &extension.methods {
+                    collector.function(&method.data, Some(item.location));
+                }
+            }
+            Item::Import(_) => {}
+        }
+    }
+    collector.symbols
+}
+
+struct SymbolCollector {
+    symbols: Vec<Symbol>,
+    /// Every variant declared in the file, which a bare name in a pattern refers to instead of binding a variable.
+    variants: Vec<String>
+}
+
+impl SymbolCollector {
+    fn add(&mut self, name: &Located<String>, kind: SymbolKind, signature: String, scope: Option<Location>) {
+        self.symbols.push(Symbol { name: name.data.clone(), kind, signature, location: name.location, scope });
+    }
+
+    fn variable(&mut self, type_name: &TypeName, name: &Located<String>, scope: Location) {
+        self.add(name, SymbolKind::Variable, format!("{} {}", type_name, name.data), Some(scope));
+    }
+
+    fn parameters(&mut self, parameters: &[Located<Parameter>], scope: Location) {
+        for parameter in parameters {
+            let signature = format!("{} {}", parameter.data.type_name.data, parameter.data.name.data);
+            self.add(&parameter.data.name, SymbolKind::Parameter, signature, Some(scope));
+        }
+    }
+
+    fn function(&mut self, function: &Function, scope: Option<Location>) {
+        self.add(&function.name, SymbolKind::Function, function_signature(function), scope);
+        if let Some(body) = &function.body {
+            self.parameters(&function.parameters, body.location);
+            self.statements(&body.data, body.location);
+        }
+    }
+
+    fn block(&mut self, block: &Block) {
+        self.statements(&block.data, block.location);
+    }
+
+    fn statements(&mut self, statements: &[Statement], scope: Location) {
+        for statement in statements {
+            self.statement(statement, scope);
+        }
+    }
+
+    fn statement(&mut self, statement: &Statement, scope: Location) {
+        match &statement.data {
+            Stmt::Declaration { type_name, name, value } => {
+                if let Some(value) = value { self.expression(value); }
+                self.variable(&type_name.data, name, scope);
+            }
+            Stmt::Destructuring { targets, value } => {
+                self.expression(value);
+                targets.iter().for_each(|(type_name, name)| self.variable(&type_name.data, name, scope));
+            }
+            Stmt::Assignment { target, value, .. } => {
+                self.expression(target);
+                self.expression(value);
+            }
+            Stmt::Expression(expression) | Stmt::Throw(expression) | Stmt::Yield(expression) | Stmt::Return(Some(expression)) => self.expression(expression),
+            Stmt::Return(None) => {}
+            Stmt::Conditional { branches, otherwise } => {
+                for (condition, body) in branches {
+                    self.expression(condition);
+                    self.block(body);
+                }
+                if let Some(otherwise) = otherwise { self.block(otherwise); }
+            }
+            Stmt::While { condition, body } => {
+                self.expression(condition);
+                self.block(body);
+            }
+            Stmt::For { type_name, variable, iterable, body } => {
+                self.expression(iterable);
+                self.variable(&type_name.data, variable, body.location);
+                self.block(body);
+            }
+            Stmt::Try { body, handlers, finally } => {
+                self.block(body);
+                for handler in handlers {
+                    if let Some(name) = &handler.data.name {
+                        self.add(name, SymbolKind::Variable, format!("{} {}", handler.data.exception.data, name.data), Some(handler.data.body.location));
+                    }
+                    self.block(&handler.data.body);
+                }
+                if let Some(finally) = finally { self.block(finally); }
+            }
+            Stmt::When { value, arms } => self.arms(value, arms)
+        }
+    }
+
+    fn expression(&mut self, expression: &Expression) {
+        match &expression.data {
+            Expr::Constant(_) | Expr::Ident(_) | Expr::Variant { .. } => {}
+            Expr::List(items) | Expr::Set(items) | Expr::Tuple(items) => items.iter().for_each(|item| self.expression(item)),
+            Expr::Dict(entries) => entries.iter().for_each(|(key, value)| {
+                self.expression(key);
+                self.expression(value);
+            }),
+            Expr::Call { function, arguments } => {
+                self.expression(function);
+                arguments.iter().for_each(|argument| self.expression(argument));
+            }
+            Expr::MethodCall { object, arguments, .. } => {
+                self.expression(object);
+                arguments.iter().for_each(|argument| self.expression(argument));
+            }
+            Expr::Attribute { object, .. } | Expr::Unary { operand: object, .. } | Expr::Cast { value: object, .. } => self.expression(object),
+            Expr::Index { object, index } | Expr::Binary { left: object, right: index, .. } | Expr::Groupment { function: object, group: index } => {
+                self.expression(object);
+                self.expression(index);
+            }
+            Expr::Slice { object, start, end } => {
+                self.expression(object);
+                start.iter().chain(end.iter()).for_each(|bound| self.expression(bound));
+            }
+            Expr::Range { start, end, step } => {
+                self.expression(start);
+                self.expression(end);
+                if let Some(step) = step { self.expression(step); }
+            }
+            Expr::Lambda { parameters, body } => {
+                self.parameters(parameters, expression.location);
+                match body.as_ref() {
+                    LambdaBody::Expression(body) => self.expression(body),
+                    LambdaBody::Block(body) => self.block(body)
+                }
+            }
+            Expr::Match { value, arms } => self.arms(value, arms),
+            Expr::Guard { arms, otherwise } => {
+                for arm in arms {
+                    self.expression(&arm.data.condition);
+                    self.block(&arm.data.exit);
+                }
+                match otherwise.as_deref() {
+                    Some(LambdaBody::Expression(value)) => self.expression(value),
+                    Some(LambdaBody::Block(value)) => self.block(value),
+                    None => {}
+                }
+            }
+            Expr::Block(body) => self.block(body)
+        }
+    }
+
+    fn arms(&mut self, value: &Expression, arms: &[Located<MatchArm>]) {
+        self.expression(value);
+        for arm in arms {
+            self.pattern(&arm.data.pattern, arm.location);
+            if let Some(guard) = &arm.data.guard { self.expression(guard); }
+            match &arm.data.body {
+                LambdaBody::Expression(body) => self.expression(body),
+                LambdaBody::Block(body) => self.block(body)
+            }
+        }
+    }
+
+    fn pattern(&mut self, pattern: &Located<Pattern>, scope: Location) {
+        match &pattern.data {
+            Pattern::Name(name) if !self.variants.contains(name) => {
+                self.add(&Located::new(name.clone(), pattern.location), SymbolKind::Variable, format!("flex {}", name), Some(scope));
+            }
+            Pattern::Variant { fields: Some(fields), .. } => fields.iter().for_each(|field| self.pattern(field, scope)),
+            _ => {}
+        }
+    }
+}
+
+fn is_ident_char(char: char) -> bool {
+    char.is_alphanumeric() || char == '_'
+}
+
+/// The identifier touching `offset` and its location.
+fn identifier_at(text: &str, offset: usize) -> Option<(String, Location)> {
+    let offset = offset.min(text.len());
+    let start = text[..offset].rfind(|c: char| !is_ident_char(c)).map_or(0, |index| index + 1);
+    let end = text[offset..].find(|c: char| !is_ident_char(c)).map_or(text.len(), |index| offset + index);
+    let name = &text[start..end];
+    if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+        return None;
+    }
+    let (line, column) = position_of(text, start);
+    Some((name.to_string(), Location::new(start, end, line + 1, column + 1)))
+}
+
+/// The 0-based line and UTF-16 column of the byte `offset`, as the protocol counts them.
+pub fn position_of(text: &str, offset: usize) -> (usize, usize) {
+    let offset = offset.min(text.len());
+    let line_start = text[..offset].rfind('\n').map_or(0, |index| index + 1);
+    let line = text[..line_start].matches('\n').count();
+    (line, text[line_start..offset].encode_utf16().count())
+}
+
+/// The byte offset of a 0-based line and UTF-16 column, clamped to the text.
+pub fn offset_of(text: &str, line: usize, character: usize) -> usize {
+    let line_start = match line {
+        0 => 0,
+        _ => match text.match_indices('\n').nth(line - 1) {
+            Some((index, _)) => index + 1,
+            None => return text.len()
+        }
+    };
+    let mut units = 0;
+    for (index, char) in text[line_start..].char_indices() {
+        if units >= character || char == '\n' {
+            return line_start + index;
+        }
+        units += char.len_utf16();
+    }
+    text.len()
+}
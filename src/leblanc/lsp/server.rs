@@ -0,0 +1,330 @@
+use std::io::{BufRead, Write};
+
+use fxhash::FxHashMap;
+
+use crate::leblanc::compiler::compiler_rewrite::ast::Location;
+use crate::leblanc::compiler::diagnostic::{Diagnostic, Severity};
+use crate::leblanc::lsp::analysis::{offset_of, position_of, Document};
+use crate::leblanc::lsp::transport::{read_message, write_message};
+use crate::leblanc::rustblanc::json::Json;
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+/// Serves the Language Server Protocol over `input` and `output` until the client sends `exit`
+/// or closes the input. Returns the process exit code the protocol asks for.
+pub fn serve(input: &mut impl BufRead, output: &mut impl Write) -> i32 {
+    let mut server = LanguageServer::new();
+    while let Some(message) = read_message(input) {
+        let replies = match message {
+            Ok(message) => server.handle(&message),
+            Err(error) => vec![error_response(&Json::Null, PARSE_ERROR, error)]
+        };
+        for reply in replies {
+            if write_message(output, &reply).is_err() {
+                return 1;
+            }
+        }
+        if server.exited { break; }
+    }
+    if server.shutdown { 0 } else { 1 }
+}
+
+/// The state of a session: the open documents, by URI, and where the shutdown handshake is at.
+#[derive(Default)]
+pub struct LanguageServer {
+    documents: FxHashMap<String, Document>,
+    shutdown: bool,
+    exited: bool
+}
+
+type RequestResult = Result<Json, (i64, String)>;
+
+impl LanguageServer {
+    pub fn new() -> LanguageServer {
+        LanguageServer::default()
+    }
+
+    /// Handles one message from the client, returning the messages to send back: the response to
+    /// a request, or any notifications a client notification causes.
+    pub fn handle(&mut self, message: &Json) -> Vec<Json> {
+        let method = message.get("method").and_then(Json::as_str).unwrap_or_default();
+        let params = message.get("params").unwrap_or(&Json::Null);
+        match message.get("id") {
+            Some(id) => vec![match self.request(method, params) {
+                Ok(result) => Json::object(vec![("jsonrpc", "2.0".into()), ("id", id.clone()), ("result", result)]),
+                Err((code, error)) => error_response(id, code, error)
+            }],
+            None => self.notification(method, params)
+        }
+    }
+
+    fn request(&mut self, method: &str, params: &Json) -> RequestResult {
+        if self.shutdown {
+            return Err((INVALID_REQUEST, String::from("the server is shutting down")));
+        }
+        match method {
+            "initialize" => Ok(capabilities()),
+            "shutdown" => {
+                self.shutdown = true;
+                Ok(Json::Null)
+            }
+            "textDocument/definition" => {
+                let (uri, document, offset) = self.position(params)?;
+                Ok(document.definition(offset).map_or(Json::Null, |symbol| {
+                    Json::object(vec![("uri", uri.into()), ("range", range(&document.text, &symbol.location))])
+                }))
+            }
+            "textDocument/hover" => {
+                let (_, document, offset) = self.position(params)?;
+                Ok(document.hover(offset).map_or(Json::Null, |(contents, location)| Json::object(vec![
+                    ("contents", Json::object(vec![("kind", "markdown".into()), ("value", contents.into())])),
+                    ("range", range(&document.text, &location))
+                ])))
+            }
+            "textDocument/completion" => {
+                let (_, document, offset) = self.position(params)?;
+                Ok(Json::Array(document.completions(offset).into_iter().map(|(label, kind, detail)| Json::object(vec![
+                    ("label", label.into()),
+                    ("kind", Json::Number(kind as i32 as f64)),
+                    ("detail", detail.into())
+                ])).collect()))
+            }
+            _ => Err((METHOD_NOT_FOUND, format!("unknown method '{}'", method)))
+        }
+    }
+
+    fn notification(&mut self, method: &str, params: &Json) -> Vec<Json> {
+        let uri = params.path(&["textDocument", "uri"]).and_then(Json::as_str).unwrap_or_default().to_string();
+        match method {
+            "exit" => {
+                self.exited = true;
+                vec![]
+            }
+            "textDocument/didOpen" => {
+                let text = params.path(&["textDocument", "text"]).and_then(Json::as_str).unwrap_or_default();
+                self.documents.insert(uri.clone(), Document::new(uri_to_path(&uri), text.to_string()));
+                vec![self.publish(&uri)]
+            }
+            "textDocument/didChange" => {
+                // Only full document sync is advertised, so the last change holds the whole text.
+                let text = params.get("contentChanges").and_then(Json::as_array)
+                    .and_then(|changes| changes.last()).and_then(|change| change.get("text")).and_then(Json::as_str);
+                match (self.documents.get_mut(&uri), text) {
+                    (Some(document), Some(text)) => {
+                        document.update(text.to_string());
+                        vec![self.publish(&uri)]
+                    }
+                    _ => vec![]
+                }
+            }
+            "textDocument/didClose" => {
+                self.documents.remove(&uri);
+                vec![publish_diagnostics(&uri, Json::Array(vec![]))]
+            }
+            _ => vec![]
+        }
+    }
+
+    /// The document and byte offset a `TextDocumentPositionParams` points at.
+    fn position(&self, params: &Json) -> Result<(String, &Document, usize), (i64, String)> {
+        let uri = params.path(&["textDocument", "uri"]).and_then(Json::as_str)
+            .ok_or_else(|| (INVALID_PARAMS, String::from("missing textDocument.uri")))?;
+        let document = self.documents.get(uri).ok_or_else(|| (INVALID_PARAMS, format!("'{}' is not open", uri)))?;
+        let line = params.path(&["position", "line"]).and_then(Json::as_u64);
+        let character = params.path(&["position", "character"]).and_then(Json::as_u64);
+        match (line, character) {
+            (Some(line), Some(character)) => Ok((uri.to_string(), document, offset_of(&document.text, line as usize, character as usize))),
+            _ => Err((INVALID_PARAMS, String::from("missing position")))
+        }
+    }
+
+    fn publish(&self, uri: &str) -> Json {
+        let document = &self.documents[uri];
+        let diagnostics = document.diagnostics().iter().map(|diagnostic| lsp_diagnostic(uri, &document.text, diagnostic)).collect();
+        publish_diagnostics(uri, Json::Array(diagnostics))
+    }
+}
+
+fn capabilities() -> Json {
+    Json::object(vec![
+        ("capabilities", Json::object(vec![
+            ("textDocumentSync", Json::Number(1.0)),
+            ("definitionProvider", true.into()),
+            ("hoverProvider", true.into()),
+            ("completionProvider", Json::object(vec![("triggerCharacters", vec!["."].into())]))
+        ])),
+        ("serverInfo", Json::object(vec![("name", "leblanc".into()), ("version", env!("CARGO_PKG_VERSION").into())]))
+    ])
+}
+
+fn lsp_diagnostic(uri: &str, text: &str, diagnostic: &Diagnostic) -> Json {
+    let severity = match diagnostic.severity() {
+        Severity::Error => 1.0,
+        Severity::Warning => 2.0
+    };
+    let mut message = diagnostic.message.clone();
+    for note in &diagnostic.notes {
+        message += &("\nnote: ".to_owned() + note);
+    }
+    let related = diagnostic.labels.iter().map(|label| Json::object(vec![
+        ("location", Json::object(vec![("uri", uri.into()), ("range", range(text, &label.location))])),
+        ("message", label.message.clone().into())
+    ])).collect();
+    Json::object(vec![
+        ("range", range(text, &diagnostic.location)),
+        ("severity", Json::Number(severity)),
+        ("code", diagnostic.code.code().into()),
+        ("source", "leblanc".into()),
+        ("message", message.into()),
+        ("relatedInformation", Json::Array(related))
+    ])
+}
+
+fn publish_diagnostics(uri: &str, diagnostics: Json) -> Json {
+    Json::object(vec![
+        ("jsonrpc", "2.0".into()),
+        ("method", "textDocument/publishDiagnostics".into()),
+        ("params", Json::object(vec![("uri", uri.into()), ("diagnostics", diagnostics)]))
+    ])
+}
+
+fn error_response(id: &Json, code: i64, message: String) -> Json {
+    Json::object(vec![
+        ("jsonrpc", "2.0".into()),
+        ("id", id.clone()),
+        ("error", Json::object(vec![("code", Json::Number(code as f64)), ("message", message.into())]))
+    ])
+}
+
+fn range(text: &str, location: &Location) -> Json {
+    let position = |offset: usize| {
+        let (line, character) = position_of(text, offset);
+        Json::object(vec![("line", line.into()), ("character", character.into())])
+    };
+    Json::object(vec![("start", position(location.start)), ("end", position(location.end))])
+}
+
+/// The file path of a `file://` URI, used to label diagnostics.
+fn uri_to_path(uri: &str) -> String {
+    let path = uri.strip_prefix("file://").unwrap_or(uri);
+    let mut decoded = vec![];
+    let mut bytes = path.bytes();
+    while let Some(byte) = bytes.next() {
+        match byte {
+            b'%' => {
+                let hex = [bytes.next().unwrap_or(b'0'), bytes.next().unwrap_or(b'0')];
+                decoded.push(u8::from_str_radix(std::str::from_utf8(&hex).unwrap_or("00"), 16).unwrap_or(b'?'));
+            }
+            byte => decoded.push(byte)
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const URI: &str = "file:///tmp/session.lb";
+
+    const SOURCE: &str = "func helper(int value) returns int {\n    return value + 1;\n}\n\nfunc main() {\n    int result = helper(2);\n    print(result + missing);\n}\n";
+
+    /// Runs a whole session of `messages` through `serve`, returning its exit code and every
+    /// message it wrote back.
+    fn session(messages: &[String]) -> (i32, Vec<Json>) {
+        let mut input = vec![];
+        for message in messages {
+            write_message(&mut input, &Json::parse(message).unwrap()).unwrap();
+        }
+        let mut output = vec![];
+        let code = serve(&mut input.as_slice(), &mut output);
+        let mut replies = vec![];
+        let mut output = output.as_slice();
+        while let Some(reply) = read_message(&mut output) {
+            replies.push(reply.unwrap());
+        }
+        (code, replies)
+    }
+
+    fn request(id: u32, method: &str, params: &str) -> String {
+        format!(r#"{{"jsonrpc": "2.0", "id": {}, "method": "{}", "params": {}}}"#, id, method, params)
+    }
+
+    fn notification(method: &str, params: &str) -> String {
+        format!(r#"{{"jsonrpc": "2.0", "method": "{}", "params": {}}}"#, method, params)
+    }
+
+    fn at(line: u32, character: u32) -> String {
+        format!(r#"{{"textDocument": {{"uri": "{}"}}, "position": {{"line": {}, "character": {}}}}}"#, URI, line, character)
+    }
+
+    fn response(replies: &[Json], id: u32) -> &Json {
+        replies.iter().find(|reply| reply.get("id").and_then(Json::as_u64) == Some(id as u64))
+            .and_then(|reply| reply.get("result"))
+            .unwrap_or_else(|| panic!("no result for request {}", id))
+    }
+
+    fn position(json: &Json) -> (u64, u64) {
+        (json.get("line").and_then(Json::as_u64).unwrap(), json.get("character").and_then(Json::as_u64).unwrap())
+    }
+
+    #[test]
+    fn serves_a_session() {
+        let open = Json::object(vec![("textDocument", Json::object(vec![
+            ("uri", URI.into()), ("languageId", "leblanc".into()), ("version", 1usize.into()), ("text", SOURCE.into())
+        ]))]);
+        let (code, replies) = session(&[
+            request(1, "initialize", "{}"),
+            notification("initialized", "{}"),
+            notification("textDocument/didOpen", &open.to_string()),
+            request(2, "textDocument/definition", &at(5, 18)),
+            request(3, "textDocument/hover", &at(5, 18)),
+            request(4, "textDocument/completion", &at(6, 10)),
+            request(5, "shutdown", "null"),
+            notification("exit", "null")
+        ]);
+        assert_eq!(code, 0);
+
+        let capabilities = response(&replies, 1).get("capabilities").unwrap();
+        assert_eq!(capabilities.get("definitionProvider"), Some(&Json::Boolean(true)));
+        assert_eq!(capabilities.get("hoverProvider"), Some(&Json::Boolean(true)));
+
+        let published = replies.iter().find(|reply| reply.get("method").and_then(Json::as_str) == Some("textDocument/publishDiagnostics")).unwrap();
+        assert_eq!(published.path(&["params", "uri"]).and_then(Json::as_str), Some(URI));
+        let diagnostics = published.path(&["params", "diagnostics"]).and_then(Json::as_array).unwrap();
+        assert_eq!(diagnostics.len(), 1, "diagnostics were {:?}", diagnostics);
+        let diagnostic = &diagnostics[0];
+        assert_eq!(diagnostic.get("severity").and_then(Json::as_u64), Some(1));
+        assert!(diagnostic.get("message").and_then(Json::as_str).unwrap().contains("missing"));
+        assert_eq!(position(diagnostic.path(&["range", "start"]).unwrap()), (6, 19));
+
+        let definition = response(&replies, 2);
+        assert_eq!(definition.get("uri").and_then(Json::as_str), Some(URI));
+        assert_eq!(position(definition.path(&["range", "start"]).unwrap()).0, 0);
+
+        let hover = response(&replies, 3).path(&["contents", "value"]).and_then(Json::as_str).unwrap();
+        assert!(hover.contains("helper"), "hover was {:?}", hover);
+
+        let completions = response(&replies, 4).as_array().unwrap();
+        let labels = completions.iter().filter_map(|item| item.get("label").and_then(Json::as_str)).collect::<Vec<&str>>();
+        assert!(labels.contains(&"helper"), "completions were {:?}", labels);
+        assert!(labels.contains(&"result"), "completions were {:?}", labels);
+
+        assert_eq!(response(&replies, 5), &Json::Null);
+    }
+
+    #[test]
+    fn exits_with_an_error_without_shutdown() {
+        let (code, replies) = session(&[
+            request(1, "initialize", "{}"),
+            request(2, "textDocument/unknown", "{}"),
+            notification("exit", "null")
+        ]);
+        assert_eq!(code, 1);
+        let error = replies.iter().find(|reply| reply.get("id").and_then(Json::as_u64) == Some(2)).and_then(|reply| reply.get("error")).unwrap();
+        assert_eq!(error.get("code"), Some(&Json::Number(METHOD_NOT_FOUND as f64)));
+    }
+}
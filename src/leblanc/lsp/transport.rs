@@ -0,0 +1,35 @@
+use std::io::{BufRead, Write};
+
+use crate::leblanc::rustblanc::json::Json;
+
+/// Reads one `Content-Length` framed message. Returns `None` once the input is closed, and an
+/// error for a message that isn't valid JSON so the caller can answer it with a parse error.
+pub fn read_message(input: &mut impl BufRead) -> Option<Result<Json, String>> {
+    let mut length = None;
+    loop {
+        let mut header = String::new();
+        if input.read_line(&mut header).ok()? == 0 {
+            return None;
+        }
+        let header = header.trim_end();
+        if header.is_empty() {
+            if length.is_some() { break; }
+            continue;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("Content-Length") {
+                length = value.trim().parse::<usize>().ok();
+            }
+        }
+    }
+
+    let mut body = vec![0; length?];
+    input.read_exact(&mut body).ok()?;
+    Some(String::from_utf8(body).map_err(|err| err.to_string()).and_then(|body| Json::parse(&body)))
+}
+
+pub fn write_message(output: &mut impl Write, message: &Json) -> std::io::Result<()> {
+    let body = message.to_string();
+    write!(output, "Content-Length: {}\r\n\r\n{}", body.len(), body)?;
+    output.flush()
+}
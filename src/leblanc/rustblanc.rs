@@ -7,6 +7,7 @@ pub mod hex;
 pub mod copystring;
 pub mod exception;
 pub mod strawberry;
+pub mod json;
 
 pub trait Appendable<T> {
     fn append_item(&mut self, item: T);
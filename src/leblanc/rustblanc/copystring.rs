@@ -1,9 +1,9 @@
 use core::fmt::{Display, Formatter};
-use std::ops::Add;
 
 static mut STRING_REFS: Vec<String> = Vec::new();
 
 #[derive(Copy, Clone, Eq, Hash, PartialOrd, Ord, Debug)]
+#[derive(Default)]
 pub struct CopyString {
     string: &'static str
 }
@@ -12,12 +12,13 @@ impl CopyString {
     pub fn new<T: Display>(string: T) -> CopyString {
         let string = string.to_string();
         let string_ref = unsafe {
-            let prev_ref = STRING_REFS.iter().find(|p| *p == &string);
+            let string_refs = (&raw mut STRING_REFS).as_mut().unwrap();
+            let prev_ref = string_refs.iter().position(|p| *p == string);
             match prev_ref {
-                Some(reference) => reference,
+                Some(index) => &string_refs[index],
                 None => {
-                    STRING_REFS.push(string);
-                    STRING_REFS.last().unwrap()
+                    string_refs.push(string);
+                    string_refs.last().unwrap()
                 }
             }
         };
@@ -28,13 +29,6 @@ impl CopyString {
     }
 }
 
-impl Default for CopyString {
-    fn default() -> Self {
-        CopyString {
-            string: ""
-        }
-    }
-}
 
 impl Display for CopyString {
     fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
@@ -4,6 +4,7 @@ use std::process;
 use crate::leblanc::include::lib::leblanc_colored::{Color, colorize_str};
 
 pub struct LeblancBaseException {
+    #[allow(dead_code)]
     rust_error: Box<dyn Error>,
     message: String,
     critical: bool,
@@ -28,9 +28,8 @@ impl Hexadecimal {
 
     pub fn from_string(string: String) -> Hexadecimal {
         let mut hex_vec = vec![];
-        for mut i in 0..string.len()/2 {
+        for i in 0..string.len()/2 {
             hex_vec.push(string[(i*2)..(i*2)+2].to_string());
-            i += 1;
         }
         Hexadecimal::new(hex_vec)
     }
@@ -74,15 +73,16 @@ impl Hexadecimal {
     }
 
     pub fn is_zero(&self) -> bool {
-        return self.bytes.iter().all(|b| b == "00")
+        self.bytes.iter().all(|b| b == "00")
     }
 
     pub fn leading_zeroes(&self) -> usize {
-        self.bytes.iter().filter(|&b| b == "00").count()
+        self.bytes.iter().take_while(|&b| b == "00").count()
     }
 
     pub fn strip_leading_zeroes(&mut self) {
-        self.bytes = self.bytes.iter().cloned().filter(|b| b != "00").collect();
+        let zeroes = self.leading_zeroes();
+        self.bytes.drain(..zeroes);
     }
 
     pub fn extend_to_length(&mut self, bytes: usize) {
@@ -0,0 +1,308 @@
+use std::fmt::{Display, Formatter, Write};
+use std::iter::Peekable;
+use std::str::Chars;
+
+/// A JSON value. Objects keep their keys in insertion order so output is stable.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Json {
+    Null,
+    Boolean(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>)
+}
+
+impl Json {
+    pub fn parse(text: &str) -> Result<Json, String> {
+        let mut chars = text.chars().peekable();
+        let value = parse_value(&mut chars)?;
+        skip_whitespace(&mut chars);
+        match chars.next() {
+            None => Ok(value),
+            Some(char) => Err(format!("unexpected '{}' after JSON value", char))
+        }
+    }
+
+    pub fn object(entries: Vec<(&str, Json)>) -> Json {
+        Json::Object(entries.into_iter().map(|(key, value)| (key.to_string(), value)).collect())
+    }
+
+    pub fn get(&self, key: &str) -> Option<&Json> {
+        match self {
+            Json::Object(entries) => entries.iter().find(|(name, _)| name == key).map(|(_, value)| value),
+            _ => None
+        }
+    }
+
+    /// Follows `keys` through nested objects, e.g. `["textDocument", "uri"]`.
+    pub fn path(&self, keys: &[&str]) -> Option<&Json> {
+        keys.iter().try_fold(self, |value, key| value.get(key))
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Json::String(string) => Some(string),
+            _ => None
+        }
+    }
+
+    pub fn as_u64(&self) -> Option<u64> {
+        match self {
+            Json::Number(number) if *number >= 0.0 && number.fract() == 0.0 => Some(*number as u64),
+            _ => None
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&Vec<Json>> {
+        match self {
+            Json::Array(items) => Some(items),
+            _ => None
+        }
+    }
+}
+
+impl Display for Json {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Json::Null => write!(f, "null"),
+            Json::Boolean(value) => write!(f, "{}", value),
+            Json::Number(number) if number.fract() == 0.0 && number.abs() < 1e15 => write!(f, "{}", *number as i64),
+            Json::Number(number) => write!(f, "{}", number),
+            Json::String(string) => write_string(f, string),
+            Json::Array(items) => {
+                write!(f, "[")?;
+                for (index, item) in items.iter().enumerate() {
+                    if index > 0 { write!(f, ",")?; }
+                    write!(f, "{}", item)?;
+                }
+                write!(f, "]")
+            }
+            Json::Object(entries) => {
+                write!(f, "{{")?;
+                for (index, (key, value)) in entries.iter().enumerate() {
+                    if index > 0 { write!(f, ",")?; }
+                    write_string(f, key)?;
+                    write!(f, ":{}", value)?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+impl From<&str> for Json {
+    fn from(string: &str) -> Self { Json::String(string.to_string()) }
+}
+
+impl From<String> for Json {
+    fn from(string: String) -> Self { Json::String(string) }
+}
+
+impl From<bool> for Json {
+    fn from(value: bool) -> Self { Json::Boolean(value) }
+}
+
+impl From<usize> for Json {
+    fn from(value: usize) -> Self { Json::Number(value as f64) }
+}
+
+impl<T: Into<Json>> From<Option<T>> for Json {
+    fn from(value: Option<T>) -> Self { value.map_or(Json::Null, Into::into) }
+}
+
+impl<T: Into<Json>> From<Vec<T>> for Json {
+    fn from(items: Vec<T>) -> Self { Json::Array(items.into_iter().map(Into::into).collect()) }
+}
+
+fn write_string(f: &mut impl Write, string: &str) -> std::fmt::Result {
+    f.write_char('"')?;
+    for char in string.chars() {
+        match char {
+            '"' => f.write_str("\\\"")?,
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            '\r' => f.write_str("\\r")?,
+            '\t' => f.write_str("\\t")?,
+            char if (char as u32) < 0x20 => write!(f, "\\u{:04x}", char as u32)?,
+            char => f.write_char(char)?
+        }
+    }
+    f.write_char('"')
+}
+
+fn skip_whitespace(chars: &mut Peekable<Chars>) {
+    while chars.next_if(|char| char.is_ascii_whitespace()).is_some() {}
+}
+
+fn expect_word(chars: &mut Peekable<Chars>, word: &str, value: Json) -> Result<Json, String> {
+    for expected in word.chars() {
+        if chars.next() != Some(expected) {
+            return Err(format!("invalid literal, expected '{}'", word));
+        }
+    }
+    Ok(value)
+}
+
+fn parse_value(chars: &mut Peekable<Chars>) -> Result<Json, String> {
+    skip_whitespace(chars);
+    match chars.peek().copied() {
+        Some('n') => expect_word(chars, "null", Json::Null),
+        Some('t') => expect_word(chars, "true", Json::Boolean(true)),
+        Some('f') => expect_word(chars, "false", Json::Boolean(false)),
+        Some('"') => parse_string(chars).map(Json::String),
+        Some('[') => {
+            chars.next();
+            let mut items = vec![];
+            skip_whitespace(chars);
+            if chars.next_if_eq(&']').is_some() {
+                return Ok(Json::Array(items));
+            }
+            loop {
+                items.push(parse_value(chars)?);
+                skip_whitespace(chars);
+                match chars.next() {
+                    Some(',') => continue,
+                    Some(']') => return Ok(Json::Array(items)),
+                    _ => return Err(String::from("expected ',' or ']' in array"))
+                }
+            }
+        }
+        Some('{') => {
+            chars.next();
+            let mut entries = vec![];
+            skip_whitespace(chars);
+            if chars.next_if_eq(&'}').is_some() {
+                return Ok(Json::Object(entries));
+            }
+            loop {
+                skip_whitespace(chars);
+                if chars.peek() != Some(&'"') {
+                    return Err(String::from("expected a string key in object"));
+                }
+                let key = parse_string(chars)?;
+                skip_whitespace(chars);
+                if chars.next() != Some(':') {
+                    return Err(String::from("expected ':' after object key"));
+                }
+                entries.push((key, parse_value(chars)?));
+                skip_whitespace(chars);
+                match chars.next() {
+                    Some(',') => continue,
+                    Some('}') => return Ok(Json::Object(entries)),
+                    _ => return Err(String::from("expected ',' or '}' in object"))
+                }
+            }
+        }
+        Some(char) if char == '-' || char.is_ascii_digit() => {
+            let mut number = String::new();
+            while let Some(char) = chars.next_if(|char| char.is_ascii_digit() || matches!(char, '-' | '+' | '.' | 'e' | 'E')) {
+                number.push(char);
+            }
+            number.parse::<f64>().map(Json::Number).map_err(|_| format!("invalid number '{}'", number))
+        }
+        Some(char) => Err(format!("unexpected '{}'", char)),
+        None => Err(String::from("unexpected end of input"))
+    }
+}
+
+/// Parses a string, joining the escaped halves of a surrogate pair. A half without its other one
+/// becomes the replacement character.
+fn parse_string(chars: &mut Peekable<Chars>) -> Result<String, String> {
+    chars.next();
+    let mut string = String::new();
+    let mut high: Option<u32> = None;
+    loop {
+        let char = chars.next().ok_or_else(|| String::from("unterminated string"))?;
+        if char == '\\' && chars.next_if_eq(&'u').is_some() {
+            let unit = parse_hex4(chars)?;
+            match (high.take(), unit) {
+                (Some(high), 0xDC00..=0xDFFF) => string.push(char::from_u32(0x10000 + ((high - 0xD800) << 10) + (unit - 0xDC00)).unwrap()),
+                (pending, unit) => {
+                    if pending.is_some() { string.push(char::REPLACEMENT_CHARACTER); }
+                    match unit {
+                        0xD800..=0xDBFF => high = Some(unit),
+                        unit => string.push(char::from_u32(unit).unwrap_or(char::REPLACEMENT_CHARACTER))
+                    }
+                }
+            }
+            continue;
+        }
+        if high.take().is_some() {
+            string.push(char::REPLACEMENT_CHARACTER);
+        }
+        match char {
+            '"' => return Ok(string),
+            '\\' => match chars.next() {
+                Some('"') => string.push('"'),
+                Some('\\') => string.push('\\'),
+                Some('/') => string.push('/'),
+                Some('b') => string.push('\u{8}'),
+                Some('f') => string.push('\u{c}'),
+                Some('n') => string.push('\n'),
+                Some('r') => string.push('\r'),
+                Some('t') => string.push('\t'),
+                _ => return Err(String::from("invalid escape in string"))
+            },
+            char => string.push(char)
+        }
+    }
+}
+
+fn parse_hex4(chars: &mut Peekable<Chars>) -> Result<u32, String> {
+    let digits = chars.by_ref().take(4).collect::<String>();
+    u32::from_str_radix(&digits, 16).map_err(|_| format!("invalid unicode escape '{}'", digits))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Json;
+
+    fn string(text: &str) -> Result<String, String> {
+        Json::parse(text).map(|value| value.as_str().unwrap().to_string())
+    }
+
+    #[test]
+    fn escapes_round_trip() {
+        let text = "quote \" backslash \\ newline \n tab \t bell \u{7} é";
+        let written = Json::from(text).to_string();
+        assert_eq!(written, r#""quote \" backslash \\ newline \n tab \t bell \u0007 é""#);
+        assert_eq!(string(&written).unwrap(), text);
+        assert_eq!(string(r#""\/\b\f\r\u00e9""#).unwrap(), "/\u{8}\u{c}\ré");
+    }
+
+    #[test]
+    fn surrogate_pairs_are_joined() {
+        assert_eq!(string(r#""\ud83d\ude00""#).unwrap(), "😀");
+        assert_eq!(string(r#""\uD83D\uDE00!""#).unwrap(), "😀!");
+    }
+
+    #[test]
+    fn unpaired_surrogates_are_replaced() {
+        assert_eq!(string(r#""\ud83dab""#).unwrap(), "\u{fffd}ab");
+        assert_eq!(string(r#""\ude00""#).unwrap(), "\u{fffd}");
+        assert_eq!(string(r#""\ud83d\n""#).unwrap(), "\u{fffd}\n");
+        assert_eq!(string(r#""\ud83d\ud83d\ude00""#).unwrap(), "\u{fffd}😀");
+        assert_eq!(string(r#""\ud83d""#).unwrap(), "\u{fffd}");
+    }
+
+    #[test]
+    fn values_parse() {
+        let value = Json::parse(r#" {"id": 3, "params": {"items": [true, false, null, -1.5e2]}, "name": "x"} "#).unwrap();
+        assert_eq!(value.get("id").and_then(Json::as_u64), Some(3));
+        assert_eq!(value.path(&["params", "items"]), Some(&Json::Array(vec![Json::Boolean(true), Json::Boolean(false), Json::Null, Json::Number(-150.0)])));
+        assert_eq!(value.to_string(), r#"{"id":3,"params":{"items":[true,false,null,-150]},"name":"x"}"#);
+    }
+
+    #[test]
+    fn malformed_input_is_an_error() {
+        let errors = ["", "nul", "[1, 2", "[1 2]", "{\"a\" 1}", "{a: 1}", "{\"a\": 1,}", "\"open", "\"\\x\"", "\"\\u12g4\"", "1.2.3", "-", "[] []", "@"]
+            .map(|text| Json::parse(text).unwrap_err());
+        assert_eq!(errors, [
+            "unexpected end of input", "invalid literal, expected 'null'", "expected ',' or ']' in array", "expected ',' or ']' in array",
+            "expected ':' after object key", "expected a string key in object", "expected a string key in object", "unterminated string",
+            "invalid escape in string", "invalid unicode escape '12g4'", "invalid number '1.2.3'", "invalid number '-'",
+            "unexpected '[' after JSON value", "unexpected '@'"
+        ].map(String::from));
+    }
+}
@@ -42,8 +42,6 @@ use std::{
     sync::{Arc, RwLock, Weak},
 };
 
-use std::ops::DerefMut;
-use crate::TypedToken;
 
 pub fn run() {}
 
@@ -213,12 +211,6 @@ impl<T> fmt::Debug for NodeData<T>
 }
 
 
-impl DerefMut for Node<TypedToken> {
-    fn deref_mut(&mut self) -> &mut Self::Target {
-        self
-    }
-}
-
 pub fn child_adapter<T: Display + Clone>(c: NodeDataRef<T>) -> Node<T> {
     Node {
         arc_ref: c,
@@ -230,6 +222,5 @@ pub fn to_node_vec<T: Display + Clone>(c: &RwLock<Vec<Child<T>>>) -> Vec<Node<T>
 }
 
 pub fn to_vec<T: Display + Clone>(c: &RwLock<Vec<Child<T>>>) -> Vec<T> {
-    println!("to vec");
     c.read().unwrap().iter().map(|f| f.value.clone()).collect()
 }
\ No newline at end of file
@@ -1,6 +1,8 @@
+use num::BigInt;
+
 use crate::leblanc::rustblanc::hex::Hexadecimal;
 use crate::leblanc::rustblanc::Hexable;
-use crate::leblanc::rustblanc::utils::{decode_hex, decode_hex_u16, encode_hex, encode_hex_u16};
+use crate::leblanc::rustblanc::utils::{decode_hex, encode_hex};
 
 impl Hexable for u16 {
     fn to_hex(&self, bytes: usize) -> Hexadecimal {
@@ -8,7 +10,7 @@ impl Hexable for u16 {
         if bytes > 2 {
             bytes = 2;
         }
-        let bytes = bytes as usize;
+        let bytes = bytes;
         encode_hex(&self.to_be_bytes()[2 - bytes..2])
     }
 
@@ -24,7 +26,7 @@ impl Hexable for i16 {
         if bytes > 2 {
             bytes = 2;
         }
-        let bytes = bytes as usize;
+        let bytes = bytes;
         encode_hex(&self.to_be_bytes()[2 - bytes..2])
     }
 
@@ -40,7 +42,7 @@ impl Hexable for u32 {
         if bytes > 4 {
             bytes = 4;
         }
-        let bytes = bytes as usize;
+        let bytes = bytes;
         encode_hex(&self.to_be_bytes()[4-bytes..4])
     }
 
@@ -56,7 +58,7 @@ impl Hexable for i32 {
         if bytes > 4 {
             bytes = 4;
         }
-        let bytes = bytes as usize;
+        let bytes = bytes;
         encode_hex(&self.to_be_bytes()[4-bytes..4])
     }
 
@@ -73,7 +75,7 @@ impl Hexable for u64 {
         if bytes > 8 {
             bytes = 8;
         }
-        let bytes = bytes as usize;
+        let bytes = bytes;
         encode_hex(&self.to_be_bytes()[8-bytes..8])
     }
 
@@ -89,7 +91,7 @@ impl Hexable for i64 {
         if bytes > 8 {
             bytes = 8;
         }
-        let bytes = bytes as usize;
+        let bytes = bytes;
         encode_hex(&self.to_be_bytes()[8-bytes..8])
     }
 
@@ -105,7 +107,7 @@ impl Hexable for u128 {
         if bytes > 16 {
             bytes = 16;
         }
-        let bytes = bytes as usize;
+        let bytes = bytes;
         encode_hex(&self.to_be_bytes()[16-bytes..16])
     }
 
@@ -122,7 +124,7 @@ impl Hexable for i128 {
         if bytes > 16 {
             bytes = 16;
         }
-        let bytes = bytes as usize;
+        let bytes = bytes;
         encode_hex(&self.to_be_bytes()[16-bytes..16])
     }
 
@@ -138,7 +140,7 @@ impl Hexable for f32 {
         if bytes > 4 {
             bytes = 4;
         }
-        let bytes = bytes as usize;
+        let bytes = bytes;
         encode_hex(&self.to_be_bytes()[4-bytes..4])
     }
 
@@ -154,7 +156,7 @@ impl Hexable for f64 {
         if bytes > 8 {
             bytes = 8;
         }
-        let bytes = bytes as usize;
+        let bytes = bytes;
         encode_hex(&self.to_be_bytes()[8-bytes..8])
     }
 
@@ -169,10 +171,10 @@ impl Hexable for usize {
     fn to_hex(&self, bytes: usize) -> Hexadecimal {
         let usize_bytes = (usize::BITS/8) as usize;
         let mut bytes = bytes;
-        if bytes as usize > usize_bytes {
+        if bytes > usize_bytes {
             bytes = usize_bytes;
         }
-        let bytes = bytes as usize;
+        let bytes = bytes;
         encode_hex(&self.to_be_bytes()[usize_bytes-bytes..usize_bytes])
     }
 
@@ -186,10 +188,10 @@ impl Hexable for isize {
     fn to_hex(&self, bytes: usize) -> Hexadecimal {
         let usize_bytes = (isize::BITS/8) as usize;
         let mut bytes = bytes;
-        if bytes as usize > usize_bytes {
+        if bytes > usize_bytes {
             bytes = usize_bytes;
         }
-        let bytes = bytes as usize;
+        let bytes = bytes;
         encode_hex(&self.to_be_bytes()[usize_bytes-bytes..usize_bytes])
     }
 
@@ -215,17 +217,14 @@ impl Hexable for bool {
     }
 }
 
+/// A char is stored as its code point.
 impl Hexable for char {
     fn to_hex(&self, _bytes: usize) -> Hexadecimal {
-        let u16_bytes: &mut [u16] = &mut [];
-        char::encode_utf16(*self, u16_bytes);
-        encode_hex_u16(u16_bytes)
+        (*self as u32).to_hex(4)
     }
 
     fn from_hex(hex: &Hexadecimal) -> Self {
-        let bytes = decode_hex_u16(hex).unwrap();
-        let u16_bytes: [u16; 4] = <[u16; 4]>::try_from(bytes).unwrap();
-        char::decode_utf16(u16_bytes).next().unwrap().unwrap()
+        char::from_u32(u32::from_hex(hex)).unwrap()
     }
 }
 
@@ -238,4 +237,14 @@ impl Hexable for String {
         let bytes = decode_hex(hex).unwrap();
         String::from_utf8_lossy(&bytes).to_string()
     }
-}
\ No newline at end of file
+}
+/// A big integer is stored as its two's complement bytes, as many as it needs.
+impl Hexable for BigInt {
+    fn to_hex(&self, _bytes: usize) -> Hexadecimal {
+        encode_hex(&self.to_signed_bytes_be())
+    }
+
+    fn from_hex(hex: &Hexadecimal) -> Self {
+        BigInt::from_signed_bytes_be(&decode_hex(hex).unwrap())
+    }
+}
@@ -1,4 +1,3 @@
-use std::ptr::{addr_of, null, null_mut};
 use parking_lot::lock_api::MutexGuard;
 use parking_lot::{Mutex, RawMutex};
 
@@ -31,6 +30,7 @@ impl<T: Clone + Default> Strawberry<T> {
         cloned
     }
 
+    #[allow(clippy::mut_from_ref)]
     pub fn underlying_pointer(&self) -> &mut T {
         unsafe {&mut *self.mutex.data_ptr()}
     }
@@ -111,7 +111,7 @@ impl Timings {
             ]));
         }
         table.set_format(*format::consts::FORMAT_NO_BORDER_LINE_SEPARATOR);
-        table.print_tty(true);
+        table.print_tty(true).unwrap();
     }
 }
 
@@ -1,15 +1,4 @@
 #![feature(downcast_unchecked)]
-#![feature(core_ffi_c)]
-#![feature(dec2flt)]
-#![feature(core_intrinsics)]
-#![feature(test)]
-#![feature(arc_unwrap_or_clone)]
-#![feature(mutex_unlock)]
-#![feature(get_mut_unchecked)]
-#![feature(fn_traits)]
-#![feature(ptr_const_cast)]
-#![feature(cell_leak)]
-#![feature(ptr_as_uninit)]
 
 #![allow(non_snake_case)]
 #![allow(non_camel_case_types)]
@@ -35,23 +24,20 @@ extern crate alloc;
 extern crate core;
 //
 
-use std::io;
+use std::env;
+use std::process::exit;
 
-use std::time::Instant;
 use clicolors_control::set_colors_enabled;
 use crate::leblanc::compiler::compile::compile;
 
 use crate::leblanc::compiler::compile_types::CompilationMode;
-use crate::leblanc::compiler::compile_types::full_reader::read_file;
+use crate::leblanc::compiler::driver::drive_from_args;
 use crate::leblanc::compiler::identifier::typed_token::TypedToken;
 use crate::leblanc::compiler::lang::leblanc_lang::BoundaryType::{BraceOpen, Semicolon};
 use crate::leblanc::compiler::lang::leblanc_lang::CompileVocab;
 use crate::leblanc::compiler::lang::leblanc_lang::CompileVocab::BOUNDARY;
 use crate::leblanc::compiler::fabric::Fabric;
-use crate::leblanc::compiler::token_stack_generator::create_stack;
 use crate::leblanc::compiler::tokenizer::create_tokens;
-use crate::leblanc::core::interpreter::interactive::start;
-use crate::leblanc::core::interpreter::run;
 use crate::leblanc::core::native_types::LeBlancType;
 use crate::leblanc::rustblanc::relationship::to_node_vec;
 
@@ -59,45 +45,16 @@ use crate::leblanc::rustblanc::relationship::to_node_vec;
 pub mod leblanc;
 pub mod playground;
 
-static INTERACTIVE: bool = false;
-
 use mimalloc::MiMalloc;
-use crate::leblanc::compiler::compiler_rewrite::lexer::lex;
 
 #[global_allocator]
 static GLOBAL: MiMalloc = MiMalloc;
 
 
-fn main() -> io::Result<()> {
-    let _DEBUG = true;
+fn main() {
     //playground::playground();
-
-    if INTERACTIVE {
-        start();
-    }
-    let now = Instant::now();
-
-
-
-
-    //exit(0);
-
-
-
-
-
-
     set_colors_enabled(true);
-    lex("test.lb".to_string());
-
-   /* //compile("test.lb".to_string(), CompilationMode::Full);
-
-
-    let bc = read_file("test.lb".to_string());
-    run(bc);
-
-    let elapsed = now.elapsed();
-    println!("Total Elapsed: {}", elapsed.as_secs_f64());*/
 
-    Ok(())
-}
\ No newline at end of file
+    let args: Vec<String> = env::args().skip(1).collect();
+    exit(drive_from_args(&args));
+}